@@ -0,0 +1,165 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{Cache, GrooveClient, Middleware, DEFAULT_ENDPOINT};
+use crate::error::Result;
+
+/// Time allowed for each request, from connecting to reading the body
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `User-Agent` sent unless the builder sets another
+pub const DEFAULT_USER_AGENT: &str = concat!("groovehq-cli/", env!("CARGO_PKG_VERSION"));
+
+/// How rate-limited (HTTP 429) requests are retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first; 1 disables retries
+    pub max_attempts: u32,
+    /// Wait before the first retry when the API sends no `Retry-After`,
+    /// doubled for each retry after that
+    pub initial_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Fail on the first rate-limited response
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Configures a [`GrooveClient`].
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use groovehq_cli::api::{GrooveClient, RetryPolicy};
+///
+/// # async fn run() -> groovehq_cli::Result<()> {
+/// let client = GrooveClient::builder("api-token")
+///     .timeout(Duration::from_secs(10))
+///     .retry(RetryPolicy::none())
+///     .user_agent("my-tool/1.0")
+///     .build()?;
+/// let me = client.me().await?;
+/// println!("Signed in as {}", me.email);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct GrooveClientBuilder {
+    pub(super) token: String,
+    pub(super) endpoint: String,
+    pub(super) timeout: Duration,
+    pub(super) retry: RetryPolicy,
+    pub(super) user_agent: String,
+    pub(super) proxy: Option<String>,
+    pub(super) cache: Option<Cache>,
+    pub(super) debug: bool,
+    pub(super) track_rate_limit: bool,
+    pub(super) middleware: Vec<Arc<dyn Middleware>>,
+}
+
+impl std::fmt::Debug for GrooveClientBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GrooveClientBuilder")
+            .field("endpoint", &self.endpoint)
+            .field("timeout", &self.timeout)
+            .field("retry", &self.retry)
+            .field("user_agent", &self.user_agent)
+            .field("proxy", &self.proxy)
+            .field("cache", &self.cache)
+            .field("debug", &self.debug)
+            .field("track_rate_limit", &self.track_rate_limit)
+            .field("middleware", &self.middleware.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl GrooveClientBuilder {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            timeout: DEFAULT_TIMEOUT,
+            retry: RetryPolicy::default(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            proxy: None,
+            cache: None,
+            debug: false,
+            track_rate_limit: true,
+            middleware: Vec::new(),
+        }
+    }
+
+    /// GraphQL endpoint, [`DEFAULT_ENDPOINT`] unless set
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Send all requests through `proxy` (e.g. http://proxy.corp:3128),
+    /// still skipping hosts listed in `NO_PROXY`. Without one, `HTTPS_PROXY`,
+    /// `HTTP_PROXY`, and `NO_PROXY` are honored.
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    /// Serve tags, folders, agents, and canned replies from `cache` when fresh
+    pub fn cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Log request and response metadata to stderr, with credentials redacted
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Record the budget reported by each response for
+    /// [`GrooveClient::rate_limit`]; on unless turned off
+    pub fn track_rate_limit(mut self, track: bool) -> Self {
+        self.track_rate_limit = track;
+        self
+    }
+
+    /// Run `middleware` on every request, after retries and before the
+    /// response cache. Middleware runs in the order it's added.
+    pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Fails with [`GrooveError::Config`](crate::error::GrooveError::Config)
+    /// for an invalid proxy URL or user agent
+    pub fn build(self) -> Result<GrooveClient> {
+        GrooveClient::from_builder(self)
+    }
+}
@@ -0,0 +1,273 @@
+use chrono::{DateTime, Utc};
+use directories::ProjectDirs;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Default lifetime of cached reference data
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// On-disk cache for rarely changing reference data (tags, folders, agents,
+/// canned replies), stored as one JSON file per key, plus query responses
+/// kept for `ETag` revalidation.
+///
+/// The cache is best effort: unreadable or corrupt entries count as misses
+/// and write failures are ignored.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    ttl: Duration,
+    refresh: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    fetched_at: DateTime<Utc>,
+    data: T,
+}
+
+/// A response body stored with the `ETag` it was served with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub etag: String,
+    pub body: String,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl,
+            refresh: false,
+        }
+    }
+
+    /// Cache in the platform cache directory for one account: kept separate
+    /// per profile, endpoint, and token, so `--endpoint` or `--token` never
+    /// reuse another account's tag, folder, or agent IDs
+    pub fn for_account(
+        profile: Option<&str>,
+        endpoint: &str,
+        token: &str,
+        ttl: Duration,
+    ) -> Option<Self> {
+        let dir = profile_dir(profile)?.join(account_key(endpoint, token));
+        Some(Self::new(dir, ttl))
+    }
+
+    /// The profile's most recently written account cache, for shell
+    /// completion, which runs without resolving a token
+    pub fn latest_for_profile(profile: Option<&str>, ttl: Duration) -> Option<Self> {
+        let (_, dir) = std::fs::read_dir(profile_dir(profile)?)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .filter_map(|entry| Some((last_write(&entry.path())?, entry.path())))
+            .max()?;
+        Some(Self::new(dir, ttl))
+    }
+
+    /// Ignore existing entries and overwrite them with fresh data
+    pub fn refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+
+    /// Return the cached value for `key` if present and younger than the TTL
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        if self.refresh || self.ttl.is_zero() {
+            return None;
+        }
+
+        let contents = std::fs::read_to_string(self.path(key)).ok()?;
+        let entry: Entry<T> = serde_json::from_str(&contents).ok()?;
+        let age = Utc::now()
+            .signed_duration_since(entry.fetched_at)
+            .to_std()
+            .ok()?;
+        (age < self.ttl).then_some(entry.data)
+    }
+
+    /// Return the cached value for `key` whatever its age, for uses like
+    /// shell completion where slightly stale data beats a network request
+    pub fn get_stale<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let contents = std::fs::read_to_string(self.path(key)).ok()?;
+        let entry: Entry<T> = serde_json::from_str(&contents).ok()?;
+        Some(entry.data)
+    }
+
+    pub fn put<T: Serialize>(&self, key: &str, data: &T) {
+        if self.ttl.is_zero() {
+            return;
+        }
+
+        let entry = Entry {
+            fetched_at: Utc::now(),
+            data,
+        };
+        if let Ok(contents) = serde_json::to_string(&entry) {
+            let _ = std::fs::create_dir_all(&self.dir)
+                .and_then(|_| std::fs::write(self.path(key), contents));
+        }
+    }
+
+    fn response_path(&self, key: &str) -> PathBuf {
+        self.dir.join("responses").join(format!("{key}.json"))
+    }
+
+    /// Return the stored response for `key` to revalidate with `If-None-Match`.
+    /// Unlike `get`, entries don't expire; the server decides if they're stale.
+    pub fn get_response(&self, key: &str) -> Option<CachedResponse> {
+        if self.refresh || self.ttl.is_zero() {
+            return None;
+        }
+
+        let contents = std::fs::read_to_string(self.response_path(key)).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    pub fn put_response(&self, key: &str, response: &CachedResponse) {
+        if self.ttl.is_zero() {
+            return;
+        }
+
+        let path = self.response_path(key);
+        if let (Some(parent), Ok(contents)) = (path.parent(), serde_json::to_string(response)) {
+            let _ = std::fs::create_dir_all(parent).and_then(|_| std::fs::write(&path, contents));
+        }
+    }
+}
+
+fn profile_dir(profile: Option<&str>) -> Option<PathBuf> {
+    let dirs = ProjectDirs::from("", "", "groove-cli")?;
+    Some(dirs.cache_dir().join(profile.unwrap_or("default")))
+}
+
+/// Directory name for an account: a SHA-256 of the endpoint and token, so the
+/// token itself isn't written to disk
+fn account_key(endpoint: &str, token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(endpoint);
+    hasher.update([0]);
+    hasher.update(token);
+    hasher.finalize()[..16]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// When a file directly in `dir` was last written
+fn last_write(dir: &Path) -> Option<SystemTime> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok()?.metadata().ok()?.modified().ok())
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path(), DEFAULT_CACHE_TTL);
+
+        assert_eq!(cache.get::<Vec<String>>("tags"), None);
+        cache.put("tags", &vec!["urgent".to_string()]);
+        assert_eq!(
+            cache.get::<Vec<String>>("tags"),
+            Some(vec!["urgent".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_account_key() {
+        let key = account_key("https://api.groovehq.com/v2/graphql", "token-a");
+        assert_eq!(key.len(), 32);
+        assert!(!key.contains("token-a"));
+        assert_eq!(
+            key,
+            account_key("https://api.groovehq.com/v2/graphql", "token-a")
+        );
+        assert_ne!(
+            key,
+            account_key("https://api.groovehq.com/v2/graphql", "token-b")
+        );
+        assert_ne!(key, account_key("http://localhost:8799", "token-a"));
+    }
+
+    #[test]
+    fn test_cache_expired() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path(), Duration::from_secs(60));
+        let entry = Entry {
+            fetched_at: Utc::now() - chrono::Duration::minutes(5),
+            data: vec!["urgent"],
+        };
+        std::fs::write(
+            dir.path().join("tags.json"),
+            serde_json::to_string(&entry).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(cache.get::<Vec<String>>("tags"), None);
+    }
+
+    #[test]
+    fn test_cache_refresh_skips_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path(), DEFAULT_CACHE_TTL);
+        cache.put("tags", &vec!["urgent".to_string()]);
+
+        let refreshing = cache.clone().refresh(true);
+        assert_eq!(refreshing.get::<Vec<String>>("tags"), None);
+
+        refreshing.put("tags", &vec!["bug".to_string()]);
+        assert_eq!(
+            cache.get::<Vec<String>>("tags"),
+            Some(vec!["bug".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_cache_zero_ttl_disables() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path(), Duration::ZERO);
+        cache.put("tags", &vec!["urgent".to_string()]);
+        assert!(!dir.path().join("tags.json").exists());
+    }
+
+    #[test]
+    fn test_cache_response_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path(), DEFAULT_CACHE_TTL);
+        let response = CachedResponse {
+            etag: "\"abc\"".to_string(),
+            body: "{}".to_string(),
+        };
+
+        assert_eq!(cache.get_response("q1"), None);
+        cache.put_response("q1", &response);
+        assert_eq!(cache.get_response("q1"), Some(response));
+        assert_eq!(cache.clone().refresh(true).get_response("q1"), None);
+    }
+
+    #[test]
+    fn test_cache_corrupt_entry_is_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("tags.json"), "not json").unwrap();
+        let cache = Cache::new(dir.path(), DEFAULT_CACHE_TTL);
+        assert_eq!(cache.get::<Vec<String>>("tags"), None);
+    }
+}
@@ -1,19 +1,75 @@
+use super::middleware::{
+    retry_after, DebugLog, Middleware, Next, RateLimitTracker, Request, ResponseCache, Retry,
+};
+use super::{Cache, GrooveClientBuilder, RateLimitInfo, RetryPolicy};
 use crate::error::{GrooveError, Result};
 use crate::types::*;
-use reqwest::Client;
+use futures::stream::{self, Stream, TryStreamExt};
+use reqwest::header::HeaderValue;
+use reqwest::{Client, NoProxy, Proxy, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-const DEFAULT_ENDPOINT: &str = "https://api.groovehq.com/v2/graphql";
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+pub const DEFAULT_ENDPOINT: &str = "https://api.groovehq.com/v2/graphql";
 const DEFAULT_MESSAGES_LIMIT: i32 = 50;
-const MAX_RETRIES: u32 = 3;
-const INITIAL_BACKOFF_SECS: u64 = 1;
+
+/// Contacts fetched by [`GrooveClient::contact_by_email`] to find the exact match
+const EMAIL_LOOKUP_LIMIT: i32 = 10;
+
+/// Response headers that may carry the API request ID
+const REQUEST_ID_HEADERS: [&str; 2] = ["x-request-id", "request-id"];
 
 /// Maximum items returned per API page for folders, tags, agents, and canned replies.
 pub const MAX_ITEMS_PER_PAGE: usize = 100;
 
+/// Conversations fetched per request by [`GrooveClient::conversations_stream`]
+pub const STREAM_PAGE_SIZE: u32 = 50;
+
+/// Conversations looked up per request by [`GrooveClient::conversations_by_number`]
+pub const BATCH_LOOKUP_SIZE: usize = 50;
+
+/// Conversations changed per mutation document by [`GrooveClient::close_many`]
+/// and [`GrooveClient::open_many`]
+pub const BATCH_MUTATION_SIZE: usize = 20;
+
+/// Fields selected for a single conversation
+const CONVERSATION_FIELDS: &str = "
+                    id
+                    number
+                    subject
+                    state
+                    createdAt
+                    updatedAt
+                    snoozedUntil
+                    messagesCount
+                    assigned {
+                        agent {
+                            id
+                            email
+                            name
+                        }
+                    }
+                    contact {
+                        id
+                        email
+                        name
+                    }
+                    channel {
+                        id
+                        name
+                    }
+                    tags {
+                        nodes {
+                            id
+                            name
+                            color
+                        }
+                    }
+                ";
+
 #[derive(Debug, Deserialize)]
 struct MutationResult {
     errors: Vec<MutationError>,
@@ -25,7 +81,9 @@ struct MutationError {
 }
 
 impl MutationResult {
-    fn into_result(self) -> Result<()> {
+    /// Convert mutation errors, tagging them with the ID of the request that
+    /// returned them
+    fn into_result(self, request_id: Option<&str>) -> Result<()> {
         if self.errors.is_empty() {
             Ok(())
         } else {
@@ -35,7 +93,7 @@ impl MutationResult {
                 .map(|e| e.message.as_str())
                 .collect::<Vec<_>>()
                 .join("; ");
-            Err(GrooveError::GraphQL(msg))
+            Err(GrooveError::graphql(msg).with_request_id(request_id))
         }
     }
 }
@@ -44,6 +102,18 @@ pub struct GrooveClient {
     client: Client,
     endpoint: String,
     token: String,
+    timeout: Duration,
+    user_agent: String,
+    retry: RetryPolicy,
+    cache: Option<Cache>,
+    debug: bool,
+    track_rate_limit: bool,
+    /// Middleware added with [`GrooveClientBuilder::middleware`]
+    layers: Vec<Arc<dyn Middleware>>,
+    /// The full pipeline, built from the settings above
+    middleware: Vec<Arc<dyn Middleware>>,
+    rate_limit: Arc<Mutex<Option<RateLimitInfo>>>,
+    last_request_id: Mutex<Option<String>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -58,17 +128,126 @@ struct GraphQLError {
 }
 
 impl GrooveClient {
+    /// Create a client with the default settings. `HTTPS_PROXY`,
+    /// `HTTP_PROXY`, and `NO_PROXY` are honored; use `with_proxy` to set a
+    /// proxy explicitly, or [`GrooveClient::builder`] for other settings.
     pub fn new(token: &str, endpoint: Option<&str>) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(REQUEST_TIMEOUT)
-            .build()
-            .map_err(GrooveError::Network)?;
-
-        Ok(Self {
-            client,
-            endpoint: endpoint.unwrap_or(DEFAULT_ENDPOINT).to_string(),
-            token: token.to_string(),
-        })
+        let builder = Self::builder(token);
+        match endpoint {
+            Some(endpoint) => builder.endpoint(endpoint).build(),
+            None => builder.build(),
+        }
+    }
+
+    pub fn builder(token: impl Into<String>) -> GrooveClientBuilder {
+        GrooveClientBuilder::new(token)
+    }
+
+    pub(super) fn from_builder(builder: GrooveClientBuilder) -> Result<Self> {
+        let mut client = Self {
+            client: Self::http_client(
+                builder.timeout,
+                &builder.user_agent,
+                builder.proxy.as_deref(),
+            )?,
+            endpoint: builder.endpoint,
+            token: builder.token,
+            timeout: builder.timeout,
+            user_agent: builder.user_agent,
+            retry: builder.retry,
+            cache: builder.cache,
+            debug: builder.debug,
+            track_rate_limit: builder.track_rate_limit,
+            layers: builder.middleware,
+            middleware: Vec::new(),
+            rate_limit: Arc::new(Mutex::new(None)),
+            last_request_id: Mutex::new(None),
+        };
+        client.build_middleware();
+        Ok(client)
+    }
+
+    /// Assemble the request pipeline, outermost first: retries, then custom
+    /// middleware, the response cache, rate-limit accounting, and logging,
+    /// so each retry is revalidated, counted, and logged on its own
+    fn build_middleware(&mut self) {
+        let mut middleware: Vec<Arc<dyn Middleware>> = Vec::new();
+        if self.retry.max_attempts > 1 {
+            middleware.push(Arc::new(Retry(self.retry)));
+        }
+        middleware.extend(self.layers.iter().cloned());
+        if let Some(cache) = &self.cache {
+            middleware.push(Arc::new(ResponseCache(cache.clone())));
+        }
+        if self.track_rate_limit {
+            middleware.push(Arc::new(RateLimitTracker(self.rate_limit.clone())));
+        }
+        if self.debug {
+            middleware.push(Arc::new(DebugLog));
+        }
+        self.middleware = middleware;
+    }
+
+    /// Send all requests through `proxy` (e.g. http://proxy.corp:3128),
+    /// still skipping hosts listed in `NO_PROXY`
+    pub fn with_proxy(mut self, proxy: &str) -> Result<Self> {
+        self.client = Self::http_client(self.timeout, &self.user_agent, Some(proxy))?;
+        Ok(self)
+    }
+
+    fn http_client(timeout: Duration, user_agent: &str, proxy: Option<&str>) -> Result<Client> {
+        let user_agent = HeaderValue::from_str(user_agent)
+            .map_err(|_| GrooveError::Config(format!("Invalid user agent '{}'", user_agent)))?;
+        let mut builder = Client::builder().timeout(timeout).user_agent(user_agent);
+        if let Some(url) = proxy {
+            let proxy = Proxy::all(url)
+                .map_err(|e| GrooveError::Config(format!("Invalid proxy URL '{}': {}", url, e)))?
+                .no_proxy(NoProxy::from_env());
+            builder = builder.proxy(proxy);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Log request and response metadata to stderr, with credentials redacted
+    pub fn with_debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self.build_middleware();
+        self
+    }
+
+    /// Serve tags, folders, agents, and canned replies from `cache` when fresh
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(cache);
+        self.build_middleware();
+        self
+    }
+
+    /// Add `middleware` to the pipeline, as with
+    /// [`GrooveClientBuilder::middleware`]
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.layers.push(Arc::new(middleware));
+        self.build_middleware();
+        self
+    }
+
+    /// Rate-limit budget from the most recent response that reported one
+    pub fn rate_limit(&self) -> Option<RateLimitInfo> {
+        self.rate_limit.lock().ok()?.clone()
+    }
+
+    /// Request ID of the most recent response, for quoting to Groove support
+    pub fn last_request_id(&self) -> Option<String> {
+        self.last_request_id.lock().ok()?.clone()
+    }
+
+    fn cache_get<T: for<'de> Deserialize<'de>>(&self, key: &str) -> Option<T> {
+        self.cache.as_ref().and_then(|cache| cache.get(key))
+    }
+
+    fn cache_put<T: Serialize>(&self, key: &str, data: &T) {
+        if let Some(cache) = &self.cache {
+            cache.put(key, data);
+        }
     }
 
     async fn execute<T: for<'de> Deserialize<'de>>(
@@ -76,36 +255,87 @@ impl GrooveClient {
         query: &str,
         variables: Option<Value>,
     ) -> Result<T> {
+        let (data, _) = self.execute_with_request_id(query, variables).await?;
+        Ok(data)
+    }
+
+    /// [`execute`](Self::execute), also returning the response's request ID.
+    /// Mutations report errors in their data, so they need the ID of their
+    /// own response rather than [`last_request_id`](Self::last_request_id),
+    /// which concurrent requests overwrite.
+    async fn execute_with_request_id<T: for<'de> Deserialize<'de>>(
+        &self,
+        query: &str,
+        variables: Option<Value>,
+    ) -> Result<(T, Option<String>)> {
         let body = json!({
             "query": query,
             "variables": variables.unwrap_or(json!({}))
         });
-
-        let response = self
+        let http = self
             .client
             .post(&self.endpoint)
             .header("Authorization", format!("Bearer {}", self.token))
             .header("Content-Type", "application/json")
             .json(&body)
-            .send()
-            .await?;
+            .build()?;
 
-        let status = response.status();
+        let request = Request { body, http };
+        let response = Next::new(&self.client, &self.middleware)
+            .run(request)
+            .await?;
 
-        if status == 429 {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
+        let request_id = REQUEST_ID_HEADERS.iter().find_map(|name| {
+            response
+                .headers
+                .get(*name)
                 .and_then(|v| v.to_str().ok())
-                .and_then(|s| s.parse::<u64>().ok());
-            return Err(GrooveError::RateLimited { retry_after });
+                .map(str::to_string)
+        });
+        if let Ok(mut last) = self.last_request_id.lock() {
+            last.clone_from(&request_id);
         }
 
-        if status == 401 {
+        if response.status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(GrooveError::RateLimited {
+                retry_after: retry_after(&response.headers),
+            });
+        }
+
+        if response.status == StatusCode::UNAUTHORIZED {
             return Err(GrooveError::AuthError("Invalid or expired token".into()));
         }
 
-        let response_body: GraphQLResponse<T> = response.json().await?;
+        if !response.status.is_success() {
+            return Err(Self::status_error(response.status, &response.body)
+                .with_request_id(request_id.as_deref()));
+        }
+
+        let data = Self::parse_response(&response.body)
+            .map_err(|e| e.with_request_id(request_id.as_deref()))?;
+        Ok((data, request_id))
+    }
+
+    /// Error for a non-2xx response: the GraphQL errors in its body if it has
+    /// any, otherwise the status (proxies answer with HTML pages)
+    fn status_error(status: StatusCode, body: &str) -> GrooveError {
+        let errors = serde_json::from_str::<GraphQLResponse<Value>>(body)
+            .ok()
+            .and_then(|response| response.errors)
+            .filter(|errors| !errors.is_empty());
+        let message = match errors {
+            Some(errors) => errors
+                .iter()
+                .map(|e| e.message.as_str())
+                .collect::<Vec<_>>()
+                .join("; "),
+            None => format!("HTTP {}", status),
+        };
+        GrooveError::graphql(message)
+    }
+
+    fn parse_response<T: for<'de> Deserialize<'de>>(text: &str) -> Result<T> {
+        let response_body: GraphQLResponse<T> = serde_json::from_str(text)?;
 
         if let Some(errors) = response_body.errors {
             let msg = errors
@@ -113,40 +343,12 @@ impl GrooveClient {
                 .map(|e| e.message.as_str())
                 .collect::<Vec<_>>()
                 .join("; ");
-            return Err(GrooveError::GraphQL(msg));
+            return Err(GrooveError::graphql(msg));
         }
 
         response_body
             .data
-            .ok_or_else(|| GrooveError::GraphQL("No data in response".into()))
-    }
-
-    async fn execute_with_retry<T: for<'de> Deserialize<'de>>(
-        &self,
-        query: &str,
-        variables: Option<Value>,
-    ) -> Result<T> {
-        let mut attempts = 0;
-        loop {
-            let vars = variables.clone();
-            match self.execute(query, vars).await {
-                Ok(result) => return Ok(result),
-                Err(GrooveError::RateLimited { retry_after }) => {
-                    attempts += 1;
-                    if attempts >= MAX_RETRIES {
-                        return Err(GrooveError::RateLimited { retry_after });
-                    }
-                    let wait_secs =
-                        retry_after.unwrap_or(INITIAL_BACKOFF_SECS * 2u64.pow(attempts - 1));
-                    eprintln!(
-                        "Rate limited. Retrying in {} seconds... (attempt {}/{})",
-                        wait_secs, attempts, MAX_RETRIES
-                    );
-                    tokio::time::sleep(Duration::from_secs(wait_secs)).await;
-                }
-                Err(e) => return Err(e),
-            }
-        }
+            .ok_or_else(|| GrooveError::graphql("No data in response"))
     }
 
     pub async fn me(&self) -> Result<CurrentAgent> {
@@ -166,17 +368,17 @@ impl GrooveClient {
             }
         "#;
 
-        let response: Response = self.execute_with_retry(query, None).await?;
+        let response: Response = self.execute(query, None).await?;
         Ok(response.me)
     }
 
+    /// One page of up to `first` (default 25) conversations matching
+    /// `filter`, starting after the `after` cursor
     pub async fn conversations(
         &self,
+        filter: &ConversationFilter,
         first: Option<u32>,
         after: Option<String>,
-        state: Option<&str>,
-        folder_id: Option<&str>,
-        search: Option<&str>,
     ) -> Result<ConversationsResponse> {
         #[derive(Deserialize)]
         struct Response {
@@ -184,8 +386,8 @@ impl GrooveClient {
         }
 
         let query = r#"
-            query Conversations($first: Int, $after: String, $filter: ConversationFilter) {
-                conversations(first: $first, after: $after, filter: $filter) {
+            query Conversations($first: Int, $after: String, $filter: ConversationFilter, $orderBy: ConversationOrder) {
+                conversations(first: $first, after: $after, filter: $filter, orderBy: $orderBy) {
                     nodes {
                         id
                         number
@@ -193,6 +395,8 @@ impl GrooveClient {
                         state
                         createdAt
                         updatedAt
+                        snoozedUntil
+                        messagesCount
                         assigned {
                             agent {
                                 id
@@ -226,85 +430,185 @@ impl GrooveClient {
             }
         "#;
 
-        let mut filter = json!({});
-        if let Some(s) = state {
-            filter["state"] = json!(s.to_uppercase());
+        let mut variables_filter = json!({});
+        if let Some(state) = filter.state {
+            variables_filter["state"] = json!(state);
+        }
+        if let Some(f) = &filter.folder_id {
+            variables_filter["folderId"] = json!(f);
+        }
+        if let Some(q) = &filter.keywords {
+            variables_filter["keywords"] = json!(q);
         }
-        if let Some(f) = folder_id {
-            filter["folderId"] = json!(f);
+        if let Some(c) = &filter.contact_id {
+            variables_filter["contactId"] = json!(c);
         }
-        if let Some(q) = search {
-            filter["keywords"] = json!(q);
+        if let Some(a) = &filter.assignee_id {
+            variables_filter["assigneeId"] = json!(a);
         }
+        if let Some(range) = &filter.dates {
+            let mut bounds = json!({});
+            if let Some(since) = range.since {
+                bounds["gte"] = json!(since.to_rfc3339());
+            }
+            if let Some(until) = range.until {
+                bounds["lte"] = json!(until.to_rfc3339());
+            }
+            variables_filter[range.field.filter_key()] = bounds;
+        }
+
+        let order_by = match filter.sort {
+            Some(sort) => {
+                let (field, direction) = sort.order_by();
+                json!({ "field": field, "direction": direction })
+            }
+            None => Value::Null,
+        };
 
         let variables = json!({
             "first": first.unwrap_or(25),
             "after": after,
-            "filter": if filter.as_object().map(|o| o.is_empty()).unwrap_or(true) {
+            "filter": if variables_filter.as_object().map(|o| o.is_empty()).unwrap_or(true) {
                 Value::Null
             } else {
-                filter
-            }
+                variables_filter
+            },
+            "orderBy": order_by
         });
 
-        let response: Response = self.execute_with_retry(query, Some(variables)).await?;
+        let response: Response = self.execute(query, Some(variables)).await?;
         Ok(response.conversations)
     }
 
+    /// Every conversation matching `filter`, fetching pages of
+    /// [`STREAM_PAGE_SIZE`] as the stream is polled. Stops after the first
+    /// error.
+    ///
+    /// ```no_run
+    /// use futures::TryStreamExt;
+    /// use groovehq_cli::{ConversationFilter, ConversationState, GrooveClient};
+    ///
+    /// # async fn run(client: GrooveClient) -> groovehq_cli::Result<()> {
+    /// let filter = ConversationFilter::new().state(ConversationState::Snoozed);
+    /// let snoozed: Vec<_> = client.conversations_stream(&filter).try_collect().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn conversations_stream<'a>(
+        &'a self,
+        filter: &'a ConversationFilter,
+    ) -> impl Stream<Item = Result<Conversation>> + 'a {
+        // `None` once the last page has been fetched
+        let first_page = Some(None::<String>);
+        stream::try_unfold(first_page, move |cursor| async move {
+            let Some(after) = cursor else {
+                return Ok::<_, GrooveError>(None);
+            };
+            let page = self
+                .conversations(filter, Some(STREAM_PAGE_SIZE), after)
+                .await?;
+            let next = match page.page_info.end_cursor {
+                Some(next) if page.page_info.has_next_page => Some(Some(next)),
+                _ => None,
+            };
+            Ok(Some((stream::iter(page.nodes.into_iter().map(Ok)), next)))
+        })
+        .try_flatten()
+    }
+
     pub async fn conversation(&self, number: i64) -> Result<Conversation> {
         #[derive(Deserialize)]
         struct Response {
             conversation: Option<Conversation>,
         }
 
-        let query = r#"
-            query Conversation($number: Int!) {
-                conversation(number: $number) {
-                    id
-                    number
-                    subject
-                    state
-                    createdAt
-                    updatedAt
-                    assigned {
-                        agent {
-                            id
-                            email
-                            name
-                        }
-                    }
-                    contact {
-                        id
-                        email
-                        name
-                    }
-                    channel {
-                        id
-                        name
-                    }
-                    tags {
-                        nodes {
-                            id
-                            name
-                            color
-                        }
-                    }
-                }
-            }
-        "#;
+        let query = format!(
+            r#"
+            query Conversation($number: Int!) {{
+                conversation(number: $number) {{{}}}
+            }}
+        "#,
+            CONVERSATION_FIELDS
+        );
 
         let variables = json!({ "number": number });
-        let response: Response = self.execute_with_retry(query, Some(variables)).await?;
+        let response: Response = self.execute(&query, Some(variables)).await?;
         response
             .conversation
             .ok_or(GrooveError::ConversationNotFound(number))
     }
 
+    /// Look up several conversations by number, [`BATCH_LOOKUP_SIZE`] per
+    /// request. Results are in the order of `numbers`, with `None` for
+    /// numbers that don't exist.
+    pub async fn conversations_by_number(
+        &self,
+        numbers: &[i64],
+    ) -> Result<Vec<Option<Conversation>>> {
+        let mut conversations = Vec::with_capacity(numbers.len());
+        for batch in numbers.chunks(BATCH_LOOKUP_SIZE) {
+            // c0: conversation(number: 12) { ... }, c1: ...
+            let fields: String = batch
+                .iter()
+                .enumerate()
+                .map(|(i, number)| {
+                    format!(
+                        "c{}: conversation(number: {}) {{{}}}\n",
+                        i, number, CONVERSATION_FIELDS
+                    )
+                })
+                .collect();
+            let query = format!("query ConversationsByNumber {{\n{}}}", fields);
+
+            let mut response: HashMap<String, Option<Conversation>> =
+                self.execute(&query, None).await?;
+            conversations
+                .extend((0..batch.len()).map(|i| response.remove(&format!("c{}", i)).flatten()));
+        }
+        Ok(conversations)
+    }
+
+    /// One page of a conversation's messages, oldest first, with the cursors
+    /// to fetch the neighbouring pages
     pub async fn messages(
         &self,
         conversation_id: &str,
-        first: Option<i32>,
-    ) -> Result<Vec<Message>> {
+        page: &MessagePage,
+    ) -> Result<(Vec<Message>, PageInfo)> {
+        let variables = match page {
+            MessagePage::First { count, after } => json!({
+                "conversationId": conversation_id,
+                "first": count,
+                "after": after
+            }),
+            MessagePage::Last { count, before } => json!({
+                "conversationId": conversation_id,
+                "last": count,
+                "before": before
+            }),
+        };
+        self.messages_page(variables).await
+    }
+
+    /// Every message in a conversation, following pagination to the end
+    pub async fn all_messages(&self, conversation_id: &str) -> Result<Vec<Message>> {
+        let mut messages = Vec::new();
+        let mut after = None;
+        loop {
+            let page = MessagePage::First {
+                count: MAX_ITEMS_PER_PAGE as i32,
+                after,
+            };
+            let (page, page_info) = self.messages(conversation_id, &page).await?;
+            messages.extend(page);
+            match page_info.end_cursor {
+                Some(next) if page_info.has_next_page => after = Some(next),
+                _ => return Ok(messages),
+            }
+        }
+    }
+
+    async fn messages_page(&self, variables: Value) -> Result<(Vec<Message>, PageInfo)> {
         #[derive(Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct Response {
@@ -312,8 +616,11 @@ impl GrooveClient {
         }
 
         #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
         struct EventsConnection {
             nodes: Vec<Event>,
+            #[serde(default)]
+            page_info: Option<PageInfo>,
         }
 
         #[derive(Deserialize)]
@@ -339,11 +646,19 @@ impl GrooveClient {
             body_plain_text: Option<String>,
             body: Option<String>,
             author: Option<MessageAuthor>,
+            #[serde(default)]
+            attachments: Vec<Attachment>,
         }
 
         let query = r#"
-            query Messages($conversationId: ID!, $first: Int) {
-                events(filter: { conversationId: $conversationId }, first: $first) {
+            query Messages($conversationId: ID!, $first: Int, $after: String, $last: Int, $before: String) {
+                events(filter: { conversationId: $conversationId }, first: $first, after: $after, last: $last, before: $before) {
+                    pageInfo {
+                        hasNextPage
+                        endCursor
+                        hasPreviousPage
+                        startCursor
+                    }
                     nodes {
                         createdAt
                         change {
@@ -352,6 +667,13 @@ impl GrooveClient {
                                 id
                                 bodyPlainText
                                 body
+                                attachments {
+                                    id
+                                    fileName
+                                    contentType
+                                    size
+                                    url
+                                }
                                 author {
                                     __typename
                                     ... on Agent {
@@ -370,6 +692,13 @@ impl GrooveClient {
                                 id
                                 bodyPlainText
                                 body
+                                attachments {
+                                    id
+                                    fileName
+                                    contentType
+                                    size
+                                    url
+                                }
                                 author {
                                     __typename
                                     ... on Agent {
@@ -390,12 +719,8 @@ impl GrooveClient {
             }
         "#;
 
-        let variables = json!({
-            "conversationId": conversation_id,
-            "first": first.unwrap_or(DEFAULT_MESSAGES_LIMIT)
-        });
-
-        let response: Response = self.execute_with_retry(query, Some(variables)).await?;
+        let response: Response = self.execute(query, Some(variables)).await?;
+        let page_info = response.events.page_info.unwrap_or_default();
 
         // Extract messages from events
         let messages: Vec<Message> = response
@@ -410,110 +735,819 @@ impl GrooveClient {
                         body_text: msg.body_plain_text,
                         body_html: msg.body,
                         author: msg.author,
+                        attachments: msg.attachments,
                     }),
                     Change::Other => None,
                 }
             })
             .collect();
 
-        Ok(messages)
+        Ok((messages, page_info))
     }
 
-    pub async fn folders(&self) -> Result<Vec<Folder>> {
+    /// Fetch a single customer message or agent reply by ID
+    pub async fn message(&self, id: &str) -> Result<Message> {
         #[derive(Deserialize)]
         struct Response {
-            folders: FoldersConnection,
+            node: Option<Node>,
         }
 
         #[derive(Deserialize)]
-        struct FoldersConnection {
-            nodes: Vec<Folder>,
+        #[serde(tag = "__typename")]
+        enum Node {
+            EmailMessage(MessageNode),
+            Reply(MessageNode),
+            #[serde(other)]
+            Other,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct MessageNode {
+            id: String,
+            created_at: chrono::DateTime<chrono::Utc>,
+            body_plain_text: Option<String>,
+            body: Option<String>,
+            author: Option<MessageAuthor>,
+            #[serde(default)]
+            attachments: Vec<Attachment>,
         }
 
         let query = r#"
-            query Folders($first: Int!) {
-                folders(first: $first) {
-                    nodes {
+            query Message($id: ID!) {
+                node(id: $id) {
+                    __typename
+                    ... on EmailMessage {
                         id
-                        name
+                        createdAt
+                        bodyPlainText
+                        body
+                        attachments {
+                            id
+                            fileName
+                            contentType
+                            size
+                            url
+                        }
+                        author {
+                            __typename
+                            ... on Agent {
+                                id
+                                email
+                                name
+                            }
+                            ... on Contact {
+                                id
+                                email
+                                name
+                            }
+                        }
+                    }
+                    ... on Reply {
+                        id
+                        createdAt
+                        bodyPlainText
+                        body
+                        attachments {
+                            id
+                            fileName
+                            contentType
+                            size
+                            url
+                        }
+                        author {
+                            __typename
+                            ... on Agent {
+                                id
+                                email
+                                name
+                            }
+                            ... on Contact {
+                                id
+                                email
+                                name
+                            }
+                        }
                     }
                 }
             }
         "#;
 
-        let variables = json!({ "first": MAX_ITEMS_PER_PAGE as i32 });
-        let response: Response = self.execute_with_retry(query, Some(variables)).await?;
-        Ok(response.folders.nodes)
+        let variables = json!({ "id": id });
+        let response: Response = self.execute(query, Some(variables)).await?;
+
+        match response.node {
+            Some(Node::EmailMessage(msg)) | Some(Node::Reply(msg)) => Ok(Message {
+                id: msg.id,
+                created_at: msg.created_at,
+                body_text: msg.body_plain_text,
+                body_html: msg.body,
+                author: msg.author,
+                attachments: msg.attachments,
+            }),
+            Some(Node::Other) | None => Err(GrooveError::MessageNotFound(id.to_string())),
+        }
     }
 
-    pub async fn tags(&self) -> Result<Vec<Tag>> {
+    /// The first `first` notes of a conversation
+    pub async fn notes(&self, conversation_id: &str, first: Option<i32>) -> Result<Vec<Note>> {
+        let first = first.unwrap_or(DEFAULT_MESSAGES_LIMIT);
+        let (notes, _) = self.notes_page(conversation_id, first, None).await?;
+        Ok(notes)
+    }
+
+    /// Every note in a conversation, following pagination to the end
+    pub async fn all_notes(&self, conversation_id: &str) -> Result<Vec<Note>> {
+        let mut notes = Vec::new();
+        let mut after = None;
+        loop {
+            let (page, page_info) = self
+                .notes_page(conversation_id, MAX_ITEMS_PER_PAGE as i32, after)
+                .await?;
+            notes.extend(page);
+            match page_info.end_cursor {
+                Some(next) if page_info.has_next_page => after = Some(next),
+                _ => return Ok(notes),
+            }
+        }
+    }
+
+    /// Notes among a page of `first` events after `after`. Notes are
+    /// filtered out of all of a conversation's events, so a page can hold
+    /// fewer than `first` notes while more follow.
+    async fn notes_page(
+        &self,
+        conversation_id: &str,
+        first: i32,
+        after: Option<String>,
+    ) -> Result<(Vec<Note>, PageInfo)> {
         #[derive(Deserialize)]
         struct Response {
-            tags: TagsConnection,
+            events: EventsConnection,
         }
 
         #[derive(Deserialize)]
-        struct TagsConnection {
-            nodes: Vec<Tag>,
+        #[serde(rename_all = "camelCase")]
+        struct EventsConnection {
+            nodes: Vec<Event>,
+            #[serde(default)]
+            page_info: Option<PageInfo>,
         }
 
-        let query = r#"
-            query Tags($first: Int!) {
-                tags(first: $first) {
-                    nodes {
-                        id
-                        name
-                        color
-                    }
-                }
-            }
-        "#;
-
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Event {
+            created_at: chrono::DateTime<chrono::Utc>,
+            change: Option<Change>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(tag = "__typename")]
+        enum Change {
+            Note(NoteChange),
+            #[serde(other)]
+            Other,
+        }
+
+        #[derive(Deserialize)]
+        struct NoteChange {
+            id: String,
+            body: Option<String>,
+            author: Option<MessageAuthor>,
+        }
+
+        let query = r#"
+            query Notes($conversationId: ID!, $first: Int, $after: String) {
+                events(filter: { conversationId: $conversationId }, first: $first, after: $after) {
+                    pageInfo {
+                        hasNextPage
+                        endCursor
+                    }
+                    nodes {
+                        createdAt
+                        change {
+                            __typename
+                            ... on Note {
+                                id
+                                body
+                                author {
+                                    __typename
+                                    ... on Agent {
+                                        id
+                                        email
+                                        name
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({
+            "conversationId": conversation_id,
+            "first": first,
+            "after": after
+        });
+
+        let response: Response = self.execute(query, Some(variables)).await?;
+        let page_info = response.events.page_info.unwrap_or_default();
+
+        let notes = response
+            .events
+            .nodes
+            .into_iter()
+            .filter_map(|event| match event.change? {
+                Change::Note(note) => Some(Note {
+                    id: note.id,
+                    created_at: event.created_at,
+                    body: note.body,
+                    author: note.author,
+                }),
+                Change::Other => None,
+            })
+            .collect();
+
+        Ok((notes, page_info))
+    }
+
+    /// Notes mentioning the current agent, newest first
+    pub async fn mentions(&self, first: u32) -> Result<Vec<Mention>> {
+        #[derive(Deserialize)]
+        struct Response {
+            mentions: MentionsConnection,
+        }
+
+        #[derive(Deserialize)]
+        struct MentionsConnection {
+            nodes: Vec<Mention>,
+        }
+
+        let query = r#"
+            query Mentions($first: Int!) {
+                mentions(first: $first) {
+                    nodes {
+                        id
+                        createdAt
+                        readAt
+                        conversation {
+                            id
+                            number
+                            subject
+                        }
+                        note {
+                            id
+                            createdAt
+                            body
+                            author {
+                                __typename
+                                ... on Agent {
+                                    id
+                                    email
+                                    name
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({ "first": first });
+        let response: Response = self.execute(query, Some(variables)).await?;
+        Ok(response.mentions.nodes)
+    }
+
+    pub async fn folders(&self) -> Result<Vec<Folder>> {
+        if let Some(cached) = self.cache_get("folders") {
+            return Ok(cached);
+        }
+
+        #[derive(Deserialize)]
+        struct Response {
+            folders: FoldersConnection,
+        }
+
+        #[derive(Deserialize)]
+        struct FoldersConnection {
+            nodes: Vec<Folder>,
+        }
+
+        let query = r#"
+            query Folders($first: Int!) {
+                folders(first: $first) {
+                    nodes {
+                        id
+                        name
+                    }
+                }
+            }
+        "#;
+
         let variables = json!({ "first": MAX_ITEMS_PER_PAGE as i32 });
-        let response: Response = self.execute_with_retry(query, Some(variables)).await?;
+        let response: Response = self.execute(query, Some(variables)).await?;
+        self.cache_put("folders", &response.folders.nodes);
+        Ok(response.folders.nodes)
+    }
+
+    pub async fn tags(&self) -> Result<Vec<Tag>> {
+        if let Some(cached) = self.cache_get("tags") {
+            return Ok(cached);
+        }
+
+        #[derive(Deserialize)]
+        struct Response {
+            tags: TagsConnection,
+        }
+
+        #[derive(Deserialize)]
+        struct TagsConnection {
+            nodes: Vec<Tag>,
+        }
+
+        let query = r#"
+            query Tags($first: Int!) {
+                tags(first: $first) {
+                    nodes {
+                        id
+                        name
+                        color
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({ "first": MAX_ITEMS_PER_PAGE as i32 });
+        let response: Response = self.execute(query, Some(variables)).await?;
+        self.cache_put("tags", &response.tags.nodes);
         Ok(response.tags.nodes)
     }
 
-    pub async fn canned_replies(&self) -> Result<Vec<CannedReply>> {
+    pub async fn canned_replies(&self) -> Result<Vec<CannedReply>> {
+        if let Some(cached) = self.cache_get("canned_replies") {
+            return Ok(cached);
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            canned_replies: CannedRepliesConnection,
+        }
+
+        #[derive(Deserialize)]
+        struct CannedRepliesConnection {
+            nodes: Vec<CannedReply>,
+        }
+
+        let query = r#"
+            query CannedReplies($first: Int!) {
+                cannedReplies(first: $first) {
+                    nodes {
+                        id
+                        name
+                        subject
+                        body
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({ "first": MAX_ITEMS_PER_PAGE as i32 });
+        let response: Response = self.execute(query, Some(variables)).await?;
+        self.cache_put("canned_replies", &response.canned_replies.nodes);
+        Ok(response.canned_replies.nodes)
+    }
+
+    /// Knowledge base articles matching `keywords`
+    pub async fn kb_articles(&self, keywords: &str, first: u32) -> Result<Vec<KbArticle>> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            kb_articles: KbArticlesConnection,
+        }
+
+        #[derive(Deserialize)]
+        struct KbArticlesConnection {
+            nodes: Vec<KbArticle>,
+        }
+
+        let query = r#"
+            query KbArticles($first: Int!, $filter: KbArticleFilter) {
+                kbArticles(first: $first, filter: $filter) {
+                    nodes {
+                        id
+                        title
+                        url
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({ "first": first, "filter": { "keywords": keywords } });
+        let response: Response = self.execute(query, Some(variables)).await?;
+        Ok(response.kb_articles.nodes)
+    }
+
+    /// Every automation rule. Not cached, since rules are listed to see
+    /// their current state.
+    pub async fn rules(&self) -> Result<Vec<Rule>> {
+        #[derive(Deserialize)]
+        struct Response {
+            rules: RulesConnection,
+        }
+
+        #[derive(Deserialize)]
+        struct RulesConnection {
+            nodes: Vec<Rule>,
+        }
+
+        let query = r#"
+            query Rules($first: Int!) {
+                rules(first: $first) {
+                    nodes {
+                        id
+                        name
+                        description
+                        enabled
+                        matchType
+                        conditions {
+                            param
+                            operator
+                            value
+                        }
+                        actions {
+                            type
+                            value
+                        }
+                        updatedAt
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({ "first": MAX_ITEMS_PER_PAGE as i32 });
+        let response: Response = self.execute(query, Some(variables)).await?;
+        Ok(response.rules.nodes)
+    }
+
+    /// Turn an automation rule on or off
+    pub async fn set_rule_enabled(&self, rule_id: &str, enabled: bool) -> Result<()> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            rule_update: MutationResult,
+        }
+
+        let query = r#"
+            mutation RuleUpdate($input: RuleUpdateInput!) {
+                ruleUpdate(input: $input) {
+                    errors {
+                        message
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({
+            "input": {
+                "ruleId": rule_id,
+                "enabled": enabled
+            }
+        });
+
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response.rule_update.into_result(request_id.as_deref())
+    }
+
+    pub async fn reply(&self, conversation_id: &str, body: &str) -> Result<()> {
+        self.send_reply(conversation_id, body, None).await
+    }
+
+    /// Reply with a subject line other than the conversation's
+    pub async fn reply_with_subject(
+        &self,
+        conversation_id: &str,
+        body: &str,
+        subject: &str,
+    ) -> Result<()> {
+        self.send_reply(conversation_id, body, Some(subject)).await
+    }
+
+    async fn send_reply(
+        &self,
+        conversation_id: &str,
+        body: &str,
+        subject: Option<&str>,
+    ) -> Result<()> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            conversation_reply: MutationResult,
+        }
+
+        let query = r#"
+            mutation Reply($input: ConversationReplyInput!) {
+                conversationReply(input: $input) {
+                    errors {
+                        message
+                    }
+                }
+            }
+        "#;
+
+        let mut variables = json!({
+            "input": {
+                "conversationId": conversation_id,
+                "body": body
+            }
+        });
+        if let Some(subject) = subject {
+            variables["input"]["subject"] = json!(subject);
+        }
+
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response
+            .conversation_reply
+            .into_result(request_id.as_deref())
+    }
+
+    pub async fn close(&self, conversation_id: &str) -> Result<()> {
+        self.update_state(conversation_id, "conversationClose")
+            .await
+    }
+
+    pub async fn open(&self, conversation_id: &str) -> Result<()> {
+        self.update_state(conversation_id, "conversationOpen").await
+    }
+
+    /// Close several conversations, [`BATCH_MUTATION_SIZE`] per request.
+    /// Returns one result per ID, in order; the outer error means a whole
+    /// request failed.
+    pub async fn close_many(&self, conversation_ids: &[&str]) -> Result<Vec<Result<()>>> {
+        self.update_states(conversation_ids, "conversationClose")
+            .await
+    }
+
+    /// Open several conversations; see [`close_many`](Self::close_many)
+    pub async fn open_many(&self, conversation_ids: &[&str]) -> Result<Vec<Result<()>>> {
+        self.update_states(conversation_ids, "conversationOpen")
+            .await
+    }
+
+    async fn update_states(
+        &self,
+        conversation_ids: &[&str],
+        mutation: &str,
+    ) -> Result<Vec<Result<()>>> {
+        let mut results = Vec::with_capacity(conversation_ids.len());
+        for batch in conversation_ids.chunks(BATCH_MUTATION_SIZE) {
+            // m0: conversationClose(input: $input0) { ... }, m1: ...
+            let params: Vec<String> = (0..batch.len())
+                .map(|i| format!("$input{}: ConversationStateInput!", i))
+                .collect();
+            let fields: String = (0..batch.len())
+                .map(|i| {
+                    format!(
+                        "m{0}: {1}(input: $input{0}) {{ errors {{ message }} }}\n",
+                        i, mutation
+                    )
+                })
+                .collect();
+            let query = format!(
+                "mutation UpdateStates({}) {{\n{}}}",
+                params.join(", "),
+                fields
+            );
+            let variables: serde_json::Map<String, Value> = batch
+                .iter()
+                .enumerate()
+                .map(|(i, id)| (format!("input{}", i), json!({ "conversationId": id })))
+                .collect();
+
+            let (mut response, request_id): (HashMap<String, MutationResult>, _) = self
+                .execute_with_request_id(&query, Some(Value::Object(variables)))
+                .await?;
+            results.extend((0..batch.len()).map(|i| {
+                response
+                    .remove(&format!("m{}", i))
+                    .ok_or_else(|| GrooveError::graphql("No data in response"))
+                    .and_then(|result| result.into_result(request_id.as_deref()))
+            }));
+        }
+        Ok(results)
+    }
+
+    async fn update_state(&self, conversation_id: &str, mutation: &str) -> Result<()> {
+        let query = format!(
+            r#"
+            mutation UpdateState($input: ConversationStateInput!) {{
+                {}(input: $input) {{
+                    errors {{
+                        message
+                    }}
+                }}
+            }}
+        "#,
+            mutation
+        );
+
+        #[derive(Deserialize)]
+        struct Response {
+            #[serde(flatten)]
+            result: std::collections::HashMap<String, MutationResult>,
+        }
+
+        let variables = json!({
+            "input": {
+                "conversationId": conversation_id
+            }
+        });
+
+        let (response, request_id): (Response, _) = self
+            .execute_with_request_id(&query, Some(variables))
+            .await?;
+        for (_, result) in response.result {
+            result.into_result(request_id.as_deref())?;
+        }
+        Ok(())
+    }
+
+    pub async fn snooze(&self, conversation_id: &str, until: &str) -> Result<()> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            conversation_snooze: MutationResult,
+        }
+
+        let query = r#"
+            mutation Snooze($input: ConversationSnoozeInput!) {
+                conversationSnooze(input: $input) {
+                    errors {
+                        message
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({
+            "input": {
+                "conversationId": conversation_id,
+                "snoozedUntil": until
+            }
+        });
+
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response
+            .conversation_snooze
+            .into_result(request_id.as_deref())
+    }
+
+    pub async fn assign(&self, conversation_id: &str, agent_id: &str) -> Result<()> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            conversation_assign: MutationResult,
+        }
+
+        let query = r#"
+            mutation Assign($input: ConversationAssignInput!) {
+                conversationAssign(input: $input) {
+                    errors {
+                        message
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({
+            "input": {
+                "conversationId": conversation_id,
+                "assigneeId": agent_id
+            }
+        });
+
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response
+            .conversation_assign
+            .into_result(request_id.as_deref())
+    }
+
+    /// File a conversation under a different customer
+    pub async fn change_contact(&self, conversation_id: &str, contact_id: &str) -> Result<()> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            conversation_change_contact: MutationResult,
+        }
+
+        let query = r#"
+            mutation ChangeContact($input: ConversationChangeContactInput!) {
+                conversationChangeContact(input: $input) {
+                    errors {
+                        message
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({
+            "input": {
+                "conversationId": conversation_id,
+                "contactId": contact_id
+            }
+        });
+
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response
+            .conversation_change_contact
+            .into_result(request_id.as_deref())
+    }
+
+    pub async fn unassign(&self, conversation_id: &str) -> Result<()> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            conversation_unassign: MutationResult,
+        }
+
+        let query = r#"
+            mutation Unassign($input: ConversationUnassignInput!) {
+                conversationUnassign(input: $input) {
+                    errors {
+                        message
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({
+            "input": {
+                "conversationId": conversation_id
+            }
+        });
+
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response
+            .conversation_unassign
+            .into_result(request_id.as_deref())
+    }
+
+    /// Agents following a conversation (notified of activity without being assigned)
+    pub async fn followers(&self, number: i64) -> Result<Vec<Agent>> {
+        #[derive(Deserialize)]
+        struct Response {
+            conversation: Option<FollowersNode>,
+        }
+
         #[derive(Deserialize)]
-        #[serde(rename_all = "camelCase")]
-        struct Response {
-            canned_replies: CannedRepliesConnection,
+        struct FollowersNode {
+            followers: AgentConnection,
         }
 
         #[derive(Deserialize)]
-        struct CannedRepliesConnection {
-            nodes: Vec<CannedReply>,
+        struct AgentConnection {
+            nodes: Vec<Agent>,
         }
 
         let query = r#"
-            query CannedReplies($first: Int!) {
-                cannedReplies(first: $first) {
-                    nodes {
-                        id
-                        name
-                        subject
-                        body
+            query Followers($number: Int!) {
+                conversation(number: $number) {
+                    followers {
+                        nodes {
+                            id
+                            email
+                            name
+                        }
                     }
                 }
             }
         "#;
 
-        let variables = json!({ "first": MAX_ITEMS_PER_PAGE as i32 });
-        let response: Response = self.execute_with_retry(query, Some(variables)).await?;
-        Ok(response.canned_replies.nodes)
+        let variables = json!({ "number": number });
+        let response: Response = self.execute(query, Some(variables)).await?;
+        response
+            .conversation
+            .map(|c| c.followers.nodes)
+            .ok_or(GrooveError::ConversationNotFound(number))
     }
 
-    pub async fn reply(&self, conversation_id: &str, body: &str) -> Result<()> {
+    pub async fn follow(&self, conversation_id: &str, agent_id: &str) -> Result<()> {
         #[derive(Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct Response {
-            conversation_reply: MutationResult,
+            conversation_follow: MutationResult,
         }
 
         let query = r#"
-            mutation Reply($input: ConversationReplyInput!) {
-                conversationReply(input: $input) {
+            mutation Follow($input: ConversationFollowInput!) {
+                conversationFollow(input: $input) {
                     errors {
                         message
                     }
@@ -524,66 +1558,97 @@ impl GrooveClient {
         let variables = json!({
             "input": {
                 "conversationId": conversation_id,
-                "body": body
+                "agentId": agent_id
             }
         });
 
-        let response: Response = self.execute_with_retry(query, Some(variables)).await?;
-        response.conversation_reply.into_result()
-    }
-
-    pub async fn close(&self, conversation_id: &str) -> Result<()> {
-        self.update_state(conversation_id, "conversationClose")
-            .await
-    }
-
-    pub async fn open(&self, conversation_id: &str) -> Result<()> {
-        self.update_state(conversation_id, "conversationOpen").await
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response
+            .conversation_follow
+            .into_result(request_id.as_deref())
     }
 
-    async fn update_state(&self, conversation_id: &str, mutation: &str) -> Result<()> {
-        let query = format!(
-            r#"
-            mutation UpdateState($input: ConversationStateInput!) {{
-                {}(input: $input) {{
-                    errors {{
-                        message
-                    }}
-                }}
-            }}
-        "#,
-            mutation
-        );
-
+    pub async fn unfollow(&self, conversation_id: &str, agent_id: &str) -> Result<()> {
         #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
         struct Response {
-            #[serde(flatten)]
-            result: std::collections::HashMap<String, MutationResult>,
+            conversation_unfollow: MutationResult,
         }
 
+        let query = r#"
+            mutation Unfollow($input: ConversationUnfollowInput!) {
+                conversationUnfollow(input: $input) {
+                    errors {
+                        message
+                    }
+                }
+            }
+        "#;
+
         let variables = json!({
             "input": {
-                "conversationId": conversation_id
+                "conversationId": conversation_id,
+                "agentId": agent_id
             }
         });
 
-        let response: Response = self.execute_with_retry(&query, Some(variables)).await?;
-        for (_, result) in response.result {
-            result.into_result()?;
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response
+            .conversation_unfollow
+            .into_result(request_id.as_deref())
+    }
+
+    /// Add an internal note, notifying the agents in `mentioned_agent_ids`
+    pub async fn add_note(
+        &self,
+        conversation_id: &str,
+        body: &str,
+        mentioned_agent_ids: &[String],
+    ) -> Result<()> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            conversation_add_note: MutationResult,
         }
-        Ok(())
+
+        let query = r#"
+            mutation AddNote($input: ConversationAddNoteInput!) {
+                conversationAddNote(input: $input) {
+                    errors {
+                        message
+                    }
+                }
+            }
+        "#;
+
+        let mut input = json!({
+            "conversationId": conversation_id,
+            "body": body
+        });
+        if !mentioned_agent_ids.is_empty() {
+            input["mentionedAgentIds"] = json!(mentioned_agent_ids);
+        }
+        let variables = json!({ "input": input });
+
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response
+            .conversation_add_note
+            .into_result(request_id.as_deref())
     }
 
-    pub async fn snooze(&self, conversation_id: &str, until: &str) -> Result<()> {
+    pub async fn update_note(&self, note_id: &str, body: &str) -> Result<()> {
         #[derive(Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct Response {
-            conversation_snooze: MutationResult,
+            note_update: MutationResult,
         }
 
         let query = r#"
-            mutation Snooze($input: ConversationSnoozeInput!) {
-                conversationSnooze(input: $input) {
+            mutation UpdateNote($input: NoteUpdateInput!) {
+                noteUpdate(input: $input) {
                     errors {
                         message
                     }
@@ -593,25 +1658,26 @@ impl GrooveClient {
 
         let variables = json!({
             "input": {
-                "conversationId": conversation_id,
-                "snoozedUntil": until
+                "noteId": note_id,
+                "body": body
             }
         });
 
-        let response: Response = self.execute_with_retry(query, Some(variables)).await?;
-        response.conversation_snooze.into_result()
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response.note_update.into_result(request_id.as_deref())
     }
 
-    pub async fn assign(&self, conversation_id: &str, agent_id: &str) -> Result<()> {
+    pub async fn delete_note(&self, note_id: &str) -> Result<()> {
         #[derive(Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct Response {
-            conversation_assign: MutationResult,
+            note_delete: MutationResult,
         }
 
         let query = r#"
-            mutation Assign($input: ConversationAssignInput!) {
-                conversationAssign(input: $input) {
+            mutation DeleteNote($input: NoteDeleteInput!) {
+                noteDelete(input: $input) {
                     errors {
                         message
                     }
@@ -621,25 +1687,26 @@ impl GrooveClient {
 
         let variables = json!({
             "input": {
-                "conversationId": conversation_id,
-                "assigneeId": agent_id
+                "noteId": note_id
             }
         });
 
-        let response: Response = self.execute_with_retry(query, Some(variables)).await?;
-        response.conversation_assign.into_result()
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response.note_delete.into_result(request_id.as_deref())
     }
 
-    pub async fn unassign(&self, conversation_id: &str) -> Result<()> {
+    /// Replace the body of a message, e.g. with secrets blanked out
+    pub async fn redact_message(&self, message_id: &str, body: &str) -> Result<()> {
         #[derive(Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct Response {
-            conversation_unassign: MutationResult,
+            message_redact: MutationResult,
         }
 
         let query = r#"
-            mutation Unassign($input: ConversationUnassignInput!) {
-                conversationUnassign(input: $input) {
+            mutation RedactMessage($input: MessageRedactInput!) {
+                messageRedact(input: $input) {
                     errors {
                         message
                     }
@@ -649,24 +1716,27 @@ impl GrooveClient {
 
         let variables = json!({
             "input": {
-                "conversationId": conversation_id
+                "messageId": message_id,
+                "body": body
             }
         });
 
-        let response: Response = self.execute_with_retry(query, Some(variables)).await?;
-        response.conversation_unassign.into_result()
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response.message_redact.into_result(request_id.as_deref())
     }
 
-    pub async fn add_note(&self, conversation_id: &str, body: &str) -> Result<()> {
+    /// Remove a message from its conversation
+    pub async fn delete_message(&self, message_id: &str) -> Result<()> {
         #[derive(Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct Response {
-            conversation_add_note: MutationResult,
+            message_delete: MutationResult,
         }
 
         let query = r#"
-            mutation AddNote($input: ConversationAddNoteInput!) {
-                conversationAddNote(input: $input) {
+            mutation DeleteMessage($input: MessageDeleteInput!) {
+                messageDelete(input: $input) {
                     errors {
                         message
                     }
@@ -676,13 +1746,13 @@ impl GrooveClient {
 
         let variables = json!({
             "input": {
-                "conversationId": conversation_id,
-                "body": body
+                "messageId": message_id
             }
         });
 
-        let response: Response = self.execute_with_retry(query, Some(variables)).await?;
-        response.conversation_add_note.into_result()
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response.message_delete.into_result(request_id.as_deref())
     }
 
     pub async fn tag(&self, conversation_id: &str, tag_ids: Vec<String>) -> Result<()> {
@@ -709,8 +1779,9 @@ impl GrooveClient {
             }
         });
 
-        let response: Response = self.execute_with_retry(query, Some(variables)).await?;
-        response.conversation_tag.into_result()
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response.conversation_tag.into_result(request_id.as_deref())
     }
 
     pub async fn untag(&self, conversation_id: &str, tag_ids: Vec<String>) -> Result<()> {
@@ -737,11 +1808,18 @@ impl GrooveClient {
             }
         });
 
-        let response: Response = self.execute_with_retry(query, Some(variables)).await?;
-        response.conversation_untag.into_result()
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response
+            .conversation_untag
+            .into_result(request_id.as_deref())
     }
 
     pub async fn agents(&self) -> Result<Vec<Agent>> {
+        if let Some(cached) = self.cache_get("agents") {
+            return Ok(cached);
+        }
+
         #[derive(Deserialize)]
         struct Response {
             agents: AgentsConnection,
@@ -765,9 +1843,239 @@ impl GrooveClient {
         "#;
 
         let variables = json!({ "first": MAX_ITEMS_PER_PAGE as i32 });
-        let response: Response = self.execute_with_retry(query, Some(variables)).await?;
+        let response: Response = self.execute(query, Some(variables)).await?;
+        self.cache_put("agents", &response.agents.nodes);
         Ok(response.agents.nodes)
     }
+
+    /// Merge contact `duplicate_id` into `contact_id`: its conversations
+    /// move over and the duplicate is removed
+    pub async fn merge_contacts(&self, contact_id: &str, duplicate_id: &str) -> Result<()> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            contact_merge: MutationResult,
+        }
+
+        let query = r#"
+            mutation MergeContacts($input: ContactMergeInput!) {
+                contactMerge(input: $input) {
+                    errors {
+                        message
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({
+            "input": {
+                "contactId": contact_id,
+                "duplicateContactId": duplicate_id
+            }
+        });
+
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response.contact_merge.into_result(request_id.as_deref())
+    }
+
+    /// Permanently delete a contact
+    pub async fn delete_contact(&self, contact_id: &str) -> Result<()> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            contact_delete: MutationResult,
+        }
+
+        let query = r#"
+            mutation DeleteContact($input: ContactDeleteInput!) {
+                contactDelete(input: $input) {
+                    errors {
+                        message
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({
+            "input": {
+                "contactId": contact_id
+            }
+        });
+
+        let (response, request_id): (Response, _) =
+            self.execute_with_request_id(query, Some(variables)).await?;
+        response.contact_delete.into_result(request_id.as_deref())
+    }
+
+    /// Look up a customer by email address. The API's email filter may also
+    /// match similar addresses, so only a contact whose email is `email`,
+    /// ignoring case, is returned.
+    pub async fn contact_by_email(&self, email: &str) -> Result<Option<Contact>> {
+        #[derive(Deserialize)]
+        struct Response {
+            contacts: ContactsConnection,
+        }
+
+        #[derive(Deserialize)]
+        struct ContactsConnection {
+            nodes: Vec<Contact>,
+        }
+
+        let query = r#"
+            query Contacts($first: Int!, $filter: ContactFilter) {
+                contacts(first: $first, filter: $filter) {
+                    nodes {
+                        id
+                        email
+                        name
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({ "first": EMAIL_LOOKUP_LIMIT, "filter": { "email": email } });
+        let response: Response = self.execute(query, Some(variables)).await?;
+        Ok(response.contacts.nodes.into_iter().find(|contact| {
+            contact
+                .email
+                .as_deref()
+                .is_some_and(|e| e.eq_ignore_ascii_case(email))
+        }))
+    }
+
+    /// A customer's full profile, by contact ID
+    pub async fn contact(&self, id: &str) -> Result<Option<ContactRecord>> {
+        #[derive(Deserialize)]
+        struct Response {
+            node: Option<Node>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(tag = "__typename")]
+        enum Node {
+            Contact(Box<ContactRecord>),
+            #[serde(other)]
+            Other,
+        }
+
+        let query = r#"
+            query ContactRecord($id: ID!) {
+                node(id: $id) {
+                    __typename
+                    ... on Contact {
+                        id
+                        email
+                        name
+                        firstName
+                        lastName
+                        title
+                        companyName
+                        phoneNumber
+                        avatarUrl
+                        createdAt
+                        updatedAt
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({ "id": id });
+        let response: Response = self.execute(query, Some(variables)).await?;
+        match response.node {
+            Some(Node::Contact(record)) => Ok(Some(*record)),
+            Some(Node::Other) | None => Ok(None),
+        }
+    }
+
+    /// Customers whose name or email matches `keywords`
+    pub async fn search_contacts(&self, keywords: &str, first: u32) -> Result<Vec<Contact>> {
+        #[derive(Deserialize)]
+        struct Response {
+            contacts: ContactsConnection,
+        }
+
+        #[derive(Deserialize)]
+        struct ContactsConnection {
+            nodes: Vec<Contact>,
+        }
+
+        let query = r#"
+            query Contacts($first: Int!, $filter: ContactFilter) {
+                contacts(first: $first, filter: $filter) {
+                    nodes {
+                        id
+                        email
+                        name
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({ "first": first, "filter": { "keywords": keywords } });
+        let response: Response = self.execute(query, Some(variables)).await?;
+        Ok(response.contacts.nodes)
+    }
+
+    /// Look up a customer by email address along with their newest `count`
+    /// conversations, in one request
+    pub async fn contact_profile(&self, email: &str, count: u32) -> Result<Option<ContactProfile>> {
+        #[derive(Deserialize)]
+        struct Response {
+            contacts: ContactsConnection,
+        }
+
+        #[derive(Deserialize)]
+        struct ContactsConnection {
+            nodes: Vec<ContactNode>,
+        }
+
+        #[derive(Deserialize)]
+        struct ContactNode {
+            #[serde(flatten)]
+            contact: Contact,
+            conversations: ConversationsConnection,
+        }
+
+        #[derive(Deserialize)]
+        struct ConversationsConnection {
+            nodes: Vec<Conversation>,
+        }
+
+        let query = r#"
+            query ContactProfile($filter: ContactFilter, $conversations: Int!) {
+                contacts(first: 1, filter: $filter) {
+                    nodes {
+                        id
+                        email
+                        name
+                        conversations(first: $conversations, orderBy: { field: CREATED_AT, direction: DESC }) {
+                            nodes {
+                                id
+                                number
+                                subject
+                                state
+                                createdAt
+                                updatedAt
+                            }
+                        }
+                    }
+                }
+            }
+        "#;
+
+        let variables = json!({ "filter": { "email": email }, "conversations": count });
+        let response: Response = self.execute(query, Some(variables)).await?;
+        Ok(response
+            .contacts
+            .nodes
+            .into_iter()
+            .next()
+            .map(|node| ContactProfile {
+                contact: node.contact,
+                conversations: node.conversations.nodes,
+            }))
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
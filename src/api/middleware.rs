@@ -0,0 +1,268 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::future::BoxFuture;
+use reqwest::header::{HeaderMap, AUTHORIZATION, ETAG, IF_NONE_MATCH};
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::{trace, Cache, CachedResponse, RateLimitInfo, RetryPolicy};
+use crate::error::Result;
+
+/// A GraphQL request on its way to the API
+pub struct Request {
+    /// The `{"query": ..., "variables": ...}` document being sent
+    pub body: Value,
+    pub http: reqwest::Request,
+}
+
+impl Request {
+    pub fn query(&self) -> &str {
+        self.body["query"].as_str().unwrap_or_default()
+    }
+
+    /// `Tags` for `query Tags($first: Int!) { ... }`; `None` if anonymous
+    pub fn operation_name(&self) -> Option<&str> {
+        trace::operation_name(self.query())
+    }
+
+    pub fn is_mutation(&self) -> bool {
+        self.query().trim_start().starts_with("mutation")
+    }
+
+    fn try_clone(&self) -> Option<Self> {
+        Some(Self {
+            body: self.body.clone(),
+            http: self.http.try_clone()?,
+        })
+    }
+}
+
+/// An HTTP response with its body read
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+/// One step of the pipeline every request passes through. A middleware may
+/// change the request, call `next` any number of times, or answer without
+/// calling it at all.
+///
+/// ```
+/// use futures::future::BoxFuture;
+/// use groovehq_cli::api::{Middleware, Next, Request, Response};
+///
+/// /// Tag every request for a proxy's access log
+/// struct Tagged;
+///
+/// impl Middleware for Tagged {
+///     fn handle<'a>(
+///         &'a self,
+///         mut request: Request,
+///         next: Next<'a>,
+///     ) -> BoxFuture<'a, groovehq_cli::Result<Response>> {
+///         request
+///             .http
+///             .headers_mut()
+///             .insert("x-caller", "nightly-report".parse().unwrap());
+///         next.run(request)
+///     }
+/// }
+/// ```
+pub trait Middleware: Send + Sync {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>>;
+}
+
+/// The rest of the pipeline after the current middleware
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    client: &'a Client,
+    middleware: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub(super) fn new(client: &'a Client, middleware: &'a [Arc<dyn Middleware>]) -> Self {
+        Self { client, middleware }
+    }
+
+    /// Pass `request` to the next middleware, or send it once none are left
+    pub fn run(self, request: Request) -> BoxFuture<'a, Result<Response>> {
+        match self.middleware.split_first() {
+            Some((first, rest)) => first.handle(request, Next::new(self.client, rest)),
+            None => Box::pin(send(self.client, request)),
+        }
+    }
+}
+
+async fn send(client: &Client, request: Request) -> Result<Response> {
+    let response = client.execute(request.http).await?;
+    Ok(Response {
+        status: response.status(),
+        headers: response.headers().clone(),
+        body: response.text().await?,
+    })
+}
+
+/// Retries rate-limited (HTTP 429) requests, waiting for `Retry-After` or
+/// backing off exponentially. The last 429 is returned once attempts run out.
+pub struct Retry(pub RetryPolicy);
+
+impl Middleware for Retry {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            let policy = self.0;
+            let mut attempts = 0;
+            loop {
+                // Bodies are JSON, so requests can always be cloned
+                let Some(attempt) = request.try_clone() else {
+                    return next.run(request).await;
+                };
+                let response = next.run(attempt).await?;
+                if response.status != StatusCode::TOO_MANY_REQUESTS {
+                    return Ok(response);
+                }
+                attempts += 1;
+                if attempts >= policy.max_attempts {
+                    return Ok(response);
+                }
+                let wait = retry_after(&response.headers)
+                    .map(Duration::from_secs)
+                    .unwrap_or_else(|| policy.initial_backoff * 2u32.saturating_pow(attempts - 1));
+                eprintln!(
+                    "Rate limited. Retrying in {} seconds... (attempt {}/{})",
+                    wait.as_secs_f64(),
+                    attempts,
+                    policy.max_attempts
+                );
+                tokio::time::sleep(wait).await;
+            }
+        })
+    }
+}
+
+/// Seconds to wait before retrying, from a 429's `Retry-After` header
+pub(super) fn retry_after(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get("retry-after")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.parse::<u64>().ok())
+}
+
+/// Revalidates queries with `If-None-Match`, answering from `Cache` when the
+/// API says the response hasn't changed. Mutations pass straight through.
+pub struct ResponseCache(pub Cache);
+
+impl ResponseCache {
+    /// Cache key for a request: endpoint, credentials, query, and variables.
+    /// SHA-256 rather than `DefaultHasher`, whose output may change between
+    /// Rust releases and orphan every cached response.
+    fn key(request: &Request) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(request.http.url().as_str());
+        hasher.update([0]);
+        if let Some(auth) = request.http.headers().get(AUTHORIZATION) {
+            hasher.update(auth.as_bytes());
+        }
+        hasher.update([0]);
+        hasher.update(request.body.to_string());
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+impl Middleware for ResponseCache {
+    fn handle<'a>(
+        &'a self,
+        mut request: Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            if request.is_mutation() {
+                return next.run(request).await;
+            }
+
+            let key = Self::key(&request);
+            let cached = self.0.get_response(&key);
+            if let Some(cached) = &cached {
+                if let Ok(etag) = cached.etag.parse() {
+                    request.http.headers_mut().insert(IF_NONE_MATCH, etag);
+                }
+            }
+
+            let mut response = next.run(request).await?;
+            match cached {
+                Some(cached) if response.status == StatusCode::NOT_MODIFIED => {
+                    response.status = StatusCode::OK;
+                    response.body = cached.body;
+                }
+                _ if response.status.is_success() => {
+                    let etag = response.headers.get(ETAG).and_then(|v| v.to_str().ok());
+                    if let Some(etag) = etag {
+                        let entry = CachedResponse {
+                            etag: etag.to_string(),
+                            body: response.body.clone(),
+                        };
+                        self.0.put_response(&key, &entry);
+                    }
+                }
+                _ => {}
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Records the rate-limit budget reported by each response
+pub struct RateLimitTracker(pub Arc<Mutex<Option<RateLimitInfo>>>);
+
+impl Middleware for RateLimitTracker {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            let response = next.run(request).await?;
+            if let Some(info) = RateLimitInfo::from_headers(&response.headers) {
+                if let Ok(mut rate_limit) = self.0.lock() {
+                    *rate_limit = Some(info);
+                }
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Logs request and response metadata to stderr, with credentials redacted
+pub struct DebugLog;
+
+impl Middleware for DebugLog {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            trace::log(format!(
+                "{} {} ({})",
+                request.http.method(),
+                request.http.url(),
+                request.operation_name().unwrap_or("anonymous")
+            ));
+            for header in trace::redact_headers(request.http.headers()) {
+                trace::log(format!("  {}", header));
+            }
+            trace::log(format!(
+                "  body: {}",
+                trace::truncate_body(&request.body["variables"].to_string())
+            ));
+
+            let started = Instant::now();
+            let response = next.run(request).await?;
+            trace::log(format!("{} in {:?}", response.status, started.elapsed()));
+            for header in trace::redact_headers(&response.headers) {
+                trace::log(format!("  {}", header));
+            }
+            trace::log(format!("  body: {}", trace::truncate_body(&response.body)));
+            Ok(response)
+        })
+    }
+}
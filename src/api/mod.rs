@@ -1,3 +1,17 @@
+mod builder;
+mod cache;
 mod client;
+mod middleware;
+mod rate_limit;
+mod trace;
 
-pub use client::{ConversationsResponse, GrooveClient, MAX_ITEMS_PER_PAGE};
+pub use builder::{GrooveClientBuilder, RetryPolicy, DEFAULT_TIMEOUT, DEFAULT_USER_AGENT};
+pub use cache::{Cache, CachedResponse, DEFAULT_CACHE_TTL};
+pub use client::{
+    ConversationsResponse, GrooveClient, BATCH_LOOKUP_SIZE, BATCH_MUTATION_SIZE, DEFAULT_ENDPOINT,
+    MAX_ITEMS_PER_PAGE, STREAM_PAGE_SIZE,
+};
+pub use middleware::{
+    DebugLog, Middleware, Next, RateLimitTracker, Request, Response, ResponseCache, Retry,
+};
+pub use rate_limit::RateLimitInfo;
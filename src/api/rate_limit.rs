@@ -0,0 +1,115 @@
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+
+const LIMIT_HEADER: &str = "x-ratelimit-limit";
+const REMAINING_HEADER: &str = "x-ratelimit-remaining";
+const RESET_HEADER: &str = "x-ratelimit-reset";
+
+/// Reset values above this are Unix timestamps; smaller ones are seconds from now
+const EPOCH_THRESHOLD: i64 = 1_000_000_000;
+
+/// Rate-limit budget reported by the API on the most recent response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitInfo {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    pub reset_at: Option<DateTime<Utc>>,
+}
+
+impl RateLimitInfo {
+    /// Parse the `X-RateLimit-*` headers; `None` if none are present
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let value = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|s| s.trim().parse::<i64>().ok())
+        };
+
+        let limit = value(LIMIT_HEADER).and_then(|v| u64::try_from(v).ok());
+        let remaining = value(REMAINING_HEADER).and_then(|v| u64::try_from(v).ok());
+        let reset_at = value(RESET_HEADER).and_then(|v| {
+            if v >= EPOCH_THRESHOLD {
+                Utc.timestamp_opt(v, 0).single()
+            } else {
+                Some(Utc::now() + chrono::Duration::seconds(v))
+            }
+        });
+
+        if limit.is_none() && remaining.is_none() && reset_at.is_none() {
+            return None;
+        }
+        Some(Self {
+            limit,
+            remaining,
+            reset_at,
+        })
+    }
+}
+
+impl std::fmt::Display for RateLimitInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.remaining, self.limit) {
+            (Some(remaining), Some(limit)) => write!(f, "{remaining}/{limit} requests remaining")?,
+            (Some(remaining), None) => write!(f, "{remaining} requests remaining")?,
+            (None, Some(limit)) => write!(f, "limit {limit} requests")?,
+            (None, None) => write!(f, "no budget reported")?,
+        }
+        if let Some(reset_at) = self.reset_at {
+            write!(
+                f,
+                ", resets at {}",
+                reset_at.format("%Y-%m-%d %H:%M:%S UTC")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_from_headers() {
+        let info = RateLimitInfo::from_headers(&headers(&[
+            ("x-ratelimit-limit", "500"),
+            ("x-ratelimit-remaining", "498"),
+            ("x-ratelimit-reset", "1700000000"),
+        ]))
+        .unwrap();
+
+        assert_eq!(info.limit, Some(500));
+        assert_eq!(info.remaining, Some(498));
+        assert_eq!(info.reset_at, Utc.timestamp_opt(1_700_000_000, 0).single());
+        assert!(info.to_string().starts_with("498/500 requests remaining"));
+    }
+
+    #[test]
+    fn test_from_headers_relative_reset() {
+        let info = RateLimitInfo::from_headers(&headers(&[("x-ratelimit-reset", "60")])).unwrap();
+        let reset_at = info.reset_at.unwrap();
+        assert!(reset_at > Utc::now());
+        assert!(reset_at <= Utc::now() + chrono::Duration::seconds(60));
+    }
+
+    #[test]
+    fn test_from_headers_missing() {
+        assert_eq!(RateLimitInfo::from_headers(&HeaderMap::new()), None);
+        assert_eq!(
+            RateLimitInfo::from_headers(&headers(&[("x-ratelimit-remaining", "lots")])),
+            None
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use colored::Colorize;
+use reqwest::header::{HeaderMap, AUTHORIZATION};
+
+/// Maximum characters of a request or response body shown by `--debug`
+const MAX_BODY_CHARS: usize = 1000;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Print a `--debug` line to stderr
+pub(crate) fn log(message: impl std::fmt::Display) {
+    eprintln!("{} {}", "[debug]".dimmed(), message);
+}
+
+/// Name of a GraphQL operation, e.g. `Tags` for `query Tags($first: Int!) {...}`
+pub(crate) fn operation_name(query: &str) -> Option<&str> {
+    let rest = query.trim_start();
+    let rest = rest
+        .strip_prefix("query")
+        .or_else(|| rest.strip_prefix("mutation"))?;
+    let name = rest
+        .trim_start()
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .next()?;
+    (!name.is_empty()).then_some(name)
+}
+
+/// Headers as `name: value` lines with credentials replaced
+pub(crate) fn redact_headers(headers: &HeaderMap) -> Vec<String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if name == AUTHORIZATION {
+                REDACTED
+            } else {
+                value.to_str().unwrap_or("<binary>")
+            };
+            format!("{}: {}", name, value)
+        })
+        .collect()
+}
+
+pub(crate) fn truncate_body(body: &str) -> String {
+    let total = body.chars().count();
+    if total <= MAX_BODY_CHARS {
+        body.to_string()
+    } else {
+        let truncated: String = body.chars().take(MAX_BODY_CHARS).collect();
+        format!("{}… ({} more chars)", truncated, total - MAX_BODY_CHARS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderValue, CONTENT_TYPE};
+
+    #[test]
+    fn test_operation_name() {
+        assert_eq!(
+            operation_name("\n  query Tags($first: Int!) { tags { id } }"),
+            Some("Tags")
+        );
+        assert_eq!(
+            operation_name("mutation CloseConversation($id: ID!) {}"),
+            Some("CloseConversation")
+        );
+        assert_eq!(operation_name("query { me { id } }"), None);
+        assert_eq!(operation_name("{ me { id } }"), None);
+    }
+
+    #[test]
+    fn test_redact_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let lines = redact_headers(&headers);
+        assert!(lines.contains(&"authorization: [REDACTED]".to_string()));
+        assert!(lines.contains(&"content-type: application/json".to_string()));
+        assert!(!lines.iter().any(|l| l.contains("secret")));
+    }
+
+    #[test]
+    fn test_truncate_body() {
+        assert_eq!(truncate_body("short"), "short");
+        let long = "x".repeat(MAX_BODY_CHARS + 5);
+        assert!(truncate_body(&long).ends_with("… (5 more chars)"));
+    }
+}
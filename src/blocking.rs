@@ -0,0 +1,133 @@
+//! A synchronous [`GrooveClient`](crate::GrooveClient) for programs that don't use async, such as
+//! build scripts and small utilities. Enabled by the `blocking` feature.
+//!
+//! Each client runs its requests on a private single-threaded runtime, so
+//! callers don't need to start one. Don't use it from inside an async
+//! runtime; use [`crate::GrooveClient`] there instead.
+//!
+//! ```no_run
+//! use groovehq_cli::blocking::GrooveClient;
+//! use groovehq_cli::{ConversationFilter, ConversationState};
+//!
+//! # fn run() -> groovehq_cli::Result<()> {
+//! let client = GrooveClient::new("api-token", None)?;
+//! let filter = ConversationFilter::new().state(ConversationState::Opened);
+//! for conv in client.conversations_iter(&filter) {
+//!     let conv = conv?;
+//!     println!("#{} {}", conv.number, conv.subject.unwrap_or_default());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::pin::Pin;
+
+use futures::{Stream, StreamExt};
+use tokio::runtime::Runtime;
+
+use crate::api::{self, ConversationsResponse, GrooveClientBuilder, RateLimitInfo};
+use crate::error::Result;
+use crate::types::*;
+
+pub struct GrooveClient {
+    inner: api::GrooveClient,
+    runtime: Runtime,
+}
+
+/// Blocking versions of the async client's methods, with the same arguments
+macro_rules! blocking {
+    ($($name:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            #[doc = concat!("See [`GrooveClient::", stringify!($name), "`](crate::GrooveClient::", stringify!($name), ")")]
+            pub fn $name(&self, $($arg: $ty),*) -> Result<$ret> {
+                self.runtime.block_on(self.inner.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+impl GrooveClient {
+    /// Create a client with the default settings; see
+    /// [`GrooveClient::new`](crate::GrooveClient::new)
+    pub fn new(token: &str, endpoint: Option<&str>) -> Result<Self> {
+        Self::from_async(api::GrooveClient::new(token, endpoint)?)
+    }
+
+    /// Wrap an async client, e.g. one made with
+    /// [`GrooveClient::builder`](crate::GrooveClient::builder)
+    pub fn from_async(inner: api::GrooveClient) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// Configure a client as with the async builder, then call
+    /// [`build_blocking`](GrooveClientBuilder::build_blocking)
+    pub fn builder(token: impl Into<String>) -> GrooveClientBuilder {
+        GrooveClientBuilder::new(token)
+    }
+
+    pub fn rate_limit(&self) -> Option<RateLimitInfo> {
+        self.inner.rate_limit()
+    }
+
+    pub fn last_request_id(&self) -> Option<String> {
+        self.inner.last_request_id()
+    }
+
+    /// Every conversation matching `filter`, fetching pages as the iterator
+    /// advances; see
+    /// [`conversations_stream`](crate::GrooveClient::conversations_stream)
+    pub fn conversations_iter<'a>(
+        &'a self,
+        filter: &'a ConversationFilter,
+    ) -> impl Iterator<Item = Result<Conversation>> + 'a {
+        let mut stream: Pin<Box<dyn Stream<Item = Result<Conversation>> + 'a>> =
+            Box::pin(self.inner.conversations_stream(filter));
+        std::iter::from_fn(move || self.runtime.block_on(stream.next()))
+    }
+
+    blocking! {
+        me() -> CurrentAgent;
+        conversations(filter: &ConversationFilter, first: Option<u32>, after: Option<String>) -> ConversationsResponse;
+        conversation(number: i64) -> Conversation;
+        conversations_by_number(numbers: &[i64]) -> Vec<Option<Conversation>>;
+        messages(conversation_id: &str, page: &MessagePage) -> (Vec<Message>, PageInfo);
+        all_messages(conversation_id: &str) -> Vec<Message>;
+        message(id: &str) -> Message;
+        notes(conversation_id: &str, first: Option<i32>) -> Vec<Note>;
+        mentions(first: u32) -> Vec<Mention>;
+        folders() -> Vec<Folder>;
+        tags() -> Vec<Tag>;
+        agents() -> Vec<Agent>;
+        canned_replies() -> Vec<CannedReply>;
+        rules() -> Vec<Rule>;
+        followers(number: i64) -> Vec<Agent>;
+        contact_by_email(email: &str) -> Option<Contact>;
+        reply(conversation_id: &str, body: &str) -> ();
+        reply_with_subject(conversation_id: &str, body: &str, subject: &str) -> ();
+        close(conversation_id: &str) -> ();
+        open(conversation_id: &str) -> ();
+        close_many(conversation_ids: &[&str]) -> Vec<Result<()>>;
+        open_many(conversation_ids: &[&str]) -> Vec<Result<()>>;
+        snooze(conversation_id: &str, until: &str) -> ();
+        assign(conversation_id: &str, agent_id: &str) -> ();
+        unassign(conversation_id: &str) -> ();
+        follow(conversation_id: &str, agent_id: &str) -> ();
+        unfollow(conversation_id: &str, agent_id: &str) -> ();
+        add_note(conversation_id: &str, body: &str, mentioned_agent_ids: &[String]) -> ();
+        update_note(note_id: &str, body: &str) -> ();
+        delete_note(note_id: &str) -> ();
+        tag(conversation_id: &str, tag_ids: Vec<String>) -> ();
+        untag(conversation_id: &str, tag_ids: Vec<String>) -> ();
+        set_rule_enabled(rule_id: &str, enabled: bool) -> ();
+    }
+}
+
+impl GrooveClientBuilder {
+    /// Build a [`blocking::GrooveClient`](GrooveClient)
+    pub fn build_blocking(self) -> Result<GrooveClient> {
+        GrooveClient::from_async(self.build()?)
+    }
+}
@@ -0,0 +1,222 @@
+use crate::cli::Cli;
+use crate::error::{GrooveError, Result};
+use clap::CommandFactory;
+use std::collections::HashMap;
+
+/// Maximum number of nested alias expansions before assuming a loop
+const MAX_ALIAS_DEPTH: usize = 10;
+
+/// Result of expanding user-defined aliases in the command line
+#[derive(Debug, PartialEq, Eq)]
+pub enum AliasExpansion {
+    /// Arguments to hand to clap (unchanged if no alias matched)
+    Args(Vec<String>),
+    /// A `!`-prefixed alias: shell command to run with the remaining arguments
+    Shell { command: String, args: Vec<String> },
+}
+
+/// Expand a configured alias in the subcommand position, git-style.
+///
+/// Built-in subcommands always win over aliases of the same name. Alias values
+/// are split with shell quoting rules, and any arguments after the alias are
+/// appended to the expansion.
+pub fn expand_aliases(
+    args: Vec<String>,
+    aliases: &HashMap<String, String>,
+) -> Result<AliasExpansion> {
+    let mut args = args;
+    let mut seen: Vec<String> = Vec::new();
+
+    loop {
+        let Some(pos) = subcommand_position(&args) else {
+            return Ok(AliasExpansion::Args(args));
+        };
+
+        let name = args[pos].clone();
+        if Cli::command().find_subcommand(&name).is_some() {
+            return Ok(AliasExpansion::Args(args));
+        }
+        let Some(value) = aliases.get(&name) else {
+            return Ok(AliasExpansion::Args(args));
+        };
+
+        if seen.contains(&name) || seen.len() >= MAX_ALIAS_DEPTH {
+            seen.push(name);
+            return Err(GrooveError::Config(format!(
+                "Alias loop detected: {}",
+                seen.join(" -> ")
+            )));
+        }
+        seen.push(name.clone());
+
+        if let Some(command) = value.strip_prefix('!') {
+            return Ok(AliasExpansion::Shell {
+                command: command.to_string(),
+                args: args[pos + 1..].to_vec(),
+            });
+        }
+
+        let expanded = shlex::split(value)
+            .filter(|words| !words.is_empty())
+            .ok_or_else(|| GrooveError::Config(format!("Invalid alias '{}': {}", name, value)))?;
+
+        args.splice(pos..=pos, expanded);
+    }
+}
+
+/// Index of the first argument that is not a global option or its value
+pub(crate) fn subcommand_position(args: &[String]) -> Option<usize> {
+    let command = Cli::command();
+    let takes_value = |flag: &str| {
+        command.get_arguments().any(|arg| {
+            arg.get_action().takes_values()
+                && (arg.get_long().is_some_and(|l| flag == format!("--{}", l))
+                    || arg.get_short().is_some_and(|s| flag == format!("-{}", s)))
+        })
+    };
+
+    let mut i = 1;
+    while i < args.len() {
+        let arg = &args[i];
+        if arg == "--" {
+            return None;
+        }
+        if !arg.starts_with('-') || arg == "-" {
+            return Some(i);
+        }
+        if !arg.contains('=') && takes_value(arg) {
+            i += 1;
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &[&str]) -> Vec<String> {
+        s.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn aliases(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_expand_simple_alias() {
+        let result = expand_aliases(
+            args(&["groove", "ls", "--status", "open"]),
+            &aliases(&[("ls", "conversation list")]),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            AliasExpansion::Args(args(&[
+                "groove",
+                "conversation",
+                "list",
+                "--status",
+                "open"
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_expand_after_global_options() {
+        let result = expand_aliases(
+            args(&["groove", "--format", "json", "--quiet", "ls"]),
+            &aliases(&[("ls", "conversation list")]),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            AliasExpansion::Args(args(&[
+                "groove",
+                "--format",
+                "json",
+                "--quiet",
+                "conversation",
+                "list"
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_expand_quoted_alias() {
+        let result = expand_aliases(
+            args(&["groove", "billing"]),
+            &aliases(&[("billing", "conversation list -q 'billing issue'")]),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            AliasExpansion::Args(args(&[
+                "groove",
+                "conversation",
+                "list",
+                "-q",
+                "billing issue"
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_builtin_wins_over_alias() {
+        let result =
+            expand_aliases(args(&["groove", "me"]), &aliases(&[("me", "tag list")])).unwrap();
+        assert_eq!(result, AliasExpansion::Args(args(&["groove", "me"])));
+    }
+
+    #[test]
+    fn test_nested_alias() {
+        let result = expand_aliases(
+            args(&["groove", "o"]),
+            &aliases(&[("o", "ls --status opened"), ("ls", "conversation list")]),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            AliasExpansion::Args(args(&[
+                "groove",
+                "conversation",
+                "list",
+                "--status",
+                "opened"
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_alias_loop() {
+        let result = expand_aliases(args(&["groove", "a"]), &aliases(&[("a", "b"), ("b", "a")]));
+        assert!(result.unwrap_err().to_string().contains("Alias loop"));
+    }
+
+    #[test]
+    fn test_shell_alias() {
+        let result = expand_aliases(
+            args(&["groove", "hello", "world"]),
+            &aliases(&[("hello", "!echo hello")]),
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            AliasExpansion::Shell {
+                command: "echo hello".to_string(),
+                args: args(&["world"]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_no_alias() {
+        let input = args(&["groove", "conversation", "list"]);
+        let result = expand_aliases(input.clone(), &HashMap::new()).unwrap();
+        assert_eq!(result, AliasExpansion::Args(input));
+    }
+}
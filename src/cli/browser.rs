@@ -0,0 +1,41 @@
+use crate::error::{GrooveError, Result};
+use std::process::{Command, Stdio};
+
+/// Open `url` in the default browser with `xdg-open` (Linux and BSD),
+/// `open` (macOS), or `start` (Windows)
+pub fn open_in_browser(url: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("open");
+        command.arg(url);
+        command
+    } else if cfg!(windows) {
+        // The empty argument is the window title `start` expects first
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]).arg(url);
+        command
+    } else {
+        let mut command = Command::new("xdg-open");
+        command.arg(url);
+        command
+    };
+
+    let status = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| {
+            GrooveError::Browser(format!(
+                "{}: {}",
+                command.get_program().to_string_lossy(),
+                e
+            ))
+        })?;
+    if !status.success() {
+        return Err(GrooveError::Browser(format!(
+            "{} exited with {}",
+            command.get_program().to_string_lossy(),
+            status
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,241 @@
+//! User-facing strings by language. The language comes from `LC_ALL`,
+//! `LC_MESSAGES`, or `LANG`, and strings without a translation fall back to
+//! English.
+
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// Language strings are printed in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+}
+
+impl Locale {
+    /// The locale named by the first non-empty of `LC_ALL`, `LC_MESSAGES`,
+    /// and `LANG`, as POSIX orders them; English if none is set or known
+    pub fn from_env() -> Locale {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Locale::parse(&value))
+            .unwrap_or_default()
+    }
+
+    /// The locale of a tag like `de_DE.UTF-8`, `de-AT`, or `en`
+    pub fn parse(tag: &str) -> Option<Locale> {
+        let language = tag.split(['_', '-', '.', '@']).next()?;
+        match language.to_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Locale::En),
+            "de" => Some(Locale::De),
+            _ => None,
+        }
+    }
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// Print strings in `locale` for the rest of the process. Only the first call
+/// has an effect.
+pub fn set_locale(locale: Locale) {
+    let _ = LOCALE.set(locale);
+}
+
+fn locale() -> Locale {
+    *LOCALE.get_or_init(Locale::default)
+}
+
+/// A user-facing string. `{0}`, `{1}`, … are filled in by [`tr`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    // Prefixes
+    Error,
+    CausedBy,
+    Warning,
+
+    // Prompts and empty results
+    Aborted,
+    NoMatches,
+    NoResults,
+    NothingToUndo,
+
+    // Conversation actions: {0} is the conversation number
+    Closed,
+    Opened,
+    /// {1}: when it wakes up
+    Snoozed,
+    /// {1}: the agent
+    Assigned,
+    Unassigned,
+    /// {1}: the new contact
+    ContactChanged,
+    TagsAdded,
+    TagsRemoved,
+    TagsCleared,
+    NoTags,
+    NoteAdded,
+    ReplySent,
+    Following,
+    /// {1}: the agent
+    AgentFollowing,
+    Unfollowed,
+    /// {1}: the agent
+    AgentUnfollowed,
+
+    // Conversation table headers
+    HeaderStatus,
+    HeaderStatusIcon,
+    HeaderSubject,
+    HeaderFrom,
+    HeaderAssignee,
+    HeaderTags,
+    HeaderChannel,
+    HeaderMessages,
+    HeaderCreated,
+    HeaderUpdated,
+    HeaderSnoozedUntil,
+    HeaderWaiting,
+    HeaderIdle,
+    HeaderMatch,
+}
+
+impl Msg {
+    /// The string in the current locale, placeholders unfilled
+    pub fn text(self) -> &'static str {
+        self.text_in(locale())
+    }
+
+    /// The string in `locale`, or in English if it has no translation
+    pub fn text_in(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => english(self),
+            Locale::De => german(self).unwrap_or_else(|| english(self)),
+        }
+    }
+}
+
+/// `msg` in the current locale with `{0}`, `{1}`, … replaced by `args`
+pub fn tr(msg: Msg, args: &[&dyn Display]) -> String {
+    fill(msg.text(), args)
+}
+
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    args.iter()
+        .enumerate()
+        .fold(template.to_string(), |text, (i, arg)| {
+            text.replace(&format!("{{{}}}", i), &arg.to_string())
+        })
+}
+
+fn english(msg: Msg) -> &'static str {
+    match msg {
+        Msg::Error => "Error: {0}",
+        Msg::CausedBy => "Caused by: {0}",
+        Msg::Warning => "Warning:",
+        Msg::Aborted => "Aborted.",
+        Msg::NoMatches => "No conversations match",
+        Msg::NoResults => "Nothing found",
+        Msg::NothingToUndo => "Nothing to undo",
+        Msg::Closed => "Closed conversation #{0}",
+        Msg::Opened => "Opened conversation #{0}",
+        Msg::Snoozed => "Snoozed conversation #{0} until {1}",
+        Msg::Assigned => "Assigned conversation #{0} to {1}",
+        Msg::Unassigned => "Unassigned conversation #{0}",
+        Msg::ContactChanged => "Moved conversation #{0} to {1}",
+        Msg::TagsAdded => "Added tags to conversation #{0}",
+        Msg::TagsRemoved => "Removed tags from conversation #{0}",
+        Msg::TagsCleared => "Cleared all tags from conversation #{0}",
+        Msg::NoTags => "Conversation #{0} has no tags",
+        Msg::NoteAdded => "Note added to conversation #{0}",
+        Msg::ReplySent => "Reply sent to conversation #{0}",
+        Msg::Following => "Following conversation #{0}",
+        Msg::AgentFollowing => "{1} is now following conversation #{0}",
+        Msg::Unfollowed => "Unfollowed conversation #{0}",
+        Msg::AgentUnfollowed => "{1} is no longer following conversation #{0}",
+        Msg::HeaderStatus => "Status",
+        Msg::HeaderStatusIcon => "St",
+        Msg::HeaderSubject => "Subject",
+        Msg::HeaderFrom => "From",
+        Msg::HeaderAssignee => "Assignee",
+        Msg::HeaderTags => "Tags",
+        Msg::HeaderChannel => "Channel",
+        Msg::HeaderMessages => "Msgs",
+        Msg::HeaderCreated => "Created",
+        Msg::HeaderUpdated => "Updated",
+        Msg::HeaderSnoozedUntil => "Snoozed until",
+        Msg::HeaderWaiting => "Waiting",
+        Msg::HeaderIdle => "Idle",
+        Msg::HeaderMatch => "Match",
+    }
+}
+
+fn german(msg: Msg) -> Option<&'static str> {
+    Some(match msg {
+        Msg::Error => "Fehler: {0}",
+        Msg::CausedBy => "Ursache: {0}",
+        Msg::Warning => "Warnung:",
+        Msg::Aborted => "Abgebrochen.",
+        Msg::NoMatches => "Keine passenden Unterhaltungen",
+        Msg::NoResults => "Nichts gefunden",
+        Msg::NothingToUndo => "Nichts rückgängig zu machen",
+        Msg::Closed => "Unterhaltung #{0} geschlossen",
+        Msg::Opened => "Unterhaltung #{0} wieder geöffnet",
+        Msg::Snoozed => "Unterhaltung #{0} zurückgestellt bis {1}",
+        Msg::Assigned => "Unterhaltung #{0} an {1} zugewiesen",
+        Msg::Unassigned => "Zuweisung von Unterhaltung #{0} aufgehoben",
+        Msg::ContactChanged => "Unterhaltung #{0} zu {1} verschoben",
+        Msg::TagsAdded => "Tags zu Unterhaltung #{0} hinzugefügt",
+        Msg::TagsRemoved => "Tags von Unterhaltung #{0} entfernt",
+        Msg::TagsCleared => "Alle Tags von Unterhaltung #{0} entfernt",
+        Msg::NoTags => "Unterhaltung #{0} hat keine Tags",
+        Msg::NoteAdded => "Notiz zu Unterhaltung #{0} hinzugefügt",
+        Msg::ReplySent => "Antwort an Unterhaltung #{0} gesendet",
+        Msg::Following => "Sie folgen Unterhaltung #{0}",
+        Msg::AgentFollowing => "{1} folgt jetzt Unterhaltung #{0}",
+        Msg::Unfollowed => "Sie folgen Unterhaltung #{0} nicht mehr",
+        Msg::AgentUnfollowed => "{1} folgt Unterhaltung #{0} nicht mehr",
+        Msg::HeaderSubject => "Betreff",
+        Msg::HeaderFrom => "Von",
+        Msg::HeaderAssignee => "Zuständig",
+        Msg::HeaderChannel => "Kanal",
+        Msg::HeaderMessages => "Nachr.",
+        Msg::HeaderCreated => "Erstellt",
+        Msg::HeaderUpdated => "Aktualisiert",
+        Msg::HeaderSnoozedUntil => "Zurückgestellt bis",
+        Msg::HeaderWaiting => "Wartet",
+        Msg::HeaderIdle => "Inaktiv",
+        Msg::HeaderMatch => "Treffer",
+        // Same as in English
+        Msg::HeaderStatus | Msg::HeaderStatusIcon | Msg::HeaderTags => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_parse() {
+        assert_eq!(Locale::parse("de_DE.UTF-8"), Some(Locale::De));
+        assert_eq!(Locale::parse("de-AT"), Some(Locale::De));
+        assert_eq!(Locale::parse("C.UTF-8"), Some(Locale::En));
+        assert_eq!(Locale::parse("en_GB"), Some(Locale::En));
+        assert_eq!(Locale::parse("fr_FR.UTF-8"), None);
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        assert_eq!(
+            fill(Msg::Assigned.text_in(Locale::En), &[&123, &"Alex"]),
+            "Assigned conversation #123 to Alex"
+        );
+        assert_eq!(
+            fill(Msg::AgentFollowing.text_in(Locale::De), &[&123, &"Alex"]),
+            "Alex folgt jetzt Unterhaltung #123"
+        );
+        assert_eq!(Msg::HeaderTags.text_in(Locale::De), "Tags");
+    }
+}
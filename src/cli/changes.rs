@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use colored::Colorize;
+
+use crate::types::{Conversation, ConversationState};
+
+/// The state and assignee of each conversation in one refresh of a watched
+/// list
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot(HashMap<i64, (ConversationState, Option<String>)>);
+
+impl Snapshot {
+    pub fn of(conversations: &[Conversation]) -> Self {
+        Snapshot(
+            conversations
+                .iter()
+                .map(|c| {
+                    (
+                        c.number,
+                        (c.state, c.assigned.as_ref().map(|a| a.id.clone())),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// What differs in `conversations` from this snapshot
+    pub fn changes(&self, conversations: &[Conversation]) -> Changes {
+        let mut changes = Changes::default();
+        for conv in conversations {
+            let change = match self.0.get(&conv.number) {
+                None => Change {
+                    arrived: true,
+                    ..Change::default()
+                },
+                Some((state, assignee)) => Change {
+                    arrived: false,
+                    state: *state != conv.state,
+                    assignee: *assignee != conv.assigned.as_ref().map(|a| a.id.clone()),
+                },
+            };
+            if change != Change::default() {
+                changes.conversations.insert(conv.number, change);
+            }
+        }
+        changes.departed = self
+            .0
+            .keys()
+            .filter(|number| !conversations.iter().any(|c| c.number == **number))
+            .count();
+        changes
+    }
+}
+
+/// How one conversation changed between refreshes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Change {
+    /// Not in the previous refresh
+    pub arrived: bool,
+    pub state: bool,
+    pub assignee: bool,
+}
+
+/// Differences between two refreshes of a watched list
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Changes {
+    conversations: HashMap<i64, Change>,
+    /// Conversations in the previous refresh but not this one
+    departed: usize,
+}
+
+impl Changes {
+    pub fn get(&self, number: i64) -> Option<&Change> {
+        self.conversations.get(&number)
+    }
+
+    /// One line counting each kind of change in its highlight color, or
+    /// `None` if nothing changed
+    pub fn summary(&self) -> Option<String> {
+        let count = |f: fn(&Change) -> bool| self.conversations.values().filter(|c| f(c)).count();
+        let mut parts = Vec::new();
+        let arrived = count(|c| c.arrived);
+        if arrived > 0 {
+            parts.push(highlight_arrived(&format!("{} new", arrived)));
+        }
+        let state = count(|c| c.state);
+        if state > 0 {
+            parts.push(highlight_state(&format!("{} changed state", state)));
+        }
+        let assignee = count(|c| c.assignee);
+        if assignee > 0 {
+            parts.push(highlight_assignee(&format!("{} reassigned", assignee)));
+        }
+        if self.departed > 0 {
+            parts.push(
+                format!("{} left the list", self.departed)
+                    .dimmed()
+                    .to_string(),
+            );
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+pub(crate) fn highlight_arrived(text: &str) -> String {
+    text.black().on_green().to_string()
+}
+
+pub(crate) fn highlight_state(text: &str) -> String {
+    text.bold().reversed().to_string()
+}
+
+pub(crate) fn highlight_assignee(text: &str) -> String {
+    text.black().on_yellow().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation(number: i64, state: ConversationState, assignee: Option<&str>) -> Conversation {
+        Conversation::builder(number)
+            .state(state)
+            .assigned(assignee)
+            .build()
+    }
+
+    #[test]
+    fn test_changes_between_refreshes() {
+        colored::control::set_override(false);
+        let before = Snapshot::of(&[
+            conversation(1, ConversationState::Unread, None),
+            conversation(2, ConversationState::Opened, None),
+            conversation(3, ConversationState::Opened, Some("a1")),
+        ]);
+        let after = [
+            conversation(1, ConversationState::Opened, None),
+            conversation(2, ConversationState::Opened, Some("a1")),
+            conversation(4, ConversationState::Unread, None),
+        ];
+        let changes = before.changes(&after);
+        let change = |number| changes.get(number).copied().unwrap_or_default();
+        assert!(change(1).state && !change(1).assignee);
+        assert!(change(2).assignee && !change(2).state);
+        assert!(change(4).arrived);
+        assert_eq!(
+            changes.summary().as_deref(),
+            Some("1 new, 1 changed state, 1 reassigned, 1 left the list")
+        );
+
+        assert_eq!(Snapshot::of(&after).changes(&after).summary(), None);
+    }
+}
@@ -0,0 +1,10 @@
+use crate::error::{GrooveError, Result};
+
+/// Place text on the system clipboard
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| GrooveError::Clipboard(e.to_string()))?;
+    clipboard
+        .set_text(text)
+        .map_err(|e| GrooveError::Clipboard(e.to_string()))
+}
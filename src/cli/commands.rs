@@ -1,6 +1,14 @@
-use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap::builder::RangedU64ValueParser;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::{generate, Shell};
 
+use super::complete::dynamic_completions;
+use super::numbers::{expand_numbers, NumberList};
+use crate::export::ExportFormat;
+use crate::types::{ConversationSort, ConversationState};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
 #[derive(Parser)]
 #[command(name = "groove")]
 #[command(
@@ -22,14 +30,38 @@ pub struct Cli {
     #[arg(long, global = true, hide_env_values = true)]
     pub token: Option<String>,
 
+    /// GraphQL API endpoint (overrides config file), e.g. a staging or mock server
+    #[arg(long, global = true, env = "GROOVEHQ_API_ENDPOINT", value_name = "URL")]
+    pub endpoint: Option<String>,
+
+    /// Config file to use instead of the default location
+    #[arg(long, global = true, env = "GROOVE_CONFIG", value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Configuration profile to use (from [profiles.<name>] in config)
+    #[arg(long, global = true, env = "GROOVE_PROFILE")]
+    pub profile: Option<String>,
+
+    /// Bypass cached tags, folders, agents, and canned replies
+    #[arg(long, global = true)]
+    pub refresh: bool,
+
     /// Suppress success messages (useful for scripting)
-    #[arg(long, short, global = true)]
+    #[arg(long, global = true)]
     pub quiet: bool,
 
     /// Show detailed error information
     #[arg(long, short, global = true)]
     pub verbose: bool,
 
+    /// Log HTTP requests and responses to stderr (credentials are redacted)
+    #[arg(long, global = true)]
+    pub debug: bool,
+
+    /// Print dates as RFC 3339 timestamps in UTC instead of relative times
+    #[arg(long, global = true)]
+    pub iso_dates: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -63,6 +95,17 @@ pub enum Commands {
         action: TagAction,
     },
 
+    /// Manage customers
+    #[command(after_help = "EXAMPLES:
+    groove contact view jane@customer.com
+    groove contact merge jane@customer.com jane.doe@oldmail.example
+    groove contact export jane@customer.com --dir ./dsar-jane
+    groove contact delete jane@customer.com --confirm")]
+    Contact {
+        #[command(subcommand)]
+        action: ContactAction,
+    },
+
     /// List canned replies
     #[command(alias = "canned", after_help = "EXAMPLES:
     groove canned-replies list
@@ -72,6 +115,326 @@ pub enum Commands {
         action: CannedRepliesAction,
     },
 
+    /// Keep personal reply snippets, separate from the shared canned replies
+    #[command(after_help = "EXAMPLES:
+    groove snippet add apology \"Sorry for the slow reply!\"
+    groove snippet list
+    groove snippet use sig
+    groove conversation reply 12345 \"Fixed!\" --snippet sig")]
+    Snippet {
+        #[command(subcommand)]
+        action: SnippetAction,
+    },
+
+    /// Audit and toggle automation rules
+    #[command(alias = "rules", after_help = "EXAMPLES:
+    groove rule list
+    groove rule show \"Tag refund requests\"
+    groove rule disable \"Close spam\"")]
+    Rule {
+        #[command(subcommand)]
+        action: RuleAction,
+    },
+
+    /// Show individual messages
+    #[command(alias = "msg", after_help = "EXAMPLES:
+    groove message view msg_abc123")]
+    Message {
+        #[command(subcommand)]
+        action: MessageAction,
+    },
+
+    /// Edit or delete private notes
+    #[command(after_help = "EXAMPLES:
+    groove conversation notes 12345
+    groove note edit note_abc123 \"Corrected note\"
+    groove note delete note_abc123")]
+    Note {
+        #[command(subcommand)]
+        action: NoteAction,
+    },
+
+    /// Run a macro defined in the [macros] section of the config
+    #[command(after_help = "EXAMPLES:
+    groove macro resolve 12345
+    groove macro resolve 12345 12346 12347
+    groove macro resolve 12340-12350")]
+    Macro {
+        /// Macro name
+        name: String,
+
+        /// Conversation number(s), ranges like 1200-1250, or comma lists
+        #[arg(required = true)]
+        numbers: Vec<NumberList>,
+    },
+
+    /// Revert the most recent conversation changes made from this machine
+    #[command(after_help = "EXAMPLES:
+    groove undo
+    groove undo --steps 3
+    groove undo --dry-run")]
+    Undo {
+        /// Number of commands to undo, most recent first
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+
+        /// Show what would be reverted without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Set a private reminder to follow up on a conversation, without
+    /// snoozing it
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true, after_help = "EXAMPLES:
+    groove remind 12345 2d \"check deploy\"
+    groove remind 12345 2024-06-03
+    groove remind list
+    groove remind done 3
+
+Reminders are stored on this machine. `groove watch` announces them when
+they come due.")]
+    Remind {
+        #[command(subcommand)]
+        action: Option<RemindAction>,
+
+        /// Conversation number
+        #[arg(required = true)]
+        number: Option<i64>,
+
+        /// When to be reminded: a delay (30m, 12h, 2d, 1w), a day with an
+        /// optional time ("tomorrow 9am", "fri 17:00", 2024-06-03; 09:00 by
+        /// default), or an RFC 3339 timestamp
+        #[arg(required = true)]
+        when: Option<String>,
+
+        /// What to follow up on
+        note: Option<String>,
+    },
+
+    /// Manage replies queued with `conversation reply --send-at`
+    #[command(after_help = "EXAMPLES:
+    groove outbox list
+    groove outbox cancel 2
+    groove outbox flush
+
+Queued replies are sent by `groove outbox flush` (e.g. from cron) or by a
+running `groove watch`:
+    */5 * * * * groove outbox flush --quiet")]
+    Outbox {
+        #[command(subcommand)]
+        action: OutboxAction,
+    },
+
+    /// Mirror conversations, messages, tags, and contacts into a local database
+    #[command(after_help = "EXAMPLES:
+    groove sync
+    groove sync --full
+    groove sync --db ./groove.db
+    groove sync --maildir ~/Mail/groove")]
+    Sync {
+        /// Database path (default: groove.db in the data directory)
+        #[arg(long)]
+        db: Option<PathBuf>,
+
+        /// Mirror into this Maildir (one mail per message) instead of the database
+        #[arg(long, value_name = "DIR", conflicts_with = "db")]
+        maildir: Option<PathBuf>,
+
+        /// List every conversation and refetch all messages, not just
+        /// conversations updated since the last sync
+        #[arg(long)]
+        full: bool,
+    },
+
+    /// Search conversations, contacts, canned replies, and knowledge base
+    /// articles at once
+    #[command(after_help = "EXAMPLES:
+    groove search refund
+    groove search \"tag:urgent assignee:me state:open billing error\"
+    groove search \"folder:billing older:3d refund\"
+    groove search 'assignee:\"Jane Doe\" tag:vip tag:escalated'
+
+Results are grouped by kind. Filters narrow conversations: state: (or
+status:), folder:, assignee: (email, name, or me), older: (not updated for
+this long, e.g. 3d), and tag:, which can repeat. Other words are searched for
+everywhere. Quote values with spaces. Save a query as an alias to reuse it:
+
+    [aliases]
+    urgent = \"search 'tag:urgent state:open'\"")]
+    Search {
+        /// Filters and keywords, e.g. \"tag:urgent state:open billing\"
+        query: String,
+
+        /// Number of results of each kind to show (default: 25, or from config)
+        #[arg(short = 'n', long)]
+        limit: Option<u32>,
+
+        #[command(flatten)]
+        table: TableArgs,
+    },
+
+    /// Full-text search over data mirrored by `groove sync`
+    #[command(after_help = "EXAMPLES:
+    groove search-local invoice
+    groove search-local \"refund policy\"
+    groove search-local \"password NOT reset\" --limit 5
+    groove search-local 'bill*' -o json")]
+    SearchLocal {
+        /// Search query (FTS5 syntax: \"phrases\", AND/OR/NOT, prefix*)
+        query: String,
+
+        /// Database path (default: groove.db in the data directory)
+        #[arg(long)]
+        db: Option<PathBuf>,
+
+        /// Maximum number of matches to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: u32,
+    },
+
+    /// Back up matching conversations, with all messages, to a directory
+    #[command(after_help = "EXAMPLES:
+    groove export --dir ./backup
+    groove export --folder inbox --since 2023-01-01 --dir ./backup
+    groove export --status closed --until 2023-12-31 --to md --dir ./archive")]
+    Export {
+        /// Directory to write to (created if missing)
+        #[arg(long, value_name = "DIR")]
+        dir: PathBuf,
+
+        /// File format: json, md, txt, html, eml, mbox, or pdf
+        #[arg(long, value_name = "FORMAT", default_value = "json")]
+        to: ExportFormat,
+
+        /// Filter by status (opened, closed, snoozed, unread)
+        #[arg(short, long)]
+        status: Option<ConversationState>,
+
+        /// Filter by folder name or ID
+        #[arg(short, long)]
+        folder: Option<String>,
+
+        /// Only conversations created on or after this date (e.g. 30d, 2023-01-01)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only conversations created on or before this date (e.g. 2023-12-31)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Apply --since/--until to the last update instead of creation
+        #[arg(long)]
+        updated: bool,
+
+        /// Export at most this many conversations
+        #[arg(short = 'n', long)]
+        limit: Option<u32>,
+    },
+
+    /// List conversations assigned to you
+    #[command(after_help = "EXAMPLES:
+    groove my
+    groove my snoozed
+    groove my all --limit 50")]
+    My {
+        /// Which of your conversations to show
+        #[arg(value_enum, default_value_t = MyScope::Open)]
+        scope: MyScope,
+
+        /// Number of results to show (default: 25, or from config)
+        #[arg(short = 'n', long)]
+        limit: Option<u32>,
+
+        /// Cursor for pagination
+        #[arg(long)]
+        after: Option<String>,
+
+        #[command(flatten)]
+        table: TableArgs,
+    },
+
+    /// Open conversations in the web app, one browser tab each
+    #[command(after_help = "EXAMPLES:
+    groove open 12345
+    groove open 1200-1210 1300
+    groove open --status open --assignee me
+    groove open --folder billing --tag urgent --limit 5
+
+Requires web_url in the config. Asks before opening more than 10 tabs.")]
+    Open {
+        /// Conversation number(s), ranges like 1200-1250, or comma lists
+        #[arg(required_unless_present = "BulkFilter", conflicts_with = "BulkFilter")]
+        numbers: Vec<NumberList>,
+
+        #[command(flatten)]
+        filter: BulkFilter,
+
+        /// Skip the confirmation prompt
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+
+    /// List notes where you were @mentioned
+    #[command(after_help = "EXAMPLES:
+    groove mentions
+    groove mentions --unread")]
+    Mentions {
+        /// Only show mentions you haven't read
+        #[arg(long)]
+        unread: bool,
+
+        /// Number of mentions to fetch
+        #[arg(short = 'n', long, default_value_t = 25)]
+        limit: u32,
+    },
+
+    /// Poll the inbox and print open, unassigned, and snoozed counts, and
+    /// announce due reminders and new assignments to you, until interrupted
+    #[command(after_help = "EXAMPLES:
+    groove watch
+    groove watch --interval 300
+    groove watch --notify
+    groove watch --metrics 127.0.0.1:9898
+
+With --metrics, Prometheus can scrape http://ADDR/metrics for the inbox
+counts and API request latencies.")]
+    Watch {
+        /// Seconds between polls
+        #[arg(long, default_value_t = 60, value_parser = RangedU64ValueParser::<u64>::new().range(10..))]
+        interval: u64,
+
+        /// Serve Prometheus metrics on this address (e.g. 127.0.0.1:9898)
+        #[arg(long, value_name = "ADDR")]
+        metrics: Option<SocketAddr>,
+
+        /// Show a desktop notification when a reminder comes due or a
+        /// conversation is assigned to you
+        #[arg(long)]
+        notify: bool,
+    },
+
+    /// Show the current API rate-limit budget
+    #[command(after_help = "EXAMPLES:
+    groove limits
+    groove limits -o json")]
+    Limits,
+
+    /// Summarize activity across conversations
+    #[command(after_help = "EXAMPLES:
+    groove report channels
+    groove report channels --since 7d -o json")]
+    Report {
+        #[command(subcommand)]
+        action: ReportAction,
+    },
+
+    /// Show a dashboard of inbox counts, the longest-waiting conversation,
+    /// and snoozes waking today
+    #[command(after_help = "EXAMPLES:
+    groove inbox
+    groove inbox -o json")]
+    Inbox,
+
     /// Show current user info
     #[command(after_help = "EXAMPLES:
     groove me")]
@@ -96,6 +459,34 @@ pub enum Commands {
         /// Shell to generate completions for
         shell: Shell,
     },
+
+    /// Print cached tag, folder, agent, or canned reply names to complete the
+    /// next word of a command line (used by the completion scripts)
+    #[command(name = "complete-names", hide = true)]
+    Complete {
+        /// The command line so far, without the program name
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        words: Vec<String>,
+    },
+
+    /// Serve fixture API responses locally, for testing scripts without a
+    /// Groove account
+    #[cfg(feature = "mock-server")]
+    #[command(after_help = "EXAMPLES:
+    groove mock-server
+    groove mock-server --port 9000
+
+Point groove at it with api_endpoint = \"http://127.0.0.1:8765\" in a
+config profile; any token is accepted.")]
+    MockServer {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// Port to listen on
+        #[arg(long, default_value_t = crate::mock::DEFAULT_PORT)]
+        port: u16,
+    },
 }
 
 #[derive(Subcommand)]
@@ -104,11 +495,37 @@ pub enum ConversationAction {
     #[command(alias = "ls", alias = "l", after_help = "EXAMPLES:
     groove conversation list
     groove conversation list --status opened --folder inbox
-    groove conversation list --search \"password reset\" --limit 10")]
+    groove conversation list --search \"password reset\" --limit 10
+    groove conversation list --search refund --search-in body
+    groove conversation list --contact jane@example.com
+    groove conversation list --since 7d
+    groove conversation list --since 2024-01-01 --until 2024-01-31 --updated
+    groove conversation list --status opened --sort waiting-longest
+    groove conversation list --status opened --group-by assignee
+    groove conversation list --waking-before 2d
+    groove conversation list --status snoozed --all
+    groove conversation list --wide > inbox.txt
+    groove conversation list --status unread --watch 60
+    groove conversation list --breaching-only
+    groove conversation list --status opened --idle-over 3d
+
+Tables fit the terminal: low-priority columns are dropped and subjects
+shortened on narrow terminals. --wide shows everything in full.
+
+With [sla] response_time set, unread and open conversations show how long
+the customer has waited since their first unanswered message, and those
+waiting longer are red.
+
+Words from --search are highlighted. --search-in subject keeps conversations
+whose subject has every word; --search-in body keeps those with a message
+that has every word, and adds a match column with the line that matched.
+
+With --watch, new conversations, state changes, and new assignments since
+the previous refresh are highlighted.")]
     List {
         /// Filter by status (opened, closed, snoozed, unread)
         #[arg(short, long)]
-        status: Option<String>,
+        status: Option<ConversationState>,
 
         /// Filter by folder name or ID
         #[arg(short, long)]
@@ -118,6 +535,47 @@ pub enum ConversationAction {
         #[arg(short = 'q', long)]
         search: Option<String>,
 
+        /// Where --search must match: subject, body, or all (default)
+        #[arg(long, value_name = "WHERE", value_enum, requires = "search")]
+        search_in: Option<SearchIn>,
+
+        /// Only show conversations with this customer (email or contact ID)
+        #[arg(long)]
+        contact: Option<String>,
+
+        /// Only show conversations created on or after this date (e.g. 7d, 2024-01-01)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only show conversations created on or before this date (e.g. 1d, 2024-01-31)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Apply --since/--until to the last update instead of creation
+        #[arg(long)]
+        updated: bool,
+
+        /// Only show snoozed conversations that wake up within this time (e.g. 2d)
+        #[arg(long)]
+        waking_before: Option<String>,
+
+        /// Only show conversations whose newest message is older than this
+        /// (e.g. 3d)
+        #[arg(long, value_name = "SPAN")]
+        idle_over: Option<String>,
+
+        /// Only show conversations waiting longer than [sla] response_time
+        #[arg(long)]
+        breaching_only: bool,
+
+        /// Sort order: newest, oldest, updated, or waiting-longest
+        #[arg(long)]
+        sort: Option<ConversationSort>,
+
+        /// Show one table per assignee, status, tag, or channel
+        #[arg(long, value_enum)]
+        group_by: Option<GroupBy>,
+
         /// Number of results to show (default: 25, or from config)
         #[arg(short = 'n', long)]
         limit: Option<u32>,
@@ -125,12 +583,44 @@ pub enum ConversationAction {
         /// Cursor for pagination
         #[arg(long)]
         after: Option<String>,
+
+        /// Fetch every matching conversation instead of one page
+        #[arg(long, conflicts_with_all = ["limit", "after"])]
+        all: bool,
+
+        /// Refresh every SECS seconds (default 30) until interrupted,
+        /// highlighting what changed
+        #[arg(
+            long,
+            value_name = "SECS",
+            num_args = 0..=1,
+            default_missing_value = "30",
+            value_parser = RangedU64ValueParser::<u64>::new().range(10..)
+        )]
+        watch: Option<u64>,
+
+        #[command(flatten)]
+        table: TableArgs,
     },
 
     /// Show a specific conversation with messages
     #[command(alias = "show", alias = "v", after_help = "EXAMPLES:
     groove conversation view 12345
-    groove conversation view 12345 --full")]
+    groove conversation view 12345 --full
+    groove conversation view 12345 --all-messages
+    groove conversation view 12345 --last 10
+    groove conversation view 12345 --collapse --expand 3,7
+    groove conversation view 12345 --copy
+    groove conversation view 12345 --message msg_abc123
+    groove conversation view 12345 --grep \"order.?4521\" -C 1
+    groove conversation view 12345 --stats
+    groove conversation view 12345 --raw | grep -i invoice
+
+--raw prints every message in full as plain text, with no colors or
+boxes, in the same layout as `conversation export --to txt`.
+
+--stats measures each agent reply from the first customer message it
+answered.")]
     View {
         /// Conversation number
         number: i64,
@@ -138,12 +628,105 @@ pub enum ConversationAction {
         /// Show full message bodies (not truncated)
         #[arg(long)]
         full: bool,
+
+        /// Print raw HTML message bodies instead of the text
+        #[arg(long, conflicts_with = "grep")]
+        html: bool,
+
+        /// Show quoted reply history and signatures (hidden by default)
+        #[arg(long)]
+        show_quoted: bool,
+
+        /// Show the newest message first
+        #[arg(long)]
+        reverse: bool,
+
+        /// Show one summary line per message
+        #[arg(long, conflicts_with = "html")]
+        collapse: bool,
+
+        /// With --collapse, show these message numbers in full (e.g. 3,7)
+        #[arg(long, value_name = "N,...", value_delimiter = ',', value_parser = RangedU64ValueParser::<usize>::new().range(1..), requires = "collapse")]
+        expand: Vec<usize>,
+
+        /// Copy the body of the last message to the clipboard
+        #[arg(long)]
+        copy: bool,
+
+        /// Show only this message, in full with its headers
+        #[arg(long, value_name = "ID", conflicts_with = "copy")]
+        message: Option<String>,
+
+        /// Search all messages for a regex (case-insensitive) and print matching lines
+        #[arg(long, value_name = "PATTERN", conflicts_with_all = ["copy", "message"])]
+        grep: Option<String>,
+
+        /// Lines of context to show around each --grep match
+        #[arg(short = 'C', long, default_value_t = 2, requires = "grep")]
+        context: usize,
+
+        /// Show every message, however long the thread (always the case
+        /// with --format json unless --last or --before is given)
+        #[arg(long, conflicts_with_all = ["last", "before", "message", "grep"])]
+        all_messages: bool,
+
+        /// Show the newest N messages instead of the oldest 50
+        #[arg(long, value_name = "N", value_parser = RangedU64ValueParser::<u32>::new().range(1..), conflicts_with_all = ["message", "grep"])]
+        last: Option<u32>,
+
+        /// Show the messages before this cursor (printed when older messages exist)
+        #[arg(long, value_name = "CURSOR", conflicts_with_all = ["message", "grep"])]
+        before: Option<String>,
+
+        /// Show the first response time and average agent response time
+        /// instead of the messages
+        #[arg(long, conflicts_with_all = ["message", "grep", "copy", "last", "before"])]
+        stats: bool,
+
+        /// Print the whole thread as plain text for piping (no colors,
+        /// boxes, or truncation)
+        #[arg(long, conflicts_with_all = ["message", "grep", "stats", "html", "collapse", "last", "before"])]
+        raw: bool,
+    },
+
+    /// Print the web app URL of a conversation
+    #[command(after_help = "EXAMPLES:
+    groove conversation url 12345
+    groove conversation url 12345 --copy")]
+    Url {
+        /// Conversation number
+        number: i64,
+
+        /// Copy the URL to the clipboard
+        #[arg(long)]
+        copy: bool,
+    },
+
+    /// Copy a message's plain-text body to the clipboard (the last message
+    /// by default)
+    #[command(after_help = "EXAMPLES:
+    groove conversation copy 12345
+    groove conversation copy 12345 --message msg_abc123
+
+HTML-only messages are copied as text, with links as numbered footnotes.")]
+    Copy {
+        /// Conversation number
+        number: i64,
+
+        /// Copy this message instead of the last one
+        #[arg(long, value_name = "ID")]
+        message: Option<String>,
     },
 
     /// Reply to a conversation
     #[command(alias = "r", after_help = "EXAMPLES:
     groove conversation reply 12345 \"Thanks for your message!\"
     groove conversation reply 12345 --canned greeting
+    groove conversation reply 12345 \"On it!\" --assign me
+    groove conversation reply 12345 \"Good morning!\" --send-at \"tomorrow 9am\"
+    groove conversation reply 12345 --snippet apology
+    groove conversation reply 12345 --template refund.md
+    groove conversation reply 12345 --template refund.md --preview
     echo \"Reply body\" | groove conversation reply 12345")]
     Reply {
         /// Conversation number
@@ -155,23 +738,82 @@ pub enum ConversationAction {
         /// Use a canned reply by name or ID
         #[arg(short, long)]
         canned: Option<String>,
+
+        /// Use one of your snippets (see `groove snippet`)
+        #[arg(long, value_name = "NAME", conflicts_with = "canned")]
+        snippet: Option<String>,
+
+        /// Use a Markdown reply template from the templates directory, or
+        /// at a path; its front matter can set the subject, add tags, and
+        /// close the conversation
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["canned", "snippet"])]
+        template: Option<String>,
+
+        /// Also assign the conversation to this agent (email, name, or "me")
+        #[arg(short, long, value_name = "AGENT")]
+        assign: Option<String>,
+
+        /// Show the reply as it will be sent and ask before sending it
+        #[arg(long)]
+        preview: bool,
+
+        /// Queue the reply in the outbox until this time (e.g. "tomorrow 9am",
+        /// "mon 14:00", 2h, 2024-06-03)
+        #[arg(long, value_name = "WHEN")]
+        send_at: Option<String>,
+    },
+
+    /// Write a reply in a full-screen composer with the thread beside it
+    #[command(after_help = "EXAMPLES:
+    groove conversation compose 12345
+    groove conversation compose 12345 --canned refund --close
+    groove conversation compose 12345 --assign me
+
+KEYS:
+    Ctrl-S          Send the reply
+    Ctrl-X          Send the reply and close the conversation
+    Ctrl-R          Insert a canned reply at the cursor
+    Tab             Switch between the reply and the thread
+    PgUp/PgDn       Scroll the thread
+    Esc, Ctrl-Q     Cancel (a non-empty draft is kept in a temp file)")]
+    Compose {
+        /// Conversation number
+        number: i64,
+
+        /// Start the reply from a canned reply (name or ID)
+        #[arg(short, long)]
+        canned: Option<String>,
+
+        /// Start the reply from one of your snippets
+        #[arg(long, value_name = "NAME", conflicts_with = "canned")]
+        snippet: Option<String>,
+
+        /// Also assign the conversation to this agent (email, name, or "me")
+        #[arg(short, long, value_name = "AGENT")]
+        assign: Option<String>,
+
+        /// Close the conversation once the reply is sent
+        #[arg(long)]
+        close: bool,
     },
 
     /// Close a conversation
     #[command(after_help = "EXAMPLES:
     groove conversation close 12345
-    groove conversation close 12345 12346 12347")]
+    groove conversation close 12345 12346 12347
+    groove conversation close 1200-1250 1300,1302
+    groove conversation close 12345 12346 12347 --concurrency 10")]
     Close {
-        /// Conversation number(s)
-        numbers: Vec<i64>,
+        #[command(flatten)]
+        bulk: BulkArgs,
     },
 
     /// Reopen a conversation
     #[command(after_help = "EXAMPLES:
     groove conversation open 12345")]
     Open {
-        /// Conversation number(s)
-        numbers: Vec<i64>,
+        #[command(flatten)]
+        bulk: BulkArgs,
     },
 
     /// Snooze a conversation
@@ -199,35 +841,123 @@ pub enum ConversationAction {
         agent: String,
     },
 
+    /// File a conversation under a different customer
+    #[command(after_help = "EXAMPLES:
+    groove conversation set-contact 12345 jane@customer.com
+    groove conversation set-contact 12345 contact_abc123
+
+For tickets filed under a colleague's address or a forwarding alias. Undo
+with `groove undo`.")]
+    SetContact {
+        /// Conversation number
+        number: i64,
+
+        /// The customer's email or contact ID
+        contact: String,
+    },
+
     /// Unassign a conversation
     #[command(after_help = "EXAMPLES:
     groove conversation unassign 12345")]
     Unassign {
-        /// Conversation number(s)
-        numbers: Vec<i64>,
+        #[command(flatten)]
+        bulk: BulkArgs,
     },
 
-    /// Add tags to a conversation
-    #[command(alias = "tag", after_help = "EXAMPLES:
-    groove conversation add-tag 12345 urgent
-    groove conversation add-tag 12345 bug feature")]
-    AddTag {
+    /// Follow a conversation to be notified of activity without being assigned
+    #[command(after_help = "EXAMPLES:
+    groove conversation follow 12345
+    groove conversation follow 12345 --agent user@example.com")]
+    Follow {
         /// Conversation number
         number: i64,
 
-        /// Tag names to add
-        tags: Vec<String>,
+        /// Agent email, or "me" (the default)
+        #[arg(long, default_value = "me")]
+        agent: String,
     },
 
-    /// Remove tags from a conversation
-    #[command(alias = "untag", after_help = "EXAMPLES:
-    groove conversation remove-tag 12345 urgent")]
-    RemoveTag {
+    /// Stop following a conversation
+    #[command(after_help = "EXAMPLES:
+    groove conversation unfollow 12345
+    groove conversation unfollow 12345 --agent user@example.com")]
+    Unfollow {
         /// Conversation number
         number: i64,
 
-        /// Tag names to remove
-        tags: Vec<String>,
+        /// Agent email, or "me" (the default)
+        #[arg(long, default_value = "me")]
+        agent: String,
+    },
+
+    /// List the agents following a conversation
+    #[command(after_help = "EXAMPLES:
+    groove conversation followers 12345
+    groove conversation followers 12345 -o json")]
+    Followers {
+        /// Conversation number
+        number: i64,
+    },
+
+    /// Add tags to conversations
+    #[command(
+        alias = "tag",
+        override_usage = "groove conversation add-tag [OPTIONS] <NUMBERS>... <TAGS>...",
+        after_help = "EXAMPLES:
+    groove conversation add-tag 12345 urgent
+    groove conversation add-tag 12345 bug feature
+    groove conversation add-tag 12345 12346 1200-1250 urgent
+    groove conversation list -q invoice -o compact | groove conversation add-tag - billing
+
+The first argument that isn't a conversation number starts the tags."
+    )]
+    AddTag {
+        #[command(flatten)]
+        targets: TagArgs,
+    },
+
+    /// Remove tags from conversations
+    #[command(
+        alias = "untag",
+        override_usage = "groove conversation remove-tag [OPTIONS] <NUMBERS>... <TAGS>...",
+        after_help = "EXAMPLES:
+    groove conversation remove-tag 12345 urgent
+    groove conversation remove-tag 1200-1250 1300,1302 urgent stale
+
+The first argument that isn't a conversation number starts the tags."
+    )]
+    RemoveTag {
+        #[command(flatten)]
+        targets: TagArgs,
+    },
+
+    /// Remove every tag from conversations
+    #[command(after_help = "EXAMPLES:
+    groove conversation clear-tags 12345
+    groove conversation clear-tags 1200-1250 1300,1302")]
+    ClearTags {
+        #[command(flatten)]
+        bulk: BulkArgs,
+    },
+
+    /// Apply an action to every conversation matching a filter
+    #[command(after_help = "EXAMPLES:
+    groove conversation bulk --status opened --older-than 30d --tag stale close
+    groove conversation bulk --folder spam --yes close
+    groove conversation bulk --search \"out of office\" add-tag auto-reply")]
+    Bulk {
+        #[command(flatten)]
+        filter: BulkFilter,
+
+        /// Skip the confirmation prompt
+        #[arg(short = 'y', long)]
+        yes: bool,
+
+        #[command(flatten)]
+        options: BulkOptions,
+
+        #[command(subcommand)]
+        action: BulkAction,
     },
 
     /// Add a private note to a conversation
@@ -241,6 +971,191 @@ pub enum ConversationAction {
         /// Note body (reads from stdin if not provided)
         body: Option<String>,
     },
+
+    /// List the private notes on a conversation
+    #[command(after_help = "EXAMPLES:
+    groove conversation notes 12345
+    groove conversation notes 12345 -o json")]
+    Notes {
+        /// Conversation number
+        number: i64,
+    },
+
+    /// Remove accidentally shared secrets (passwords, card numbers) from a
+    /// message
+    #[command(after_help = "EXAMPLES:
+    groove conversation redact 12345 --message msg_abc123 --pattern \"hunter2\"
+    groove conversation redact 12345 --message msg_abc123 --pattern \"\\b(?:\\d[ -]?){13,16}\\b\"
+    groove conversation redact 12345 --message msg_abc123 --yes
+
+With --pattern, matching text is replaced by [redacted] and the rest of the
+message kept. Without it, the whole message is deleted. Neither can be
+undone.")]
+    Redact {
+        /// Conversation number
+        number: i64,
+
+        /// Message ID (shown by `conversation view -o json`)
+        #[arg(long, value_name = "ID")]
+        message: String,
+
+        /// Redact only text matching this regex (case-insensitive)
+        #[arg(long, value_name = "REGEX")]
+        pattern: Option<String>,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Export the full thread to a file (Markdown, HTML, EML, mbox, JSON, or PDF)
+    #[command(after_help = "EXAMPLES:
+    groove conversation export 12345 --out thread.md
+    groove conversation export 12345 --to mbox --out thread.mbox
+    groove conversation export 12345 --out transcript.pdf
+    groove conversation export 12345 --to html > thread.html")]
+    Export {
+        /// Conversation number
+        number: i64,
+
+        /// Export format: md, txt, html, eml, mbox, json, or pdf (default: from --out extension, else md)
+        #[arg(long, value_name = "FORMAT")]
+        to: Option<ExportFormat>,
+
+        /// File to write (default: stdout)
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+}
+
+/// Default number of conversations a bulk command works on at once
+pub const DEFAULT_CONCURRENCY: usize = 5;
+
+/// Overrides for how conversation tables fit the terminal
+#[derive(Args, Debug, Clone, Default)]
+pub struct TableArgs {
+    /// Show every column in full, however wide the terminal is
+    #[arg(long, conflicts_with = "narrow")]
+    pub wide: bool,
+
+    /// Show only the number, status, and subject
+    #[arg(long)]
+    pub narrow: bool,
+}
+
+/// Arguments shared by commands that act on several conversations
+#[derive(Args, Debug, Clone)]
+pub struct BulkArgs {
+    /// Conversation number(s), ranges like 1200-1250, or comma lists
+    #[arg(required = true)]
+    pub numbers: Vec<NumberList>,
+
+    #[command(flatten)]
+    pub options: BulkOptions,
+}
+
+/// Conversations and tags for `add-tag` and `remove-tag`
+#[derive(Args, Debug, Clone)]
+pub struct TagArgs {
+    /// Conversation number(s), ranges like 1200-1250, comma lists, or - to
+    /// read numbers from stdin, followed by the tag names
+    #[arg(required = true, value_name = "NUMBERS|TAGS")]
+    pub args: Vec<String>,
+
+    #[command(flatten)]
+    pub options: BulkOptions,
+}
+
+/// How a bulk command works through its conversations
+#[derive(Args, Debug, Clone)]
+pub struct BulkOptions {
+    /// Maximum number of conversations to update at once (for close and
+    /// open, batches of 20 conversations)
+    #[arg(long, default_value_t = DEFAULT_CONCURRENCY, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub concurrency: usize,
+
+    /// Stop at the first failure instead of continuing with the rest
+    #[arg(long)]
+    pub fail_fast: bool,
+}
+
+/// Conversation filters for `conversation bulk`
+#[derive(Args, Debug, Clone, Default, PartialEq)]
+pub struct BulkFilter {
+    /// Filter by status (opened, closed, snoozed, unread)
+    #[arg(short, long)]
+    pub status: Option<ConversationState>,
+
+    /// Filter by folder name or ID
+    #[arg(short, long)]
+    pub folder: Option<String>,
+
+    /// Search by keyword in subject/body
+    #[arg(short = 'q', long)]
+    pub search: Option<String>,
+
+    /// Only conversations not updated for this long (e.g. "12h", "30d", "2w")
+    #[arg(long)]
+    pub older_than: Option<String>,
+
+    /// Only conversations with this tag (repeat to require several)
+    #[arg(long)]
+    pub tag: Vec<String>,
+
+    /// Only conversations assigned to this agent (email, name, or "me")
+    #[arg(long)]
+    pub assignee: Option<String>,
+
+    /// Act on at most this many conversations
+    #[arg(short = 'n', long)]
+    pub limit: Option<u32>,
+}
+
+/// Actions `conversation bulk` can apply
+#[derive(Subcommand, Debug, Clone)]
+pub enum BulkAction {
+    /// Close the matching conversations
+    Close,
+
+    /// Reopen the matching conversations
+    Open,
+
+    /// Unassign the matching conversations
+    Unassign,
+
+    /// Add tags to the matching conversations
+    AddTag {
+        /// Tag names to add
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    /// Remove tags from the matching conversations
+    RemoveTag {
+        /// Tag names to remove
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+}
+
+impl BulkAction {
+    /// Subcommand name, also used for hook names
+    pub fn name(&self) -> &'static str {
+        match self {
+            BulkAction::Close => "close",
+            BulkAction::Open => "open",
+            BulkAction::Unassign => "unassign",
+            BulkAction::AddTag { .. } => "add-tag",
+            BulkAction::RemoveTag { .. } => "remove-tag",
+        }
+    }
+}
+
+impl BulkArgs {
+    /// The conversation numbers with ranges expanded and repeats removed
+    pub fn numbers(&self) -> Vec<i64> {
+        expand_numbers(&self.numbers)
+    }
 }
 
 #[derive(Subcommand)]
@@ -249,6 +1164,31 @@ pub enum FolderAction {
     #[command(alias = "ls", alias = "l", after_help = "EXAMPLES:
     groove folder list")]
     List,
+
+    /// Show a folder's counts and the conversations in it
+    #[command(alias = "show", after_help = "EXAMPLES:
+    groove folder view billing
+    groove folder view billing --status unread
+    groove folder view \"VIP customers\" -n 50")]
+    View {
+        /// Folder name or ID
+        name: String,
+
+        /// Filter by status (opened, closed, snoozed, unread)
+        #[arg(short, long)]
+        status: Option<ConversationState>,
+
+        /// Number of conversations to show (default: 25, or from config)
+        #[arg(short = 'n', long)]
+        limit: Option<u32>,
+
+        /// Cursor for pagination
+        #[arg(long)]
+        after: Option<String>,
+
+        #[command(flatten)]
+        table: TableArgs,
+    },
 }
 
 #[derive(Subcommand)]
@@ -259,6 +1199,191 @@ pub enum TagAction {
     List,
 }
 
+#[derive(Subcommand)]
+pub enum MessageAction {
+    /// Print one message in full with its headers
+    #[command(alias = "show", after_help = "EXAMPLES:
+    groove message view msg_abc123
+    groove message view msg_abc123 -o json")]
+    View {
+        /// Message ID (shown by `conversation view` and `-o json`)
+        id: String,
+
+        /// Print the raw HTML body instead of the text
+        #[arg(long)]
+        html: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum NoteAction {
+    /// Replace the body of a note
+    #[command(after_help = "EXAMPLES:
+    groove note edit note_abc123 \"Corrected note\"
+    echo \"Corrected note\" | groove note edit note_abc123")]
+    Edit {
+        /// Note ID (see `conversation notes`)
+        id: String,
+
+        /// New note body (reads from stdin if not provided)
+        body: Option<String>,
+    },
+
+    /// Delete a note
+    #[command(alias = "rm", after_help = "EXAMPLES:
+    groove note delete note_abc123
+    groove note delete note_abc123 --yes")]
+    Delete {
+        /// Note ID (see `conversation notes`)
+        id: String,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ContactAction {
+    /// Show a contact and their most recent conversations
+    #[command(after_help = "EXAMPLES:
+    groove contact view jane@customer.com
+    groove contact view jane@customer.com -n 25 -o json")]
+    View {
+        /// The contact's email address
+        email: String,
+
+        /// Number of conversations to show, newest first
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: u32,
+    },
+
+    /// Merge a duplicate contact into another, moving its conversations
+    #[command(after_help = "EXAMPLES:
+    groove contact merge jane@customer.com jane.doe@oldmail.example
+    groove contact merge jane@customer.com contact_abc123 --yes
+
+The duplicate's conversations move to the primary contact and the duplicate
+is removed. This can't be undone.")]
+    Merge {
+        /// Contact to keep (email or contact ID)
+        primary: String,
+
+        /// Contact to merge into it and remove (email or contact ID)
+        duplicate: String,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Export a contact's profile and every conversation with them, for
+    /// data subject access requests
+    #[command(after_help = "EXAMPLES:
+    groove contact export jane@customer.com --dir ./dsar-jane
+    groove contact export jane@customer.com --dir ./dsar-jane --to pdf
+
+Each conversation is written to <number>.<ext> with all of its messages, and
+manifest.json holds the contact's profile and an index of the files.")]
+    Export {
+        /// The contact's email address
+        email: String,
+
+        /// Directory to write to (created if missing)
+        #[arg(long, value_name = "DIR")]
+        dir: PathBuf,
+
+        /// File format: json, md, txt, html, eml, mbox, or pdf
+        #[arg(long, value_name = "FORMAT", default_value = "json")]
+        to: ExportFormat,
+    },
+
+    /// Permanently delete a contact, for erasure requests
+    #[command(after_help = "EXAMPLES:
+    groove contact export jane@customer.com --dir ./dsar-jane
+    groove contact delete jane@customer.com --confirm
+
+This can't be undone; export the contact first if the data must be kept.")]
+    Delete {
+        /// Email or contact ID
+        contact: String,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long, alias = "confirm")]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ReportAction {
+    /// Volume, open conversations, and median response times per channel
+    #[command(after_help = "EXAMPLES:
+    groove report channels
+    groove report channels --since 90d
+    groove report channels --since 2024-01-01 --until 2024-03-31
+
+Covers conversations created in the period. Response times run from the
+first unanswered customer message to the agent reply, so every conversation
+in the period is read in full; long periods take a while.")]
+    Channels {
+        /// Start of the period (e.g. 30d, 2024-01-01)
+        #[arg(long, default_value = "30d")]
+        since: String,
+
+        /// End of the period (e.g. 2024-01-31; default: now)
+        #[arg(long)]
+        until: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RemindAction {
+    /// List reminders, soonest first
+    #[command(alias = "ls", alias = "l", after_help = "EXAMPLES:
+    groove remind list
+    groove remind list --due")]
+    List {
+        /// Only show reminders that have come due
+        #[arg(long)]
+        due: bool,
+    },
+
+    /// Delete a reminder
+    #[command(alias = "rm", alias = "cancel", after_help = "EXAMPLES:
+    groove remind done 3")]
+    Done {
+        /// Reminder ID (see `remind list`)
+        id: u64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum OutboxAction {
+    /// List queued replies, soonest first
+    #[command(alias = "ls", alias = "l", after_help = "EXAMPLES:
+    groove outbox list
+    groove outbox list -o json")]
+    List,
+
+    /// Delete a queued reply without sending it
+    #[command(alias = "rm", after_help = "EXAMPLES:
+    groove outbox cancel 2")]
+    Cancel {
+        /// Queued reply ID (see `outbox list`)
+        id: u64,
+    },
+
+    /// Send the queued replies that are due
+    #[command(after_help = "EXAMPLES:
+    groove outbox flush
+    groove outbox flush --all")]
+    Flush {
+        /// Send every queued reply now, due or not
+        #[arg(long)]
+        all: bool,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum CannedRepliesAction {
     /// List all canned replies
@@ -276,6 +1401,82 @@ pub enum CannedRepliesAction {
     },
 }
 
+#[derive(Subcommand)]
+pub enum SnippetAction {
+    /// Save a snippet, replacing any with the same name
+    #[command(after_help = "EXAMPLES:
+    groove snippet add apology \"Sorry for the slow reply!\"
+    groove snippet add sig < signature.txt")]
+    Add {
+        /// Snippet name (letters, digits, '-', '_', and '.')
+        name: String,
+
+        /// Snippet text (reads from stdin if not provided)
+        body: Option<String>,
+    },
+
+    /// List your snippets
+    #[command(alias = "ls", alias = "l", after_help = "EXAMPLES:
+    groove snippet list
+    groove snippet list -o json")]
+    List,
+
+    /// Print a snippet, or copy it to the clipboard
+    #[command(alias = "show", after_help = "EXAMPLES:
+    groove snippet use sig
+    groove snippet use sig --copy")]
+    Use {
+        /// Snippet name
+        name: String,
+
+        /// Copy to the clipboard instead of printing
+        #[arg(long)]
+        copy: bool,
+    },
+
+    /// Delete a snippet
+    #[command(alias = "rm", after_help = "EXAMPLES:
+    groove snippet remove sig")]
+    Remove {
+        /// Snippet name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RuleAction {
+    /// List automation rules and whether they're enabled
+    #[command(alias = "ls", alias = "l", after_help = "EXAMPLES:
+    groove rule list
+    groove rule list -o json")]
+    List,
+
+    /// Show a rule's conditions and actions
+    #[command(after_help = "EXAMPLES:
+    groove rule show \"Tag refund requests\"
+    groove rule show rule_abc123")]
+    Show {
+        /// Rule name or ID
+        name: String,
+    },
+
+    /// Turn a rule on
+    #[command(after_help = "EXAMPLES:
+    groove rule enable \"Close spam\"")]
+    Enable {
+        /// Rule name or ID
+        name: String,
+    },
+
+    /// Turn a rule off without deleting it
+    #[command(after_help = "EXAMPLES:
+    groove rule disable \"Close spam\"")]
+    Disable {
+        /// Rule name or ID
+        name: String,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum ConfigAction {
     /// Interactive configuration setup
@@ -290,18 +1491,99 @@ pub enum ConfigAction {
 
     /// Set API token
     #[command(after_help = "EXAMPLES:
-    groove config set-token your-api-token-here")]
+    groove config set-token your-api-token-here
+    groove --profile work config set-token your-api-token-here
+    groove config set-token --keyring your-api-token-here")]
     SetToken {
         /// API token value
         token: String,
+
+        /// Store the token in the OS keyring instead of the config file
+        #[arg(long)]
+        keyring: bool,
     },
 
+    /// Set a configuration value by key
+    #[command(after_help = "EXAMPLES:
+    groove config set defaults.format json
+    groove config set defaults.limit 50
+    groove config set aliases.ls \"conversation list\"
+    groove config set profiles.work.api_endpoint https://example.com/graphql")]
+    Set {
+        /// Dot-separated key (e.g. defaults.limit)
+        key: String,
+
+        /// Value to store
+        value: String,
+    },
+
+    /// Print a configuration value by key
+    #[command(after_help = "EXAMPLES:
+    groove config get defaults.format
+    groove config get aliases")]
+    Get {
+        /// Dot-separated key (e.g. defaults.limit)
+        key: String,
+    },
+
+    /// Remove a configuration value by key
+    #[command(after_help = "EXAMPLES:
+    groove config unset defaults.folder
+    groove config unset aliases.ls")]
+    Unset {
+        /// Dot-separated key (e.g. defaults.limit)
+        key: String,
+    },
+
+    /// Verify that the configured token works
+    #[command(after_help = "EXAMPLES:
+    groove config verify
+    groove --profile work config verify")]
+    Verify,
+
     /// Show config file path
     #[command(after_help = "EXAMPLES:
     groove config path")]
     Path,
 }
 
+/// Conversations shown by `groove my`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MyScope {
+    Open,
+    Snoozed,
+    All,
+}
+
+impl MyScope {
+    /// State filter to pass to the API, if any
+    pub fn state(&self) -> Option<ConversationState> {
+        match self {
+            MyScope::Open => Some(ConversationState::Opened),
+            MyScope::Snoozed => Some(ConversationState::Snoozed),
+            MyScope::All => None,
+        }
+    }
+}
+
+/// Key for `conversation list --group-by`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupBy {
+    Assignee,
+    Status,
+    Tag,
+    Channel,
+}
+
+/// Where `conversation list --search` must match
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SearchIn {
+    Subject,
+    Body,
+    #[default]
+    All,
+}
+
 #[derive(ValueEnum, Clone, Debug, Default)]
 pub enum OutputFormat {
     #[default]
@@ -328,5 +1610,8 @@ impl std::str::FromStr for OutputFormat {
 
 pub fn print_completions(shell: Shell) {
     let mut cmd = Cli::command();
-    generate(shell, &mut cmd, "groove", &mut std::io::stdout());
+    let mut script = Vec::new();
+    generate(shell, &mut cmd, "groove", &mut script);
+    let script = String::from_utf8_lossy(&script).into_owned();
+    print!("{}", dynamic_completions(shell, script));
 }
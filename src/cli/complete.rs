@@ -0,0 +1,211 @@
+use clap_complete::Shell;
+
+use crate::api::Cache;
+use crate::snippets::Snippets;
+use crate::types::{Agent, CannedReply, Folder, Tag};
+
+/// Options whose values are names from the API
+const VALUE_OPTIONS: &[(&str, &str)] = &[
+    ("--folder", "folders"),
+    ("-f", "folders"),
+    ("--tag", "tags"),
+    ("--canned", "canned_replies"),
+    ("-c", "canned_replies"),
+    ("--assign", "agents"),
+    ("-a", "agents"),
+    ("--agent", "agents"),
+    ("--snippet", "snippets"),
+];
+
+/// Global options that take a value, skipped when finding subcommands
+const GLOBAL_VALUE_OPTIONS: &[&str] = &["-o", "--format", "--token", "--profile"];
+
+/// Candidates for the word after `words` (the command line so far, without
+/// the program name), read from the reference data cache.
+///
+/// Returns nothing when the word isn't a tag, folder, agent, canned reply, or
+/// snippet name, or when that data hasn't been cached yet; shells then fall
+/// back to the static completions. Snippet names come from disk rather than
+/// the cache.
+pub fn completion_values(cache: &Cache, words: &[String]) -> Vec<String> {
+    match completion_key(words) {
+        Some("tags") => names(cache.get_stale::<Vec<Tag>>("tags"), |t| t.name),
+        Some("folders") => names(cache.get_stale::<Vec<Folder>>("folders"), |f| f.name),
+        Some("canned_replies") => {
+            names(cache.get_stale::<Vec<CannedReply>>("canned_replies"), |c| {
+                c.name
+            })
+        }
+        Some("agents") => {
+            let agents = cache.get_stale::<Vec<Agent>>("agents");
+            let mut values = vec!["me".to_string()];
+            values.extend(names(agents, |a| a.email));
+            values
+        }
+        Some("snippets") => {
+            let snippets = Snippets::default_dir().and_then(|dir| Snippets::new(dir).all().ok());
+            names(snippets, |s| s.name)
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn names<T>(items: Option<Vec<T>>, name: impl Fn(T) -> String) -> Vec<String> {
+    items.unwrap_or_default().into_iter().map(name).collect()
+}
+
+/// Cache key of the data the next word is completed from
+fn completion_key(words: &[String]) -> Option<&'static str> {
+    if let Some(last) = words.last() {
+        if let Some((_, key)) = VALUE_OPTIONS.iter().find(|(opt, _)| opt == last) {
+            return Some(key);
+        }
+    }
+
+    // Positional arguments: `conversation assign N <agent>`,
+    // `conversation add-tag N <tag>...`, and `snippet use <name>`
+    let mut positional = Vec::new();
+    let mut skip_value = false;
+    for word in words {
+        if std::mem::take(&mut skip_value) {
+            continue;
+        }
+        if GLOBAL_VALUE_OPTIONS.contains(&word.as_str()) {
+            skip_value = true;
+        } else if !word.starts_with('-') {
+            positional.push(word.as_str());
+        }
+    }
+    match positional.as_slice() {
+        ["conversation" | "conv" | "c", "assign", _number] => Some("agents"),
+        ["conversation" | "conv" | "c", "add-tag" | "tag" | "remove-tag" | "untag", _number, ..] => {
+            Some("tags")
+        }
+        ["snippet", "use" | "show" | "remove" | "rm"] => Some("snippets"),
+        _ => None,
+    }
+}
+
+/// Shell code added to the generated completions so values come from
+/// `groove complete-names`, falling back to the static completions
+pub fn dynamic_completions(shell: Shell, script: String) -> String {
+    match shell {
+        Shell::Bash => script + BASH_DYNAMIC,
+        // Hook in before the generated script registers `_groove`
+        Shell::Zsh => match script.rfind("if [ \"$funcstack[1]\" = \"_groove\" ]") {
+            Some(at) => format!("{}{}", &script[..at], ZSH_DYNAMIC),
+            None => script,
+        },
+        Shell::Fish => script + FISH_DYNAMIC,
+        _ => script,
+    }
+}
+
+const BASH_DYNAMIC: &str = r#"
+_groove_dynamic() {
+    local values
+    values=$(groove complete-names -- "${COMP_WORDS[@]:1:COMP_CWORD-1}" 2>/dev/null)
+    if [[ -n "$values" ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($(compgen -W "$values" -- "${COMP_WORDS[COMP_CWORD]}"))
+        return 0
+    fi
+    _groove "$@"
+}
+
+complete -F _groove_dynamic -o bashdefault -o default groove
+"#;
+
+const ZSH_DYNAMIC: &str = r#"_groove_dynamic() {
+    local -a values
+    values=("${(@f)$(groove complete-names -- "${(@)words[2,CURRENT-1]}" 2>/dev/null)}")
+    if [[ -n "${values[1]}" ]]; then
+        compadd -a values
+        return
+    fi
+    _groove "$@"
+}
+
+if [ "$funcstack[1]" = "_groove" ]; then
+    _groove_dynamic "$@"
+else
+    compdef _groove_dynamic groove
+fi
+"#;
+
+const FISH_DYNAMIC: &str = r#"
+function __groove_dynamic_values
+    set -l words (commandline -opc)
+    groove complete-names -- $words[2..-1] 2>/dev/null
+end
+complete -c groove -f -n '__groove_dynamic_values | string length -q' -a '(__groove_dynamic_values)'
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(line: &str) -> Option<&'static str> {
+        let words: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        completion_key(&words)
+    }
+
+    #[test]
+    fn test_completion_key_options() {
+        assert_eq!(key("conv list --folder"), Some("folders"));
+        assert_eq!(key("conv bulk -f"), Some("folders"));
+        assert_eq!(key("conv bulk --tag"), Some("tags"));
+        assert_eq!(key("conv reply 12 -c"), Some("canned_replies"));
+        assert_eq!(key("conv reply 12 --assign"), Some("agents"));
+        assert_eq!(key("conv follow 12 --agent"), Some("agents"));
+        assert_eq!(key("conv reply 12 --snippet"), Some("snippets"));
+        assert_eq!(key("conv list --status"), None);
+    }
+
+    #[test]
+    fn test_completion_key_positionals() {
+        assert_eq!(key("conversation assign 12"), Some("agents"));
+        assert_eq!(key("-o json c assign 12"), Some("agents"));
+        assert_eq!(key("conv assign"), None);
+        assert_eq!(key("conv assign 12 me"), None);
+        assert_eq!(key("conv add-tag 12"), Some("tags"));
+        assert_eq!(key("conv untag 12 urgent"), Some("tags"));
+        assert_eq!(key("snippet use"), Some("snippets"));
+        assert_eq!(key("snippet use sig"), None);
+        assert_eq!(key("tag list"), None);
+        assert_eq!(key(""), None);
+    }
+
+    #[test]
+    fn test_completion_values_from_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path(), std::time::Duration::from_secs(60));
+        let words =
+            |line: &str| -> Vec<String> { line.split_whitespace().map(str::to_string).collect() };
+        assert!(completion_values(&cache, &words("conv list --folder")).is_empty());
+        assert_eq!(
+            completion_values(&cache, &words("conv assign 12")),
+            vec!["me"]
+        );
+
+        cache.put(
+            "folders",
+            &vec![Folder {
+                id: "f1".to_string(),
+                name: "Inbox".to_string(),
+            }],
+        );
+        assert_eq!(
+            completion_values(&cache, &words("conv list --folder")),
+            vec!["Inbox"]
+        );
+    }
+
+    #[test]
+    fn test_dynamic_completions_zsh_registers_wrapper() {
+        let script = "_groove() {\n}\n\nif [ \"$funcstack[1]\" = \"_groove\" ]; then\n    _groove \"$@\"\nelse\n    compdef _groove groove\nfi\n";
+        let script = dynamic_completions(Shell::Zsh, script.to_string());
+        assert!(script.contains("compdef _groove_dynamic groove"));
+        assert!(!script.contains("compdef _groove groove"));
+    }
+}
@@ -0,0 +1,182 @@
+/// Maximum number of "did you mean" suggestions
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Result of looking a name up among agents, tags, or canned replies
+#[derive(Debug, PartialEq)]
+pub enum Lookup<'a, T> {
+    /// Case-insensitive exact match
+    Exact(&'a T),
+    /// Unambiguous near-match, with the name it matched on
+    Corrected(&'a T, &'a str),
+    /// No usable match; the closest names, best first
+    NotFound(Vec<String>),
+}
+
+/// Find `query` among `items`, each known by one or more names (`keys`).
+///
+/// An exact match wins. Otherwise a single item within a small edit distance,
+/// or the only item whose name starts with `query`, is picked automatically;
+/// anything else yields suggestions.
+pub fn lookup<'a, T>(
+    query: &str,
+    items: &'a [T],
+    keys: impl Fn(&'a T) -> Vec<&'a str>,
+) -> Lookup<'a, T> {
+    let query_lower = query.to_lowercase();
+    let candidates: Vec<(&'a T, &'a str)> = items
+        .iter()
+        .flat_map(|item| keys(item).into_iter().map(move |key| (item, key)))
+        .collect();
+
+    if let Some((item, _)) = candidates
+        .iter()
+        .find(|(_, key)| key.to_lowercase() == query_lower)
+    {
+        return Lookup::Exact(item);
+    }
+
+    // Best distance per item, so an agent matching on both name and email
+    // doesn't count as two candidates
+    let mut scored: Vec<(&'a T, &'a str, usize)> = Vec::new();
+    for (item, key) in &candidates {
+        let distance = levenshtein(&query_lower, &key.to_lowercase());
+        match scored.iter_mut().find(|(i, _, _)| std::ptr::eq(*i, *item)) {
+            Some(entry) if distance < entry.2 => *entry = (item, key, distance),
+            Some(_) => {}
+            None => scored.push((item, key, distance)),
+        }
+    }
+    scored.sort_by_key(|(_, key, distance)| (*distance, key.len()));
+
+    let threshold = max_typos(&query_lower);
+    if let [(item, key, best), rest @ ..] = scored.as_slice() {
+        let unique = rest.first().is_none_or(|(_, _, next)| next > best);
+        if *best <= threshold && unique {
+            return Lookup::Corrected(item, key);
+        }
+    }
+
+    let mut prefixed: Vec<&(&'a T, &'a str)> = candidates
+        .iter()
+        .filter(|(_, key)| key.to_lowercase().starts_with(&query_lower))
+        .collect();
+    prefixed.dedup_by(|a, b| std::ptr::eq(a.0, b.0));
+    if let [(item, key)] = prefixed.as_slice() {
+        if query_lower.chars().count() >= 3 {
+            return Lookup::Corrected(item, key);
+        }
+    }
+
+    let suggestions = scored
+        .iter()
+        .filter(|(_, key, distance)| {
+            *distance <= threshold + 2 || key.to_lowercase().contains(&query_lower)
+        })
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, key, _)| key.to_string())
+        .collect();
+    Lookup::NotFound(suggestions)
+}
+
+/// Typos tolerated before a near-match stops being picked automatically
+fn max_typos(query: &str) -> usize {
+    match query.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Number of single-character edits between `a` and `b`
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names<'a>(item: &'a &'a str) -> Vec<&'a str> {
+        vec![*item]
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("urgent", "urgent"), 0);
+        assert_eq!(levenshtein("urgnet", "urgent"), 2);
+        assert_eq!(levenshtein("urgen", "urgent"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_exact_match_is_case_insensitive() {
+        let tags = ["Urgent", "billing"];
+        assert_eq!(lookup("urgent", &tags, names), Lookup::Exact(&tags[0]));
+    }
+
+    #[test]
+    fn test_single_typo_is_corrected() {
+        let tags = ["urgent", "billing", "bug"];
+        assert_eq!(
+            lookup("urgnt", &tags, names),
+            Lookup::Corrected(&tags[0], "urgent")
+        );
+    }
+
+    #[test]
+    fn test_unique_prefix_is_corrected() {
+        let tags = ["billing", "bug"];
+        assert_eq!(
+            lookup("bill", &tags, names),
+            Lookup::Corrected(&tags[0], "billing")
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_match_suggests() {
+        let tags = ["bug-1", "bug-2", "billing"];
+        match lookup("bug-3", &tags, names) {
+            Lookup::NotFound(suggestions) => {
+                assert_eq!(suggestions, vec!["bug-1".to_string(), "bug-2".to_string()])
+            }
+            other => panic!("expected suggestions, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_short_names_are_not_guessed() {
+        let tags = ["vip", "bug"];
+        assert!(matches!(lookup("vop", &tags, names), Lookup::NotFound(_)));
+    }
+
+    #[test]
+    fn test_unrelated_name_has_no_suggestions() {
+        let tags = ["urgent", "billing"];
+        assert_eq!(
+            lookup("zzzzzzzz", &tags, names),
+            Lookup::NotFound(Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_matches_any_key() {
+        let agents = [("Alice", "alice@example.com"), ("Bob", "bob@example.com")];
+        let keys = |a: &(&'static str, &'static str)| vec![a.0, a.1];
+        assert_eq!(
+            lookup("bob@exmple.com", &agents, keys),
+            Lookup::Corrected(&agents[1], "bob@example.com")
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use regex::{Regex, RegexBuilder};
+
+/// One line of grep output: its 1-based number, text, and whether it matched
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrepLine<'a> {
+    pub number: usize,
+    pub text: &'a str,
+    pub matched: bool,
+}
+
+/// Lines of `body` matching `pattern`, each with up to `context` lines
+/// around it. Overlapping context is merged; each inner `Vec` is a run of
+/// consecutive lines, so callers can print a separator between runs.
+pub fn grep_lines<'a>(body: &'a str, pattern: &Regex, context: usize) -> Vec<Vec<GrepLine<'a>>> {
+    let lines: Vec<&str> = body.lines().collect();
+    let matches: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| pattern.is_match(line))
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut hunks: Vec<Vec<GrepLine>> = Vec::new();
+    let mut last_shown: Option<usize> = None;
+    for &m in &matches {
+        let start = m.saturating_sub(context);
+        let end = (m + context).min(lines.len() - 1);
+        let start = match last_shown {
+            Some(last) if start <= last + 1 => last + 1,
+            _ => {
+                hunks.push(Vec::new());
+                start
+            }
+        };
+        let hunk = hunks.last_mut().expect("pushed above");
+        for (i, text) in lines.iter().enumerate().take(end + 1).skip(start) {
+            hunk.push(GrepLine {
+                number: i + 1,
+                text,
+                matched: pattern.is_match(text),
+            });
+        }
+        last_shown = Some(last_shown.map_or(end, |last| last.max(end)));
+    }
+    hunks
+}
+
+/// Characters kept before the match in a [`Keywords::snippet`]
+const SNIPPET_LEAD: usize = 15;
+
+/// The words of a `--search` query, matched case-insensitively to check
+/// results and highlight where they matched
+#[derive(Debug, Clone)]
+pub struct Keywords {
+    words: Vec<String>,
+    pub pattern: Regex,
+}
+
+impl Keywords {
+    /// The whitespace-separated words of `query`; `None` if it has none
+    pub fn parse(query: &str) -> Option<Keywords> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        if words.is_empty() {
+            return None;
+        }
+        let alternatives: Vec<String> = words.iter().map(|w| regex::escape(w)).collect();
+        let pattern = RegexBuilder::new(&alternatives.join("|"))
+            .case_insensitive(true)
+            .build()
+            .expect("escaped words form a valid pattern");
+        Some(Keywords { words, pattern })
+    }
+
+    /// Whether `text` contains every word
+    pub fn all_in(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.words.iter().all(|word| text.contains(word.as_str()))
+    }
+
+    /// The first line of `text` containing any of the words, trimmed and
+    /// starting a few words before the match so it shows when cut short
+    pub fn snippet(&self, text: &str) -> Option<String> {
+        let line = text
+            .lines()
+            .map(str::trim)
+            .find(|line| self.pattern.is_match(line))?;
+        let start = self.pattern.find(line).map_or(0, |m| m.start());
+        if line[..start].chars().count() <= SNIPPET_LEAD {
+            return Some(line.to_string());
+        }
+        // Back up to the start of a word within the lead
+        let lead_start = line[..start]
+            .char_indices()
+            .rev()
+            .nth(SNIPPET_LEAD - 1)
+            .map_or(0, |(i, _)| i);
+        let from = line[lead_start..start]
+            .find(' ')
+            .map_or(start, |i| lead_start + i + 1);
+        Some(format!("…{}", &line[from..]))
+    }
+}
+
+impl PartialEq for Keywords {
+    fn eq(&self, other: &Self) -> bool {
+        self.words == other.words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(hunks: &[Vec<GrepLine>]) -> Vec<Vec<usize>> {
+        hunks
+            .iter()
+            .map(|hunk| hunk.iter().map(|l| l.number).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_grep_lines_with_context() {
+        let body = "a\nb\norder 4521\nc\nd\ne\nf\ng\norder 4521 again\nh";
+        let pattern = Regex::new("4521").unwrap();
+        let hunks = grep_lines(body, &pattern, 1);
+        assert_eq!(numbers(&hunks), vec![vec![2, 3, 4], vec![8, 9, 10]]);
+        assert!(hunks[0][1].matched);
+        assert!(!hunks[0][0].matched);
+    }
+
+    #[test]
+    fn test_grep_lines_merges_overlapping_context() {
+        let body = "x\nmatch\ny\nmatch\nz";
+        let pattern = Regex::new("match").unwrap();
+        let hunks = grep_lines(body, &pattern, 1);
+        assert_eq!(numbers(&hunks), vec![vec![1, 2, 3, 4, 5]]);
+    }
+
+    #[test]
+    fn test_grep_lines_no_context() {
+        let body = "one\ntwo\nthree";
+        let pattern = Regex::new("t").unwrap();
+        assert_eq!(numbers(&grep_lines(body, &pattern, 0)), vec![vec![2, 3]]);
+    }
+
+    #[test]
+    fn test_grep_lines_no_match() {
+        let pattern = Regex::new("missing").unwrap();
+        assert!(grep_lines("one\ntwo", &pattern, 2).is_empty());
+        assert!(grep_lines("", &pattern, 2).is_empty());
+    }
+
+    #[test]
+    fn test_keywords() {
+        let keywords = Keywords::parse("  Refund  order.4521 ").unwrap();
+        assert!(keywords.all_in("Your REFUND for order.4521"));
+        assert!(!keywords.all_in("Your refund for order 4521"));
+        assert_eq!(
+            keywords.snippet("Hi,\n  a refund is on its way  \nThanks"),
+            Some("a refund is on its way".to_string())
+        );
+        assert_eq!(
+            keywords.snippet("My parcel arrived damaged and I'd like a refund please"),
+            Some("…I'd like a refund please".to_string())
+        );
+        assert_eq!(keywords.snippet("nothing here"), None);
+        assert!(Keywords::parse("   ").is_none());
+    }
+}
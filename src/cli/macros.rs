@@ -0,0 +1,53 @@
+use crate::error::{GrooveError, Result};
+
+/// Turn one macro step (a `conversation` subcommand such as `add-tag resolved`)
+/// into a full argument list targeting the given conversation number.
+///
+/// The number is inserted directly after the subcommand name so steps with
+/// further positional arguments (tags, reply bodies) keep working.
+pub fn macro_step_args(step: &str, number: i64) -> Result<Vec<String>> {
+    let mut words = shlex::split(step)
+        .filter(|words| !words.is_empty())
+        .ok_or_else(|| GrooveError::Config(format!("Invalid macro step: '{}'", step)))?;
+
+    let subcommand = words.remove(0);
+    let mut args = vec![
+        "groove".to_string(),
+        "conversation".to_string(),
+        subcommand,
+        number.to_string(),
+    ];
+    args.extend(words);
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_macro_step_args() {
+        assert_eq!(
+            macro_step_args("add-tag resolved vip", 42).unwrap(),
+            vec!["groove", "conversation", "add-tag", "42", "resolved", "vip"]
+        );
+        assert_eq!(
+            macro_step_args("close", 42).unwrap(),
+            vec!["groove", "conversation", "close", "42"]
+        );
+    }
+
+    #[test]
+    fn test_macro_step_args_quoted() {
+        assert_eq!(
+            macro_step_args("note 'Handled by macro'", 7).unwrap(),
+            vec!["groove", "conversation", "note", "7", "Handled by macro"]
+        );
+    }
+
+    #[test]
+    fn test_macro_step_args_invalid() {
+        assert!(macro_step_args("", 1).is_err());
+        assert!(macro_step_args("note 'unterminated", 1).is_err());
+    }
+}
@@ -0,0 +1,59 @@
+/// `@name` / `@email` tokens in a note body, without the `@`, in order of
+/// first appearance. An `@` only starts a mention at the beginning of the
+/// text or after whitespace or an opening bracket, so plain email addresses
+/// in the body aren't mistaken for mentions.
+pub fn mention_tokens(body: &str) -> Vec<&str> {
+    let mut tokens: Vec<&str> = Vec::new();
+    let mut prev: Option<char> = None;
+    for (i, c) in body.char_indices() {
+        let starts_mention = c == '@'
+            && prev.is_none_or(|p| p.is_whitespace() || matches!(p, '(' | '[' | '{' | '"' | '\''));
+        prev = Some(c);
+        if !starts_mention {
+            continue;
+        }
+
+        let rest = &body[i + 1..];
+        let end = rest
+            .find(|ch: char| !(ch.is_alphanumeric() || matches!(ch, '.' | '_' | '-' | '+' | '@')))
+            .unwrap_or(rest.len());
+        // Sentence punctuation isn't part of the name: "thanks @alice."
+        let token = rest[..end].trim_end_matches(['.', '-', '@']);
+        if !token.is_empty() && !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mention_tokens() {
+        assert_eq!(mention_tokens("@alice can you check this?"), vec!["alice"]);
+        assert_eq!(
+            mention_tokens("cc @bob@example.com and @carol."),
+            vec!["bob@example.com", "carol"]
+        );
+    }
+
+    #[test]
+    fn test_mention_tokens_ignores_email_addresses() {
+        assert!(mention_tokens("Customer wrote from jane@example.com").is_empty());
+    }
+
+    #[test]
+    fn test_mention_tokens_dedupes() {
+        assert_eq!(
+            mention_tokens("@alice, (@alice) @dave"),
+            vec!["alice", "dave"]
+        );
+    }
+
+    #[test]
+    fn test_mention_tokens_bare_at() {
+        assert!(mention_tokens("meet @ 3pm").is_empty());
+    }
+}
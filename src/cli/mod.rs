@@ -1,8 +1,44 @@
+mod alias;
+mod browser;
+mod catalog;
+mod changes;
+mod clipboard;
 mod commands;
+mod complete;
+mod fuzzy;
+mod grep;
+mod macros;
+mod mentions;
+mod numbers;
 mod output;
+mod plugin;
+mod query;
+mod quotes;
+mod shell;
+mod term;
+mod theme;
 
+pub use alias::{expand_aliases, AliasExpansion};
+pub use browser::open_in_browser;
+pub use catalog::{set_locale, tr, Locale, Msg};
+pub use changes::{Change, Changes, Snapshot};
+pub use clipboard::copy_to_clipboard;
 pub use commands::{
-    print_completions, CannedRepliesAction, Cli, Commands, ConfigAction, ConversationAction,
-    FolderAction, OutputFormat, TagAction,
+    print_completions, BulkAction, BulkArgs, BulkFilter, BulkOptions, CannedRepliesAction, Cli,
+    Commands, ConfigAction, ContactAction, ConversationAction, FolderAction, GroupBy,
+    MessageAction, MyScope, NoteAction, OutboxAction, OutputFormat, RemindAction, ReportAction,
+    RuleAction, SearchIn, SnippetAction, TableArgs, TagAction, TagArgs, DEFAULT_CONCURRENCY,
 };
+pub use complete::completion_values;
+pub use fuzzy::{lookup, Lookup};
+pub use grep::{grep_lines, GrepLine, Keywords};
+pub use macros::macro_step_args;
+pub use mentions::mention_tokens;
+pub use numbers::{expand_numbers, read_numbers, split_numbers, NumberList, MAX_RANGE_LEN};
 pub use output::*;
+pub use plugin::{find_plugin, global_option_value, Plugin, PLUGIN_PREFIX};
+pub use query::parse_query;
+pub use quotes::split_quoted;
+pub use shell::shell_command;
+pub use term::terminal_width;
+pub use theme::{format_timestamp, set_theme, DateFormat, TableStyle, Theme};
@@ -0,0 +1,153 @@
+use std::io::BufRead;
+use std::str::FromStr;
+
+/// Largest number of conversations a single range may expand to
+pub const MAX_RANGE_LEN: i64 = 10_000;
+
+/// Conversation numbers given as `123`, `1200-1250`, or a comma list of those
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberList(pub Vec<i64>);
+
+impl FromStr for NumberList {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut numbers = Vec::new();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match part.split_once('-') {
+                Some((start, end)) => {
+                    let start = parse_number(start)?;
+                    let end = parse_number(end)?;
+                    if start > end {
+                        return Err(format!("Invalid range '{}': start is after end", part));
+                    }
+                    if end - start >= MAX_RANGE_LEN {
+                        return Err(format!(
+                            "Range '{}' is too large (max {} conversations)",
+                            part, MAX_RANGE_LEN
+                        ));
+                    }
+                    numbers.extend(start..=end);
+                }
+                None => numbers.push(parse_number(part)?),
+            }
+        }
+
+        if numbers.is_empty() {
+            return Err(format!("No conversation numbers in '{}'", s));
+        }
+        Ok(NumberList(numbers))
+    }
+}
+
+fn parse_number(s: &str) -> Result<i64, String> {
+    let s = s.trim();
+    match s.parse::<i64>() {
+        Ok(n) if n > 0 => Ok(n),
+        Ok(_) => Err(format!("Conversation number must be positive, got: {}", s)),
+        Err(_) => Err(format!("Invalid conversation number: '{}'", s)),
+    }
+}
+
+/// Flatten number lists in order, dropping repeats
+pub fn expand_numbers(lists: &[NumberList]) -> Vec<i64> {
+    let mut seen = std::collections::HashSet::new();
+    lists
+        .iter()
+        .flat_map(|list| list.0.iter().copied())
+        .filter(|n| seen.insert(*n))
+        .collect()
+}
+
+/// Split `args` into the leading conversation numbers (number lists, or `-`
+/// for numbers on stdin) and what follows them
+pub fn split_numbers(args: &[String]) -> (&[String], &[String]) {
+    let count = args
+        .iter()
+        .take_while(|arg| *arg == "-" || arg.parse::<NumberList>().is_ok())
+        .count();
+    args.split_at(count)
+}
+
+/// Conversation numbers read one per line, as the first word of the line
+/// with an optional `#`, so `conversation list -o compact` output can be
+/// piped in. Blank lines are skipped.
+pub fn read_numbers(reader: impl BufRead) -> Result<Vec<i64>, String> {
+    let mut numbers = Vec::new();
+    for line in reader.lines() {
+        let line = line.map_err(|e| format!("Could not read conversation numbers: {}", e))?;
+        let Some(word) = line.split_whitespace().next() else {
+            continue;
+        };
+        numbers.extend(word.trim_start_matches('#').parse::<NumberList>()?.0);
+    }
+    Ok(numbers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<Vec<i64>, String> {
+        s.parse::<NumberList>().map(|list| list.0)
+    }
+
+    #[test]
+    fn test_single_number() {
+        assert_eq!(parse("123"), Ok(vec![123]));
+    }
+
+    #[test]
+    fn test_range() {
+        assert_eq!(parse("1200-1203"), Ok(vec![1200, 1201, 1202, 1203]));
+        assert_eq!(parse("5-5"), Ok(vec![5]));
+    }
+
+    #[test]
+    fn test_comma_list() {
+        assert_eq!(parse("1,3,10-12"), Ok(vec![1, 3, 10, 11, 12]));
+        assert_eq!(parse("1, 2,"), Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(parse("abc").is_err());
+        assert!(parse("0").is_err());
+        assert!(parse("10-5").unwrap_err().contains("start is after end"));
+        assert!(parse("1-").is_err());
+        assert!(parse("-5").is_err());
+        assert!(parse(",").is_err());
+        assert!(parse("1-100000").unwrap_err().contains("too large"));
+    }
+
+    #[test]
+    fn test_expand_numbers_dedupes() {
+        let lists = vec![
+            NumberList(vec![3, 1]),
+            NumberList(vec![1, 2]),
+            NumberList(vec![3]),
+        ];
+        assert_eq!(expand_numbers(&lists), vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn test_split_numbers() {
+        let args: Vec<String> = ["12", "1200-1202", "-", "urgent", "7"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let (numbers, rest) = split_numbers(&args);
+        assert_eq!(numbers, ["12", "1200-1202", "-"]);
+        assert_eq!(rest, ["urgent", "7"]);
+    }
+
+    #[test]
+    fn test_read_numbers() {
+        let input = "#1001 [open] Refund - jane@customer.com\n\n1002\n1200-1201\n";
+        assert_eq!(
+            read_numbers(input.as_bytes()),
+            Ok(vec![1001, 1002, 1200, 1201])
+        );
+        assert!(read_numbers("refund\n".as_bytes()).is_err());
+    }
+}
@@ -1,44 +1,583 @@
-use crate::api::ConversationsResponse;
-use crate::cli::OutputFormat;
+use crate::api::{ConversationsResponse, RateLimitInfo};
+use crate::cli::catalog::Msg;
+use crate::cli::changes::{highlight_arrived, highlight_assignee, highlight_state, Changes};
+use crate::cli::grep::{grep_lines, Keywords};
+use crate::cli::quotes::split_quoted;
+use crate::cli::theme::{
+    configured_date, icons, iso_dates, link_conversation, link_email, state_color, styled,
+};
+use crate::cli::{format_timestamp, terminal_width, GroupBy, OutputFormat};
+use crate::outbox::{QueuedReply, ReplyOptions};
+use crate::reminders::Reminder;
+use crate::report::ChannelStats;
+use crate::seen::Seen;
+use crate::sla::{ResponseTimes, Sla};
+use crate::snippets::Snippet;
+use crate::store::{SearchHit, SEARCH_MATCH_END, SEARCH_MATCH_START};
 use crate::types::*;
 use chrono::{DateTime, Utc};
 use colored::Colorize;
-use tabled::settings::Style;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use tabled::builder::Builder;
 use tabled::{Table, Tabled};
 
-#[derive(Tabled)]
-struct ConversationRow {
-    #[tabled(rename = "#")]
-    number: i64,
-    #[tabled(rename = "Status")]
-    status: String,
-    #[tabled(rename = "Subject")]
-    subject: String,
-    #[tabled(rename = "From")]
-    from: String,
-    #[tabled(rename = "Updated")]
-    updated: String,
+/// A column of the conversation table, chosen with `[defaults.list] columns`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Number,
+    Status,
+    Subject,
+    From,
+    Assignee,
+    Tags,
+    Channel,
+    Messages,
+    Created,
+    Updated,
+    SnoozedUntil,
+    Waiting,
+    Idle,
+    Match,
 }
 
-impl ConversationRow {
-    fn from_conversation(conv: &Conversation) -> Self {
-        let status = format_state(&conv.state);
-        let subject = truncate(conv.subject.as_deref().unwrap_or("(no subject)"), 40);
-        let contact = conv
-            .contact
-            .as_ref()
-            .and_then(|c| c.email.as_deref().or(c.name.as_deref()))
-            .unwrap_or("unknown");
-        let updated = format_relative_time(&conv.updated_at);
+impl Column {
+    /// The columns shown unless configured otherwise
+    pub const DEFAULT: &'static [Column] = &[
+        Column::Number,
+        Column::Status,
+        Column::Subject,
+        Column::From,
+        Column::Updated,
+    ];
+
+    const ALL: &'static [Column] = &[
+        Column::Number,
+        Column::Status,
+        Column::Subject,
+        Column::From,
+        Column::Assignee,
+        Column::Tags,
+        Column::Channel,
+        Column::Messages,
+        Column::Created,
+        Column::Updated,
+        Column::SnoozedUntil,
+        Column::Waiting,
+        Column::Idle,
+        Column::Match,
+    ];
+
+    /// Name in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Number => "number",
+            Column::Status => "status",
+            Column::Subject => "subject",
+            Column::From => "from",
+            Column::Assignee => "assignee",
+            Column::Tags => "tags",
+            Column::Channel => "channel",
+            Column::Messages => "messages",
+            Column::Created => "created",
+            Column::Updated => "updated",
+            Column::SnoozedUntil => "snoozed_until",
+            Column::Waiting => "waiting",
+            Column::Idle => "idle",
+            Column::Match => "match",
+        }
+    }
+
+    fn header(self) -> &'static str {
+        match self {
+            Column::Number => "#",
+            Column::Status if icons() => Msg::HeaderStatusIcon.text(),
+            Column::Status => Msg::HeaderStatus.text(),
+            Column::Subject => Msg::HeaderSubject.text(),
+            Column::From => Msg::HeaderFrom.text(),
+            Column::Assignee => Msg::HeaderAssignee.text(),
+            Column::Tags => Msg::HeaderTags.text(),
+            Column::Channel => Msg::HeaderChannel.text(),
+            Column::Messages => Msg::HeaderMessages.text(),
+            Column::Created => Msg::HeaderCreated.text(),
+            Column::Updated => Msg::HeaderUpdated.text(),
+            Column::SnoozedUntil => Msg::HeaderSnoozedUntil.text(),
+            Column::Waiting => Msg::HeaderWaiting.text(),
+            Column::Idle => Msg::HeaderIdle.text(),
+            Column::Match => Msg::HeaderMatch.text(),
+        }
+    }
+
+    fn cell(self, conv: &Conversation) -> String {
+        let value = self.value(conv);
+        match self {
+            Column::Status => in_state_color(&conv.state, status_label(&conv.state)),
+            Column::From => truncate(&value, 25),
+            Column::Tags => truncate(&value, 30),
+            Column::Assignee | Column::Channel => truncate(&value, 20),
+            _ => value,
+        }
+    }
+
+    /// The column's value for `conv`, uncolored and in full
+    fn value(self, conv: &Conversation) -> String {
+        match self {
+            Column::Number => conv.number.to_string(),
+            Column::Status => format_state(&conv.state),
+            Column::Subject => conv
+                .subject
+                .clone()
+                .unwrap_or_else(|| "(no subject)".to_string()),
+            Column::From => conv
+                .contact
+                .as_ref()
+                .and_then(|c| c.email.as_deref().or(c.name.as_deref()))
+                .unwrap_or("unknown")
+                .to_string(),
+            Column::Assignee => conv
+                .assigned
+                .as_ref()
+                .map(|a| a.name.clone().unwrap_or_else(|| a.email.clone()))
+                .unwrap_or_else(|| "-".to_string()),
+            Column::Tags if conv.tags.is_empty() => "-".to_string(),
+            Column::Tags => {
+                let names: Vec<&str> = conv.tags.iter().map(|t| t.name.as_str()).collect();
+                names.join(", ")
+            }
+            Column::Channel => conv
+                .channel
+                .as_ref()
+                .and_then(|c| c.name.clone())
+                .unwrap_or_else(|| "-".to_string()),
+            Column::Messages => conv
+                .messages_count
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            Column::Created => format_relative_time(&conv.created_at),
+            Column::Updated => format_relative_time(&conv.updated_at),
+            Column::SnoozedUntil => conv
+                .snoozed_until
+                .as_ref()
+                .map(format_wake_time)
+                .unwrap_or_else(|| "-".to_string()),
+            // Filled in by `ListDisplay` from the conversation's messages
+            Column::Waiting | Column::Idle | Column::Match => "-".to_string(),
+        }
+    }
+}
+
+impl std::str::FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase().replace('-', "_");
+        Column::ALL
+            .iter()
+            .copied()
+            .find(|c| c.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Column::ALL.iter().map(|c| c.name()).collect();
+                format!("Invalid column: {}. Use {}", s, names.join(", "))
+            })
+    }
+}
+
+/// A `-o compact` line for conversations, like
+/// `#{number} [{state}] {subject} <{contact}>`. Fields are the column names,
+/// plus `state` and `contact` for `status` and `from`; `{{` and `}}` are
+/// literal braces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactFormat(Vec<CompactPiece>);
+
+#[derive(Debug, Clone, PartialEq)]
+enum CompactPiece {
+    Text(String),
+    Field(Column),
+}
 
+impl CompactFormat {
+    fn uses(&self, column: Column) -> bool {
+        self.0.contains(&CompactPiece::Field(column))
+    }
+
+    fn render(&self, display: &ListDisplay, conv: &Conversation) -> String {
+        self.0
+            .iter()
+            .map(|piece| match piece {
+                CompactPiece::Text(text) => text.clone(),
+                CompactPiece::Field(column) => display.value(*column, conv),
+            })
+            .collect()
+    }
+}
+
+impl std::str::FromStr for CompactFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(format!("Unclosed '{{{}'", name)),
+                        }
+                    }
+                    let column = match name.trim() {
+                        "state" => Column::Status,
+                        "contact" => Column::From,
+                        other => other.parse().map_err(|_| {
+                            let names: Vec<&str> = ["state", "contact"]
+                                .into_iter()
+                                .chain(Column::ALL.iter().map(|c| c.name()))
+                                .collect();
+                            format!("Unknown field {{{}}}. Use {}", name, names.join(", "))
+                        })?,
+                    };
+                    if !text.is_empty() {
+                        pieces.push(CompactPiece::Text(std::mem::take(&mut text)));
+                    }
+                    pieces.push(CompactPiece::Field(column));
+                }
+                '}' => return Err("Unmatched '}'; write '}}' for a literal brace".to_string()),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            pieces.push(CompactPiece::Text(text));
+        }
+        Ok(CompactFormat(pieces))
+    }
+}
+
+/// How conversation lists are laid out
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListDisplay {
+    pub columns: Vec<Column>,
+    pub width: TableWidth,
+    /// `-o compact` line; `None` for the built-in one
+    pub compact_format: Option<CompactFormat>,
+    /// Conversations viewed here, to mark new activity
+    pub seen: Option<Seen>,
+    /// What changed since the previous refresh, for `list --watch`
+    pub changes: Changes,
+    /// Response-time target and waiting times, to flag breaches
+    pub sla: Option<Sla>,
+    /// When the newest message in each conversation was sent, for the idle
+    /// column
+    pub last_message: HashMap<i64, DateTime<Utc>>,
+    /// Words searched for, highlighted in subjects and matches
+    pub search: Option<Keywords>,
+    /// The message line each conversation matched the search on, for the
+    /// match column
+    pub matches: HashMap<i64, String>,
+}
+
+impl Default for ListDisplay {
+    fn default() -> Self {
         Self {
-            number: conv.number,
-            status: format!("{}", status.color(state_color_str(&conv.state))),
-            subject,
-            from: truncate(contact, 25),
-            updated,
+            columns: Column::DEFAULT.to_vec(),
+            width: TableWidth::default(),
+            compact_format: None,
+            seen: None,
+            changes: Changes::default(),
+            sla: None,
+            last_message: HashMap::new(),
+            search: None,
+            matches: HashMap::new(),
+        }
+    }
+}
+
+impl ListDisplay {
+    /// Whether conversations show `column`, in the table or compact line
+    pub fn shows(&self, column: Column) -> bool {
+        self.columns.contains(&column)
+            || self
+                .compact_format
+                .as_ref()
+                .is_some_and(|format| format.uses(column))
+    }
+
+    /// A column's uncolored value, with waiting times from the SLA, idle
+    /// times from the newest messages, and matched lines from the search
+    fn value(&self, column: Column, conv: &Conversation) -> String {
+        match (column, &self.sla) {
+            (Column::Waiting, Some(sla)) => sla
+                .waiting
+                .get(&conv.number)
+                .map(format_age)
+                .unwrap_or_else(|| "-".to_string()),
+            (Column::Idle, _) => self
+                .last_message
+                .get(&conv.number)
+                .map(format_age)
+                .unwrap_or_else(|| "-".to_string()),
+            (Column::Match, _) => self
+                .matches
+                .get(&conv.number)
+                .cloned()
+                .unwrap_or_else(|| "-".to_string()),
+            _ => column.value(conv),
+        }
+    }
+
+    /// A table cell, with a badge next to the number of conversations with
+    /// news since they were last viewed
+    fn cell(&self, column: Column, conv: &Conversation) -> String {
+        let mut cell = match column {
+            Column::Waiting | Column::Idle => self.value(column, conv),
+            Column::Match => self.highlight(&truncate(&self.value(column, conv), 40)),
+            _ => column.cell(conv),
+        };
+        // Conversations past the SLA are red, apart from their state
+        if column != Column::Status
+            && self
+                .sla
+                .as_ref()
+                .is_some_and(|sla| sla.is_breaching(conv.number, Utc::now()))
+        {
+            cell = cell.red().bold().to_string();
+        }
+        if let Some(change) = self.changes.get(conv.number) {
+            // Assignment changes show on the number when there's no
+            // assignee column
+            let assignee_shown = self.columns.contains(&Column::Assignee);
+            cell = match column {
+                Column::Number if change.arrived => highlight_arrived(&cell),
+                Column::Number if change.assignee && !assignee_shown => highlight_assignee(&cell),
+                Column::Status if change.state => highlight_state(&status_label(&conv.state)),
+                Column::Assignee if change.assignee => highlight_assignee(&cell),
+                _ => cell,
+            };
+        }
+        cell = match column {
+            Column::Number => link_conversation(conv.number, &cell),
+            Column::From => match conv.contact.as_ref().and_then(|c| c.email.as_deref()) {
+                Some(email) => link_email(email, &cell),
+                None => cell,
+            },
+            _ => cell,
+        };
+        match &self.seen {
+            Some(seen) if column == Column::Number && seen.has_news(conv) => {
+                format!("{} {}", cell, "NEW".cyan().bold())
+            }
+            _ => cell,
         }
     }
+
+    /// `text` with the searched-for words in yellow
+    fn highlight(&self, text: &str) -> String {
+        match &self.search {
+            Some(keywords) => keywords
+                .pattern
+                .replace_all(text, |m: &regex::Captures| m[0].yellow().bold().to_string())
+                .into_owned(),
+            None => text.to_string(),
+        }
+    }
+
+    /// One line per conversation for `-o compact`
+    fn compact_line(&self, conv: &Conversation) -> String {
+        match &self.compact_format {
+            Some(format) => format.render(self, conv),
+            None => compact_line(conv),
+        }
+    }
+}
+
+/// How a conversation table uses the terminal's width
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TableWidth {
+    /// Fit the terminal when printing to one
+    #[default]
+    Auto,
+    /// Every column in full, however wide
+    Wide,
+    /// Only the number, status, and subject, fitted to the terminal
+    Narrow,
+}
+
+/// Subject width when the terminal's width isn't known
+const SUBJECT_WIDTH: usize = 40;
+
+/// Narrowest the subject is squeezed to before columns are dropped
+const MIN_SUBJECT_WIDTH: usize = 20;
+
+/// Width assumed for `--narrow` when the terminal's isn't known
+const NARROW_WIDTH: usize = 80;
+
+/// Columns kept by `--narrow`
+const NARROW_COLUMNS: &[Column] = &[Column::Number, Column::Status, Column::Subject];
+
+/// Columns dropped to fit the terminal, first to last
+const DROP_ORDER: &[Column] = &[
+    Column::Match,
+    Column::Channel,
+    Column::Created,
+    Column::Tags,
+    Column::Assignee,
+    Column::Messages,
+    Column::From,
+    Column::Updated,
+    Column::SnoozedUntil,
+];
+
+/// Print conversations as a table laid out per `display`, or as compact lines
+/// when even the essential columns don't fit the terminal
+fn print_conversation_table<'a>(
+    conversations: impl IntoIterator<Item = &'a Conversation>,
+    display: &ListDisplay,
+) {
+    let conversations: Vec<&Conversation> = conversations.into_iter().collect();
+    let mut columns = display.columns.clone();
+    let max_width = match display.width {
+        TableWidth::Auto => terminal_width(),
+        TableWidth::Wide => None,
+        TableWidth::Narrow => {
+            columns.retain(|c| NARROW_COLUMNS.contains(c));
+            if columns.is_empty() {
+                columns = NARROW_COLUMNS.to_vec();
+            }
+            Some(terminal_width().unwrap_or(NARROW_WIDTH))
+        }
+    };
+    let subject_width = match (display.width, max_width) {
+        (TableWidth::Wide, _) => usize::MAX,
+        (_, None) => SUBJECT_WIDTH,
+        (_, Some(max)) => match fit_columns(display, &conversations, &mut columns, max) {
+            Some(width) => width,
+            None => {
+                for conv in conversations {
+                    println!("{}", display.compact_line(conv));
+                }
+                return;
+            }
+        },
+    };
+    println!(
+        "{}",
+        conversation_table(display, &conversations, &columns, subject_width)
+    );
+}
+
+/// Drop columns in [`DROP_ORDER`] until the table fits in `max` characters.
+/// Returns how wide the subject can be, or `None` if nothing droppable is
+/// left and it still doesn't fit.
+fn fit_columns(
+    display: &ListDisplay,
+    conversations: &[&Conversation],
+    columns: &mut Vec<Column>,
+    max: usize,
+) -> Option<usize> {
+    loop {
+        // Bordered tables take one character on the left plus " x │" per
+        // column; psql's are a character narrower
+        let fixed: usize = 1 + columns
+            .iter()
+            .map(|&c| match c {
+                Column::Subject => 3,
+                c => column_width(display, c, conversations) + 3,
+            })
+            .sum::<usize>();
+        if !columns.contains(&Column::Subject) {
+            if fixed <= max {
+                return Some(0);
+            }
+        } else {
+            let natural = column_width(display, Column::Subject, conversations);
+            let room = max.saturating_sub(fixed);
+            if room >= natural.min(MIN_SUBJECT_WIDTH) {
+                return Some(room.min(natural));
+            }
+        }
+        let drop = DROP_ORDER.iter().find(|c| columns.contains(c))?;
+        columns.retain(|c| c != drop);
+    }
+}
+
+/// Widest cell or header of a column
+fn column_width(display: &ListDisplay, column: Column, conversations: &[&Conversation]) -> usize {
+    conversations
+        .iter()
+        .map(|conv| visible_width(&display.cell(column, conv)))
+        .chain([column.header().chars().count()])
+        .max()
+        .unwrap_or(0)
+}
+
+/// Characters a string takes on screen, not counting color codes or
+/// hyperlink targets
+fn visible_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            match chars.next() {
+                // OSC 8 hyperlink, up to the closing ESC \
+                Some(']') => {
+                    chars.by_ref().find(|&c| c == '\x1b');
+                    chars.next();
+                }
+                _ => {
+                    chars.by_ref().find(|&c| c == 'm');
+                }
+            }
+        } else if c >= '\u{1F300}' {
+            // Emoji such as 💤 take two columns
+            width += 2;
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+/// A table of conversations with one column per entry in `columns` and
+/// subjects cut to `subject_width`
+fn conversation_table(
+    display: &ListDisplay,
+    conversations: &[&Conversation],
+    columns: &[Column],
+    subject_width: usize,
+) -> String {
+    let mut builder = Builder::default();
+    builder.push_record(columns.iter().map(|c| c.header()));
+    for conv in conversations {
+        builder.push_record(columns.iter().map(|&c| match c {
+            Column::Subject => display.highlight(&truncate(&c.cell(conv), subject_width)),
+            c => display.cell(c, conv),
+        }));
+    }
+    styled(builder.build())
+}
+
+/// The built-in `-o compact` line for a conversation
+fn compact_line(conv: &Conversation) -> String {
+    let status = format!("[{}]", conv.state);
+    let subject = conv.subject.as_deref().unwrap_or("(no subject)");
+    let contact = conv
+        .contact
+        .as_ref()
+        .and_then(|c| c.email.as_deref())
+        .unwrap_or("unknown");
+    format!("#{} {} {} - {}", conv.number, status, subject, contact)
 }
 
 #[derive(Tabled)]
@@ -78,6 +617,56 @@ impl From<&Tag> for TagRow {
     }
 }
 
+#[derive(Tabled)]
+struct AgentRow {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Email")]
+    email: String,
+    #[tabled(rename = "ID")]
+    id: String,
+}
+
+impl From<&Agent> for AgentRow {
+    fn from(agent: &Agent) -> Self {
+        Self {
+            name: agent.name.as_deref().unwrap_or("-").to_string(),
+            email: agent.email.clone(),
+            id: agent.id.clone(),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct NoteRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Author")]
+    author: String,
+    #[tabled(rename = "Note")]
+    body: String,
+    #[tabled(rename = "Created")]
+    created: String,
+}
+
+impl From<&Note> for NoteRow {
+    fn from(note: &Note) -> Self {
+        Self {
+            id: note.id.clone(),
+            author: truncate(note_author(note), 20),
+            body: truncate(&single_line(note.body.as_deref().unwrap_or("")), 50),
+            created: format_relative_time(&note.created_at),
+        }
+    }
+}
+
+fn note_author(note: &Note) -> &str {
+    note.author
+        .as_ref()
+        .and_then(|a| a.name.as_deref().or(a.email.as_deref()))
+        .unwrap_or("unknown")
+}
+
 #[derive(Tabled)]
 struct CannedReplyRow {
     #[tabled(rename = "Name")]
@@ -98,7 +687,212 @@ impl From<&CannedReply> for CannedReplyRow {
     }
 }
 
-pub fn format_conversations(response: &ConversationsResponse, format: &OutputFormat) {
+#[derive(Tabled)]
+struct ContactRow {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Email")]
+    email: String,
+    #[tabled(rename = "ID")]
+    id: String,
+}
+
+impl From<&Contact> for ContactRow {
+    fn from(contact: &Contact) -> Self {
+        Self {
+            name: contact.name.as_deref().unwrap_or("-").to_string(),
+            email: contact.email.as_deref().unwrap_or("-").to_string(),
+            id: contact.id.clone(),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct ArticleRow {
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "URL")]
+    url: String,
+}
+
+impl From<&KbArticle> for ArticleRow {
+    fn from(article: &KbArticle) -> Self {
+        Self {
+            title: article.title.clone(),
+            url: article.url.as_deref().unwrap_or("-").to_string(),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct RuleRow {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Actions")]
+    actions: String,
+    #[tabled(rename = "Updated")]
+    updated: String,
+    #[tabled(rename = "ID")]
+    id: String,
+}
+
+impl From<&Rule> for RuleRow {
+    fn from(rule: &Rule) -> Self {
+        Self {
+            name: rule.name.clone(),
+            status: rule_status(rule),
+            actions: truncate(
+                &rule
+                    .actions
+                    .iter()
+                    .map(describe_rule_step)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                50,
+            ),
+            updated: rule
+                .updated_at
+                .as_ref()
+                .map(format_relative_time)
+                .unwrap_or_else(|| "-".to_string()),
+            id: rule.id.clone(),
+        }
+    }
+}
+
+fn rule_status(rule: &Rule) -> String {
+    if rule.enabled {
+        "enabled".green().to_string()
+    } else {
+        "disabled".dimmed().to_string()
+    }
+}
+
+/// `SUBJECT`, `CONTAINS`, and `refund` as `subject contains "refund"`
+fn describe_rule_condition(condition: &RuleCondition) -> String {
+    let mut text = format!(
+        "{} {}",
+        humanize_enum(&condition.param),
+        humanize_enum(&condition.operator)
+    );
+    if let Some(value) = &condition.value {
+        text.push_str(&format!(" \"{}\"", value));
+    }
+    text
+}
+
+/// `ADD_TAG` and `billing` as `add tag billing`
+fn describe_rule_step(step: &RuleStep) -> String {
+    match &step.value {
+        Some(value) => format!("{} {}", humanize_enum(&step.kind), value),
+        None => humanize_enum(&step.kind),
+    }
+}
+
+fn humanize_enum(value: &str) -> String {
+    value.to_lowercase().replace('_', " ")
+}
+
+/// What a change did to one conversation, printed with `-o json`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionResult {
+    pub action: &'static str,
+    pub conversation_id: String,
+    pub number: i64,
+    /// State after the change, for actions that set one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<ConversationState>,
+    /// Agent assigned, or added to or removed from the followers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snoozed_until: Option<String>,
+    /// Tags added or removed
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tag_ids: Vec<String>,
+    /// The reply sent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    /// Customer the conversation was moved to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_id: Option<String>,
+}
+
+impl ActionResult {
+    pub fn new(action: &'static str, conv: &Conversation) -> Self {
+        ActionResult {
+            action,
+            conversation_id: conv.id.clone(),
+            number: conv.number,
+            state: None,
+            agent_id: None,
+            snoozed_until: None,
+            tag_ids: Vec::new(),
+            message_id: None,
+            contact_id: None,
+        }
+    }
+}
+
+/// Outcome of a bulk command for one conversation
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkResult {
+    pub number: i64,
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<ActionResult>,
+}
+
+impl BulkResult {
+    pub fn succeeded(number: i64, result: ActionResult) -> Self {
+        Self {
+            number,
+            error: None,
+            result: Some(result),
+        }
+    }
+
+    pub fn failed(number: i64, error: &impl std::fmt::Display) -> Self {
+        Self {
+            number,
+            error: Some(error.to_string()),
+            result: None,
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct BulkResultRow {
+    #[tabled(rename = "#")]
+    number: i64,
+    #[tabled(rename = "Result")]
+    result: String,
+    #[tabled(rename = "Error")]
+    error: String,
+}
+
+impl From<&BulkResult> for BulkResultRow {
+    fn from(result: &BulkResult) -> Self {
+        Self {
+            number: result.number,
+            result: if result.error.is_some() {
+                "failed".red().to_string()
+            } else {
+                "ok".green().to_string()
+            },
+            error: result.error.clone().unwrap_or_default(),
+        }
+    }
+}
+
+pub fn format_conversations(
+    response: &ConversationsResponse,
+    format: &OutputFormat,
+    display: &ListDisplay,
+) {
     match format {
         OutputFormat::Json => {
             println!(
@@ -108,31 +902,60 @@ pub fn format_conversations(response: &ConversationsResponse, format: &OutputFor
         }
         OutputFormat::Compact => {
             for conv in &response.nodes {
-                let status = format!("[{}]", conv.state);
+                println!("{}", display.compact_line(conv));
+            }
+        }
+        OutputFormat::Table => {
+            print_conversation_table(&response.nodes, display);
+            println!(
+                "\nShowing {} of {} conversations",
+                response.nodes.len(),
+                response.total_count
+            );
+
+            if response.page_info.has_next_page {
+                if let Some(cursor) = &response.page_info.end_cursor {
+                    println!("Next page: --after {}", cursor);
+                }
+            }
+        }
+    }
+}
+
+/// Like `format_conversations`, with a column for when each conversation
+/// wakes up if the display doesn't have one
+pub fn format_snoozed_conversations(
+    response: &ConversationsResponse,
+    format: &OutputFormat,
+    display: &ListDisplay,
+) {
+    match format {
+        OutputFormat::Json => format_conversations(response, format, display),
+        OutputFormat::Compact if display.compact_format.is_some() => {
+            format_conversations(response, format, display)
+        }
+        OutputFormat::Compact => {
+            for conv in &response.nodes {
                 let subject = conv.subject.as_deref().unwrap_or("(no subject)");
-                let contact = conv
-                    .contact
+                let until = conv
+                    .snoozed_until
                     .as_ref()
-                    .and_then(|c| c.email.as_deref())
-                    .unwrap_or("unknown");
-                println!("#{} {} {} - {}", conv.number, status, subject, contact);
+                    .map(format_wake_time)
+                    .unwrap_or_else(|| "-".to_string());
+                println!("#{} [until {}] {}", conv.number, until, subject);
             }
         }
         OutputFormat::Table => {
-            let rows: Vec<ConversationRow> = response
-                .nodes
-                .iter()
-                .map(ConversationRow::from_conversation)
-                .collect();
-            let table = Table::new(rows).with(Style::rounded()).to_string();
-
-            println!("{table}");
+            let mut display = display.clone();
+            if !display.columns.contains(&Column::SnoozedUntil) {
+                display.columns.push(Column::SnoozedUntil);
+            }
+            print_conversation_table(&response.nodes, &display);
             println!(
                 "\nShowing {} of {} conversations",
                 response.nodes.len(),
                 response.total_count
             );
-
             if response.page_info.has_next_page {
                 if let Some(cursor) = &response.page_info.end_cursor {
                     println!("Next page: --after {}", cursor);
@@ -142,28 +965,170 @@ pub fn format_conversations(response: &ConversationsResponse, format: &OutputFor
     }
 }
 
-pub fn format_conversation_detail(conv: &Conversation, messages: &[Message], full: bool) {
-    println!("{}", "─".repeat(60).dimmed());
-    println!(
-        "{} #{}",
-        "Conversation".bold(),
-        conv.number.to_string().bold()
-    );
-    println!("{}", "─".repeat(60).dimmed());
+/// Conversations sharing one value of a `--group-by` key
+#[derive(Debug, Serialize)]
+pub struct ConversationGroup<'a> {
+    pub group: String,
+    pub count: usize,
+    pub conversations: Vec<&'a Conversation>,
+}
 
-    if let Some(subject) = &conv.subject {
-        println!("{}: {}", "Subject".dimmed(), subject);
+/// Split conversations by `key`, largest group first. With `GroupBy::Tag` a
+/// conversation appears under each of its tags.
+pub fn group_conversations(
+    conversations: &[Conversation],
+    key: GroupBy,
+) -> Vec<ConversationGroup<'_>> {
+    let mut groups: Vec<ConversationGroup> = Vec::new();
+    for conv in conversations {
+        for name in group_names(conv, key) {
+            match groups.iter_mut().find(|g| g.group == name) {
+                Some(group) => group.conversations.push(conv),
+                None => groups.push(ConversationGroup {
+                    group: name,
+                    count: 0,
+                    conversations: vec![conv],
+                }),
+            }
+        }
     }
+    for group in &mut groups {
+        group.count = group.conversations.len();
+    }
+    groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.group.cmp(&b.group)));
+    groups
+}
 
-    println!(
-        "{}: {}",
-        "Status".dimmed(),
-        format_state(&conv.state).color(state_color_str(&conv.state))
+fn group_names(conv: &Conversation, key: GroupBy) -> Vec<String> {
+    match key {
+        GroupBy::Assignee => vec![conv
+            .assigned
+            .as_ref()
+            .map(|a| a.name.clone().unwrap_or_else(|| a.email.clone()))
+            .unwrap_or_else(|| "(unassigned)".to_string())],
+        GroupBy::Status => vec![format_state(&conv.state)],
+        GroupBy::Tag if conv.tags.is_empty() => vec!["(untagged)".to_string()],
+        GroupBy::Tag => conv.tags.iter().map(|t| t.name.clone()).collect(),
+        GroupBy::Channel => vec![conv
+            .channel
+            .as_ref()
+            .and_then(|c| c.name.clone())
+            .unwrap_or_else(|| "(no channel)".to_string())],
+    }
+}
+
+pub fn format_grouped_conversations(
+    response: &ConversationsResponse,
+    key: GroupBy,
+    format: &OutputFormat,
+    display: &ListDisplay,
+) {
+    let groups = group_conversations(&response.nodes, key);
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&groups).expect("serialization should not fail")
+            );
+        }
+        OutputFormat::Compact => {
+            for group in &groups {
+                println!("{} ({})", group.group, group.count);
+                for conv in &group.conversations {
+                    if display.compact_format.is_some() {
+                        println!("  {}", display.compact_line(conv));
+                        continue;
+                    }
+                    let subject = conv.subject.as_deref().unwrap_or("(no subject)");
+                    println!("  #{} [{}] {}", conv.number, conv.state, subject);
+                }
+            }
+        }
+        OutputFormat::Table => {
+            for (i, group) in groups.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                println!(
+                    "{} {}",
+                    group.group.bold(),
+                    format!("({})", group.count).dimmed()
+                );
+                print_conversation_table(group.conversations.iter().copied(), display);
+            }
+            println!(
+                "\nShowing {} of {} conversations in {} groups",
+                response.nodes.len(),
+                response.total_count,
+                groups.len()
+            );
+            if response.page_info.has_next_page {
+                if let Some(cursor) = &response.page_info.end_cursor {
+                    println!("Next page: --after {}", cursor);
+                }
+            }
+        }
+    }
+}
+
+/// How message bodies are shown in the conversation and message views
+#[derive(Debug, Clone, Default)]
+pub struct MessageDisplay {
+    /// Show whole bodies instead of the first 10 lines
+    pub full: bool,
+    /// Print the raw HTML body instead of the text
+    pub html: bool,
+    /// Keep quoted reply history and signatures instead of hiding them
+    pub show_quoted: bool,
+    /// Newest message first
+    pub reverse: bool,
+    /// One summary line per message, except the numbers in `expand`
+    pub collapse: bool,
+    /// Message numbers (1 = oldest shown) to print in full when collapsed
+    pub expand: Vec<usize>,
+}
+
+/// Print a conversation with its messages. `-o json` prints one
+/// `{"conversation": ..., "messages": [...]}` document, oldest message first.
+pub fn format_conversation_detail(
+    conv: &Conversation,
+    messages: &[Message],
+    format: &OutputFormat,
+    display: &MessageDisplay,
+) {
+    if let OutputFormat::Json = format {
+        let thread = serde_json::json!({ "conversation": conv, "messages": messages });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&thread).expect("serialization should not fail")
+        );
+        return;
+    }
+
+    println!("{}", "─".repeat(60).dimmed());
+    println!(
+        "{} #{}",
+        "Conversation".bold(),
+        link_conversation(conv.number, &conv.number.to_string().bold().to_string())
     );
+    println!("{}", "─".repeat(60).dimmed());
+
+    if let Some(subject) = &conv.subject {
+        println!("{}: {}", "Subject".dimmed(), subject);
+    }
+
+    println!("{}: {}", "Status".dimmed(), colored_state(&conv.state));
+
+    if let Some(until) = &conv.snoozed_until {
+        println!("{}: {}", "Snoozed until".dimmed(), format_wake_time(until));
+    }
 
     if let Some(contact) = &conv.contact {
         let name = contact.name.as_deref().unwrap_or("");
-        let email = contact.email.as_deref().unwrap_or("unknown");
+        let email = match contact.email.as_deref() {
+            Some(email) => link_email(email, email),
+            None => "unknown".to_string(),
+        };
         if name.is_empty() {
             println!("{}: {}", "From".dimmed(), email);
         } else {
@@ -186,21 +1151,111 @@ pub fn format_conversation_detail(conv: &Conversation, messages: &[Message], ful
     println!(
         "{}: {}",
         "Created".dimmed(),
-        conv.created_at.format("%Y-%m-%d %H:%M")
+        format_date(&conv.created_at, "%Y-%m-%d %H:%M")
     );
 
     println!("{}", "─".repeat(60).dimmed());
     println!();
 
-    for (i, msg) in messages.iter().enumerate() {
-        print_message(msg, full);
-        if i < messages.len() - 1 {
-            println!("{}", "─".repeat(60).dimmed());
+    let total = messages.len();
+    let mut numbered: Vec<(usize, &Message)> = messages
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (i + 1, m))
+        .collect();
+    if display.reverse {
+        numbered.reverse();
+    }
+
+    for (number, msg) in numbered {
+        if display.collapse && !display.expand.contains(&number) {
+            print_message_summary(number, msg, display);
+            continue;
+        }
+        println!("{}", message_separator(number, total).dimmed());
+        print_message(msg, display);
+    }
+}
+
+/// `── 3/12 ────…`, 60 columns wide
+fn message_separator(number: usize, total: usize) -> String {
+    let label = format!("── {}/{} ", number, total);
+    let width = label.chars().count();
+    format!("{}{}", label, "─".repeat(60usize.saturating_sub(width)))
+}
+
+/// One line per message for `--collapse`: number, author, time, and the
+/// start of the reply
+fn print_message_summary(number: usize, msg: &Message, display: &MessageDisplay) {
+    let author = msg
+        .author
+        .as_ref()
+        .and_then(|a| a.name.as_deref().or(a.email.as_deref()))
+        .unwrap_or("Unknown");
+    let preview = msg
+        .text()
+        .map(|body| message_preview(&body, display.show_quoted))
+        .unwrap_or_default();
+    println!(
+        "{:>4}  {}  {}  {}",
+        number.to_string().bold(),
+        format_date(&msg.created_at, "%b %d, %H:%M").dimmed(),
+        truncate(author, 20),
+        truncate(&preview, 60)
+    );
+}
+
+/// First non-blank line of the reply, with quoted history skipped
+fn message_preview(body: &str, show_quoted: bool) -> String {
+    let reply = if show_quoted {
+        body
+    } else {
+        split_quoted(body).0
+    };
+    clean_message_body(reply)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("")
+        .to_string()
+}
+
+fn print_message(msg: &Message, display: &MessageDisplay) {
+    print_message_header(msg);
+
+    if display.html {
+        print_raw_html(msg);
+    } else if let Some(body) = msg.text() {
+        let (reply, quoted) = if display.show_quoted {
+            (body.as_ref(), None)
+        } else {
+            split_quoted(&body)
+        };
+        let text = if display.full {
+            reply.to_string()
+        } else {
+            truncate_lines(reply, 10)
+        };
+        let text = clean_message_body(&text);
+        print_message_body(&text);
+        if let Some(quoted) = quoted {
+            let hidden = quoted.lines().filter(|l| !l.trim().is_empty()).count();
+            println!(
+                "{}\n",
+                format!("[{} quoted lines hidden; use --show-quoted]", hidden).dimmed()
+            );
         }
     }
 }
 
-fn print_message(msg: &Message, full: bool) {
+fn print_raw_html(msg: &Message) {
+    match msg.body_html.as_deref().filter(|h| !h.trim().is_empty()) {
+        Some(html) => println!("{}\n", html.trim_end()),
+        None => println!("{}\n", "(no HTML body)".dimmed()),
+    }
+}
+
+fn print_message_header(msg: &Message) {
     let author_name = msg
         .author
         .as_ref()
@@ -213,7 +1268,7 @@ fn print_message(msg: &Message, full: bool) {
         .and_then(|a| a.typename.as_deref())
         .unwrap_or("Unknown");
 
-    let time = msg.created_at.format("%b %d, %H:%M");
+    let time = format_date(&msg.created_at, "%b %d, %H:%M");
 
     let label = match author_type {
         "Agent" => format!("[Agent] {}", author_name).cyan(),
@@ -221,16 +1276,184 @@ fn print_message(msg: &Message, full: bool) {
         _ => format!("[{}] {}", author_type, author_name).normal(),
     };
 
-    println!("{} • {}", label, time.to_string().dimmed());
+    println!(
+        "{} • {} • {}",
+        label,
+        time.to_string().dimmed(),
+        msg.id.dimmed()
+    );
+}
 
-    if let Some(body) = &msg.body_text {
-        let text = if full {
-            body.clone()
-        } else {
-            truncate_lines(body, 10)
-        };
-        let text = clean_message_body(&text);
-        print_message_body(&text);
+/// Print the messages with lines matching `pattern`, showing `context` lines
+/// around each match
+pub fn format_message_matches(
+    messages: &[Message],
+    pattern: &Regex,
+    context: usize,
+    format: &OutputFormat,
+) {
+    let bodies: Vec<(&Message, String)> = messages
+        .iter()
+        .filter_map(|m| Some((m, clean_message_body(&m.text()?))))
+        .filter(|(_, body)| pattern.is_match(body))
+        .collect();
+
+    match format {
+        OutputFormat::Json => {
+            let matching: Vec<&Message> = bodies.iter().map(|(m, _)| *m).collect();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&matching).expect("serialization should not fail")
+            );
+        }
+        OutputFormat::Compact => {
+            for (msg, body) in &bodies {
+                for line in grep_lines(body, pattern, 0).iter().flatten() {
+                    println!("{}:{}: {}", msg.id, line.number, line.text);
+                }
+            }
+        }
+        OutputFormat::Table => {
+            for (msg, body) in &bodies {
+                print_message_header(msg);
+                for (i, hunk) in grep_lines(body, pattern, context).iter().enumerate() {
+                    if i > 0 {
+                        println!("{}", "--".dimmed());
+                    }
+                    for line in hunk {
+                        let number = format!("{:>4}", line.number);
+                        if line.matched {
+                            let highlighted = pattern
+                                .replace_all(line.text, |caps: &regex::Captures| {
+                                    caps[0].red().bold().to_string()
+                                });
+                            println!("{} {}", number.yellow(), highlighted);
+                        } else {
+                            println!("{} {}", number.dimmed(), line.text);
+                        }
+                    }
+                }
+                println!();
+            }
+            println!("{} of {} messages match", bodies.len(), messages.len());
+        }
+    }
+}
+
+/// Response times of one conversation, printed with `-o json`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResponseStats {
+    number: i64,
+    first_response_secs: Option<i64>,
+    average_response_secs: Option<i64>,
+    responses: usize,
+    waiting_since: Option<DateTime<Utc>>,
+}
+
+/// Print how long agents took to answer the customer in `conv`
+pub fn format_response_times(conv: &Conversation, times: &ResponseTimes, format: &OutputFormat) {
+    let first = times.first_response();
+    let average = times.average_response();
+    match format {
+        OutputFormat::Json => {
+            let stats = ResponseStats {
+                number: conv.number,
+                first_response_secs: first.map(|d| d.num_seconds()),
+                average_response_secs: average.map(|d| d.num_seconds()),
+                responses: times.responses.len(),
+                waiting_since: times.waiting_since,
+            };
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&stats).expect("serialization should not fail")
+            );
+        }
+        OutputFormat::Compact => {
+            let span = |d: Option<chrono::Duration>| d.map_or("-".to_string(), format_duration);
+            println!(
+                "#{} first {} avg {} replies {}",
+                conv.number,
+                span(first),
+                span(average),
+                times.responses.len()
+            );
+        }
+        OutputFormat::Table => {
+            let subject = conv.subject.as_deref().unwrap_or("(no subject)");
+            println!("{} {}", format!("#{}", conv.number).bold(), subject);
+            let unanswered = || "no reply yet".dimmed().to_string();
+            println!(
+                "{}: {}",
+                "First response".dimmed(),
+                first.map_or_else(unanswered, format_duration)
+            );
+            println!(
+                "{}: {}",
+                "Average response".dimmed(),
+                average.map_or_else(unanswered, |d| format!(
+                    "{} over {} {}",
+                    format_duration(d),
+                    times.responses.len(),
+                    if times.responses.len() == 1 {
+                        "reply"
+                    } else {
+                        "replies"
+                    }
+                ))
+            );
+            if let Some(since) = &times.waiting_since {
+                println!(
+                    "{}: {} (since {})",
+                    "Waiting".dimmed(),
+                    format_age(since),
+                    format_date(since, "%Y-%m-%d %H:%M")
+                );
+            }
+        }
+    }
+}
+
+/// Print one message in full, headers first. With `html`, the raw HTML
+/// body is printed instead of the text.
+pub fn format_message(msg: &Message, format: &OutputFormat, html: bool) {
+    if let OutputFormat::Json = format {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(msg).expect("serialization should not fail")
+        );
+        return;
+    }
+
+    let author = msg.author.as_ref();
+    let from = match author.map(|a| (a.name.as_deref(), a.email.as_deref())) {
+        Some((Some(name), Some(email))) => format!("{} <{}>", name, email),
+        Some((Some(name), None)) => name.to_string(),
+        Some((None, Some(email))) => email.to_string(),
+        _ => "Unknown".to_string(),
+    };
+    let kind = match author.and_then(|a| a.typename.as_deref()) {
+        Some("Agent") => "Agent reply",
+        Some("Contact") => "Customer message",
+        _ => "Message",
+    };
+
+    println!("{}: {}", "Message".dimmed(), msg.id);
+    println!("{}: {}", "Type".dimmed(), kind);
+    println!("{}: {}", "From".dimmed(), from);
+    println!(
+        "{}: {}",
+        "Date".dimmed(),
+        format_date(&msg.created_at, "%Y-%m-%d %H:%M:%S UTC")
+    );
+    println!("{}", "─".repeat(60).dimmed());
+    if html {
+        print_raw_html(msg);
+        return;
+    }
+    match msg.text() {
+        Some(body) => print_message_body(&clean_message_body(&body)),
+        None => println!("{}", "(no body)".dimmed()),
     }
 }
 
@@ -261,76 +1484,771 @@ fn clean_message_body(body: &str) -> String {
         }
     }
 
-    result_lines.join("\n").trim().to_string()
+    result_lines.join("\n").trim().to_string()
+}
+
+/// Print message body with quoted content dimmed
+fn print_message_body(body: &str) {
+    let mut in_quote = false;
+
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+
+        // Detect start of quoted section: "On ... wrote:" pattern
+        if trimmed.starts_with("On ") && trimmed.contains(" wrote:") {
+            in_quote = true;
+        }
+
+        // Lines starting with > are always quoted
+        let is_quoted = in_quote || trimmed.starts_with('>');
+
+        if is_quoted {
+            println!("{}", line.bright_black());
+        } else {
+            println!("{}", line);
+        }
+    }
+    println!();
+}
+
+#[derive(Tabled)]
+struct ChannelStatsRow {
+    #[tabled(rename = "Channel")]
+    channel: String,
+    #[tabled(rename = "Conversations")]
+    conversations: usize,
+    #[tabled(rename = "Open")]
+    open: usize,
+    #[tabled(rename = "Median first response")]
+    median_first_response: String,
+    #[tabled(rename = "Median response")]
+    median_response: String,
+}
+
+/// One channel's figures, printed with `-o json`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChannelStatsJson<'a> {
+    channel: &'a str,
+    conversations: usize,
+    open: usize,
+    median_first_response_secs: Option<i64>,
+    median_response_secs: Option<i64>,
+}
+
+pub fn format_channel_stats(stats: &[ChannelStats], format: &OutputFormat) {
+    let span = |d: Option<chrono::Duration>| d.map_or("-".to_string(), format_duration);
+    match format {
+        OutputFormat::Json => {
+            let stats: Vec<ChannelStatsJson> = stats
+                .iter()
+                .map(|s| ChannelStatsJson {
+                    channel: &s.channel,
+                    conversations: s.conversations,
+                    open: s.open,
+                    median_first_response_secs: s.median_first_response.map(|d| d.num_seconds()),
+                    median_response_secs: s.median_response.map(|d| d.num_seconds()),
+                })
+                .collect();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&stats).expect("serialization should not fail")
+            );
+        }
+        OutputFormat::Compact => {
+            for s in stats {
+                println!(
+                    "{}: {} conversations, {} open, first response {}, response {}",
+                    s.channel,
+                    s.conversations,
+                    s.open,
+                    span(s.median_first_response),
+                    span(s.median_response)
+                );
+            }
+        }
+        OutputFormat::Table => {
+            if stats.is_empty() {
+                println!("No conversations in this period");
+                return;
+            }
+            let rows: Vec<ChannelStatsRow> = stats
+                .iter()
+                .map(|s| ChannelStatsRow {
+                    channel: s.channel.clone(),
+                    conversations: s.conversations,
+                    open: s.open,
+                    median_first_response: span(s.median_first_response),
+                    median_response: span(s.median_response),
+                })
+                .collect();
+            println!("{}", styled(Table::new(rows)));
+        }
+    }
+}
+
+pub fn format_folders(folders: &[Folder], format: &OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(folders).expect("serialization should not fail")
+            );
+        }
+        OutputFormat::Compact => {
+            for folder in folders {
+                println!("{}", folder.name);
+            }
+        }
+        OutputFormat::Table => {
+            let rows: Vec<FolderRow> = folders.iter().map(FolderRow::from).collect();
+            let table = styled(Table::new(rows));
+            println!("{table}");
+        }
+    }
+}
+
+/// How many conversations a folder holds, and how many are waiting on an
+/// agent
+#[derive(Debug, Clone, Serialize)]
+pub struct FolderCounts {
+    pub total: i32,
+    pub unread: i32,
+    pub opened: i32,
+}
+
+/// A folder's counts above its conversations, for `folder view`
+pub fn format_folder_view(
+    folder: &Folder,
+    counts: &FolderCounts,
+    response: &ConversationsResponse,
+    format: &OutputFormat,
+    display: &ListDisplay,
+) {
+    match format {
+        OutputFormat::Json => {
+            let view = serde_json::json!({
+                "folder": folder,
+                "total": counts.total,
+                "unread": counts.unread,
+                "open": counts.opened,
+                "conversations": response,
+            });
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&view).expect("serialization should not fail")
+            );
+        }
+        // Compact output stays one conversation per line for scripts
+        OutputFormat::Compact => format_conversations(response, format, display),
+        OutputFormat::Table => {
+            println!("{} {}", "Folder".bold(), folder.name.bold());
+            println!(
+                "{}: {}  {}: {}  {}: {}",
+                "Total".dimmed(),
+                counts.total,
+                "Unread".dimmed(),
+                counts.unread.to_string().yellow(),
+                "Open".dimmed(),
+                counts.opened.to_string().green()
+            );
+            println!();
+            format_conversations(response, format, display);
+        }
+    }
+}
+
+pub fn format_tags(tags: &[Tag], format: &OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(tags).expect("serialization should not fail")
+            );
+        }
+        OutputFormat::Compact => {
+            for tag in tags {
+                println!("{}", tag.name);
+            }
+        }
+        OutputFormat::Table => {
+            let rows: Vec<TagRow> = tags.iter().map(TagRow::from).collect();
+            let table = styled(Table::new(rows));
+            println!("{table}");
+        }
+    }
+}
+
+pub fn format_canned_replies(replies: &[CannedReply], format: &OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(replies).expect("serialization should not fail")
+            );
+        }
+        OutputFormat::Compact => {
+            for reply in replies {
+                println!("{}", reply.name);
+            }
+        }
+        OutputFormat::Table => {
+            let rows: Vec<CannedReplyRow> = replies.iter().map(CannedReplyRow::from).collect();
+            let table = styled(Table::new(rows));
+            println!("{table}");
+        }
+    }
+}
+
+pub fn format_canned_reply(reply: &CannedReply) {
+    println!("{}: {}", "Name".dimmed(), reply.name);
+    if let Some(subject) = &reply.subject {
+        println!("{}: {}", "Subject".dimmed(), subject);
+    }
+    println!("{}", "─".repeat(40).dimmed());
+    if let Some(body) = &reply.body {
+        println!("{}", body);
+    }
+}
+
+pub fn format_rules(rules: &[Rule], format: &OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(rules).expect("serialization should not fail")
+            );
+        }
+        OutputFormat::Compact => {
+            for rule in rules {
+                let status = if rule.enabled { "enabled" } else { "disabled" };
+                println!("{}\t{}", status, rule.name);
+            }
+        }
+        OutputFormat::Table => {
+            let rows: Vec<RuleRow> = rules.iter().map(RuleRow::from).collect();
+            let table = styled(Table::new(rows));
+            println!("{table}");
+            let enabled = rules.iter().filter(|r| r.enabled).count();
+            println!("\n{} rules, {} enabled", rules.len(), enabled);
+        }
+    }
+}
+
+pub fn format_rule(rule: &Rule, format: &OutputFormat) {
+    if let OutputFormat::Json = format {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(rule).expect("serialization should not fail")
+        );
+        return;
+    }
+    println!("{}: {}", "Name".dimmed(), rule.name);
+    println!("{}: {}", "Status".dimmed(), rule_status(rule));
+    if let Some(description) = &rule.description {
+        println!("{}: {}", "Description".dimmed(), description);
+    }
+    if let Some(updated) = rule.updated_at {
+        println!(
+            "{}: {}",
+            "Updated".dimmed(),
+            format_date(&updated, "%Y-%m-%d %H:%M")
+        );
+    }
+    println!("{}: {}", "ID".dimmed(), rule.id);
+    println!("{}", "─".repeat(40).dimmed());
+    let when = match rule.match_type.as_deref() {
+        Some("ALL") => "When all of these match:",
+        _ => "When any of these match:",
+    };
+    println!("{}", when.bold());
+    for condition in &rule.conditions {
+        println!("  • {}", describe_rule_condition(condition));
+    }
+    println!("{}", "Then:".bold());
+    for step in &rule.actions {
+        println!("  • {}", describe_rule_step(step));
+    }
+}
+
+pub fn format_contact_profile(profile: &ContactProfile, format: &OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(profile).expect("serialization should not fail")
+            );
+        }
+        OutputFormat::Compact => {
+            for conv in &profile.conversations {
+                println!("{}", compact_line(conv));
+            }
+        }
+        OutputFormat::Table => {
+            let contact = &profile.contact;
+            println!(
+                "{}: {}",
+                "Name".dimmed(),
+                contact.name.as_deref().unwrap_or("-")
+            );
+            println!(
+                "{}: {}",
+                "Email".dimmed(),
+                contact.email.as_deref().unwrap_or("-")
+            );
+            println!("{}: {}", "ID".dimmed(), contact.id);
+            println!();
+            if profile.conversations.is_empty() {
+                println!("No conversations");
+                return;
+            }
+            let display = ListDisplay {
+                columns: vec![
+                    Column::Number,
+                    Column::Status,
+                    Column::Subject,
+                    Column::Created,
+                ],
+                ..ListDisplay::default()
+            };
+            print_conversation_table(&profile.conversations, &display);
+        }
+    }
+}
+
+pub fn format_agent(agent: &CurrentAgent, format: &OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(agent).expect("serialization should not fail")
+            );
+        }
+        _ => {
+            println!(
+                "{}: {}",
+                "Name".dimmed(),
+                agent.name.as_deref().unwrap_or("-")
+            );
+            println!("{}: {}", "Email".dimmed(), agent.email);
+            if let Some(role) = &agent.role {
+                println!("{}: {}", "Role".dimmed(), role);
+            }
+            println!("{}: {}", "ID".dimmed(), agent.id);
+        }
+    }
+}
+
+pub fn format_followers(agents: &[Agent], format: &OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(agents).expect("serialization should not fail")
+            );
+        }
+        OutputFormat::Compact => {
+            for agent in agents {
+                println!("{}", agent.email);
+            }
+        }
+        OutputFormat::Table => {
+            if agents.is_empty() {
+                println!("No followers");
+                return;
+            }
+            let rows: Vec<AgentRow> = agents.iter().map(AgentRow::from).collect();
+            println!("{}", styled(Table::new(rows)));
+        }
+    }
+}
+
+pub fn format_search_hits(hits: &[SearchHit], format: &OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            let hits: Vec<SearchHit> = hits
+                .iter()
+                .map(|hit| SearchHit {
+                    snippet: strip_match_markers(&hit.snippet),
+                    ..hit.clone()
+                })
+                .collect();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&hits).expect("serialization should not fail")
+            );
+        }
+        OutputFormat::Compact => {
+            for hit in hits {
+                println!(
+                    "#{}\t{}\t{}",
+                    hit.number,
+                    hit.kind,
+                    strip_match_markers(&hit.snippet).replace('\n', " ")
+                );
+            }
+        }
+        OutputFormat::Table => {
+            if hits.is_empty() {
+                println!("No matches found.");
+                return;
+            }
+            for hit in hits {
+                println!(
+                    "{} {} {} {}",
+                    format!("#{}", hit.number).bold(),
+                    hit.subject.as_deref().unwrap_or("(no subject)"),
+                    format!("[{}]", hit.state).dimmed(),
+                    format!("({})", hit.kind).dimmed()
+                );
+                println!("  {}", highlight_matches(&hit.snippet).replace('\n', " "));
+                println!();
+            }
+        }
+    }
+}
+
+fn strip_match_markers(snippet: &str) -> String {
+    snippet
+        .replace(SEARCH_MATCH_START, "")
+        .replace(SEARCH_MATCH_END, "")
+}
+
+fn highlight_matches(snippet: &str) -> String {
+    let mut out = String::new();
+    for (i, part) in snippet.split(SEARCH_MATCH_START).enumerate() {
+        match part.split_once(SEARCH_MATCH_END) {
+            Some((matched, rest)) if i > 0 => {
+                out.push_str(&matched.yellow().bold().to_string());
+                out.push_str(rest);
+            }
+            _ => out.push_str(part),
+        }
+    }
+    out
+}
+
+pub fn format_notes(notes: &[Note], format: &OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(notes).expect("serialization should not fail")
+            );
+        }
+        OutputFormat::Compact => {
+            for note in notes {
+                println!(
+                    "{} {}: {}",
+                    note.id,
+                    note_author(note),
+                    single_line(note.body.as_deref().unwrap_or(""))
+                );
+            }
+        }
+        OutputFormat::Table => {
+            if notes.is_empty() {
+                println!("No notes");
+                return;
+            }
+            let rows: Vec<NoteRow> = notes.iter().map(NoteRow::from).collect();
+            println!("{}", styled(Table::new(rows)));
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct MentionRow {
+    #[tabled(rename = "")]
+    unread: String,
+    #[tabled(rename = "#")]
+    number: i64,
+    #[tabled(rename = "Subject")]
+    subject: String,
+    #[tabled(rename = "By")]
+    by: String,
+    #[tabled(rename = "Note")]
+    note: String,
+    #[tabled(rename = "When")]
+    when: String,
+}
+
+impl From<&Mention> for MentionRow {
+    fn from(mention: &Mention) -> Self {
+        Self {
+            unread: if mention.is_unread() {
+                "●".blue().to_string()
+            } else {
+                String::new()
+            },
+            number: mention.conversation.number,
+            subject: truncate(
+                mention
+                    .conversation
+                    .subject
+                    .as_deref()
+                    .unwrap_or("(no subject)"),
+                30,
+            ),
+            by: truncate(mention_author(mention), 20),
+            note: truncate(&mention_snippet(mention), 40),
+            when: format_relative_time(&mention.created_at),
+        }
+    }
+}
+
+fn mention_author(mention: &Mention) -> &str {
+    mention
+        .note
+        .as_ref()
+        .and_then(|n| n.author.as_ref())
+        .and_then(|a| a.name.as_deref().or(a.email.as_deref()))
+        .unwrap_or("unknown")
 }
 
-/// Print message body with quoted content dimmed
-fn print_message_body(body: &str) {
-    let mut in_quote = false;
+/// Note body on a single line
+fn mention_snippet(mention: &Mention) -> String {
+    single_line(
+        mention
+            .note
+            .as_ref()
+            .and_then(|n| n.body.as_deref())
+            .unwrap_or(""),
+    )
+}
 
-    for line in body.lines() {
-        let trimmed = line.trim_start();
+/// Collapse runs of whitespace, including newlines, into single spaces
+fn single_line(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
 
-        // Detect start of quoted section: "On ... wrote:" pattern
-        if trimmed.starts_with("On ") && trimmed.contains(" wrote:") {
-            in_quote = true;
+pub fn format_mentions(mentions: &[Mention], format: &OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(mentions).expect("serialization should not fail")
+            );
         }
+        OutputFormat::Compact => {
+            for mention in mentions {
+                let marker = if mention.is_unread() { "*" } else { " " };
+                println!(
+                    "{} #{} {} - {}: {}",
+                    marker,
+                    mention.conversation.number,
+                    mention
+                        .conversation
+                        .subject
+                        .as_deref()
+                        .unwrap_or("(no subject)"),
+                    mention_author(mention),
+                    mention_snippet(mention)
+                );
+            }
+        }
+        OutputFormat::Table => {
+            if mentions.is_empty() {
+                println!("No mentions");
+                return;
+            }
+            let rows: Vec<MentionRow> = mentions.iter().map(MentionRow::from).collect();
+            println!("{}", styled(Table::new(rows)));
+            let unread = mentions.iter().filter(|m| m.is_unread()).count();
+            println!("\n{} mentions, {} unread", mentions.len(), unread);
+        }
+    }
+}
 
-        // Lines starting with > are always quoted
-        let is_quoted = in_quote || trimmed.starts_with('>');
+#[derive(Tabled)]
+struct ReminderRow {
+    #[tabled(rename = "ID")]
+    id: u64,
+    #[tabled(rename = "#")]
+    number: i64,
+    #[tabled(rename = "Due")]
+    due: String,
+    #[tabled(rename = "Note")]
+    note: String,
+}
 
-        if is_quoted {
-            println!("{}", line.bright_black());
-        } else {
-            println!("{}", line);
+impl From<&Reminder> for ReminderRow {
+    fn from(reminder: &Reminder) -> Self {
+        let due = format_wake_time(&reminder.due);
+        Self {
+            id: reminder.id,
+            number: reminder.number,
+            due: if reminder.is_due(Utc::now()) {
+                due.yellow().to_string()
+            } else {
+                due
+            },
+            note: truncate(reminder.note.as_deref().unwrap_or(""), 50),
         }
     }
-    println!();
 }
 
-pub fn format_folders(folders: &[Folder], format: &OutputFormat) {
+pub fn format_reminders(reminders: &[Reminder], format: &OutputFormat) {
     match format {
         OutputFormat::Json => {
             println!(
                 "{}",
-                serde_json::to_string_pretty(folders).expect("serialization should not fail")
+                serde_json::to_string_pretty(reminders).expect("serialization should not fail")
             );
         }
         OutputFormat::Compact => {
-            for folder in folders {
-                println!("{}", folder.name);
+            for reminder in reminders {
+                let line = format!(
+                    "{} #{} [{}] {}",
+                    reminder.id,
+                    reminder.number,
+                    format_wake_time(&reminder.due),
+                    reminder.note.as_deref().unwrap_or("")
+                );
+                println!("{}", line.trim_end());
             }
         }
         OutputFormat::Table => {
-            let rows: Vec<FolderRow> = folders.iter().map(FolderRow::from).collect();
-            let table = Table::new(rows).with(Style::rounded()).to_string();
-            println!("{table}");
+            if reminders.is_empty() {
+                println!("No reminders");
+                return;
+            }
+            let rows: Vec<ReminderRow> = reminders.iter().map(ReminderRow::from).collect();
+            println!("{}", styled(Table::new(rows)));
         }
     }
 }
 
-pub fn format_tags(tags: &[Tag], format: &OutputFormat) {
+#[derive(Tabled)]
+struct SnippetRow {
+    #[tabled(rename = "Name")]
+    name: String,
+    #[tabled(rename = "Text")]
+    text: String,
+}
+
+impl From<&Snippet> for SnippetRow {
+    fn from(snippet: &Snippet) -> Self {
+        Self {
+            name: snippet.name.clone(),
+            text: truncate(&snippet.body.trim().replace('\n', " "), 60),
+        }
+    }
+}
+
+pub fn format_snippets(snippets: &[Snippet], format: &OutputFormat) {
     match format {
         OutputFormat::Json => {
             println!(
                 "{}",
-                serde_json::to_string_pretty(tags).expect("serialization should not fail")
+                serde_json::to_string_pretty(snippets).expect("serialization should not fail")
             );
         }
         OutputFormat::Compact => {
-            for tag in tags {
-                println!("{}", tag.name);
+            for snippet in snippets {
+                println!("{}", snippet.name);
             }
         }
         OutputFormat::Table => {
-            let rows: Vec<TagRow> = tags.iter().map(TagRow::from).collect();
-            let table = Table::new(rows).with(Style::rounded()).to_string();
-            println!("{table}");
+            if snippets.is_empty() {
+                println!("No snippets. Add one with 'groove snippet add <name>'");
+                return;
+            }
+            let rows: Vec<SnippetRow> = snippets.iter().map(SnippetRow::from).collect();
+            println!("{}", styled(Table::new(rows)));
         }
     }
 }
 
-pub fn format_canned_replies(replies: &[CannedReply], format: &OutputFormat) {
+#[derive(Tabled)]
+struct QueuedReplyRow {
+    #[tabled(rename = "ID")]
+    id: u64,
+    #[tabled(rename = "#")]
+    number: i64,
+    #[tabled(rename = "Send at")]
+    send_at: String,
+    #[tabled(rename = "Body")]
+    body: String,
+    #[tabled(rename = "Then")]
+    then: String,
+}
+
+impl From<&QueuedReply> for QueuedReplyRow {
+    fn from(reply: &QueuedReply) -> Self {
+        Self {
+            id: reply.id,
+            number: reply.number,
+            send_at: format_wake_time(&reply.send_at),
+            body: truncate(
+                &reply.body.split_whitespace().collect::<Vec<_>>().join(" "),
+                50,
+            ),
+            then: describe_reply_options(&reply.options),
+        }
+    }
+}
+
+/// What happens after a queued reply is sent, like "assign me, add 2 tags"
+fn describe_reply_options(options: &ReplyOptions) -> String {
+    let mut steps = Vec::new();
+    if let Some(agent) = &options.assign {
+        steps.push(format!("assign {}", agent.name));
+    }
+    match options.tag_ids.len() {
+        0 => {}
+        1 => steps.push("add 1 tag".to_string()),
+        n => steps.push(format!("add {} tags", n)),
+    }
+    if options.close {
+        steps.push("close".to_string());
+    }
+    steps.join(", ")
+}
+
+/// A reply as it will be sent, for `reply --preview`
+pub fn format_reply_preview(
+    conv: &Conversation,
+    body: &str,
+    options: &ReplyOptions,
+    send_at: Option<&DateTime<Utc>>,
+) {
+    let to = match conv.contact.as_ref() {
+        Some(Contact {
+            name: Some(name),
+            email: Some(email),
+            ..
+        }) => format!("{} <{}>", name, email),
+        Some(contact) => contact
+            .email
+            .clone()
+            .or_else(|| contact.name.clone())
+            .unwrap_or_else(|| "-".to_string()),
+        None => "-".to_string(),
+    };
+    println!("{}: {}", "To".dimmed(), to);
+    println!(
+        "{}: #{} {}",
+        "Conversation".dimmed(),
+        conv.number,
+        conv.subject.as_deref().unwrap_or("(no subject)")
+    );
+    if let Some(subject) = &options.subject {
+        println!("{}: {}", "Subject".dimmed(), subject);
+    }
+    let then = describe_reply_options(options);
+    if !then.is_empty() {
+        println!("{}: {}", "Then".dimmed(), then);
+    }
+    if let Some(send_at) = send_at {
+        println!("{}: {}", "Send at".dimmed(), format_wake_time(send_at));
+    }
+    println!("{}", "─".repeat(40).dimmed());
+    println!("{}", body.trim_end());
+    println!("{}", "─".repeat(40).dimmed());
+}
+
+pub fn format_outbox(replies: &[QueuedReply], format: &OutputFormat) {
     match format {
         OutputFormat::Json => {
             println!(
@@ -340,47 +2258,234 @@ pub fn format_canned_replies(replies: &[CannedReply], format: &OutputFormat) {
         }
         OutputFormat::Compact => {
             for reply in replies {
-                println!("{}", reply.name);
+                println!(
+                    "{} #{} [{}] {}",
+                    reply.id,
+                    reply.number,
+                    format_wake_time(&reply.send_at),
+                    truncate(&reply.body.replace('\n', " "), 60)
+                );
             }
         }
         OutputFormat::Table => {
-            let rows: Vec<CannedReplyRow> = replies.iter().map(CannedReplyRow::from).collect();
-            let table = Table::new(rows).with(Style::rounded()).to_string();
+            if replies.is_empty() {
+                println!("Outbox is empty");
+                return;
+            }
+            let rows: Vec<QueuedReplyRow> = replies.iter().map(QueuedReplyRow::from).collect();
+            println!("{}", styled(Table::new(rows)));
+        }
+    }
+}
+
+pub fn format_bulk_summary(results: &[BulkResult], format: &OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(results).expect("serialization should not fail")
+            );
+        }
+        OutputFormat::Compact => {
+            for result in results {
+                match &result.error {
+                    Some(error) => println!("#{}\tfailed\t{}", result.number, error),
+                    None => println!("#{}\tok", result.number),
+                }
+            }
+        }
+        OutputFormat::Table => {
+            let rows: Vec<BulkResultRow> = results.iter().map(BulkResultRow::from).collect();
+            let table = styled(Table::new(rows));
             println!("{table}");
         }
     }
 }
 
-pub fn format_canned_reply(reply: &CannedReply) {
-    println!("{}: {}", "Name".dimmed(), reply.name);
-    if let Some(subject) = &reply.subject {
-        println!("{}: {}", "Subject".dimmed(), subject);
+/// What `groove search` found, by kind
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResults {
+    pub conversations: Vec<Conversation>,
+    pub contacts: Vec<Contact>,
+    pub canned_replies: Vec<CannedReply>,
+    pub articles: Vec<KbArticle>,
+}
+
+impl SearchResults {
+    fn is_empty(&self) -> bool {
+        self.conversations.is_empty()
+            && self.contacts.is_empty()
+            && self.canned_replies.is_empty()
+            && self.articles.is_empty()
     }
-    println!("{}", "─".repeat(40).dimmed());
-    if let Some(body) = &reply.body {
-        println!("{}", body);
+}
+
+/// Print search results grouped by kind, skipping kinds with no results
+pub fn format_search_results(
+    results: &SearchResults,
+    format: &OutputFormat,
+    display: &ListDisplay,
+) {
+    if let OutputFormat::Json = format {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(results).expect("serialization should not fail")
+        );
+        return;
+    }
+    if results.is_empty() {
+        println!("{}", Msg::NoResults.text());
+        return;
+    }
+    let compact = matches!(format, OutputFormat::Compact);
+    let mut first = true;
+    let mut heading = |title: &str, count: usize| {
+        if compact {
+            println!("{} ({})", title, count);
+            return;
+        }
+        if !first {
+            println!();
+        }
+        first = false;
+        println!("{} {}", title.bold(), format!("({})", count).dimmed());
+    };
+    if !results.conversations.is_empty() {
+        heading("Conversations", results.conversations.len());
+        if compact {
+            for conv in &results.conversations {
+                println!("  {}", display.compact_line(conv));
+            }
+        } else {
+            print_conversation_table(&results.conversations, display);
+        }
+    }
+    if !results.contacts.is_empty() {
+        heading("Contacts", results.contacts.len());
+        if compact {
+            for contact in &results.contacts {
+                let row = ContactRow::from(contact);
+                println!("  {} <{}>", row.name, row.email);
+            }
+        } else {
+            let rows = results.contacts.iter().map(ContactRow::from);
+            println!("{}", styled(Table::new(rows)));
+        }
+    }
+    if !results.canned_replies.is_empty() {
+        heading("Canned replies", results.canned_replies.len());
+        if compact {
+            for reply in &results.canned_replies {
+                println!("  {}", reply.name);
+            }
+        } else {
+            let rows = results.canned_replies.iter().map(CannedReplyRow::from);
+            println!("{}", styled(Table::new(rows)));
+        }
+    }
+    if !results.articles.is_empty() {
+        heading("Articles", results.articles.len());
+        if compact {
+            for article in &results.articles {
+                let row = ArticleRow::from(article);
+                println!("  {} {}", row.title, row.url);
+            }
+        } else {
+            let rows = results.articles.iter().map(ArticleRow::from);
+            println!("{}", styled(Table::new(rows)));
+        }
     }
 }
 
-pub fn format_agent(agent: &CurrentAgent, format: &OutputFormat) {
+/// The numbers `groove inbox` shows
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxSummary {
+    pub unread: i32,
+    pub opened: i32,
+    pub snoozed: i32,
+    /// Open conversations assigned to the current agent
+    pub mine: i32,
+    /// The unread or open conversation least recently updated
+    pub oldest_waiting: Option<Conversation>,
+    /// Snoozed conversations waking before midnight, soonest first
+    pub waking_today: Vec<Conversation>,
+}
+
+pub fn format_inbox_summary(summary: &InboxSummary, format: &OutputFormat) {
+    let oldest = summary
+        .oldest_waiting
+        .as_ref()
+        .map(|conv| format!("#{} ({})", conv.number, format_age(&conv.updated_at)));
+    let waking = summary
+        .waking_today
+        .iter()
+        .map(|conv| format!("#{}", conv.number))
+        .collect::<Vec<_>>()
+        .join(", ");
     match format {
         OutputFormat::Json => {
             println!(
                 "{}",
-                serde_json::to_string_pretty(agent).expect("serialization should not fail")
+                serde_json::to_string_pretty(summary).expect("serialization should not fail")
             );
         }
-        _ => {
+        OutputFormat::Compact => {
+            println!(
+                "unread={} open={} snoozed={} mine={} oldest={} waking={}",
+                summary.unread,
+                summary.opened,
+                summary.snoozed,
+                summary.mine,
+                oldest.as_deref().unwrap_or("-"),
+                summary.waking_today.len()
+            );
+        }
+        OutputFormat::Table => {
+            println!("{}: {}", "Unread".dimmed(), summary.unread);
+            println!("{}: {}", "Open".dimmed(), summary.opened);
+            println!("{}: {}", "Snoozed".dimmed(), summary.snoozed);
+            println!("{}: {}", "Mine".dimmed(), summary.mine);
             println!(
                 "{}: {}",
-                "Name".dimmed(),
-                agent.name.as_deref().unwrap_or("-")
+                "Oldest waiting".dimmed(),
+                oldest.as_deref().unwrap_or("-")
             );
-            println!("{}: {}", "Email".dimmed(), agent.email);
-            if let Some(role) = &agent.role {
-                println!("{}: {}", "Role".dimmed(), role);
+            if waking.is_empty() {
+                println!("{}: 0", "Waking today".dimmed());
+            } else {
+                println!(
+                    "{}: {} ({})",
+                    "Waking today".dimmed(),
+                    summary.waking_today.len(),
+                    waking
+                );
+            }
+        }
+    }
+}
+
+pub fn format_rate_limit(info: &RateLimitInfo, format: &OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(info).expect("serialization should not fail")
+            );
+        }
+        OutputFormat::Compact => println!("{}", info),
+        OutputFormat::Table => {
+            let show = |v: Option<u64>| v.map_or("-".to_string(), |v| v.to_string());
+            println!("{}: {}", "Limit".dimmed(), show(info.limit));
+            println!("{}: {}", "Remaining".dimmed(), show(info.remaining));
+            if let Some(reset_at) = info.reset_at {
+                println!(
+                    "{}: {}",
+                    "Resets".dimmed(),
+                    format_date(&reset_at, "%Y-%m-%d %H:%M:%S UTC")
+                );
             }
-            println!("{}: {}", "ID".dimmed(), agent.id);
         }
     }
 }
@@ -396,18 +2501,50 @@ fn format_state(state: &ConversationState) -> String {
     }
 }
 
-fn state_color_str(state: &ConversationState) -> &'static str {
+/// Glyph standing in for the state's name in icon mode
+fn state_icon(state: &ConversationState) -> &'static str {
     match state {
-        ConversationState::Unread => "yellow",
-        ConversationState::Opened => "green",
-        ConversationState::Closed => "white",
-        ConversationState::Snoozed => "blue",
-        ConversationState::Spam => "red",
-        ConversationState::Deleted => "white",
+        ConversationState::Unread => "●",
+        ConversationState::Opened => "◌",
+        ConversationState::Closed => "✔",
+        ConversationState::Snoozed => "💤",
+        ConversationState::Spam => "⚠",
+        ConversationState::Deleted => "✖",
+    }
+}
+
+/// The state as the Status column shows it: its name, or a glyph when
+/// `[theme] icons` is on
+fn status_label(state: &ConversationState) -> String {
+    if icons() {
+        state_icon(state).to_string()
+    } else {
+        format_state(state)
+    }
+}
+
+/// `text` in the theme color of `state`
+fn in_state_color(state: &ConversationState, text: String) -> String {
+    match state_color(state) {
+        Some(color) => text.color(color).to_string(),
+        None => text,
     }
 }
 
+/// The state's name in its theme color
+fn colored_state(state: &ConversationState) -> String {
+    in_state_color(state, format_state(state))
+}
+
+/// `dt` in the configured date format, or in UTC with `default`
+fn format_date(dt: &DateTime<Utc>, default: &str) -> String {
+    configured_date(dt).unwrap_or_else(|| dt.format(default).to_string())
+}
+
 fn format_relative_time(dt: &DateTime<Utc>) -> String {
+    if iso_dates() {
+        return format_date(dt, "%Y-%m-%d");
+    }
     let now = Utc::now();
     let duration = now.signed_duration_since(*dt);
 
@@ -420,8 +2557,55 @@ fn format_relative_time(dt: &DateTime<Utc>) -> String {
     } else if duration.num_days() < 7 {
         format!("{}d ago", duration.num_days())
     } else {
-        dt.format("%Y-%m-%d").to_string()
+        format_date(dt, "%Y-%m-%d")
+    }
+}
+
+/// How long ago `dt` was, in its largest whole unit, e.g. `3d`
+fn format_age(dt: &DateTime<Utc>) -> String {
+    let age = Utc::now().signed_duration_since(*dt);
+    if age.num_minutes() < 1 {
+        "<1m".to_string()
+    } else if age.num_minutes() < 60 {
+        format!("{}m", age.num_minutes())
+    } else if age.num_hours() < 24 {
+        format!("{}h", age.num_hours())
+    } else {
+        format!("{}d", age.num_days())
+    }
+}
+
+/// A length of time in its two largest units, e.g. `2h 14m` or `3d 4h`
+fn format_duration(d: chrono::Duration) -> String {
+    let minutes = d.num_minutes();
+    if minutes < 1 {
+        "<1m".to_string()
+    } else if minutes < 60 {
+        format!("{}m", minutes)
+    } else if minutes < 24 * 60 {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}d {}h", minutes / (24 * 60), minutes % (24 * 60) / 60)
+    }
+}
+
+/// Wake-up time as a local timestamp plus how far away it is, e.g.
+/// `2024-01-05 09:00 (in 2d)`
+fn format_wake_time(dt: &DateTime<Utc>) -> String {
+    if iso_dates() {
+        return format_date(dt, "%Y-%m-%d %H:%M");
     }
+    let remaining = dt.signed_duration_since(Utc::now());
+    let relative = if remaining.num_minutes() < 1 {
+        "due".to_string()
+    } else if remaining.num_minutes() < 60 {
+        format!("in {}m", remaining.num_minutes())
+    } else if remaining.num_hours() < 24 {
+        format!("in {}h", remaining.num_hours())
+    } else {
+        format!("in {}d", remaining.num_days())
+    };
+    format!("{} ({})", format_timestamp(dt), relative)
 }
 
 fn truncate(s: &str, max_len: usize) -> String {
@@ -450,6 +2634,19 @@ fn truncate_lines(s: &str, max_lines: usize) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_strip_match_markers() {
+        let snippet = format!("the {SEARCH_MATCH_START}invoice{SEARCH_MATCH_END} is wrong");
+        assert_eq!(strip_match_markers(&snippet), "the invoice is wrong");
+    }
+
+    #[test]
+    fn test_highlight_matches_keeps_text() {
+        colored::control::set_override(false);
+        let snippet = format!("{SEARCH_MATCH_START}order{SEARCH_MATCH_END} and {SEARCH_MATCH_START}invoice{SEARCH_MATCH_END}");
+        assert_eq!(highlight_matches(&snippet), "order and invoice");
+    }
+
     #[test]
     fn test_truncate_short_string() {
         let result = truncate("hello", 10);
@@ -544,4 +2741,221 @@ mod tests {
         assert!(result.contains("-"));
         assert!(!result.contains("ago"));
     }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(chrono::Duration::seconds(30)), "<1m");
+        assert_eq!(format_duration(chrono::Duration::minutes(45)), "45m");
+        assert_eq!(format_duration(chrono::Duration::minutes(134)), "2h 14m");
+        assert_eq!(
+            format_duration(chrono::Duration::hours(76) + chrono::Duration::minutes(5)),
+            "3d 4h"
+        );
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(&Utc::now()), "<1m");
+        assert_eq!(format_age(&(Utc::now() - chrono::Duration::hours(5))), "5h");
+        assert_eq!(
+            format_age(&(Utc::now() - chrono::Duration::days(40))),
+            "40d"
+        );
+    }
+
+    fn conversation(number: i64, state: ConversationState, tags: &[&str]) -> Conversation {
+        Conversation::builder(number)
+            .state(state)
+            .tags(tags)
+            .build()
+    }
+
+    #[test]
+    fn test_columns() {
+        assert_eq!("Messages".parse(), Ok(Column::Messages));
+        assert_eq!("snoozed-until".parse(), Ok(Column::SnoozedUntil));
+        let err = "size".parse::<Column>().unwrap_err();
+        assert!(err.contains("Invalid column: size"), "{}", err);
+
+        let mut conv = conversation(7, ConversationState::Opened, &["bug", "vip"]);
+        assert_eq!(Column::Messages.cell(&conv), "-");
+        conv.messages_count = Some(4);
+        assert_eq!(Column::Messages.cell(&conv), "4");
+        assert_eq!(Column::Tags.cell(&conv), "bug, vip");
+        assert_eq!(Column::Assignee.cell(&conv), "-");
+
+        let table = conversation_table(
+            &ListDisplay::default(),
+            &[&conv],
+            &[Column::Number, Column::Messages],
+            40,
+        );
+        assert!(table.contains("Msgs"));
+        assert!(!table.contains("Subject"));
+    }
+
+    #[test]
+    fn test_compact_format() {
+        let mut conv = conversation(7, ConversationState::Opened, &["bug", "vip"]);
+        conv.subject = Some("Refund {urgent}".to_string());
+        let format: CompactFormat = "#{number} [{state}] {subject} <{contact}> {{{tags}}}"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            format.render(&ListDisplay::default(), &conv),
+            "#7 [open] Refund {urgent} <unknown> {bug, vip}"
+        );
+
+        let err = "{number} {size}".parse::<CompactFormat>().unwrap_err();
+        assert!(
+            err.starts_with("Unknown field {size}. Use state, contact, number"),
+            "{}",
+            err
+        );
+        assert!("{number}}".parse::<CompactFormat>().is_err());
+        assert!("#{number".parse::<CompactFormat>().is_err());
+    }
+
+    #[test]
+    fn test_fit_columns() {
+        let mut conv = conversation(7, ConversationState::Opened, &["bug", "vip"]);
+        conv.subject = Some("Refund for a duplicate charge on the March invoice".to_string());
+        let convs = [&conv];
+        let display = ListDisplay::default();
+
+        // Everything fits on a wide terminal
+        let mut columns = Column::DEFAULT.to_vec();
+        assert_eq!(fit_columns(&display, &convs, &mut columns, 300), Some(50));
+        assert_eq!(columns, Column::DEFAULT);
+
+        // Narrower terminals shorten the subject before dropping anything
+        let table = conversation_table(&display, &convs, &columns, usize::MAX);
+        let natural = table.lines().next().unwrap().chars().count();
+        assert_eq!(
+            fit_columns(&display, &convs, &mut columns, natural),
+            Some(50)
+        );
+        assert_eq!(
+            fit_columns(&display, &convs, &mut columns, natural - 10),
+            Some(40)
+        );
+        assert_eq!(columns, Column::DEFAULT);
+
+        // Then low-priority columns go, tags before the number or subject
+        let mut columns = Column::DEFAULT.to_vec();
+        let width = fit_columns(&display, &convs, &mut columns, 40).unwrap();
+        assert!(width >= MIN_SUBJECT_WIDTH);
+        assert!(!columns.contains(&Column::Tags));
+        assert!(columns.contains(&Column::Number) && columns.contains(&Column::Subject));
+        let table = conversation_table(&display, &convs, &columns, width);
+        assert!(
+            table.lines().all(|line| line.chars().count() <= 40),
+            "{}",
+            table
+        );
+
+        // Too narrow for even the number, status, and subject
+        let mut columns = Column::DEFAULT.to_vec();
+        assert_eq!(fit_columns(&display, &convs, &mut columns, 20), None);
+    }
+
+    #[test]
+    fn test_visible_width_ignores_colors() {
+        assert_eq!(visible_width(&"open".green().to_string()), 4);
+        assert_eq!(visible_width("café"), 4);
+        let link = "\x1b]8;;https://acme.groovehq.com/conversations/1001\x1b\\1001\x1b]8;;\x1b\\";
+        assert_eq!(visible_width(link), 4);
+        assert_eq!(visible_width("💤"), 2);
+    }
+
+    #[test]
+    fn test_group_by_status_largest_first() {
+        let convs = vec![
+            conversation(1, ConversationState::Closed, &[]),
+            conversation(2, ConversationState::Opened, &[]),
+            conversation(3, ConversationState::Opened, &[]),
+        ];
+        let groups = group_conversations(&convs, GroupBy::Status);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].group, "open");
+        assert_eq!(groups[0].count, 2);
+        assert_eq!(groups[1].group, "closed");
+    }
+
+    #[test]
+    fn test_group_by_tag_repeats_multi_tagged() {
+        let convs = vec![
+            conversation(1, ConversationState::Opened, &["bug", "urgent"]),
+            conversation(2, ConversationState::Opened, &["bug"]),
+            conversation(3, ConversationState::Opened, &[]),
+        ];
+        let groups = group_conversations(&convs, GroupBy::Tag);
+        let summary: Vec<(&str, usize)> =
+            groups.iter().map(|g| (g.group.as_str(), g.count)).collect();
+        assert_eq!(summary, vec![("bug", 2), ("(untagged)", 1), ("urgent", 1)]);
+    }
+
+    #[test]
+    fn test_group_by_assignee_unassigned() {
+        let mut assigned = conversation(1, ConversationState::Opened, &[]);
+        assigned.assigned = Some(Agent {
+            id: "a1".to_string(),
+            email: "alice@example.com".to_string(),
+            name: None,
+        });
+        let convs = vec![assigned, conversation(2, ConversationState::Opened, &[])];
+        let groups = group_conversations(&convs, GroupBy::Assignee);
+        let names: Vec<&str> = groups.iter().map(|g| g.group.as_str()).collect();
+        assert_eq!(names, vec!["(unassigned)", "alice@example.com"]);
+    }
+
+    #[test]
+    fn test_format_wake_time() {
+        let soon = Utc::now() + chrono::Duration::minutes(90);
+        assert!(format_wake_time(&soon).ends_with("(in 1h)"));
+
+        let later = Utc::now() + chrono::Duration::days(3) + chrono::Duration::minutes(1);
+        assert!(format_wake_time(&later).ends_with("(in 3d)"));
+
+        let past = Utc::now() - chrono::Duration::hours(1);
+        assert!(format_wake_time(&past).ends_with("(due)"));
+    }
+
+    #[test]
+    fn test_message_separator_width() {
+        let sep = message_separator(3, 12);
+        assert!(sep.starts_with("── 3/12 ─"));
+        assert_eq!(sep.chars().count(), 60);
+    }
+
+    #[test]
+    fn test_message_preview_skips_blank_and_quoted() {
+        let body = "\n\n  Thanks, fixed!\nMore\n\nOn Mon, Jan 1, 2024 Support wrote:\n> hi";
+        assert_eq!(message_preview(body, false), "Thanks, fixed!");
+        assert_eq!(message_preview("> only quoted", false), "> only quoted");
+        assert_eq!(message_preview("", false), "");
+    }
+
+    #[test]
+    fn test_action_result_json_omits_unset_fields() {
+        let conv: Conversation = serde_json::from_value(serde_json::json!({
+            "id": "c1",
+            "number": 12,
+            "state": "OPENED",
+            "createdAt": "2024-01-01T00:00:00Z",
+            "updatedAt": "2024-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        let mut result = ActionResult::new("close", &conv);
+        result.state = Some(ConversationState::Closed);
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::json!({
+                "action": "close",
+                "conversationId": "c1",
+                "number": 12,
+                "state": "CLOSED"
+            })
+        );
+    }
 }
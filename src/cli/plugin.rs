@@ -0,0 +1,114 @@
+use crate::cli::alias::subcommand_position;
+use crate::cli::Cli;
+use clap::CommandFactory;
+use std::ffi::OsStr;
+use std::path::PathBuf;
+
+/// Prefix of executables on PATH that provide external subcommands
+pub const PLUGIN_PREFIX: &str = "groove-";
+
+/// An external `groove-<name>` executable to run in place of a built-in command
+#[derive(Debug, PartialEq, Eq)]
+pub struct Plugin {
+    pub path: PathBuf,
+    /// Global options that appeared before the subcommand
+    pub global_args: Vec<String>,
+    /// Arguments after the subcommand, passed through to the plugin
+    pub args: Vec<String>,
+}
+
+/// Look for an external plugin matching the subcommand in `args`.
+///
+/// Built-in subcommands are never shadowed by plugins.
+pub fn find_plugin(args: &[String]) -> Option<Plugin> {
+    let path_var = std::env::var_os("PATH")?;
+    find_plugin_in(args, &path_var)
+}
+
+fn find_plugin_in(args: &[String], path_var: &OsStr) -> Option<Plugin> {
+    let pos = subcommand_position(args)?;
+    let name = &args[pos];
+    if Cli::command().find_subcommand(name).is_some() {
+        return None;
+    }
+
+    let file_name = format!("{}{}{}", PLUGIN_PREFIX, name, std::env::consts::EXE_SUFFIX);
+    let path = std::env::split_paths(path_var)
+        .map(|dir| dir.join(&file_name))
+        .find(|candidate| candidate.is_file())?;
+
+    Some(Plugin {
+        path,
+        global_args: args[1..pos].to_vec(),
+        args: args[pos + 1..].to_vec(),
+    })
+}
+
+/// Value of a global `--name value` or `--name=value` option, if given
+pub fn global_option_value(global_args: &[String], name: &str) -> Option<String> {
+    let flag = format!("--{}", name);
+    let prefix = format!("--{}=", name);
+    let mut iter = global_args.iter();
+    while let Some(arg) = iter.next() {
+        if *arg == flag {
+            return iter.next().cloned();
+        }
+        if let Some(value) = arg.strip_prefix(&prefix) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &[&str]) -> Vec<String> {
+        s.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_find_plugin_in_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = dir
+            .path()
+            .join(format!("groove-hello{}", std::env::consts::EXE_SUFFIX));
+        std::fs::write(&plugin, "").unwrap();
+
+        let found = find_plugin_in(
+            &args(&["groove", "--profile", "work", "hello", "a", "b"]),
+            dir.path().as_os_str(),
+        )
+        .unwrap();
+        assert_eq!(found.path, plugin);
+        assert_eq!(found.global_args, args(&["--profile", "work"]));
+        assert_eq!(found.args, args(&["a", "b"]));
+    }
+
+    #[test]
+    fn test_find_plugin_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(find_plugin_in(&args(&["groove", "hello"]), dir.path().as_os_str()).is_none());
+    }
+
+    #[test]
+    fn test_builtin_not_shadowed() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path()
+                .join(format!("groove-me{}", std::env::consts::EXE_SUFFIX)),
+            "",
+        )
+        .unwrap();
+        assert!(find_plugin_in(&args(&["groove", "me"]), dir.path().as_os_str()).is_none());
+    }
+
+    #[test]
+    fn test_global_option_value() {
+        let global = args(&["--quiet", "--profile", "work", "--token=abc"]);
+        assert_eq!(global_option_value(&global, "profile"), Some("work".into()));
+        assert_eq!(global_option_value(&global, "token"), Some("abc".into()));
+        assert_eq!(global_option_value(&global, "format"), None);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::cli::BulkFilter;
+
+/// Parse a `groove search` query like `tag:urgent assignee:me state:open
+/// billing error` into conversation filters.
+///
+/// `state:` (or `status:`), `folder:`, `assignee:`, and `older:` take one
+/// value each, and `tag:` may repeat; values with spaces are quoted, as in
+/// `assignee:"Jane Doe"`. Everything else, including words with an unknown
+/// prefix, is searched for as keywords.
+pub fn parse_query(query: &str) -> Result<BulkFilter, String> {
+    let terms = shlex::split(query).ok_or_else(|| format!("Unbalanced quotes in: {}", query))?;
+    let mut filter = BulkFilter::default();
+    let mut words = Vec::new();
+    for term in terms {
+        let Some((key, value)) = term.split_once(':') else {
+            words.push(term);
+            continue;
+        };
+        let key = key.to_lowercase();
+        if !FILTER_KEYS.contains(&key.as_str()) {
+            words.push(term);
+            continue;
+        }
+        if value.is_empty() {
+            return Err(format!("Missing value for '{}:'", key));
+        }
+        let repeated = match key.as_str() {
+            "tag" => {
+                filter.tag.push(value.to_string());
+                false
+            }
+            "state" | "status" => filter.status.replace(value.parse()?).is_some(),
+            "folder" => filter.folder.replace(value.to_string()).is_some(),
+            "assignee" => filter.assignee.replace(value.to_string()).is_some(),
+            "older" => filter.older_than.replace(value.to_string()).is_some(),
+            _ => unreachable!("checked against FILTER_KEYS"),
+        };
+        if repeated {
+            return Err(format!("'{}:' given more than once", key));
+        }
+    }
+    if !words.is_empty() {
+        filter.search = Some(words.join(" "));
+    }
+    Ok(filter)
+}
+
+/// Prefixes [`parse_query`] reads as filters
+const FILTER_KEYS: &[&str] = &["tag", "state", "status", "folder", "assignee", "older"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ConversationState;
+
+    #[test]
+    fn test_parse_query() {
+        let filter =
+            parse_query("tag:urgent assignee:me state:open billing Tag:vip error").unwrap();
+        assert_eq!(filter.tag, vec!["urgent", "vip"]);
+        assert_eq!(filter.assignee.as_deref(), Some("me"));
+        assert_eq!(filter.status, Some(ConversationState::Opened));
+        assert_eq!(filter.search.as_deref(), Some("billing error"));
+        assert_eq!(filter.folder, None);
+    }
+
+    #[test]
+    fn test_parse_query_quoted_values() {
+        let filter =
+            parse_query(r#"assignee:"Jane Doe" folder:billing older:3d "order:4521""#).unwrap();
+        assert_eq!(filter.assignee.as_deref(), Some("Jane Doe"));
+        assert_eq!(filter.folder.as_deref(), Some("billing"));
+        assert_eq!(filter.older_than.as_deref(), Some("3d"));
+        assert_eq!(filter.search.as_deref(), Some("order:4521"));
+    }
+
+    #[test]
+    fn test_parse_query_errors() {
+        assert!(parse_query("state:bogus").is_err());
+        assert!(parse_query("tag:").is_err());
+        assert!(parse_query("state:open status:closed").is_err());
+        assert!(parse_query("\"unbalanced").is_err());
+        assert_eq!(parse_query("").unwrap(), BulkFilter::default());
+    }
+}
@@ -0,0 +1,142 @@
+/// Split an email body into the new content and the trailing quoted history
+/// (and signature) a reply carries along.
+///
+/// The quote starts at the first of:
+/// - an attribution line like `On Mon, Jan 1, 2024, Jane <jane@example.com> wrote:`,
+///   which clients sometimes wrap over two lines
+/// - an Outlook `-----Original Message-----` separator or `From:` / `Sent:` header block
+/// - a signature delimiter (`-- `)
+/// - a run of `>` lines that continues to the end of the body
+///
+/// Returns the body unchanged with `None` when nothing is quoted, or when the
+/// whole body would be hidden.
+pub fn split_quoted(body: &str) -> (&str, Option<&str>) {
+    let lines: Vec<(usize, &str)> = line_offsets(body);
+    let Some(cut) = quote_start(&lines) else {
+        return (body, None);
+    };
+    let offset = lines[cut].0;
+    let (reply, quoted) = body.split_at(offset);
+    if reply.trim().is_empty() {
+        return (body, None);
+    }
+    (reply.trim_end(), Some(quoted))
+}
+
+fn line_offsets(body: &str) -> Vec<(usize, &str)> {
+    let mut offset = 0;
+    body.split_inclusive('\n')
+        .map(|line| {
+            let start = offset;
+            offset += line.len();
+            (start, line.trim_end_matches(['\n', '\r']))
+        })
+        .collect()
+}
+
+fn quote_start(lines: &[(usize, &str)]) -> Option<usize> {
+    for (i, (_, line)) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if is_attribution(trimmed) {
+            return Some(i);
+        }
+        // "On Mon, Jan 1, 2024 at 10:00 AM Jane Doe <" / "jane@example.com> wrote:"
+        if trimmed.starts_with("On ")
+            && lines
+                .get(i + 1)
+                .is_some_and(|(_, next)| next.trim_end().ends_with("wrote:"))
+        {
+            return Some(i);
+        }
+        if is_original_message(trimmed) || *line == "-- " || *line == "--" {
+            return Some(i);
+        }
+        if trimmed.starts_with("From:")
+            && lines[i + 1..]
+                .iter()
+                .take(3)
+                .any(|(_, next)| next.trim_start().starts_with("Sent:"))
+        {
+            return Some(i);
+        }
+    }
+    trailing_quote(lines)
+}
+
+fn is_attribution(line: &str) -> bool {
+    line.starts_with("On ") && line.ends_with("wrote:")
+}
+
+fn is_original_message(line: &str) -> bool {
+    let inner = line.trim_matches('-').trim();
+    line.starts_with("---") && inner.eq_ignore_ascii_case("original message")
+}
+
+/// Start of a block of `>` lines (blank lines allowed) running to the end
+fn trailing_quote(lines: &[(usize, &str)]) -> Option<usize> {
+    let mut start = None;
+    for (i, (_, line)) in lines.iter().enumerate().rev() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('>') {
+            start = Some(i);
+        } else if !trimmed.is_empty() {
+            break;
+        }
+    }
+    start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_quoted_attribution() {
+        let body = "Thanks, that fixed it!\n\nOn Mon, Jan 1, 2024 at 10:00 AM Support <help@example.com> wrote:\n> Try restarting.\n> Old text";
+        let (reply, quoted) = split_quoted(body);
+        assert_eq!(reply, "Thanks, that fixed it!");
+        assert!(quoted.unwrap().starts_with("On Mon"));
+    }
+
+    #[test]
+    fn test_split_quoted_wrapped_attribution() {
+        let body = "Still broken.\n\nOn Mon, Jan 1, 2024 at 10:00 AM Support <\nhelp@example.com> wrote:\n\nTry restarting.";
+        let (reply, quoted) = split_quoted(body);
+        assert_eq!(reply, "Still broken.");
+        assert!(quoted.is_some());
+    }
+
+    #[test]
+    fn test_split_quoted_outlook_headers() {
+        let body = "See below.\r\n\r\nFrom: Support <help@example.com>\r\nSent: Monday, January 1, 2024 10:00 AM\r\nTo: Jane\r\n\r\nOld text";
+        assert_eq!(split_quoted(body).0, "See below.");
+
+        let body = "See below.\n-----Original Message-----\nOld text";
+        assert_eq!(split_quoted(body).0, "See below.");
+    }
+
+    #[test]
+    fn test_split_quoted_signature() {
+        let body = "Order 4521 never arrived.\n\n-- \nJane Doe\nAcme Inc.";
+        assert_eq!(split_quoted(body).0, "Order 4521 never arrived.");
+    }
+
+    #[test]
+    fn test_split_quoted_trailing_gt_block() {
+        let body = "Yes please.\n\n> Would you like a refund?\n>\n> Support\n";
+        assert_eq!(split_quoted(body).0, "Yes please.");
+    }
+
+    #[test]
+    fn test_split_quoted_keeps_inline_quotes() {
+        let body = "> Which order?\n4521\n> Since when?\nLast Monday";
+        assert_eq!(split_quoted(body), (body, None));
+    }
+
+    #[test]
+    fn test_split_quoted_never_hides_everything() {
+        let body = "> only quoted\n> text";
+        assert_eq!(split_quoted(body), (body, None));
+        assert_eq!(split_quoted("Plain reply"), ("Plain reply", None));
+    }
+}
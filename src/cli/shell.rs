@@ -0,0 +1,18 @@
+use std::process::Command;
+
+/// Build a process that runs `command` through the platform shell, passing
+/// extra arguments through as positional parameters like git does
+pub fn shell_command(command: &str, args: &[String]) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command).args(args);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(format!("{} \"$@\"", command))
+            .arg(command)
+            .args(args);
+        cmd
+    }
+}
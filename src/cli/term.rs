@@ -0,0 +1,29 @@
+use std::io::IsTerminal;
+
+/// Columns of the terminal stdout is printed to, or `None` when it's piped or
+/// the size can't be read. `COLUMNS` overrides the detected width.
+pub fn terminal_width() -> Option<usize> {
+    if let Some(columns) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.trim().parse().ok())
+        .filter(|&c: &usize| c > 0)
+    {
+        return Some(columns);
+    }
+    let stdout = std::io::stdout();
+    if !stdout.is_terminal() {
+        return None;
+    }
+    detect_width(&stdout)
+}
+
+#[cfg(unix)]
+fn detect_width(stdout: &std::io::Stdout) -> Option<usize> {
+    let size = rustix::termios::tcgetwinsize(stdout).ok()?;
+    (size.ws_col > 0).then_some(size.ws_col as usize)
+}
+
+#[cfg(not(unix))]
+fn detect_width(_stdout: &std::io::Stdout) -> Option<usize> {
+    None
+}
@@ -0,0 +1,261 @@
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use chrono::format::StrftimeItems;
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use colored::Color;
+use tabled::settings::object::Segment;
+use tabled::settings::{Format, Style};
+use tabled::Table;
+
+use crate::types::ConversationState;
+
+/// Border style of every table
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TableStyle {
+    #[default]
+    Rounded,
+    Ascii,
+    /// GitHub-flavored Markdown, for pasting into issues and chat
+    Markdown,
+    Psql,
+}
+
+impl FromStr for TableStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rounded" => Ok(TableStyle::Rounded),
+            "ascii" => Ok(TableStyle::Ascii),
+            "markdown" | "md" => Ok(TableStyle::Markdown),
+            "psql" => Ok(TableStyle::Psql),
+            _ => Err(format!(
+                "Invalid table style: {}. Use rounded, ascii, markdown, or psql",
+                s
+            )),
+        }
+    }
+}
+
+/// How timestamps are written
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum DateFormat {
+    /// Each view's own format, with relative times in lists
+    #[default]
+    Default,
+    /// A strftime format, in local time
+    Custom(String),
+    /// RFC 3339 in UTC everywhere, without relative times
+    Iso,
+}
+
+impl FromStr for DateFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("iso") {
+            return Ok(DateFormat::Iso);
+        }
+        StrftimeItems::new(s).parse().map_err(|_| {
+            format!(
+                "Invalid date format: {}. Use strftime codes like %d.%m.%Y %H:%M",
+                s
+            )
+        })?;
+        Ok(DateFormat::Custom(s.to_string()))
+    }
+}
+
+/// Table style, conversation state colors, and date format, from `[theme]`
+/// and `defaults.date_format` in config
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Theme {
+    pub table_style: TableStyle,
+    /// Color overrides by state; `None` prints the state uncolored
+    pub state_colors: Vec<(ConversationState, Option<Color>)>,
+    pub date_format: DateFormat,
+    /// Print OSC 8 hyperlinks; only set when writing to a terminal
+    pub hyperlinks: bool,
+    /// Show conversation states as glyphs in tables
+    pub icons: bool,
+    /// Web app URL conversation numbers link to
+    pub web_url: Option<String>,
+}
+
+impl Theme {
+    /// Set a state's color from its config name, e.g. `open = "bright green"`.
+    /// `none` turns coloring off for the state.
+    pub fn set_state_color(&mut self, state: &str, color: &str) -> Result<(), String> {
+        let state = parse_state(state)?;
+        let color = match color.to_lowercase().as_str() {
+            "none" => None,
+            name => Some(Color::from_str(name).map_err(|_| {
+                format!(
+                    "Invalid color: {}. Use black, red, green, yellow, blue, magenta, cyan, \
+                     white, a \"bright\" variant like \"bright red\", or none",
+                    color
+                )
+            })?),
+        };
+        self.state_colors.retain(|(s, _)| *s != state);
+        self.state_colors.push((state, color));
+        Ok(())
+    }
+}
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Use `theme` for the rest of the process. Only the first call has an effect.
+pub fn set_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
+
+/// Render `table` in the configured style
+pub(crate) fn styled(mut table: Table) -> String {
+    match theme().table_style {
+        TableStyle::Rounded => table.with(Style::rounded()),
+        TableStyle::Ascii => table.with(Style::ascii()),
+        TableStyle::Markdown => table
+            .modify(Segment::all(), Format::content(|s| s.replace('|', "\\|")))
+            .with(Style::markdown()),
+        TableStyle::Psql => table.with(Style::psql()),
+    }
+    .to_string()
+}
+
+/// The color `state` is printed in, if any
+pub(crate) fn state_color(state: &ConversationState) -> Option<Color> {
+    if let Some((_, color)) = theme().state_colors.iter().find(|(s, _)| s == state) {
+        return *color;
+    }
+    Some(match state {
+        ConversationState::Unread => Color::Yellow,
+        ConversationState::Opened => Color::Green,
+        ConversationState::Closed => Color::White,
+        ConversationState::Snoozed => Color::Blue,
+        ConversationState::Spam => Color::Red,
+        ConversationState::Deleted => Color::White,
+    })
+}
+
+/// `dt` in the configured date format, or `None` to use the caller's own
+pub(crate) fn configured_date(dt: &DateTime<Utc>) -> Option<String> {
+    match &theme().date_format {
+        DateFormat::Default => None,
+        DateFormat::Custom(format) => Some(dt.with_timezone(&Local).format(format).to_string()),
+        DateFormat::Iso => Some(dt.to_rfc3339_opts(SecondsFormat::Secs, true)),
+    }
+}
+
+/// Whether table Status columns show glyphs instead of state names
+pub(crate) fn icons() -> bool {
+    theme().icons
+}
+
+/// Whether relative times like "3h ago" are replaced by timestamps
+pub(crate) fn iso_dates() -> bool {
+    theme().date_format == DateFormat::Iso
+}
+
+/// `text` as an OSC 8 hyperlink to `url`, or unchanged when hyperlinks are
+/// off. Markdown tables are meant for pasting, so they get plain text.
+pub(crate) fn hyperlink(text: &str, url: &str) -> String {
+    let theme = theme();
+    if !theme.hyperlinks || theme.table_style == TableStyle::Markdown {
+        return text.to_string();
+    }
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// `text` linked to conversation `number` in the web app, if `web_url` is set
+pub(crate) fn link_conversation(number: i64, text: &str) -> String {
+    match &theme().web_url {
+        Some(base) => hyperlink(
+            text,
+            &format!("{}/conversations/{}", base.trim_end_matches('/'), number),
+        ),
+        None => text.to_string(),
+    }
+}
+
+/// `text` linked to a new email to `email`
+pub(crate) fn link_email(email: &str, text: &str) -> String {
+    hyperlink(text, &format!("mailto:{}", email))
+}
+
+/// A local timestamp for messages, e.g. when a reply is queued for
+pub fn format_timestamp(dt: &DateTime<Utc>) -> String {
+    configured_date(dt).unwrap_or_else(|| {
+        dt.with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    })
+}
+
+/// A state as named in `[theme.colors]`: the name `groove` prints it with
+fn parse_state(name: &str) -> Result<ConversationState, String> {
+    match name.to_lowercase().as_str() {
+        "unread" => Ok(ConversationState::Unread),
+        "open" | "opened" => Ok(ConversationState::Opened),
+        "closed" => Ok(ConversationState::Closed),
+        "snoozed" => Ok(ConversationState::Snoozed),
+        "spam" => Ok(ConversationState::Spam),
+        "deleted" => Ok(ConversationState::Deleted),
+        _ => Err(format!(
+            "Invalid state: {}. Use unread, open, closed, snoozed, spam, or deleted",
+            name
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_parsing() {
+        assert_eq!("Markdown".parse(), Ok(TableStyle::Markdown));
+        assert!("fancy"
+            .parse::<TableStyle>()
+            .unwrap_err()
+            .contains("Invalid table style: fancy"));
+
+        let mut theme = Theme::default();
+        theme.set_state_color("open", "bright green").unwrap();
+        theme.set_state_color("closed", "none").unwrap();
+        theme.set_state_color("open", "cyan").unwrap();
+        assert_eq!(
+            theme.state_colors,
+            vec![
+                (ConversationState::Closed, None),
+                (ConversationState::Opened, Some(Color::Cyan)),
+            ]
+        );
+        assert!(theme
+            .set_state_color("pending", "red")
+            .unwrap_err()
+            .contains("Invalid state: pending"));
+        assert!(theme
+            .set_state_color("open", "teal")
+            .unwrap_err()
+            .contains("Invalid color: teal"));
+    }
+
+    #[test]
+    fn test_date_format_parsing() {
+        assert_eq!("ISO".parse(), Ok(DateFormat::Iso));
+        assert_eq!(
+            "%d.%m.%Y %H:%M".parse(),
+            Ok(DateFormat::Custom("%d.%m.%Y %H:%M".to_string()))
+        );
+        assert!("%d.%m.%Q"
+            .parse::<DateFormat>()
+            .unwrap_err()
+            .contains("Invalid date format"));
+    }
+}
@@ -0,0 +1,535 @@
+//! The terminal composer behind `groove conversation compose`: the thread on
+//! the left, the reply being written on the right, and shortcuts to insert a
+//! canned reply and to send.
+
+use std::io;
+use std::path::PathBuf;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::export::{self, ExportFormat};
+use crate::types::{CannedReply, Conversation, Message};
+
+/// Shortcuts shown along the bottom of the composer
+const HELP: &str =
+    "Ctrl-S send · Ctrl-X send and close · Ctrl-R canned reply · Tab switch pane · Esc cancel";
+
+/// Lines the thread scrolls by for Page Up and Page Down
+const PAGE: u16 = 10;
+
+/// How a compose session ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Send `body`, then close the conversation if `close` is set
+    Send { body: String, close: bool },
+    /// Left without sending; `draft` is what had been written
+    Cancel { draft: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Reply,
+    Thread,
+}
+
+pub struct Composer {
+    title: String,
+    thread: String,
+    thread_scroll: u16,
+    reply: Editor,
+    /// Rows and columns the reply pane is scrolled by to keep the cursor in view
+    reply_scroll: (u16, u16),
+    focus: Focus,
+    canned: Vec<CannedReply>,
+    /// The canned reply picker, while it's open
+    picker: Option<ListState>,
+    status: Option<String>,
+}
+
+impl Composer {
+    /// A composer for replying to `conv`, with the reply started from
+    /// `prefill` and `canned` offered for insertion
+    pub fn new(
+        conv: &Conversation,
+        messages: &[Message],
+        prefill: &str,
+        canned: Vec<CannedReply>,
+    ) -> Self {
+        Self {
+            title: format!(
+                "#{} {}",
+                conv.number,
+                conv.subject.as_deref().unwrap_or("(no subject)")
+            ),
+            thread: export::render(conv, messages, ExportFormat::Text),
+            thread_scroll: 0,
+            reply: Editor::new(prefill),
+            reply_scroll: (0, 0),
+            focus: Focus::Reply,
+            canned,
+            picker: None,
+            status: None,
+        }
+    }
+
+    /// Take over the terminal until the reply is sent or abandoned
+    pub fn run(mut self) -> io::Result<Outcome> {
+        let mut terminal = ratatui::try_init()?;
+        let outcome = self.event_loop(&mut terminal);
+        ratatui::restore();
+        outcome
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> io::Result<Outcome> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                if let Some(outcome) = self.handle_key(key) {
+                    return Ok(outcome);
+                }
+            }
+        }
+    }
+
+    /// Apply a key press, returning how the session ended if it did
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Outcome> {
+        self.status = None;
+        if self.picker.is_some() {
+            self.handle_picker_key(key);
+            return None;
+        }
+
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char('s') => return self.send(false),
+                KeyCode::Char('x') => return self.send(true),
+                KeyCode::Char('r') => self.open_picker(),
+                KeyCode::Char('c') | KeyCode::Char('q') => return Some(self.cancel()),
+                _ => {}
+            }
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Esc => return Some(self.cancel()),
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Reply => Focus::Thread,
+                    Focus::Thread => Focus::Reply,
+                }
+            }
+            KeyCode::PageUp => self.thread_scroll = self.thread_scroll.saturating_sub(PAGE),
+            KeyCode::PageDown => self.scroll_thread(PAGE),
+            _ if self.focus == Focus::Thread => match key.code {
+                KeyCode::Up => self.thread_scroll = self.thread_scroll.saturating_sub(1),
+                KeyCode::Down => self.scroll_thread(1),
+                KeyCode::Home => self.thread_scroll = 0,
+                KeyCode::End => self.scroll_thread(u16::MAX),
+                _ => {}
+            },
+            KeyCode::Char(c) => self.reply.insert(c),
+            KeyCode::Enter => self.reply.insert('\n'),
+            KeyCode::Backspace => self.reply.backspace(),
+            KeyCode::Delete => self.reply.delete(),
+            KeyCode::Left => self.reply.left(),
+            KeyCode::Right => self.reply.right(),
+            KeyCode::Up => self.reply.up(),
+            KeyCode::Down => self.reply.down(),
+            KeyCode::Home => self.reply.col = 0,
+            KeyCode::End => self.reply.col = self.reply.line_len(),
+            _ => {}
+        }
+        None
+    }
+
+    fn handle_picker_key(&mut self, key: KeyEvent) {
+        let Some(picker) = &mut self.picker else {
+            return;
+        };
+        match key.code {
+            KeyCode::Up => picker.select_previous(),
+            KeyCode::Down => picker.select_next(),
+            KeyCode::Enter => {
+                let body = picker
+                    .selected()
+                    .and_then(|i| self.canned.get(i))
+                    .and_then(|reply| reply.body.clone());
+                self.picker = None;
+                if let Some(body) = body {
+                    self.reply.insert_str(body.trim_end());
+                }
+            }
+            KeyCode::Esc => self.picker = None,
+            _ => {}
+        }
+    }
+
+    fn open_picker(&mut self) {
+        if self.canned.is_empty() {
+            self.status = Some("No canned replies".to_string());
+        } else {
+            self.picker = Some(ListState::default().with_selected(Some(0)));
+        }
+    }
+
+    fn send(&mut self, close: bool) -> Option<Outcome> {
+        let body = self.reply.text();
+        let body = body.trim();
+        if body.is_empty() {
+            self.status = Some("Write a reply first".to_string());
+            return None;
+        }
+        Some(Outcome::Send {
+            body: body.to_string(),
+            close,
+        })
+    }
+
+    fn cancel(&self) -> Outcome {
+        Outcome::Cancel {
+            draft: self.reply.text().trim().to_string(),
+        }
+    }
+
+    fn scroll_thread(&mut self, lines: u16) {
+        let last = u16::try_from(self.thread.lines().count()).unwrap_or(u16::MAX);
+        self.thread_scroll = self.thread_scroll.saturating_add(lines).min(last);
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+        let [thread_area, reply_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(main);
+
+        let pane = |title: String, focused: bool| {
+            let block = Block::bordered().title(title);
+            if focused {
+                block.border_style(Style::new().add_modifier(Modifier::BOLD))
+            } else {
+                block.border_style(Style::new().add_modifier(Modifier::DIM))
+            }
+        };
+
+        let thread = Paragraph::new(self.thread.as_str())
+            .block(pane(self.title.clone(), self.focus == Focus::Thread))
+            .wrap(Wrap { trim: false })
+            .scroll((self.thread_scroll, 0));
+        frame.render_widget(thread, thread_area);
+
+        self.follow_cursor(reply_area);
+        let reply = Paragraph::new(self.reply.lines.join("\n"))
+            .block(pane("Reply".to_string(), self.focus == Focus::Reply))
+            .scroll(self.reply_scroll);
+        frame.render_widget(reply, reply_area);
+        if self.focus == Focus::Reply && self.picker.is_none() {
+            let (row, col) = self.reply.cursor();
+            frame.set_cursor_position(Position::new(
+                reply_area.x + 1 + col - self.reply_scroll.1,
+                reply_area.y + 1 + row - self.reply_scroll.0,
+            ));
+        }
+
+        let footer_text = self.status.as_deref().unwrap_or(HELP);
+        frame.render_widget(
+            Paragraph::new(footer_text).style(Style::new().add_modifier(Modifier::DIM)),
+            footer,
+        );
+
+        if let Some(picker) = &mut self.picker {
+            let area = centered(main, 50, 60);
+            let items: Vec<ListItem> = self
+                .canned
+                .iter()
+                .map(|reply| ListItem::new(Line::from(reply.name.as_str())))
+                .collect();
+            let list = List::new(items)
+                .block(Block::bordered().title("Insert canned reply (Enter, Esc)"))
+                .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(list, area, picker);
+        }
+    }
+
+    /// Scroll the reply pane so the cursor stays inside `area`'s borders
+    fn follow_cursor(&mut self, area: Rect) {
+        let height = area.height.saturating_sub(2).max(1);
+        let width = area.width.saturating_sub(2).max(1);
+        let (row, col) = self.reply.cursor();
+        let (top, left) = &mut self.reply_scroll;
+        *top = (*top).clamp(row.saturating_sub(height - 1), row);
+        *left = (*left).clamp(col.saturating_sub(width - 1), col);
+    }
+}
+
+/// A `percent_x` by `percent_y` area in the middle of `area`
+fn centered(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let [_, middle, _] = Layout::vertical([
+        Constraint::Percentage((100 - percent_y) / 2),
+        Constraint::Percentage(percent_y),
+        Constraint::Percentage((100 - percent_y) / 2),
+    ])
+    .areas(area);
+    let [_, center, _] = Layout::horizontal([
+        Constraint::Percentage((100 - percent_x) / 2),
+        Constraint::Percentage(percent_x),
+        Constraint::Percentage((100 - percent_x) / 2),
+    ])
+    .areas(middle);
+    center
+}
+
+/// The reply being written: lines of text and a cursor, in characters
+#[derive(Debug, Clone, Default)]
+struct Editor {
+    lines: Vec<String>,
+    row: usize,
+    col: usize,
+}
+
+impl Editor {
+    /// An editor holding `text`, with the cursor at its end
+    fn new(text: &str) -> Self {
+        let mut editor = Self {
+            lines: vec![String::new()],
+            row: 0,
+            col: 0,
+        };
+        editor.insert_str(text);
+        editor
+    }
+
+    fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// The cursor's row and column, for placing it on screen
+    fn cursor(&self) -> (u16, u16) {
+        let clamp = |n: usize| u16::try_from(n).unwrap_or(u16::MAX);
+        (clamp(self.row), clamp(self.col))
+    }
+
+    fn line_len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+
+    /// Byte offset of the cursor in its line
+    fn offset(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices()
+            .nth(self.col)
+            .map_or(line.len(), |(i, _)| i)
+    }
+
+    fn insert(&mut self, c: char) {
+        let offset = self.offset();
+        if c == '\n' {
+            let rest = self.lines[self.row].split_off(offset);
+            self.row += 1;
+            self.lines.insert(self.row, rest);
+            self.col = 0;
+        } else {
+            self.lines[self.row].insert(offset, c);
+            self.col += 1;
+        }
+    }
+
+    fn insert_str(&mut self, text: &str) {
+        for c in text.chars().filter(|c| *c != '\r') {
+            self.insert(c);
+        }
+    }
+
+    fn backspace(&mut self) {
+        if self.col > 0 {
+            self.left();
+            self.delete();
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line_len();
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.col < self.line_len() {
+            let offset = self.offset();
+            self.lines[self.row].remove(offset);
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+        }
+    }
+
+    fn left(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.line_len();
+        }
+    }
+
+    fn right(&mut self) {
+        if self.col < self.line_len() {
+            self.col += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+    }
+
+    fn up(&mut self) {
+        if self.row > 0 {
+            self.row -= 1;
+            self.col = self.col.min(self.line_len());
+        }
+    }
+
+    fn down(&mut self) {
+        if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = self.col.min(self.line_len());
+        }
+    }
+}
+
+/// Where to keep the draft of a reply to conversation `number` that wasn't
+/// sent
+pub fn draft_path(number: i64) -> PathBuf {
+    std::env::temp_dir().join(format!("groove-reply-{}-{}.md", number, std::process::id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    fn composer(prefill: &str) -> Composer {
+        let conv = Conversation::builder(42)
+            .id("conv-1")
+            .subject("Order #881")
+            .build();
+        let canned = vec![CannedReply {
+            id: "canned-1".to_string(),
+            name: "Refund".to_string(),
+            subject: None,
+            body: Some("Your refund is on its way.\n".to_string()),
+        }];
+        Composer::new(&conv, &[], prefill, canned)
+    }
+
+    fn type_text(composer: &mut Composer, text: &str) {
+        for c in text.chars() {
+            let code = if c == '\n' {
+                KeyCode::Enter
+            } else {
+                KeyCode::Char(c)
+            };
+            assert_eq!(composer.handle_key(key(code)), None);
+        }
+    }
+
+    #[test]
+    fn test_editor() {
+        let mut editor = Editor::new("Hi there,\n");
+        assert_eq!(editor.cursor(), (1, 0));
+        editor.insert_str("Thanks — sorted");
+        editor.backspace();
+        editor.left();
+        editor.left();
+        editor.insert('!');
+        assert_eq!(editor.text(), "Hi there,\nThanks — sor!te");
+
+        editor.col = 0;
+        editor.backspace();
+        assert_eq!(editor.text(), "Hi there,Thanks — sor!te");
+        assert_eq!(editor.cursor(), (0, 9));
+        editor.insert('\n');
+        editor.up();
+        editor.delete();
+        assert_eq!(editor.text(), "i there,\nThanks — sor!te");
+    }
+
+    #[test]
+    fn test_send_and_close_shortcuts() {
+        // Nothing is sent without a reply
+        let mut empty = composer("");
+        assert_eq!(empty.handle_key(ctrl('s')), None);
+        assert_eq!(empty.status.as_deref(), Some("Write a reply first"));
+
+        let mut composer = composer("Hi,");
+        type_text(&mut composer, "\nAll done.");
+        assert_eq!(
+            composer.handle_key(ctrl('x')),
+            Some(Outcome::Send {
+                body: "Hi,\nAll done.".to_string(),
+                close: true
+            })
+        );
+        assert_eq!(
+            composer.handle_key(key(KeyCode::Esc)),
+            Some(Outcome::Cancel {
+                draft: "Hi,\nAll done.".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_insert_canned_reply() {
+        let mut composer = composer("Hi Jane,\n\n");
+        composer.handle_key(ctrl('r'));
+        assert!(composer.picker.is_some());
+        // Typing goes nowhere while the picker is open
+        type_text(&mut composer, "zz");
+        composer.handle_key(key(KeyCode::Enter));
+        assert!(composer.picker.is_none());
+        assert_eq!(
+            composer.handle_key(ctrl('s')),
+            Some(Outcome::Send {
+                body: "Hi Jane,\n\nYour refund is on its way.".to_string(),
+                close: false
+            })
+        );
+    }
+
+    #[test]
+    fn test_draw_thread_beside_reply() {
+        let mut composer = composer("Hello from the reply pane");
+        let mut terminal = Terminal::new(TestBackend::new(100, 12)).unwrap();
+        terminal.draw(|frame| composer.draw(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("#42 Order #881"));
+        assert!(screen.contains("Hello from the reply pane"));
+        assert!(screen.contains("Ctrl-S send"));
+
+        composer.handle_key(key(KeyCode::Tab));
+        composer.handle_key(key(KeyCode::Char('x')));
+        assert_eq!(composer.reply.text(), "Hello from the reply pane");
+    }
+}
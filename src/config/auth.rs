@@ -1,5 +1,7 @@
-use crate::config::Config;
+use crate::cli::shell_command;
+use crate::config::{keychain, Config};
 use crate::error::{GrooveError, Result};
+use std::process::Stdio;
 
 pub fn resolve_token(cli_token: Option<&str>, config: &Config) -> Result<String> {
     // 1. CLI flag (--token)
@@ -14,10 +16,100 @@ pub fn resolve_token(cli_token: Option<&str>, config: &Config) -> Result<String>
         }
     }
 
-    // 3. Config file
+    // 3. The selected profile's keyring entry, unless the profile has its
+    //    own token or token_cmd; it comes before the top-level credentials
+    //    the profile would otherwise inherit
+    if let Some(profile) = keyring_profile(config) {
+        if let Ok(Some(token)) = keychain::get_token(Some(profile)) {
+            return Ok(token);
+        }
+    }
+
+    // 4. Config file
     if let Some(token) = &config.api_token {
         return Ok(token.clone());
     }
 
+    // 5. Password manager command from the config file
+    if let Some(command) = &config.token_cmd {
+        return run_token_cmd(command);
+    }
+
+    // 6. OS keyring (an unavailable keyring is treated as no token)
+    if let Ok(Some(token)) = keychain::get_token(config.active_profile.as_deref()) {
+        return Ok(token);
+    }
+
     Err(GrooveError::TokenNotFound)
 }
+
+/// The active profile, if it sets neither `api_token` nor `token_cmd` itself
+fn keyring_profile(config: &Config) -> Option<&str> {
+    let name = config.active_profile.as_deref()?;
+    let profile = config.profiles.get(name)?;
+    (profile.api_token.is_none() && profile.token_cmd.is_none()).then_some(name)
+}
+
+/// Run `token_cmd` and take the first line of its output, so `pass show`
+/// entries with extra lines work. Stderr and stdin stay attached to the
+/// terminal for passphrase prompts.
+fn run_token_cmd(command: &str) -> Result<String> {
+    let output = shell_command(command, &[])
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| {
+            GrooveError::Config(format!("Could not run token_cmd '{}': {}", command, e))
+        })?;
+    if !output.status.success() {
+        return Err(GrooveError::Config(format!(
+            "token_cmd '{}' failed ({})",
+            command, output.status
+        )));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.lines().next().map(str::trim) {
+        Some(token) if !token.is_empty() => Ok(token.to_string()),
+        _ => Err(GrooveError::Config(format!(
+            "token_cmd '{}' printed no token",
+            command
+        ))),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_cmd() {
+        let config = Config {
+            token_cmd: Some("printf 'secret\\nuser: alex\\n'".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(resolve_token(Some("flag"), &config).unwrap(), "flag");
+        if std::env::var_os("GROOVEHQ_API_TOKEN").is_none() {
+            assert_eq!(resolve_token(None, &config).unwrap(), "secret");
+        }
+
+        let err = run_token_cmd("exit 3").unwrap_err();
+        assert!(err.to_string().contains("failed"), "{}", err);
+        let err = run_token_cmd("true").unwrap_err();
+        assert!(err.to_string().contains("printed no token"), "{}", err);
+    }
+
+    #[test]
+    fn test_keyring_profile() {
+        let config: Config = toml::from_str(
+            "api_token = \"default-token\"\n\
+             [profiles.work]\n\
+             [profiles.own]\napi_token = \"own-token\"\n",
+        )
+        .unwrap();
+        assert_eq!(keyring_profile(&config), None);
+        let work = config.with_profile(Some("work")).unwrap();
+        assert_eq!(keyring_profile(&work), Some("work"));
+        let own = config.with_profile(Some("own")).unwrap();
+        assert_eq!(keyring_profile(&own), None);
+    }
+}
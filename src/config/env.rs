@@ -0,0 +1,107 @@
+//! `GROOVE_*` environment variables, for containers and CI where editing
+//! the config file is awkward.
+//!
+//! Settings resolve, highest first: command-line flags, these variables, the
+//! selected profile, then the top-level config. `GROOVE_PROFILE`,
+//! `GROOVEHQ_API_TOKEN`, and `GROOVEHQ_API_ENDPOINT` are read with the flags
+//! they stand in for.
+
+use super::Config;
+use crate::error::{GrooveError, Result};
+
+/// Each variable and the config key it sets
+pub const VARIABLES: &[(&str, &str)] = &[
+    ("GROOVE_ENDPOINT", "api_endpoint"),
+    ("GROOVE_WEB_URL", "web_url"),
+    ("GROOVE_PROXY", "proxy"),
+    ("GROOVE_PDF_COMMAND", "pdf_command"),
+    ("GROOVE_TEMPLATES_DIR", "templates_dir"),
+    ("GROOVE_FORMAT", "defaults.format"),
+    ("GROOVE_LIMIT", "defaults.limit"),
+    ("GROOVE_FOLDER", "defaults.folder"),
+    ("GROOVE_CACHE_TTL", "defaults.cache_ttl"),
+    ("GROOVE_COMPACT_FORMAT", "defaults.compact_format"),
+    ("GROOVE_DATE_FORMAT", "defaults.date_format"),
+    ("GROOVE_TABLE_STYLE", "theme.table_style"),
+];
+
+impl Config {
+    /// Override settings with any `GROOVE_*` variables that are set. Apply
+    /// after [`with_profile`](Self::with_profile) so they win over it.
+    pub fn with_env(self) -> Result<Config> {
+        self.with_vars(|name| std::env::var(name).ok())
+    }
+
+    fn with_vars(mut self, var: impl Fn(&str) -> Option<String>) -> Result<Config> {
+        for (name, key) in VARIABLES {
+            let Some(value) = var(name).filter(|v| !v.is_empty()) else {
+                continue;
+            };
+            self.set_key(key, &value).map_err(|_| {
+                GrooveError::Config(format!("Invalid {} '{}' (sets {})", name, value, key))
+            })?;
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn vars(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let map: HashMap<String, String> = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| map.get(name).cloned()
+    }
+
+    #[test]
+    fn test_env_overrides_config() {
+        let config: Config = toml::from_str(
+            "api_endpoint = \"https://file.example.com\"\n\
+             [defaults]\nlimit = 10\nformat = \"table\"\n",
+        )
+        .unwrap();
+        let config = config
+            .with_vars(vars(&[
+                ("GROOVE_ENDPOINT", "https://env.example.com"),
+                ("GROOVE_LIMIT", "50"),
+                ("GROOVE_FORMAT", ""),
+            ]))
+            .unwrap();
+        assert_eq!(
+            config.api_endpoint.as_deref(),
+            Some("https://env.example.com")
+        );
+        assert_eq!(config.defaults.limit, Some(50));
+        // Empty counts as unset
+        assert_eq!(config.defaults.format.as_deref(), Some("table"));
+    }
+
+    #[test]
+    fn test_env_keeps_active_profile() {
+        let config: Config = toml::from_str("[profiles.work]\n").unwrap();
+        let config = config
+            .with_profile(Some("work"))
+            .unwrap()
+            .with_vars(vars(&[("GROOVE_LIMIT", "5")]))
+            .unwrap();
+        assert_eq!(config.defaults.limit, Some(5));
+        assert_eq!(config.active_profile.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn test_env_invalid_value() {
+        let err = Config::default()
+            .with_vars(vars(&[("GROOVE_LIMIT", "lots")]))
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid GROOVE_LIMIT 'lots'"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use crate::error::{GrooveError, Result};
+
+const SERVICE: &str = "groove-cli";
+const DEFAULT_ACCOUNT: &str = "default";
+
+fn entry(profile: Option<&str>) -> Result<keyring::Entry> {
+    keyring::Entry::new(SERVICE, profile.unwrap_or(DEFAULT_ACCOUNT))
+        .map_err(|e| GrooveError::Keyring(e.to_string()))
+}
+
+/// Read the token for a profile from the OS keyring, if one is stored
+pub fn get_token(profile: Option<&str>) -> Result<Option<String>> {
+    match entry(profile)?.get_password() {
+        Ok(token) => Ok(Some(token)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(GrooveError::Keyring(e.to_string())),
+    }
+}
+
+/// Store the token for a profile in the OS keyring
+pub fn set_token(profile: Option<&str>, token: &str) -> Result<()> {
+    entry(profile)?
+        .set_password(token)
+        .map_err(|e| GrooveError::Keyring(e.to_string()))
+}
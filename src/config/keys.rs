@@ -0,0 +1,196 @@
+use super::Config;
+use crate::error::{GrooveError, Result};
+use toml::{Table, Value};
+
+impl Config {
+    /// Look up a setting by dot-path key (e.g. `defaults.limit`, `aliases.ls`)
+    pub fn get_key(&self, key: &str) -> Result<Option<Value>> {
+        let path = parse_key(key)?;
+        let mut current = &Value::Table(self.to_table()?);
+        for segment in path {
+            match current.get(segment) {
+                Some(value) => current = value,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(current.clone()))
+    }
+
+    /// Set a setting by dot-path key. Numbers and booleans are stored as such
+    /// when the setting accepts them, anything else is stored as a string.
+    pub fn set_key(&mut self, key: &str, value: &str) -> Result<()> {
+        let path = parse_key(key)?;
+        let candidates = [parse_scalar(value), Value::String(value.to_string())];
+
+        let mut last_err = None;
+        for candidate in candidates {
+            let mut table = self.to_table()?;
+            insert_at(&mut table, &path, candidate)?;
+            match Self::from_table(table) {
+                Ok(mut updated) => {
+                    if updated.get_key(key)?.is_none() {
+                        return Err(GrooveError::Config(format!("Unknown config key '{}'", key)));
+                    }
+                    updated.active_profile = self.active_profile.take();
+                    *self = updated;
+                    return Ok(());
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+
+        Err(GrooveError::Config(format!(
+            "Invalid value '{}' for '{}': {}",
+            value,
+            key,
+            last_err.map(|e| e.to_string()).unwrap_or_default()
+        )))
+    }
+
+    /// Remove a setting by dot-path key. Returns whether the key was set.
+    pub fn unset_key(&mut self, key: &str) -> Result<bool> {
+        let path = parse_key(key)?;
+        let mut table = self.to_table()?;
+
+        let (last, parents) = path
+            .split_last()
+            .expect("parse_key returns at least one segment");
+        let mut current = &mut table;
+        for segment in parents {
+            match current.get_mut(*segment) {
+                Some(Value::Table(t)) => current = t,
+                _ => return Ok(false),
+            }
+        }
+        if current.remove(*last).is_none() {
+            return Ok(false);
+        }
+
+        let mut updated = Self::from_table(table)?;
+        updated.active_profile = self.active_profile.take();
+        *self = updated;
+        Ok(true)
+    }
+
+    fn to_table(&self) -> Result<Table> {
+        Table::try_from(self).map_err(|e| GrooveError::Config(e.to_string()))
+    }
+
+    /// The config described by `table`. `active_profile` isn't part of the
+    /// file, so callers carry it over themselves.
+    fn from_table(table: Table) -> Result<Config> {
+        Value::Table(table)
+            .try_into()
+            .map_err(|e: toml::de::Error| GrooveError::Config(e.message().to_string()))
+    }
+}
+
+fn parse_key(key: &str) -> Result<Vec<&str>> {
+    let path: Vec<&str> = key.split('.').collect();
+    if path.iter().any(|s| s.is_empty()) {
+        return Err(GrooveError::Config(format!("Invalid config key '{}'", key)));
+    }
+    Ok(path)
+}
+
+fn parse_scalar(value: &str) -> Value {
+    if let Ok(n) = value.parse::<i64>() {
+        Value::Integer(n)
+    } else if let Ok(b) = value.parse::<bool>() {
+        Value::Boolean(b)
+    } else {
+        Value::String(value.to_string())
+    }
+}
+
+fn insert_at(table: &mut Table, path: &[&str], value: Value) -> Result<()> {
+    let (last, parents) = path
+        .split_last()
+        .expect("parse_key returns at least one segment");
+    let mut current = table;
+    for segment in parents {
+        let entry = current
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Table(Table::new()));
+        current = match entry {
+            Value::Table(t) => t,
+            _ => {
+                return Err(GrooveError::Config(format!(
+                    "'{}' is not a section and cannot contain keys",
+                    segment
+                )))
+            }
+        };
+    }
+    current.insert(last.to_string(), value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_get_key() {
+        let mut config = Config::default();
+        config.set_key("defaults.limit", "50").unwrap();
+        config.set_key("defaults.format", "json").unwrap();
+        config.set_key("aliases.ls", "conversation list").unwrap();
+
+        assert_eq!(config.defaults.limit, Some(50));
+        assert_eq!(config.defaults.format, Some("json".to_string()));
+        assert_eq!(
+            config.get_key("aliases.ls").unwrap(),
+            Some(Value::String("conversation list".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_set_numeric_string_value() {
+        let mut config = Config::default();
+        config.set_key("defaults.folder", "12345").unwrap();
+        assert_eq!(config.defaults.folder, Some("12345".to_string()));
+    }
+
+    #[test]
+    fn test_set_profile_key_creates_section() {
+        let mut config = Config::default();
+        config.set_key("profiles.work.api_token", "abc").unwrap();
+        assert_eq!(
+            config
+                .profiles
+                .get("work")
+                .and_then(|p| p.api_token.clone()),
+            Some("abc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_set_invalid_value() {
+        let mut config = Config::default();
+        let err = config.set_key("defaults.limit", "lots").unwrap_err();
+        assert!(err.to_string().contains("Invalid value"));
+    }
+
+    #[test]
+    fn test_set_unknown_key() {
+        let mut config = Config::default();
+        let err = config.set_key("defaults.colour", "red").unwrap_err();
+        assert!(err.to_string().contains("Unknown config key"));
+    }
+
+    #[test]
+    fn test_unset_key() {
+        let mut config = Config::default();
+        config.set_key("defaults.limit", "50").unwrap();
+        assert!(config.unset_key("defaults.limit").unwrap());
+        assert!(config.defaults.limit.is_none());
+        assert!(!config.unset_key("defaults.limit").unwrap());
+    }
+
+    #[test]
+    fn test_invalid_key() {
+        let config = Config::default();
+        assert!(config.get_key("defaults..limit").is_err());
+    }
+}
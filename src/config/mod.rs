@@ -1,23 +1,70 @@
 mod auth;
+mod env;
+pub mod keychain;
+mod keys;
 
 pub use auth::resolve_token;
+pub use env::VARIABLES as ENV_VARIABLES;
 
 use crate::error::{GrooveError, Result};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub api_token: Option<String>,
+
+    /// Command whose output is the API token, e.g. "pass show groove/token",
+    /// used when `api_token` isn't set
+    pub token_cmd: Option<String>,
+
     pub api_endpoint: Option<String>,
 
+    /// Base URL of the Groove web app, e.g. https://acme.groovehq.com
+    pub web_url: Option<String>,
+
+    /// HTTP(S) proxy for API requests; overrides HTTPS_PROXY/HTTP_PROXY
+    pub proxy: Option<String>,
+
+    /// HTML-to-PDF converter for PDF exports, run with the input and output
+    /// paths appended (e.g. "weasyprint")
+    pub pdf_command: Option<String>,
+
+    /// Directory `reply --template` looks in; defaults to `templates/` next
+    /// to the config file
+    pub templates_dir: Option<PathBuf>,
+
     #[serde(default)]
     pub defaults: DefaultSettings,
 
+    /// `[theme]`: table style and state colors
+    #[serde(default, skip_serializing_if = "ThemeSettings::is_empty")]
+    pub theme: ThemeSettings,
+
+    /// `[sla]`: response-time target flagged in conversation lists
+    #[serde(default, skip_serializing_if = "SlaSettings::is_empty")]
+    pub sla: SlaSettings,
+
     #[serde(default)]
     pub aliases: HashMap<String, String>,
+
+    /// Commands run before/after conversation actions, keyed like `post-reply`
+    #[serde(default)]
+    pub hooks: HashMap<String, String>,
+
+    /// Named sequences of conversation subcommands run by `groove macro`
+    #[serde(default)]
+    pub macros: HashMap<String, Vec<String>>,
+
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>,
+
+    /// Name of the profile applied by `with_profile`
+    #[serde(skip)]
+    pub active_profile: Option<String>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -25,14 +72,159 @@ pub struct DefaultSettings {
     pub format: Option<String>,
     pub limit: Option<u32>,
     pub folder: Option<String>,
+
+    /// Seconds to cache tags, folders, agents, and canned replies (0 disables)
+    pub cache_ttl: Option<u64>,
+
+    /// `-o compact` line for conversation lists, e.g. "#{number} {subject}"
+    pub compact_format: Option<String>,
+
+    /// strftime format for dates and times (e.g. "%d.%m.%Y %H:%M"), or "iso"
+    pub date_format: Option<String>,
+
+    /// `[defaults.list]`: flags for `conversation list`
+    #[serde(default, skip_serializing_if = "ListDefaults::is_empty")]
+    pub list: ListDefaults,
+
+    /// `[defaults.view]`: flags for `conversation view`
+    #[serde(default, skip_serializing_if = "ViewDefaults::is_empty")]
+    pub view: ViewDefaults,
+}
+
+/// Defaults for `conversation list` flags. Values are spelled as on the
+/// command line and checked when the command runs.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListDefaults {
+    pub status: Option<String>,
+    pub folder: Option<String>,
+    pub sort: Option<String>,
+    pub group_by: Option<String>,
+    pub limit: Option<u32>,
+    /// Table columns, in order (e.g. ["number", "status", "subject", "messages"])
+    pub columns: Option<Vec<String>>,
+}
+
+/// Defaults for `conversation view` flags
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewDefaults {
+    pub full: Option<bool>,
+    pub show_quoted: Option<bool>,
+    pub reverse: Option<bool>,
+}
+
+/// How tables and conversation states look. Values are checked when a
+/// command runs.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThemeSettings {
+    /// "rounded" (default), "ascii", "markdown", or "psql"
+    pub table_style: Option<String>,
+
+    /// Color per state, e.g. `open = "bright green"` or `closed = "none"`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub colors: HashMap<String, String>,
+
+    /// Make conversation numbers and contact emails clickable (OSC 8) when
+    /// printing to a terminal; on by default
+    pub hyperlinks: Option<bool>,
+
+    /// Show conversation states as glyphs in table Status columns
+    pub icons: Option<bool>,
+}
+
+impl DefaultSettings {
+    /// Fill any unset values from `base`
+    fn or(self, base: DefaultSettings) -> DefaultSettings {
+        DefaultSettings {
+            format: self.format.or(base.format),
+            limit: self.limit.or(base.limit),
+            folder: self.folder.or(base.folder),
+            cache_ttl: self.cache_ttl.or(base.cache_ttl),
+            compact_format: self.compact_format.or(base.compact_format),
+            date_format: self.date_format.or(base.date_format),
+            list: ListDefaults {
+                status: self.list.status.or(base.list.status),
+                folder: self.list.folder.or(base.list.folder),
+                sort: self.list.sort.or(base.list.sort),
+                group_by: self.list.group_by.or(base.list.group_by),
+                limit: self.list.limit.or(base.list.limit),
+                columns: self.list.columns.or(base.list.columns),
+            },
+            view: ViewDefaults {
+                full: self.view.full.or(base.view.full),
+                show_quoted: self.view.show_quoted.or(base.view.show_quoted),
+                reverse: self.view.reverse.or(base.view.reverse),
+            },
+        }
+    }
 }
 
+impl ListDefaults {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Response-time target. Checked when a command runs.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlaSettings {
+    /// How long a customer may wait for a reply, e.g. "4h"
+    pub response_time: Option<String>,
+}
+
+impl ViewDefaults {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl ThemeSettings {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl SlaSettings {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A named account profile (`[profiles.<name>]`) overriding the top-level settings
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub api_token: Option<String>,
+    pub token_cmd: Option<String>,
+    pub api_endpoint: Option<String>,
+    pub web_url: Option<String>,
+    pub proxy: Option<String>,
+
+    #[serde(default)]
+    pub defaults: DefaultSettings,
+}
+
+/// Config file given with `--config`, set once at startup
+static PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
 impl Config {
+    /// The config file: `--config`, then `GROOVE_CONFIG`, then
+    /// `config.toml` in the platform config directory
     pub fn path() -> Option<PathBuf> {
+        if let Some(path) = PATH_OVERRIDE.get() {
+            return Some(path.clone());
+        }
+        if let Some(path) = std::env::var_os("GROOVE_CONFIG").filter(|p| !p.is_empty()) {
+            return Some(PathBuf::from(path));
+        }
         ProjectDirs::from("", "", "groove-cli")
             .map(|dirs| dirs.config_dir().join("config.toml"))
     }
 
+    /// Read and write `path` instead of the default config file for the rest
+    /// of the process. Only the first call has an effect.
+    pub fn set_path(path: PathBuf) {
+        let _ = PATH_OVERRIDE.set(path);
+    }
+
     pub fn load() -> Result<Self> {
         let path = match Self::path() {
             Some(p) => p,
@@ -62,10 +254,93 @@ impl Config {
         Ok(())
     }
 
-    pub fn set_token(&mut self, token: String) -> Result<()> {
-        self.api_token = Some(token);
+    /// Store the token at the top level, or in the named profile if given
+    pub fn set_token(&mut self, token: String, profile: Option<&str>) -> Result<()> {
+        match profile {
+            Some(name) => {
+                self.profiles.entry(name.to_string()).or_default().api_token = Some(token);
+            }
+            None => self.api_token = Some(token),
+        }
         self.save()
     }
+
+    /// Store the token in the OS keyring, removing any plaintext copy for the
+    /// same profile from the config file and adding the profile if it's new
+    pub fn set_token_in_keyring(&mut self, token: &str, profile: Option<&str>) -> Result<()> {
+        keychain::set_token(profile, token)?;
+
+        // A new profile gets an empty entry so `--profile` can select it
+        let mut changed = false;
+        let plaintext = match profile {
+            Some(name) => {
+                let entry = self.profiles.entry(name.to_string()).or_insert_with(|| {
+                    changed = true;
+                    Profile::default()
+                });
+                entry.api_token.take()
+            }
+            None => self.api_token.take(),
+        };
+        if changed || plaintext.is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Return the effective configuration with the named profile layered over
+    /// the top-level settings. `None` returns the configuration unchanged.
+    pub fn with_profile(&self, name: Option<&str>) -> Result<Config> {
+        let Some(name) = name else {
+            return Ok(self.clone());
+        };
+
+        let profile = self.profiles.get(name).cloned().ok_or_else(|| {
+            let mut available: Vec<_> = self.profiles.keys().map(String::as_str).collect();
+            available.sort_unstable();
+            GrooveError::Config(format!(
+                "Profile '{}' not found. Available profiles: {}",
+                name,
+                if available.is_empty() {
+                    "(none)".to_string()
+                } else {
+                    available.join(", ")
+                }
+            ))
+        })?;
+
+        let base = self.clone();
+        // A profile's own token or token_cmd replaces both top-level ones
+        let (api_token, token_cmd) = if profile.api_token.is_some() || profile.token_cmd.is_some() {
+            (profile.api_token, profile.token_cmd)
+        } else {
+            (base.api_token.clone(), base.token_cmd.clone())
+        };
+        Ok(Config {
+            api_token,
+            token_cmd,
+            api_endpoint: profile.api_endpoint.or(base.api_endpoint),
+            web_url: profile.web_url.or(base.web_url),
+            proxy: profile.proxy.or(base.proxy),
+            defaults: profile.defaults.or(base.defaults),
+            active_profile: Some(name.to_string()),
+            ..base
+        })
+    }
+
+    /// Build the web app URL for a conversation from the configured `web_url`
+    pub fn conversation_url(&self, number: i64) -> Result<String> {
+        let base = self.web_url.as_deref().ok_or_else(|| {
+            GrooveError::Config(
+                "web_url is not set. Add it to config.toml, e.g. web_url = \"https://acme.groovehq.com\"".into(),
+            )
+        })?;
+        Ok(format!(
+            "{}/conversations/{}",
+            base.trim_end_matches('/'),
+            number
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -81,6 +356,9 @@ mod tests {
         assert!(config.defaults.limit.is_none());
         assert!(config.defaults.folder.is_none());
         assert!(config.aliases.is_empty());
+        assert!(config.hooks.is_empty());
+        assert!(config.macros.is_empty());
+        assert!(config.profiles.is_empty());
     }
 
     #[test]
@@ -103,9 +381,13 @@ api_endpoint = "https://custom.api.com/graphql"
 format = "json"
 limit = 50
 folder = "inbox"
+cache_ttl = 600
 
 [aliases]
 ls = "conversation list"
+
+[macros]
+resolve = ["reply --canned thanks", "add-tag resolved", "close"]
 "#;
         let config: Config = toml::from_str(toml_str).unwrap();
         assert_eq!(config.api_token, Some("test-token".to_string()));
@@ -116,10 +398,12 @@ ls = "conversation list"
         assert_eq!(config.defaults.format, Some("json".to_string()));
         assert_eq!(config.defaults.limit, Some(50));
         assert_eq!(config.defaults.folder, Some("inbox".to_string()));
+        assert_eq!(config.defaults.cache_ttl, Some(600));
         assert_eq!(
             config.aliases.get("ls"),
             Some(&"conversation list".to_string())
         );
+        assert_eq!(config.macros.get("resolve").map(Vec::len), Some(3));
     }
 
     #[test]
@@ -131,9 +415,14 @@ ls = "conversation list"
 
     #[test]
     fn test_config_serialize_roundtrip() {
-        let mut config = Config::default();
-        config.api_token = Some("secret-token".to_string());
-        config.defaults.limit = Some(100);
+        let config = Config {
+            api_token: Some("secret-token".to_string()),
+            defaults: DefaultSettings {
+                limit: Some(100),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
 
         let serialized = toml::to_string_pretty(&config).unwrap();
         let deserialized: Config = toml::from_str(&serialized).unwrap();
@@ -142,6 +431,143 @@ ls = "conversation list"
         assert_eq!(config.defaults.limit, deserialized.defaults.limit);
     }
 
+    #[test]
+    fn test_config_with_profile() {
+        let toml_str = r#"
+api_token = "default-token"
+proxy = "http://proxy.example.com:3128"
+
+[defaults]
+format = "json"
+limit = 50
+
+[profiles.work]
+api_token = "work-token"
+api_endpoint = "https://work.example.com/graphql"
+
+[profiles.work.defaults]
+limit = 10
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+
+        let unchanged = config.with_profile(None).unwrap();
+        assert_eq!(unchanged.api_token, Some("default-token".to_string()));
+        assert!(unchanged.active_profile.is_none());
+
+        let work = config.with_profile(Some("work")).unwrap();
+        assert_eq!(work.active_profile, Some("work".to_string()));
+        assert_eq!(work.api_token, Some("work-token".to_string()));
+        assert_eq!(
+            work.api_endpoint,
+            Some("https://work.example.com/graphql".to_string())
+        );
+        assert_eq!(
+            work.proxy,
+            Some("http://proxy.example.com:3128".to_string())
+        );
+        assert_eq!(work.defaults.limit, Some(10));
+        assert_eq!(work.defaults.format, Some("json".to_string()));
+    }
+
+    #[test]
+    fn test_config_command_defaults() {
+        let toml_str = r#"
+[defaults.list]
+status = "open"
+sort = "oldest"
+
+[defaults.view]
+full = true
+
+[profiles.work.defaults.list]
+status = "unread"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.defaults.list.status.as_deref(), Some("open"));
+        assert_eq!(config.defaults.view.full, Some(true));
+        assert_eq!(config.defaults.view.reverse, None);
+
+        let work = config.with_profile(Some("work")).unwrap();
+        assert_eq!(work.defaults.list.status.as_deref(), Some("unread"));
+        assert_eq!(work.defaults.list.sort.as_deref(), Some("oldest"));
+        assert_eq!(work.defaults.view.full, Some(true));
+
+        // Empty sections aren't written back
+        let serialized = toml::to_string_pretty(&Config::default()).unwrap();
+        assert!(!serialized.contains("[defaults.list]"));
+    }
+
+    #[test]
+    fn test_config_theme() {
+        let mut config: Config = toml::from_str("[theme]\ntable_style = \"markdown\"\n").unwrap();
+        assert_eq!(config.theme.table_style.as_deref(), Some("markdown"));
+        config.set_key("theme.colors.open", "bright green").unwrap();
+        assert_eq!(
+            config.theme.colors.get("open").map(String::as_str),
+            Some("bright green")
+        );
+        config.set_key("theme.hyperlinks", "false").unwrap();
+        assert_eq!(config.theme.hyperlinks, Some(false));
+        config.set_key("theme.icons", "true").unwrap();
+        assert_eq!(config.theme.icons, Some(true));
+        let serialized = toml::to_string_pretty(&config).unwrap();
+        assert!(serialized.contains("[theme.colors]"), "{}", serialized);
+    }
+
+    #[test]
+    fn test_config_profile_token_cmd() {
+        let toml_str = r#"
+api_token = "default-token"
+
+[profiles.work]
+token_cmd = "pass show groove/work"
+
+[profiles.other]
+api_endpoint = "https://other.example.com/graphql"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+
+        let work = config.with_profile(Some("work")).unwrap();
+        assert_eq!(work.token_cmd.as_deref(), Some("pass show groove/work"));
+        assert!(work.api_token.is_none());
+
+        let other = config.with_profile(Some("other")).unwrap();
+        assert_eq!(other.api_token.as_deref(), Some("default-token"));
+        assert!(other.token_cmd.is_none());
+    }
+
+    #[test]
+    fn test_config_with_unknown_profile() {
+        let toml_str = r#"
+[profiles.work]
+api_token = "work-token"
+"#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let err = config.with_profile(Some("sandbox")).unwrap_err();
+        assert!(err.to_string().contains("Profile 'sandbox' not found"));
+        assert!(err.to_string().contains("work"));
+    }
+
+    #[test]
+    fn test_conversation_url() {
+        let config = Config {
+            web_url: Some("https://acme.groovehq.com/".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.conversation_url(123).unwrap(),
+            "https://acme.groovehq.com/conversations/123"
+        );
+    }
+
+    #[test]
+    fn test_conversation_url_requires_web_url() {
+        let config = Config::default();
+        let result = config.conversation_url(123);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("web_url"));
+    }
+
     #[test]
     fn test_config_path_returns_some() {
         // Config path should return Some on most systems
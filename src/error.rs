@@ -1,6 +1,14 @@
 use thiserror::Error;
 
+/// Errors from the API client and the CLI.
+///
+/// [`GrooveClient`](crate::GrooveClient) returns `AuthError`, `RateLimited`,
+/// `GraphQL`, `Network`, `Json`, `Config` (invalid client settings), and the
+/// `ConversationNotFound` and `MessageNotFound` lookups. The other variants
+/// come from the CLI's configuration, lookups by name, and integrations.
+/// New variants may be added in minor releases.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum GrooveError {
     #[error("Authentication failed: {0}")]
     AuthError(String),
@@ -11,20 +19,47 @@ pub enum GrooveError {
     #[error("Conversation #{0} not found")]
     ConversationNotFound(i64),
 
-    #[error("Tag '{0}' not found")]
-    TagNotFound(String),
+    #[error("Message '{0}' not found")]
+    MessageNotFound(String),
 
-    #[error("Agent '{0}' not found")]
-    AgentNotFound(String),
+    #[error("Tag '{}' not found{}", .0, did_you_mean(.1))]
+    TagNotFound(String, Vec<String>),
 
-    #[error("Canned reply '{0}' not found")]
-    CannedReplyNotFound(String),
+    #[error("No contact found with email '{0}'")]
+    ContactNotFound(String),
 
-    #[error("GraphQL error: {0}")]
-    GraphQL(String),
+    #[error("Folder '{}' not found{}", .0, available_folders(.1))]
+    FolderNotFound(String, Vec<String>),
 
-    #[error("Network error: {0}")]
-    Network(#[from] reqwest::Error),
+    #[error("Agent '{}' not found{}", .0, did_you_mean(.1))]
+    AgentNotFound(String, Vec<String>),
+
+    #[error("Canned reply '{}' not found{}", .0, did_you_mean(.1))]
+    CannedReplyNotFound(String, Vec<String>),
+
+    #[error("Rule '{}' not found{}", .0, did_you_mean(.1))]
+    RuleNotFound(String, Vec<String>),
+
+    #[error("Snippet '{}' not found{}", .0, did_you_mean(.1))]
+    SnippetNotFound(String, Vec<String>),
+
+    #[error("Template '{0}' not found")]
+    TemplateNotFound(String),
+
+    #[error("Template error: {0}")]
+    Template(String),
+
+    #[error("GraphQL error: {message}{}", request_id_suffix(.request_id))]
+    GraphQL {
+        message: String,
+        request_id: Option<String>,
+    },
+
+    #[error("Network error: {source}{}", request_id_suffix(.request_id))]
+    Network {
+        source: reqwest::Error,
+        request_id: Option<String>,
+    },
 
     #[error("Configuration error: {0}")]
     Config(String),
@@ -35,9 +70,34 @@ pub enum GrooveError {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    #[error("Hook failed: {0}")]
+    Hook(String),
+
+    #[error("Keyring error: {0}")]
+    Keyring(String),
+
+    #[error("Clipboard error: {0}")]
+    Clipboard(String),
+
+    #[error("Could not open browser: {0}")]
+    Browser(String),
+
+    #[error("Desktop notification failed: {0}")]
+    Notification(String),
+
+    #[error("PDF export failed: {0}")]
+    Pdf(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
     #[error("TOML parse error: {0}")]
     TomlParse(#[from] toml::de::Error),
 
+    /// Some conversations in a bulk command or macro run failed
+    #[error("{0}")]
+    PartialFailure(String),
+
     #[error("Rate limited{}", match .retry_after {
         Some(secs) => format!(". Retry after {} seconds", secs),
         None => ". Please wait and try again".to_string(),
@@ -45,4 +105,195 @@ pub enum GrooveError {
     RateLimited { retry_after: Option<u64> },
 }
 
+/// Process exit codes, so scripts can branch on the class of failure
+pub mod exit_code {
+    /// Any error without a more specific code
+    pub const FAILURE: i32 = 1;
+    /// Invalid arguments (reported by clap)
+    pub const USAGE: i32 = 2;
+    /// A conversation, message, tag, agent, folder, contact, canned reply,
+    /// rule, snippet, or template does not exist
+    pub const NOT_FOUND: i32 = 3;
+    /// Missing, invalid, or expired API token
+    pub const AUTH: i32 = 4;
+    pub const RATE_LIMITED: i32 = 5;
+    /// Some conversations in a bulk command or macro run failed
+    pub const PARTIAL_FAILURE: i32 = 6;
+}
+
+impl GrooveError {
+    /// Exit code for the class of failure; see [`exit_code`]
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            GrooveError::ConversationNotFound(_)
+            | GrooveError::MessageNotFound(_)
+            | GrooveError::TagNotFound(..)
+            | GrooveError::ContactNotFound(_)
+            | GrooveError::FolderNotFound(..)
+            | GrooveError::AgentNotFound(..)
+            | GrooveError::CannedReplyNotFound(..)
+            | GrooveError::RuleNotFound(..)
+            | GrooveError::SnippetNotFound(..)
+            | GrooveError::TemplateNotFound(_) => exit_code::NOT_FOUND,
+            GrooveError::AuthError(_) | GrooveError::TokenNotFound => exit_code::AUTH,
+            GrooveError::RateLimited { .. } => exit_code::RATE_LIMITED,
+            GrooveError::PartialFailure(_) => exit_code::PARTIAL_FAILURE,
+            _ => exit_code::FAILURE,
+        }
+    }
+
+    pub fn graphql(message: impl Into<String>) -> Self {
+        GrooveError::GraphQL {
+            message: message.into(),
+            request_id: None,
+        }
+    }
+
+    /// The API request ID to quote to Groove support, if known
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            GrooveError::GraphQL { request_id, .. } | GrooveError::Network { request_id, .. } => {
+                request_id.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    /// Attach the API request ID to GraphQL and network errors
+    pub fn with_request_id(self, id: Option<&str>) -> Self {
+        match self {
+            GrooveError::GraphQL {
+                message,
+                request_id,
+            } => GrooveError::GraphQL {
+                message,
+                request_id: request_id.or(id.map(str::to_string)),
+            },
+            GrooveError::Network { source, request_id } => GrooveError::Network {
+                source,
+                request_id: request_id.or(id.map(str::to_string)),
+            },
+            other => other,
+        }
+    }
+}
+
+impl From<reqwest::Error> for GrooveError {
+    fn from(source: reqwest::Error) -> Self {
+        GrooveError::Network {
+            source,
+            request_id: None,
+        }
+    }
+}
+
+fn request_id_suffix(request_id: &Option<String>) -> String {
+    match request_id {
+        Some(id) => format!(" (request ID: {})", id),
+        None => String::new(),
+    }
+}
+
+fn did_you_mean(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [only] => format!(". Did you mean '{}'?", only),
+        _ => format!(
+            ". Did you mean one of: {}?",
+            suggestions
+                .iter()
+                .map(|s| format!("'{}'", s))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+fn available_folders(folders: &[String]) -> String {
+    if folders.is_empty() {
+        String::new()
+    } else {
+        format!(". Available folders: {}", folders.join(", "))
+    }
+}
+
 pub type Result<T> = std::result::Result<T, GrooveError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphql_error_request_id() {
+        let err = GrooveError::graphql("Conversation is locked");
+        assert_eq!(err.to_string(), "GraphQL error: Conversation is locked");
+
+        let err = err.with_request_id(Some("req-123"));
+        assert_eq!(err.request_id(), Some("req-123"));
+        assert_eq!(
+            err.to_string(),
+            "GraphQL error: Conversation is locked (request ID: req-123)"
+        );
+    }
+
+    #[test]
+    fn test_with_request_id_ignores_other_errors() {
+        let err = GrooveError::TokenNotFound.with_request_id(Some("req-123"));
+        assert!(err.request_id().is_none());
+    }
+
+    #[test]
+    fn test_not_found_suggestions() {
+        let err = GrooveError::TagNotFound("urgnet".to_string(), Vec::new());
+        assert_eq!(err.to_string(), "Tag 'urgnet' not found");
+
+        let err = GrooveError::TagNotFound("urgnet".to_string(), vec!["urgent".to_string()]);
+        assert_eq!(
+            err.to_string(),
+            "Tag 'urgnet' not found. Did you mean 'urgent'?"
+        );
+
+        let err = GrooveError::AgentNotFound(
+            "bob".to_string(),
+            vec!["bob@example.com".to_string(), "rob@example.com".to_string()],
+        );
+        assert_eq!(
+            err.to_string(),
+            "Agent 'bob' not found. Did you mean one of: 'bob@example.com', 'rob@example.com'?"
+        );
+    }
+
+    #[test]
+    fn test_exit_codes_by_failure_class() {
+        assert_eq!(
+            GrooveError::ConversationNotFound(12).exit_code(),
+            exit_code::NOT_FOUND
+        );
+        assert_eq!(
+            GrooveError::TagNotFound("x".to_string(), Vec::new()).exit_code(),
+            exit_code::NOT_FOUND
+        );
+        assert_eq!(GrooveError::TokenNotFound.exit_code(), exit_code::AUTH);
+        assert_eq!(
+            GrooveError::RateLimited { retry_after: None }.exit_code(),
+            exit_code::RATE_LIMITED
+        );
+        assert_eq!(
+            GrooveError::PartialFailure("1 of 2 conversations failed".to_string()).exit_code(),
+            exit_code::PARTIAL_FAILURE
+        );
+        assert_eq!(GrooveError::graphql("boom").exit_code(), exit_code::FAILURE);
+    }
+
+    #[test]
+    fn test_folder_not_found_lists_folders() {
+        let err = GrooveError::FolderNotFound(
+            "archive".to_string(),
+            vec!["Inbox".to_string(), "Spam".to_string()],
+        );
+        assert_eq!(
+            err.to_string(),
+            "Folder 'archive' not found. Available folders: Inbox, Spam"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use std::path::PathBuf;
+use std::pin::pin;
+
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, TryStreamExt};
+use serde::Serialize;
+
+use super::{write_export, ExportFormat};
+use crate::api::GrooveClient;
+use crate::error::Result;
+use crate::types::{ContactRecord, ConversationFilter, ConversationState, DateRange};
+
+/// Name of the index file written next to the exported conversations
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Debug, Clone)]
+pub struct BackupOptions {
+    /// Directory to write into (created if missing)
+    pub dir: PathBuf,
+    pub format: ExportFormat,
+    pub status: Option<ConversationState>,
+    /// Folder ID
+    pub folder_id: Option<String>,
+    pub dates: Option<DateRange>,
+    /// Only this customer's conversations; the profile goes in the manifest
+    pub contact: Option<ContactRecord>,
+    /// Stop after this many conversations
+    pub limit: Option<usize>,
+    /// HTML-to-PDF converter for `ExportFormat::Pdf`
+    pub pdf_command: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BackupSummary {
+    pub conversations: usize,
+    pub messages: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    exported_at: DateTime<Utc>,
+    format: &'static str,
+    status: Option<ConversationState>,
+    folder_id: Option<String>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contact: Option<ContactRecord>,
+    conversations: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestEntry {
+    number: i64,
+    id: String,
+    subject: Option<String>,
+    state: ConversationState,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    messages: usize,
+    /// Path relative to the export directory
+    file: String,
+}
+
+/// Write every conversation matching `options` to `<dir>/<number>.<ext>`,
+/// with all of its messages, plus a `manifest.json` indexing the files.
+///
+/// The manifest is written last, so its presence marks a complete export.
+pub async fn backup(client: &GrooveClient, options: &BackupOptions) -> Result<BackupSummary> {
+    std::fs::create_dir_all(&options.dir)?;
+    let limit = options.limit.unwrap_or(usize::MAX);
+    let filter = ConversationFilter {
+        state: options.status,
+        folder_id: options.folder_id.clone(),
+        dates: options.dates.clone(),
+        contact_id: options.contact.as_ref().map(|c| c.contact.id.clone()),
+        ..ConversationFilter::default()
+    };
+
+    let mut summary = BackupSummary::default();
+    let mut entries = Vec::new();
+    let mut conversations = pin!(client.conversations_stream(&filter).take(limit));
+    while let Some(conv) = conversations.try_next().await? {
+        let messages = client.all_messages(&conv.id).await?;
+        let file = format!("{}.{}", conv.number, options.format.extension());
+        write_export(
+            &conv,
+            &messages,
+            options.format,
+            &options.dir.join(&file),
+            options.pdf_command.as_deref(),
+        )?;
+
+        summary.conversations += 1;
+        summary.messages += messages.len();
+        entries.push(ManifestEntry {
+            number: conv.number,
+            id: conv.id,
+            subject: conv.subject,
+            state: conv.state,
+            created_at: conv.created_at,
+            updated_at: conv.updated_at,
+            messages: messages.len(),
+            file,
+        });
+    }
+
+    let manifest = Manifest {
+        exported_at: Utc::now(),
+        format: options.format.extension(),
+        status: options.status,
+        folder_id: options.folder_id.clone(),
+        since: options.dates.as_ref().and_then(|d| d.since),
+        until: options.dates.as_ref().and_then(|d| d.until),
+        contact: options.contact.clone(),
+        conversations: entries,
+    };
+    std::fs::write(
+        options.dir.join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)? + "\n",
+    )?;
+
+    Ok(summary)
+}
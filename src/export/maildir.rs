@@ -0,0 +1,160 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::pin::pin;
+
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+
+use super::email_message;
+use crate::api::GrooveClient;
+use crate::error::Result;
+use crate::types::{ConversationFilter, DateField, DateRange};
+
+/// Start time of the last completed mirror, kept in the Maildir root
+const LAST_SYNC_FILE: &str = ".groove-last-sync";
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MaildirSummary {
+    pub conversations: usize,
+    /// Messages delivered into `new/` on this run
+    pub messages_written: usize,
+    /// Whether only conversations updated since the last run were checked
+    pub incremental: bool,
+}
+
+/// Mirror conversations into a Maildir at `root`, one mail per message.
+///
+/// File names are derived from the message ID, so messages already in the
+/// Maildir (in `new/` or `cur/`, whatever flags the mail reader added) are not
+/// written again. After the first run only conversations updated since the
+/// previous one are fetched, unless `full` is set.
+pub async fn sync_maildir(
+    client: &GrooveClient,
+    root: &Path,
+    full: bool,
+) -> Result<MaildirSummary> {
+    for sub in ["tmp", "new", "cur"] {
+        std::fs::create_dir_all(root.join(sub))?;
+    }
+
+    let started_at = Utc::now();
+    let last_sync = if full { None } else { read_last_sync(root) };
+    let dates = last_sync.map(|since| DateRange {
+        field: DateField::Updated,
+        since: Some(since),
+        until: None,
+    });
+
+    let mut summary = MaildirSummary {
+        incremental: dates.is_some(),
+        ..Default::default()
+    };
+    let filter = ConversationFilter {
+        dates,
+        ..ConversationFilter::default()
+    };
+    let mut existing = delivered_names(root)?;
+    let mut conversations = pin!(client.conversations_stream(&filter));
+    while let Some(conv) = conversations.try_next().await? {
+        summary.conversations += 1;
+        let messages = client.all_messages(&conv.id).await?;
+        let root_message = messages.first();
+        for (i, msg) in messages.iter().enumerate() {
+            let name = mail_name(conv.number, &msg.id);
+            if existing.contains(&name) {
+                continue;
+            }
+            let mail = email_message(&conv, msg, root_message.filter(|_| i > 0));
+            deliver(root, &name, &mail)?;
+            existing.insert(name);
+            summary.messages_written += 1;
+        }
+    }
+
+    std::fs::write(root.join(LAST_SYNC_FILE), started_at.to_rfc3339())?;
+    Ok(summary)
+}
+
+fn read_last_sync(root: &Path) -> Option<DateTime<Utc>> {
+    let text = std::fs::read_to_string(root.join(LAST_SYNC_FILE)).ok()?;
+    DateTime::parse_from_rfc3339(text.trim())
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Unique part of a message's Maildir file name. Only characters that are
+/// safe in file names are kept from the ID.
+fn mail_name(number: i64, message_id: &str) -> String {
+    let id: String = message_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("groove-{}-{}", number, id)
+}
+
+/// Names of the mails in `new/` and `cur/`, without the `:2,<flags>` suffix
+/// mail readers add
+fn delivered_names(root: &Path) -> Result<HashSet<String>> {
+    let mut names = HashSet::new();
+    for sub in ["new", "cur"] {
+        for entry in std::fs::read_dir(root.join(sub))? {
+            let file_name = entry?.file_name();
+            let file_name = file_name.to_string_lossy();
+            if let Some(name) = file_name.split(':').next() {
+                names.insert(name.to_string());
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// Write into `tmp/` and rename into `new/`, as the Maildir spec requires
+fn deliver(root: &Path, name: &str, mail: &str) -> Result<()> {
+    let tmp = root.join("tmp").join(name);
+    std::fs::write(&tmp, mail)?;
+    std::fs::rename(&tmp, root.join("new").join(name))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mail_name_sanitizes_ids() {
+        assert_eq!(mail_name(42, "msg_a1"), "groove-42-msg_a1");
+        assert_eq!(mail_name(42, "a/b:c"), "groove-42-a_b_c");
+    }
+
+    #[test]
+    fn test_delivered_names_ignore_flags() {
+        let dir = tempfile::tempdir().unwrap();
+        for sub in ["tmp", "new", "cur"] {
+            std::fs::create_dir_all(dir.path().join(sub)).unwrap();
+        }
+        assert!(delivered_names(dir.path()).unwrap().is_empty());
+
+        deliver(dir.path(), "groove-1-m1", "Subject: hi\n\nbody\n").unwrap();
+        deliver(dir.path(), "groove-1-m2", "Subject: hi\n\nbody\n").unwrap();
+        assert!(!dir.path().join("tmp/groove-1-m1").exists());
+
+        // A mail reader marks one read and moves it to cur/
+        std::fs::rename(
+            dir.path().join("new/groove-1-m1"),
+            dir.path().join("cur/groove-1-m1:2,S"),
+        )
+        .unwrap();
+        let names = delivered_names(dir.path()).unwrap();
+        let expected: HashSet<String> = ["groove-1-m1", "groove-1-m2"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(names, expected);
+    }
+}
@@ -0,0 +1,613 @@
+mod backup;
+mod maildir;
+mod pdf;
+
+pub use backup::{backup, BackupOptions, BackupSummary, MANIFEST_FILE};
+pub use maildir::{sync_maildir, MaildirSummary};
+pub use pdf::html_to_pdf;
+
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use crate::types::{Attachment, Conversation, Message, MessageAuthor};
+
+/// File format for `conversation export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    /// Plain text with no markup, for piping into other tools
+    Text,
+    Html,
+    /// A single RFC 822 file with every message as a `message/rfc822` part
+    Eml,
+    /// One mbox entry per message
+    Mbox,
+    Json,
+    /// Printed from the HTML rendering by an external converter
+    Pdf,
+}
+
+impl ExportFormat {
+    /// File extension, without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Text => "txt",
+            ExportFormat::Html => "html",
+            ExportFormat::Eml => "eml",
+            ExportFormat::Mbox => "mbox",
+            ExportFormat::Json => "json",
+            ExportFormat::Pdf => "pdf",
+        }
+    }
+
+    /// Guess the format from an output file name
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "txt" | "text" => Ok(ExportFormat::Text),
+            "html" | "htm" => Ok(ExportFormat::Html),
+            "eml" => Ok(ExportFormat::Eml),
+            "mbox" => Ok(ExportFormat::Mbox),
+            "json" => Ok(ExportFormat::Json),
+            "pdf" => Ok(ExportFormat::Pdf),
+            _ => Err(format!(
+                "Invalid export format: {}. Use md, txt, html, eml, mbox, json, or pdf",
+                s
+            )),
+        }
+    }
+}
+
+/// Render a conversation and its messages (oldest first) in `format`. PDF
+/// renders the HTML it is printed from; see [`write_export`].
+pub fn render(conv: &Conversation, messages: &[Message], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Markdown => render_markdown(conv, messages),
+        ExportFormat::Text => render_text(conv, messages),
+        ExportFormat::Html | ExportFormat::Pdf => render_html(conv, messages),
+        ExportFormat::Eml => render_eml(conv, messages),
+        ExportFormat::Mbox => render_mbox(conv, messages),
+        ExportFormat::Json => {
+            let value = json!({ "conversation": conv, "messages": messages });
+            // Serializing plain data can't fail
+            serde_json::to_string_pretty(&value).unwrap_or_default() + "\n"
+        }
+    }
+}
+
+/// Render a conversation to the file at `path`. PDFs are converted from HTML
+/// with `pdf_command` (see [`html_to_pdf`]).
+pub fn write_export(
+    conv: &Conversation,
+    messages: &[Message],
+    format: ExportFormat,
+    path: &Path,
+    pdf_command: Option<&str>,
+) -> crate::error::Result<()> {
+    let rendered = render(conv, messages, format);
+    match format {
+        ExportFormat::Pdf => html_to_pdf(&rendered, path, pdf_command),
+        _ => Ok(std::fs::write(path, rendered)?),
+    }
+}
+
+fn subject(conv: &Conversation) -> &str {
+    conv.subject.as_deref().unwrap_or("(no subject)")
+}
+
+fn author_name(author: Option<&MessageAuthor>) -> String {
+    match author {
+        Some(a) => a
+            .name
+            .clone()
+            .or_else(|| a.email.clone())
+            .unwrap_or_else(|| a.id.clone()),
+        None => "Unknown".to_string(),
+    }
+}
+
+/// "Jane Doe <jane@example.com>", as far as the author is known
+fn sender(author: Option<&MessageAuthor>) -> String {
+    let email = author.and_then(|a| a.email.as_deref());
+    let name = author.and_then(|a| a.name.as_deref());
+    match (name, email) {
+        (Some(name), Some(email)) => format!("{} <{}>", name, email),
+        (None, Some(email)) => email.to_string(),
+        _ => author_name(author),
+    }
+}
+
+fn message_body(msg: &Message) -> Cow<'_, str> {
+    msg.text().unwrap_or_default()
+}
+
+fn format_timestamp(ts: DateTime<Utc>) -> String {
+    ts.format("%Y-%m-%d %H:%M UTC").to_string()
+}
+
+fn format_size(bytes: i64) -> String {
+    const KB: f64 = 1024.0;
+    let b = bytes as f64;
+    if b < KB {
+        format!("{} B", bytes)
+    } else if b < KB * KB {
+        format!("{:.1} KB", b / KB)
+    } else {
+        format!("{:.1} MB", b / (KB * KB))
+    }
+}
+
+/// "report.pdf (application/pdf, 12.0 KB)"
+fn describe_attachment(att: &Attachment) -> String {
+    let details: Vec<String> = att
+        .content_type
+        .iter()
+        .cloned()
+        .chain(att.size.map(format_size))
+        .collect();
+    if details.is_empty() {
+        att.file_name.clone()
+    } else {
+        format!("{} ({})", att.file_name, details.join(", "))
+    }
+}
+
+fn conversation_fields(conv: &Conversation) -> Vec<(&'static str, String)> {
+    let mut fields = vec![("Status", conv.state.to_string())];
+    if let Some(contact) = &conv.contact {
+        let customer = match (&contact.name, &contact.email) {
+            (Some(name), Some(email)) => format!("{} <{}>", name, email),
+            (Some(name), None) => name.clone(),
+            (None, Some(email)) => email.clone(),
+            (None, None) => contact.id.clone(),
+        };
+        fields.push(("Customer", customer));
+    }
+    if let Some(agent) = &conv.assigned {
+        fields.push((
+            "Assigned",
+            agent.name.clone().unwrap_or(agent.email.clone()),
+        ));
+    }
+    if !conv.tags.is_empty() {
+        let tags: Vec<&str> = conv.tags.iter().map(|t| t.name.as_str()).collect();
+        fields.push(("Tags", tags.join(", ")));
+    }
+    fields.push(("Created", format_timestamp(conv.created_at)));
+    fields
+}
+
+fn render_markdown(conv: &Conversation, messages: &[Message]) -> String {
+    let mut out = format!("# #{} {}\n\n", conv.number, subject(conv));
+    for (name, value) in conversation_fields(conv) {
+        let _ = writeln!(out, "- **{}:** {}", name, value);
+    }
+
+    for msg in messages {
+        let _ = write!(
+            out,
+            "\n---\n\n## {} — {}\n\n",
+            author_name(msg.author.as_ref()),
+            format_timestamp(msg.created_at)
+        );
+        let body = message_body(msg);
+        let body = body.trim_end();
+        if !body.is_empty() {
+            out.push_str(body);
+            out.push('\n');
+        }
+        if !msg.attachments.is_empty() {
+            out.push_str("\n**Attachments:**\n\n");
+            for att in &msg.attachments {
+                let label = describe_attachment(att);
+                let _ = match &att.url {
+                    Some(url) => writeln!(out, "- [{}]({})", label, url),
+                    None => writeln!(out, "- {}", label),
+                };
+            }
+        }
+    }
+    out
+}
+
+/// Stable plain-text layout: a header block, then each message as
+/// `From:`/`Date:` lines and its whole body, with nothing wrapped or elided
+fn render_text(conv: &Conversation, messages: &[Message]) -> String {
+    let mut out = format!(
+        "Conversation #{}\nSubject: {}\n",
+        conv.number,
+        subject(conv)
+    );
+    for (name, value) in conversation_fields(conv) {
+        let _ = writeln!(out, "{}: {}", name, value);
+    }
+
+    let total = messages.len();
+    for (i, msg) in messages.iter().enumerate() {
+        let _ = write!(
+            out,
+            "\n=== Message {}/{} ({})\nFrom: {}\nDate: {}\n\n",
+            i + 1,
+            total,
+            msg.id,
+            sender(msg.author.as_ref()),
+            format_timestamp(msg.created_at)
+        );
+        let body = message_body(msg);
+        let body = body.trim_end();
+        if !body.is_empty() {
+            out.push_str(body);
+            out.push('\n');
+        }
+        if !msg.attachments.is_empty() {
+            out.push_str("\nAttachments:\n");
+            for att in &msg.attachments {
+                let _ = match &att.url {
+                    Some(url) => writeln!(out, "- {} <{}>", describe_attachment(att), url),
+                    None => writeln!(out, "- {}", describe_attachment(att)),
+                };
+            }
+        }
+    }
+    out
+}
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn render_html(conv: &Conversation, messages: &[Message]) -> String {
+    let title = escape_html(&format!("#{} {}", conv.number, subject(conv)));
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>\n\
+         body {{ font-family: sans-serif; max-width: 48em; margin: 2em auto; }}\n\
+         article {{ border-top: 1px solid #ccc; padding: 1em 0; }}\n\
+         .meta {{ color: #666; }}\n\
+         .body-text {{ white-space: pre-wrap; }}\n\
+         </style>\n</head>\n<body>\n<h1>{title}</h1>\n<dl>\n"
+    );
+    for (name, value) in conversation_fields(conv) {
+        let _ = writeln!(out, "<dt>{}</dt><dd>{}</dd>", name, escape_html(&value));
+    }
+    out.push_str("</dl>\n");
+
+    for msg in messages {
+        let _ = write!(
+            out,
+            "<article id=\"{}\">\n<p class=\"meta\"><strong>{}</strong> — {}</p>\n",
+            escape_html(&msg.id),
+            escape_html(&author_name(msg.author.as_ref())),
+            format_timestamp(msg.created_at)
+        );
+        // Message HTML comes from the helpdesk as-is; fall back to the text body
+        match &msg.body_html {
+            Some(html) if !html.trim().is_empty() => {
+                let _ = writeln!(out, "<div class=\"body\">{}</div>", html);
+            }
+            _ => {
+                let _ = writeln!(
+                    out,
+                    "<div class=\"body-text\">{}</div>",
+                    escape_html(&message_body(msg))
+                );
+            }
+        }
+        if !msg.attachments.is_empty() {
+            out.push_str("<ul class=\"attachments\">\n");
+            for att in &msg.attachments {
+                let label = escape_html(&describe_attachment(att));
+                let _ = match &att.url {
+                    Some(url) => writeln!(
+                        out,
+                        "<li><a href=\"{}\">{}</a></li>",
+                        escape_html(url),
+                        label
+                    ),
+                    None => writeln!(out, "<li>{}</li>", label),
+                };
+            }
+            out.push_str("</ul>\n");
+        }
+        out.push_str("</article>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Encode a header value as an RFC 2047 encoded-word if it isn't plain ASCII
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        return value.to_string();
+    }
+    let mut out = String::from("=?UTF-8?Q?");
+    for b in value.bytes() {
+        match b {
+            b' ' => out.push('_'),
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'!' | b'*' | b'+' | b'-' | b'/' => {
+                out.push(b as char)
+            }
+            _ => {
+                let _ = write!(out, "={:02X}", b);
+            }
+        }
+    }
+    out.push_str("?=");
+    out
+}
+
+fn address(author: Option<&MessageAuthor>) -> String {
+    let email = author.and_then(|a| a.email.as_deref());
+    let name = author.and_then(|a| a.name.as_deref());
+    match (name, email) {
+        (Some(name), Some(email)) => format!("{} <{}>", encode_header(name), email),
+        (None, Some(email)) => format!("<{}>", email),
+        _ => encode_header(&author_name(author)),
+    }
+}
+
+fn message_id(msg: &Message) -> String {
+    format!("<{}@groovehq.com>", msg.id)
+}
+
+/// Headers and body of one message as an RFC 822 email. Replies reference the
+/// first message so mail clients thread them together.
+pub fn email_message(conv: &Conversation, msg: &Message, thread_root: Option<&Message>) -> String {
+    let mut subject_line = format!("[#{}] {}", conv.number, subject(conv));
+    if thread_root.is_some() {
+        subject_line.insert_str(0, "Re: ");
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, "From: {}", address(msg.author.as_ref()));
+    let _ = writeln!(out, "Date: {}", msg.created_at.to_rfc2822());
+    let _ = writeln!(out, "Subject: {}", encode_header(&subject_line));
+    let _ = writeln!(out, "Message-ID: {}", message_id(msg));
+    if let Some(root) = thread_root {
+        let _ = writeln!(out, "In-Reply-To: {}", message_id(root));
+        let _ = writeln!(out, "References: {}", message_id(root));
+    }
+    let _ = writeln!(out, "X-Groove-Conversation: {}", conv.number);
+    out.push_str("MIME-Version: 1.0\n");
+    out.push_str("Content-Type: text/plain; charset=utf-8\n");
+    out.push_str("Content-Transfer-Encoding: 8bit\n\n");
+
+    out.push_str(message_body(msg).trim_end());
+    out.push('\n');
+    if !msg.attachments.is_empty() {
+        out.push_str("\nAttachments:\n");
+        for att in &msg.attachments {
+            let _ = match &att.url {
+                Some(url) => writeln!(out, "- {} <{}>", describe_attachment(att), url),
+                None => writeln!(out, "- {}", describe_attachment(att)),
+            };
+        }
+    }
+    out
+}
+
+fn render_eml(conv: &Conversation, messages: &[Message]) -> String {
+    let boundary = format!("groove-conversation-{}", conv.number);
+    let mut out = String::new();
+    if let Some(first) = messages.first() {
+        let _ = writeln!(out, "From: {}", address(first.author.as_ref()));
+    }
+    let _ = writeln!(out, "Date: {}", conv.created_at.to_rfc2822());
+    let _ = writeln!(
+        out,
+        "Subject: {}",
+        encode_header(&format!("[#{}] {}", conv.number, subject(conv)))
+    );
+    out.push_str("MIME-Version: 1.0\n");
+    let _ = write!(
+        out,
+        "Content-Type: multipart/digest; boundary=\"{}\"\n\n",
+        boundary
+    );
+
+    let root = messages.first();
+    for (i, msg) in messages.iter().enumerate() {
+        // Digest parts default to message/rfc822, so a blank line starts the message
+        let _ = write!(out, "--{}\n\n", boundary);
+        out.push_str(&email_message(conv, msg, root.filter(|_| i > 0)));
+    }
+    let _ = writeln!(out, "--{}--", boundary);
+    out
+}
+
+fn render_mbox(conv: &Conversation, messages: &[Message]) -> String {
+    let mut out = String::new();
+    let root = messages.first();
+    for (i, msg) in messages.iter().enumerate() {
+        let sender = msg
+            .author
+            .as_ref()
+            .and_then(|a| a.email.as_deref())
+            .unwrap_or("MAILER-DAEMON");
+        let _ = writeln!(
+            out,
+            "From {} {}",
+            sender,
+            msg.created_at.format("%a %b %e %H:%M:%S %Y")
+        );
+        let email = email_message(conv, msg, root.filter(|_| i > 0));
+        // mboxrd: quote body lines that would look like a message separator
+        for line in email.lines() {
+            if line.trim_start_matches('>').starts_with("From ") {
+                out.push('>');
+            }
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn conversation() -> Conversation {
+        Conversation::builder(42)
+            .id("conv-1")
+            .subject("Refund request")
+            .created_at(Utc.with_ymd_and_hms(2024, 1, 2, 9, 30, 0).unwrap())
+            .updated_at(Utc.with_ymd_and_hms(2024, 1, 2, 10, 0, 0).unwrap())
+            .build()
+    }
+
+    fn message(id: &str, name: &str, body: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            created_at: Utc.with_ymd_and_hms(2024, 1, 2, 9, 30, 0).unwrap(),
+            body_text: Some(body.to_string()),
+            body_html: None,
+            author: Some(MessageAuthor {
+                typename: Some("Contact".to_string()),
+                id: "ct1".to_string(),
+                email: Some(format!("{}@example.com", name.to_lowercase())),
+                name: Some(name.to_string()),
+            }),
+            attachments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_export_format_from_str_and_path() {
+        assert_eq!("md".parse(), Ok(ExportFormat::Markdown));
+        assert_eq!("MBOX".parse(), Ok(ExportFormat::Mbox));
+        assert_eq!("PDF".parse(), Ok(ExportFormat::Pdf));
+        assert!("docx".parse::<ExportFormat>().is_err());
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out/thread.html")),
+            Some(ExportFormat::Html)
+        );
+        assert_eq!(ExportFormat::from_path(Path::new("thread")), None);
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let mut msg = message("m1", "Jane", "Hi, I'd like a refund.");
+        msg.attachments.push(Attachment {
+            id: "att1".to_string(),
+            file_name: "receipt.pdf".to_string(),
+            content_type: Some("application/pdf".to_string()),
+            size: Some(2048),
+            url: Some("https://files.example.com/receipt.pdf".to_string()),
+        });
+        let md = render(&conversation(), &[msg], ExportFormat::Markdown);
+        assert!(md.starts_with("# #42 Refund request\n"));
+        assert!(md.contains("## Jane — 2024-01-02 09:30 UTC\n\nHi, I'd like a refund.\n"));
+        assert!(md.contains(
+            "- [receipt.pdf (application/pdf, 2.0 KB)](https://files.example.com/receipt.pdf)"
+        ));
+    }
+
+    #[test]
+    fn test_render_text() {
+        let messages = [
+            message("m1", "Jane", "Hi, I'd like a refund.\n\n"),
+            message("m2", "Bob", "Done!"),
+        ];
+        let text = render(&conversation(), &messages, ExportFormat::Text);
+        assert!(text.starts_with(
+            "Conversation #42\nSubject: Refund request\nStatus: open\nCreated: 2024-01-02 09:30 UTC\n"
+        ));
+        assert!(text.contains(
+            "\n=== Message 1/2 (m1)\nFrom: Jane <jane@example.com>\nDate: 2024-01-02 09:30 UTC\n\nHi, I'd like a refund.\n\n=== Message 2/2"
+        ));
+        assert!(text.ends_with("Done!\n"));
+        assert!(!text.contains('\u{1b}'));
+    }
+
+    #[test]
+    fn test_render_html_escapes_text_bodies() {
+        let html = render(
+            &conversation(),
+            &[message("m1", "Jane", "<script>alert(1)</script>")],
+            ExportFormat::Html,
+        );
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn test_email_message_threads_replies() {
+        let conv = conversation();
+        let first = message("m1", "Jane", "Hello");
+        let reply = message("m2", "Bob", "Hi Jane");
+        let eml = email_message(&conv, &reply, Some(&first));
+        assert!(eml.contains("From: Bob <bob@example.com>\n"));
+        assert!(eml.contains("Subject: Re: [#42] Refund request\n"));
+        assert!(eml.contains("In-Reply-To: <m1@groovehq.com>\n"));
+        assert!(eml.ends_with("\n\nHi Jane\n"));
+    }
+
+    #[test]
+    fn test_encode_header_non_ascii() {
+        assert_eq!(encode_header("plain"), "plain");
+        assert_eq!(
+            encode_header("Café au lait"),
+            "=?UTF-8?Q?Caf=C3=A9_au_lait?="
+        );
+    }
+
+    #[test]
+    fn test_render_eml_digest() {
+        let conv = conversation();
+        let messages = [message("m1", "Jane", "Hello"), message("m2", "Bob", "Hi")];
+        let eml = render(&conv, &messages, ExportFormat::Eml);
+        assert!(eml.contains("Content-Type: multipart/digest; boundary=\"groove-conversation-42\""));
+        assert_eq!(eml.matches("--groove-conversation-42\n\n").count(), 2);
+        assert!(eml.ends_with("--groove-conversation-42--\n"));
+    }
+
+    #[test]
+    fn test_render_mbox_quotes_from_lines() {
+        let conv = conversation();
+        let messages = [message("m1", "Jane", "From what I can tell\n>From here")];
+        let mbox = render(&conv, &messages, ExportFormat::Mbox);
+        assert!(mbox.starts_with("From jane@example.com Tue Jan  2 09:30:00 2024\n"));
+        assert!(mbox.contains("\n>From what I can tell\n>>From here\n"));
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json = render(
+            &conversation(),
+            &[message("m1", "Jane", "Hello")],
+            ExportFormat::Json,
+        );
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["conversation"]["number"], 42);
+        assert_eq!(value["messages"][0]["bodyText"], "Hello");
+    }
+}
@@ -0,0 +1,148 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::cli::shell_command;
+use crate::error::{GrooveError, Result};
+
+/// Converters tried, in order, when no `pdf_command` is configured
+const CONVERTERS: &[&str] = &[
+    "wkhtmltopdf",
+    "weasyprint",
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+];
+
+/// Convert an HTML document to a PDF at `output`.
+///
+/// `command`, when set, is run through the shell with the input HTML file and
+/// the output path appended as arguments (e.g. `weasyprint` or
+/// `wkhtmltopdf --quiet`). Otherwise the first converter from [`CONVERTERS`]
+/// found on PATH is used.
+pub fn html_to_pdf(html: &str, output: &Path, command: Option<&str>) -> Result<()> {
+    let input = tempfile_path(output);
+    std::fs::write(&input, html)?;
+    let result = convert(&input, output, command);
+    let _ = std::fs::remove_file(&input);
+    result
+}
+
+fn convert(input: &Path, output: &Path, command: Option<&str>) -> Result<()> {
+    let mut cmd = match command {
+        Some(command) => shell_command(
+            command,
+            &[input.display().to_string(), output.display().to_string()],
+        ),
+        None => {
+            let path_var = std::env::var_os("PATH").unwrap_or_default();
+            let (name, path) = find_converter(&path_var).ok_or_else(|| {
+                GrooveError::Pdf(format!(
+                    "no HTML-to-PDF converter found (tried {}). Install one or set pdf_command in config",
+                    CONVERTERS.join(", ")
+                ))
+            })?;
+            converter_command(name, &path, input, output)
+        }
+    };
+
+    let result = cmd.stdout(Stdio::null()).output()?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(GrooveError::Pdf(format!(
+            "converter exited with {}: {}",
+            result
+                .status
+                .code()
+                .map(|c| format!("status {}", c))
+                .unwrap_or_else(|| "a signal".to_string()),
+            stderr.trim()
+        )));
+    }
+    if !output.is_file() {
+        return Err(GrooveError::Pdf(format!(
+            "converter did not write {}",
+            output.display()
+        )));
+    }
+    Ok(())
+}
+
+/// HTML intermediate next to the output, so relative paths resolve the same
+fn tempfile_path(output: &Path) -> PathBuf {
+    let stem = output
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "export".to_string());
+    output.with_file_name(format!(".{}.{}.html", stem, std::process::id()))
+}
+
+fn find_converter(path_var: &OsStr) -> Option<(&'static str, PathBuf)> {
+    CONVERTERS.iter().find_map(|name| {
+        let file_name = format!("{}{}", name, std::env::consts::EXE_SUFFIX);
+        std::env::split_paths(path_var)
+            .map(|dir| dir.join(&file_name))
+            .find(|candidate| candidate.is_file())
+            .map(|path| (*name, path))
+    })
+}
+
+fn converter_command(name: &str, path: &Path, input: &Path, output: &Path) -> Command {
+    let mut cmd = Command::new(path);
+    match name {
+        "wkhtmltopdf" => {
+            cmd.arg("--quiet").arg(input).arg(output);
+        }
+        "weasyprint" => {
+            cmd.arg(input).arg(output);
+        }
+        // Chrome and Chromium print headlessly
+        _ => {
+            let mut print_to = std::ffi::OsString::from("--print-to-pdf=");
+            print_to.push(output);
+            cmd.args(["--headless", "--disable-gpu", "--no-pdf-header-footer"])
+                .arg(print_to)
+                .arg(input);
+        }
+    }
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_converter_in_path_order() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(find_converter(dir.path().as_os_str()), None);
+
+        std::fs::write(dir.path().join("google-chrome"), "").unwrap();
+        std::fs::write(dir.path().join("weasyprint"), "").unwrap();
+        let (name, path) = find_converter(dir.path().as_os_str()).unwrap();
+        assert_eq!(name, "weasyprint");
+        assert_eq!(path, dir.path().join("weasyprint"));
+    }
+
+    #[test]
+    fn test_tempfile_path_is_hidden_sibling() {
+        let path = tempfile_path(Path::new("out/thread.pdf"));
+        assert_eq!(path.parent(), Some(Path::new("out")));
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.starts_with(".thread.") && name.ends_with(".html"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_html_to_pdf_with_configured_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("thread.pdf");
+        html_to_pdf("<p>hi</p>", &output, Some("cp")).unwrap();
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), "<p>hi</p>");
+        // The HTML intermediate is cleaned up
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let err = html_to_pdf("<p>hi</p>", &output, Some("false")).unwrap_err();
+        assert!(err.to_string().contains("status 1"));
+    }
+}
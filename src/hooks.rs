@@ -0,0 +1,126 @@
+use crate::cli::shell_command;
+use crate::error::{GrooveError, Result};
+use crate::types::Conversation;
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::Stdio;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    Pre,
+    Post,
+    /// Something noticed by `groove watch`, like an assignment
+    On,
+}
+
+impl std::fmt::Display for HookStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HookStage::Pre => write!(f, "pre"),
+            HookStage::Post => write!(f, "post"),
+            HookStage::On => write!(f, "on"),
+        }
+    }
+}
+
+/// Config key for a hook, e.g. `post-reply`
+pub fn hook_name(stage: HookStage, action: &str) -> String {
+    format!("{}-{}", stage, action)
+}
+
+/// Run the hook configured for `stage` and `action`, if any.
+///
+/// The conversation is written to the hook's stdin as JSON, and
+/// `GROOVE_HOOK`, `GROOVE_CONVERSATION_ID`, and `GROOVE_CONVERSATION_NUMBER`
+/// are set in its environment. A non-zero exit status is returned as an error.
+pub fn run_hook(
+    hooks: &HashMap<String, String>,
+    stage: HookStage,
+    action: &str,
+    conversation: &Conversation,
+) -> Result<()> {
+    let name = hook_name(stage, action);
+    let Some(command) = hooks.get(&name) else {
+        return Ok(());
+    };
+
+    let payload = serde_json::to_vec(conversation)?;
+    let mut child = shell_command(command, &[])
+        .env("GROOVE_HOOK", &name)
+        .env("GROOVE_CONVERSATION_ID", &conversation.id)
+        .env(
+            "GROOVE_CONVERSATION_NUMBER",
+            conversation.number.to_string(),
+        )
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that exits without reading stdin closes the pipe early
+        match stdin.write_all(&payload) {
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => return Err(e.into()),
+            _ => {}
+        }
+    }
+
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(GrooveError::Hook(format!(
+            "'{}' exited with {}",
+            name,
+            status
+                .code()
+                .map(|c| format!("status {}", c))
+                .unwrap_or_else(|| "a signal".to_string())
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation() -> Conversation {
+        Conversation::builder(42)
+            .id("conv-1")
+            .subject("Help")
+            .build()
+    }
+
+    #[test]
+    fn test_hook_name() {
+        assert_eq!(hook_name(HookStage::Pre, "close"), "pre-close");
+        assert_eq!(hook_name(HookStage::Post, "reply"), "post-reply");
+        assert_eq!(hook_name(HookStage::On, "assigned"), "on-assigned");
+    }
+
+    #[test]
+    fn test_run_hook_not_configured() {
+        let hooks = HashMap::new();
+        assert!(run_hook(&hooks, HookStage::Post, "reply", &conversation()).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hook_receives_conversation() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out.json");
+        let hooks = HashMap::from([("post-close".to_string(), format!("cat > {}", out.display()))]);
+
+        run_hook(&hooks, HookStage::Post, "close", &conversation()).unwrap();
+
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(out).unwrap()).unwrap();
+        assert_eq!(written["number"], 42);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hook_failure() {
+        let hooks = HashMap::from([("pre-close".to_string(), "exit 3".to_string())]);
+        let err = run_hook(&hooks, HookStage::Pre, "close", &conversation()).unwrap_err();
+        assert!(err.to_string().contains("pre-close"));
+        assert!(err.to_string().contains("status 3"));
+    }
+}
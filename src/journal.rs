@@ -0,0 +1,306 @@
+use crate::api::GrooveClient;
+use crate::error::Result;
+use crate::types::{Conversation, ConversationState};
+use chrono::{DateTime, Utc};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A conversation mutation and the state needed to revert it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Groups the entries written by one command invocation
+    pub batch: String,
+    pub at: DateTime<Utc>,
+    /// Subcommand name, e.g. `close` or `add-tag`
+    pub action: String,
+    pub conversation_id: String,
+    pub number: i64,
+    pub previous_state: ConversationState,
+    pub previous_assignee: Option<String>,
+    pub previous_tags: Vec<String>,
+    #[serde(default)]
+    pub previous_contact: Option<String>,
+    /// Tags passed to `add-tag`/`remove-tag`
+    #[serde(default)]
+    pub tag_ids: Vec<String>,
+}
+
+impl JournalEntry {
+    /// Snapshot `conv` before `action` is applied to it
+    pub fn new(batch: &str, action: &str, conv: &Conversation, tag_ids: &[String]) -> Self {
+        Self {
+            batch: batch.to_string(),
+            at: Utc::now(),
+            action: action.to_string(),
+            conversation_id: conv.id.clone(),
+            number: conv.number,
+            previous_state: conv.state,
+            previous_assignee: conv.assigned.as_ref().map(|a| a.id.clone()),
+            previous_tags: conv.tags.iter().map(|t| t.id.clone()).collect(),
+            previous_contact: conv.contact.as_ref().map(|c| c.id.clone()),
+            tag_ids: tag_ids.to_vec(),
+        }
+    }
+
+    /// Tags this entry's action added (for `add-tag`) or removed (for
+    /// `remove-tag` and `clear-tags`), i.e. the ones undo has to change back
+    fn changed_tags(&self) -> Vec<String> {
+        let was_tagged = |id: &String| self.previous_tags.contains(id);
+        self.tag_ids
+            .iter()
+            .filter(|id| match self.action.as_str() {
+                "add-tag" => !was_tagged(id),
+                _ => was_tagged(id),
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Describe what undoing this entry does, or `None` if it can't be undone
+    pub fn describe_undo(&self) -> Option<String> {
+        let number = self.number;
+        match self.action.as_str() {
+            "close" | "open" | "snooze" => Some(format!(
+                "Restore conversation #{} to {}",
+                number,
+                restored_state(self.previous_state)
+            )),
+            "assign" | "unassign" => Some(match &self.previous_assignee {
+                Some(agent) => format!("Reassign conversation #{} to agent {}", number, agent),
+                None => format!("Unassign conversation #{}", number),
+            }),
+            "add-tag" => Some(format!("Remove added tags from conversation #{}", number)),
+            "remove-tag" | "clear-tags" => {
+                Some(format!("Re-add removed tags to conversation #{}", number))
+            }
+            "set-contact" => self.previous_contact.as_ref().map(|contact| {
+                format!("Move conversation #{} back to contact {}", number, contact)
+            }),
+            _ => None,
+        }
+    }
+
+    /// Revert this entry's action through the API
+    pub async fn revert(&self, client: &GrooveClient) -> Result<()> {
+        let id = &self.conversation_id;
+        match self.action.as_str() {
+            "close" | "open" | "snooze" => match restored_state(self.previous_state) {
+                ConversationState::Closed => client.close(id).await,
+                _ => client.open(id).await,
+            },
+            "assign" | "unassign" => match &self.previous_assignee {
+                Some(agent) => client.assign(id, agent).await,
+                None => client.unassign(id).await,
+            },
+            "add-tag" => match self.changed_tags() {
+                tags if tags.is_empty() => Ok(()),
+                tags => client.untag(id, tags).await,
+            },
+            "remove-tag" | "clear-tags" => match self.changed_tags() {
+                tags if tags.is_empty() => Ok(()),
+                tags => client.tag(id, tags).await,
+            },
+            "set-contact" => match &self.previous_contact {
+                Some(contact) => client.change_contact(id, contact).await,
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+/// State undo can put a conversation back into. Snoozes can't be restored
+/// without their wake-up time, so they reopen instead.
+fn restored_state(previous: ConversationState) -> ConversationState {
+    match previous {
+        ConversationState::Closed => ConversationState::Closed,
+        _ => ConversationState::Opened,
+    }
+}
+
+/// Append-only log of conversation mutations, one JSON entry per line
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Default journal location in the platform data directory
+    pub fn default_path() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "groove-cli").map(|dirs| dirs.data_dir().join("journal.jsonl"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record(&self, entry: &JournalEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    pub fn entries(&self) -> Result<Vec<JournalEntry>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect()
+    }
+
+    /// The most recent `count` batches, newest first
+    pub fn last_batches(&self, count: usize) -> Result<Vec<Vec<JournalEntry>>> {
+        let mut batches: Vec<Vec<JournalEntry>> = Vec::new();
+        for entry in self.entries()?.into_iter().rev() {
+            let same_batch = batches
+                .last()
+                .is_some_and(|batch| batch[0].batch == entry.batch);
+            if same_batch {
+                batches.last_mut().expect("checked above").push(entry);
+            } else if batches.len() == count {
+                break;
+            } else {
+                batches.push(vec![entry]);
+            }
+        }
+        Ok(batches)
+    }
+
+    /// Drop every entry belonging to one of `batches`
+    pub fn remove_batches(&self, batches: &[String]) -> Result<()> {
+        let kept: Vec<String> = self
+            .entries()?
+            .iter()
+            .filter(|entry| !batches.contains(&entry.batch))
+            .map(serde_json::to_string)
+            .collect::<std::result::Result<_, _>>()?;
+        let mut contents = kept.join("\n");
+        if !contents.is_empty() {
+            contents.push('\n');
+        }
+        std::fs::write(&self.path, contents)?;
+        Ok(())
+    }
+}
+
+/// A batch ID unique to this process
+pub fn new_batch_id() -> String {
+    format!(
+        "{}-{}",
+        Utc::now().format("%Y%m%dT%H%M%S%.3f"),
+        std::process::id()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation(state: ConversationState, tags: &[&str]) -> Conversation {
+        Conversation::builder(42)
+            .id("conv-1")
+            .state(state)
+            .tags(tags)
+            .build()
+    }
+
+    #[test]
+    fn test_record_and_group_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = Journal::new(dir.path().join("journal.jsonl"));
+        let conv = conversation(ConversationState::Opened, &[]);
+
+        journal
+            .record(&JournalEntry::new("b1", "close", &conv, &[]))
+            .unwrap();
+        journal
+            .record(&JournalEntry::new("b2", "close", &conv, &[]))
+            .unwrap();
+        journal
+            .record(&JournalEntry::new("b2", "close", &conv, &[]))
+            .unwrap();
+
+        let batches = journal.last_batches(1).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].len(), 2);
+        assert_eq!(batches[0][0].batch, "b2");
+
+        assert_eq!(journal.last_batches(5).unwrap().len(), 2);
+
+        journal.remove_batches(&["b2".to_string()]).unwrap();
+        let entries = journal.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].batch, "b1");
+    }
+
+    #[test]
+    fn test_missing_journal_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = Journal::new(dir.path().join("journal.jsonl"));
+        assert!(journal.entries().unwrap().is_empty());
+        assert!(journal.last_batches(1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_changed_tags() {
+        let conv = conversation(ConversationState::Opened, &["t1"]);
+        let tags = vec!["t1".to_string(), "t2".to_string()];
+
+        // Only tags that weren't already there are removed again
+        let added = JournalEntry::new("b", "add-tag", &conv, &tags);
+        assert_eq!(added.changed_tags(), vec!["t2".to_string()]);
+
+        // Only tags that were actually there are re-added
+        let removed = JournalEntry::new("b", "remove-tag", &conv, &tags);
+        assert_eq!(removed.changed_tags(), vec!["t1".to_string()]);
+
+        let cleared = JournalEntry::new("b", "clear-tags", &conv, &["t1".to_string()]);
+        assert_eq!(cleared.changed_tags(), vec!["t1".to_string()]);
+        assert!(cleared.describe_undo().is_some());
+    }
+
+    #[test]
+    fn test_describe_undo() {
+        let conv = conversation(ConversationState::Opened, &[]);
+        let entry = JournalEntry::new("b", "close", &conv, &[]);
+        assert_eq!(
+            entry.describe_undo().as_deref(),
+            Some("Restore conversation #42 to open")
+        );
+        assert!(JournalEntry::new("b", "reply", &conv, &[])
+            .describe_undo()
+            .is_none());
+
+        let mut conv = conversation(ConversationState::Opened, &[]);
+        assert!(JournalEntry::new("b", "set-contact", &conv, &[])
+            .describe_undo()
+            .is_none());
+        conv.contact = Some(crate::types::Contact {
+            id: "contact-1".to_string(),
+            email: None,
+            name: None,
+        });
+        assert_eq!(
+            JournalEntry::new("b", "set-contact", &conv, &[])
+                .describe_undo()
+                .as_deref(),
+            Some("Move conversation #42 back to contact contact-1")
+        );
+    }
+}
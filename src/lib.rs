@@ -1,5 +1,56 @@
+//! Client library for the GrooveHQ GraphQL API, and the `groove` CLI built
+//! on it.
+//!
+//! The library surface is [`GrooveClient`] (configured with
+//! [`GrooveClientBuilder`]), the response types it returns (re-exported here
+//! from [`types`]), and [`GrooveError`]. With the `blocking` feature,
+//! `blocking::GrooveClient` offers the same calls without async, and the
+//! `mock-server` feature adds `mock`, a local fake of the API for tests. The
+//! other modules exist for the CLI and may change between minor releases.
+//!
+//! ```no_run
+//! use groovehq_cli::{GrooveClient, GrooveError};
+//!
+//! # async fn run() -> groovehq_cli::Result<()> {
+//! let client = GrooveClient::builder("api-token").build()?;
+//! match client.conversation(12345).await {
+//!     Ok(conv) => {
+//!         let messages = client.all_messages(&conv.id).await?;
+//!         println!("#{} has {} messages", conv.number, messages.len());
+//!     }
+//!     Err(GrooveError::ConversationNotFound(number)) => println!("No #{}", number),
+//!     Err(err) => return Err(err),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
 pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod cli;
+pub mod compose;
 pub mod config;
 pub mod error;
+pub mod export;
+pub mod hooks;
+pub mod journal;
+pub mod metrics;
+#[cfg(feature = "mock-server")]
+pub mod mock;
+pub mod notify;
+pub mod outbox;
+pub mod reminders;
+pub mod report;
+pub mod seen;
+pub mod sla;
+pub mod snippets;
+pub mod store;
+pub mod templates;
 pub mod types;
+
+pub use api::{
+    ConversationsResponse, GrooveClient, GrooveClientBuilder, RateLimitInfo, RetryPolicy,
+};
+pub use error::{GrooveError, Result};
+pub use types::*;
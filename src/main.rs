@@ -1,43 +1,123 @@
 use anyhow::Context;
-use chrono::{Duration, Utc};
-use clap::Parser;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, Utc};
+use clap::{Parser, ValueEnum};
+use futures::future;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::io::{self, IsTerminal, Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
 
-use groovehq_cli::api::{GrooveClient, MAX_ITEMS_PER_PAGE};
+use groovehq_cli::api::{
+    Cache, ConversationsResponse, GrooveClient, BATCH_MUTATION_SIZE, DEFAULT_CACHE_TTL,
+    DEFAULT_ENDPOINT, MAX_ITEMS_PER_PAGE,
+};
 use groovehq_cli::cli::{
-    self, print_completions, CannedRepliesAction, Cli, Commands, ConfigAction, ConversationAction,
-    FolderAction, OutputFormat, TagAction,
+    self, print_completions, AliasExpansion, BulkAction, BulkArgs, BulkFilter, BulkOptions,
+    CannedRepliesAction, Cli, Commands, ConfigAction, ContactAction, ConversationAction,
+    FolderAction, GroupBy, InboxSummary, MessageAction, MessageDisplay, NoteAction, OutboxAction,
+    OutputFormat, RemindAction, ReportAction, RuleAction, SearchIn, SnippetAction, TagAction,
 };
+use groovehq_cli::compose;
 use groovehq_cli::config::{self, Config};
 use groovehq_cli::error;
+use groovehq_cli::export::{self, BackupOptions, ExportFormat};
+use groovehq_cli::hooks::{self, HookStage};
+use groovehq_cli::journal::{self, Journal, JournalEntry};
+use groovehq_cli::metrics::{InboxCounts, Metrics, RecordLatency};
+use groovehq_cli::outbox::{Outbox, QueuedAssignee, ReplyOptions};
+use groovehq_cli::reminders::Reminders;
+use groovehq_cli::report;
+use groovehq_cli::seen::Seen;
+use groovehq_cli::sla::{self, ResponseTimes, Sla};
+use groovehq_cli::snippets::{Snippet, Snippets};
+use groovehq_cli::store::{self, Store, SyncOptions};
+use groovehq_cli::templates::{Template, Variables};
+use groovehq_cli::types::{
+    Conversation, ConversationFilter, ConversationSort, ConversationState, DateField, DateRange,
+    Folder, Message, MessagePage, PageInfo,
+};
 
 const DEFAULT_CONVERSATION_LIMIT: u32 = 25;
 const DEFAULT_MESSAGE_LIMIT: i32 = 50;
+/// `groove open` asks before opening more browser tabs than this
+const OPEN_TABS_WITHOUT_CONFIRM: usize = 10;
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() {
     if let Err(err) = run().await {
-        eprintln!("Error: {err}");
+        eprintln!("{}", cli::tr(cli::Msg::Error, &[&err]));
 
         // Show error chain if verbose flag was passed
         if std::env::args().any(|arg| arg == "--verbose" || arg == "-v") {
             let mut source = err.source();
             while let Some(cause) = source {
-                eprintln!("Caused by: {cause}");
+                eprintln!("{}", cli::tr(cli::Msg::CausedBy, &[&cause]));
                 source = cause.source();
             }
         }
 
-        std::process::exit(1);
+        let code = err
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<error::GrooveError>())
+            .map_or(error::exit_code::FAILURE, error::GrooveError::exit_code);
+        std::process::exit(code);
     }
 }
 
 async fn run() -> anyhow::Result<()> {
-    let cli = Cli::parse();
-    let config = Config::load().context("Failed to load configuration")?;
+    cli::set_locale(cli::Locale::from_env());
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(path) = config_arg(&args) {
+        Config::set_path(path);
+    }
+    let loaded = Config::load();
+
+    // Expand user-defined aliases before clap sees the arguments
+    let aliases = loaded
+        .as_ref()
+        .map(|c| c.aliases.clone())
+        .unwrap_or_default();
+    let args = match cli::expand_aliases(args, &aliases)? {
+        AliasExpansion::Args(args) => args,
+        AliasExpansion::Shell { command, args } => {
+            let status = cli::shell_command(&command, &args).status()?;
+            std::process::exit(status.code().unwrap_or(1));
+        }
+    };
+
+    // Hand unknown subcommands to a `groove-<name>` executable on PATH
+    if let Some(plugin) = cli::find_plugin(&args) {
+        let status = plugin_command(&plugin, loaded.as_ref().ok()).status()?;
+        std::process::exit(status.code().unwrap_or(1));
+    }
+
+    let cli = Cli::parse_from(args);
+    let raw_config = loaded.context("Failed to load configuration")?;
+
+    // Config commands work on the file as-is so `set-token` can create new profiles
+    let config = match &cli.command {
+        Commands::Config { .. } => raw_config.clone(),
+        _ => {
+            let mut config = raw_config
+                .with_profile(cli.profile.as_deref())?
+                .with_env()?;
+            if let Some(endpoint) = &cli.endpoint {
+                config.api_endpoint = Some(endpoint.clone());
+            }
+            config
+        }
+    };
+
+    // A bad theme shouldn't stop `groove config` from fixing it
+    if !matches!(cli.command, Commands::Config { .. }) {
+        cli::set_theme(theme(&config, cli.iso_dates)?);
+    }
 
     // Resolve format: CLI flag > config default > "table"
-    let format = cli.format.unwrap_or_else(|| {
+    let format = cli.format.clone().unwrap_or_else(|| {
         config
             .defaults
             .format
@@ -47,21 +127,127 @@ async fn run() -> anyhow::Result<()> {
     });
 
     match &cli.command {
-        Commands::Config { action } => handle_config(&action, &config, cli.quiet)?,
+        Commands::Config { action } => handle_config(action, &raw_config, &cli, &format).await?,
         Commands::Completions { shell } => {
-            print_completions(shell.clone());
+            print_completions(*shell);
+        }
+        // Completion reads cached names only, so it's fast and works offline
+        Commands::Complete { words } => {
+            let profile = cli.profile.as_deref();
+            if let Some(cache) = Cache::latest_for_profile(profile, DEFAULT_CACHE_TTL) {
+                for value in cli::completion_values(&cache, words) {
+                    println!("{}", value);
+                }
+            }
+        }
+        // Local search reads the synced database and needs no token
+        Commands::SearchLocal { query, db, limit } => {
+            let path = store_path(db.as_ref())?;
+            if !path.exists() {
+                anyhow::bail!(
+                    "No local database at {}. Run 'groove sync' first",
+                    path.display()
+                );
+            }
+            let store = Store::open(&path)?;
+            let hits = store
+                .search(query, *limit)
+                .map_err(|e| anyhow::anyhow!("Search for '{}' failed: {}", query, e))?;
+            cli::format_search_hits(&hits, &format);
+        }
+        #[cfg(feature = "mock-server")]
+        Commands::MockServer { host, port } => {
+            let listener = std::net::TcpListener::bind((host.as_str(), *port))
+                .with_context(|| format!("Failed to listen on {}:{}", host, port))?;
+            let addr = listener.local_addr()?;
+            eprintln!("Mock Groove API listening on http://{}", addr);
+            eprintln!(
+                "Use it with api_endpoint = \"http://{}\" in a config profile (any token works)",
+                addr
+            );
+            groovehq_cli::mock::serve(listener)?;
         }
+        // Snippets are local and need no token
+        Commands::Snippet { action } => handle_snippet(action, &format, cli.quiet)?,
+        // Listing and cancelling queued replies is local and needs no token
+        Commands::Outbox { action } if !matches!(action, OutboxAction::Flush { .. }) => {
+            handle_outbox(action, &format, cli.quiet)?
+        }
+        // Reminders are local and need no token
+        Commands::Remind {
+            action,
+            number,
+            when,
+            note,
+        } => handle_remind(
+            action.as_ref(),
+            *number,
+            when.as_deref(),
+            note,
+            &format,
+            cli.quiet,
+        )?,
         _ => {
             let token = config::resolve_token(cli.token.as_deref(), &config)?;
-            let client = GrooveClient::new(&token, config.api_endpoint.as_deref())?;
-            handle_command(&cli.command, &client, &format, &config, cli.quiet).await?;
+            let mut client = api_client(&token, &config)?.with_debug(cli.debug);
+            let ttl = config
+                .defaults
+                .cache_ttl
+                .map(std::time::Duration::from_secs)
+                .unwrap_or(DEFAULT_CACHE_TTL);
+            let endpoint = config.api_endpoint.as_deref().unwrap_or(DEFAULT_ENDPOINT);
+            if let Some(cache) = Cache::for_account(cli.profile.as_deref(), endpoint, &token, ttl) {
+                client = client.with_cache(cache.refresh(cli.refresh));
+            }
+            if let Commands::Watch {
+                metrics: Some(_), ..
+            } = &cli.command
+            {
+                client = client.with_middleware(RecordLatency(watch_metrics().clone()));
+            }
+            let result = handle_command(&cli.command, &client, &format, &config, cli.quiet).await;
+            if cli.verbose {
+                if let Some(info) = client.rate_limit() {
+                    eprintln!("Rate limit: {}", info);
+                }
+            }
+            result?;
         }
     }
 
     Ok(())
 }
 
-fn handle_config(action: &ConfigAction, config: &Config, quiet: bool) -> anyhow::Result<()> {
+/// `--config` from the raw arguments. The config is loaded before clap
+/// parses them, since aliases are expanded first.
+fn config_arg(args: &[String]) -> Option<PathBuf> {
+    let end = args
+        .iter()
+        .position(|arg| arg == "--")
+        .unwrap_or(args.len());
+    cli::global_option_value(args.get(1..end)?, "config").map(PathBuf::from)
+}
+
+/// Client for the configured endpoint, routed through the configured proxy if any
+fn api_client(token: &str, config: &Config) -> anyhow::Result<GrooveClient> {
+    let mut builder = GrooveClient::builder(token);
+    if let Some(endpoint) = &config.api_endpoint {
+        builder = builder.endpoint(endpoint);
+    }
+    if let Some(proxy) = &config.proxy {
+        builder = builder.proxy(proxy);
+    }
+    Ok(builder.build()?)
+}
+
+async fn handle_config(
+    action: &ConfigAction,
+    raw_config: &Config,
+    args: &Cli,
+    format: &OutputFormat,
+) -> anyhow::Result<()> {
+    let profile = args.profile.as_deref();
+    let quiet = args.quiet;
     match action {
         ConfigAction::Init => {
             let path = Config::path()
@@ -78,7 +264,7 @@ fn handle_config(action: &ConfigAction, config: &Config, quiet: bool) -> anyhow:
                 io::stdin().read_line(&mut input)?;
 
                 if !input.trim().eq_ignore_ascii_case("y") {
-                    println!("Aborted.");
+                    println!("{}", cli::Msg::Aborted.text());
                     return Ok(());
                 }
             }
@@ -107,11 +293,11 @@ fn handle_config(action: &ConfigAction, config: &Config, quiet: bool) -> anyhow:
             let api_endpoint = api_endpoint.trim();
 
             // Create config
-            let mut new_config = Config::default();
-            new_config.api_token = Some(api_token.to_string());
-            if !api_endpoint.is_empty() {
-                new_config.api_endpoint = Some(api_endpoint.to_string());
-            }
+            let new_config = Config {
+                api_token: Some(api_token.to_string()),
+                api_endpoint: (!api_endpoint.is_empty()).then(|| api_endpoint.to_string()),
+                ..Default::default()
+            };
 
             new_config.save()?;
 
@@ -119,6 +305,13 @@ fn handle_config(action: &ConfigAction, config: &Config, quiet: bool) -> anyhow:
             println!("You can now use 'groove' commands!");
         }
         ConfigAction::Show => {
+            let mut config = raw_config.with_profile(profile)?.with_env()?;
+            if let Some(endpoint) = &args.endpoint {
+                config.api_endpoint = Some(endpoint.clone());
+            }
+            if let Some(name) = profile {
+                println!("profile: {}", name);
+            }
             if let Some(token) = &config.api_token {
                 let masked = if token.len() > 8 {
                     format!("{}...{}", &token[..4], &token[token.len() - 4..])
@@ -126,18 +319,89 @@ fn handle_config(action: &ConfigAction, config: &Config, quiet: bool) -> anyhow:
                     "********".to_string()
                 };
                 println!("api_token: {}", masked);
+            } else if let Some(command) = &config.token_cmd {
+                println!("api_token: (from token_cmd: {})", command);
+            } else if let Ok(Some(_)) = config::keychain::get_token(profile) {
+                println!("api_token: (stored in keyring)");
             } else {
                 println!("api_token: (not set)");
             }
             if let Some(endpoint) = &config.api_endpoint {
                 println!("api_endpoint: {}", endpoint);
             }
+            if let Some(web_url) = &config.web_url {
+                println!("web_url: {}", web_url);
+            }
+            if let Some(proxy) = &config.proxy {
+                println!("proxy: {}", proxy);
+            }
+            if let Some(pdf_command) = &config.pdf_command {
+                println!("pdf_command: {}", pdf_command);
+            }
+            let overrides: Vec<&str> = config::ENV_VARIABLES
+                .iter()
+                .map(|(name, _)| *name)
+                .filter(|name| std::env::var(name).is_ok_and(|v| !v.is_empty()))
+                .collect();
+            if !overrides.is_empty() {
+                println!("environment overrides: {}", overrides.join(", "));
+            }
         }
-        ConfigAction::SetToken { token } => {
-            let mut config = config.clone();
-            config.set_token(token.clone())?;
-            if !quiet {
-                println!("Token saved successfully");
+        ConfigAction::SetToken { token, keyring } => {
+            let mut config = raw_config.clone();
+            if *keyring {
+                config.set_token_in_keyring(token, profile)?;
+                success_msg(quiet, "Token saved to OS keyring");
+            } else {
+                config.set_token(token.clone(), profile)?;
+                success_msg(quiet, "Token saved successfully");
+            }
+        }
+        ConfigAction::Set { key, value } => {
+            let mut config = raw_config.clone();
+            config.set_key(key, value)?;
+            config.save()?;
+            success_msg(quiet, format!("Set {} = {}", key, value));
+        }
+        ConfigAction::Get { key } => match raw_config.get_key(key)? {
+            Some(toml::Value::String(value)) => println!("{}", value),
+            Some(toml::Value::Table(table)) => print!("{}", table),
+            Some(value) => println!("{}", value),
+            None => anyhow::bail!("Config key '{}' is not set", key),
+        },
+        ConfigAction::Unset { key } => {
+            let mut config = raw_config.clone();
+            if config.unset_key(key)? {
+                config.save()?;
+                success_msg(quiet, format!("Unset {}", key));
+            } else {
+                success_msg(quiet, format!("{} was not set", key));
+            }
+        }
+        ConfigAction::Verify => {
+            let mut config = raw_config.with_profile(profile)?.with_env()?;
+            if let Some(endpoint) = &args.endpoint {
+                config.api_endpoint = Some(endpoint.clone());
+            }
+            let token = config::resolve_token(args.token.as_deref(), &config)?;
+            let client = api_client(&token, &config)?.with_debug(args.debug);
+            let agent = client.me().await.context("Token verification failed")?;
+
+            match format {
+                OutputFormat::Json => cli::format_agent(&agent, format),
+                _ => {
+                    if !quiet {
+                        println!("Token is valid");
+                        if let Some(name) = profile {
+                            println!("profile: {}", name);
+                        }
+                        println!(
+                            "api_endpoint: {}",
+                            config.api_endpoint.as_deref().unwrap_or(DEFAULT_ENDPOINT)
+                        );
+                        cli::format_agent(&agent, format);
+                    }
+                }
             }
         }
         ConfigAction::Path => {
@@ -159,33 +423,346 @@ async fn handle_command(
     quiet: bool,
 ) -> anyhow::Result<()> {
     match command {
+        Commands::Inbox => {
+            let summary = inbox_summary(client).await?;
+            cli::format_inbox_summary(&summary, format);
+        }
+
         Commands::Me => {
             let agent = client.me().await?;
             cli::format_agent(&agent, format);
         }
 
+        Commands::My {
+            scope,
+            limit,
+            after,
+            table,
+        } => {
+            let me = client.me().await?;
+            let limit = limit
+                .or(config.defaults.limit)
+                .unwrap_or(DEFAULT_CONVERSATION_LIMIT);
+            let response = client
+                .conversations(
+                    &ConversationFilter {
+                        state: scope.state(),
+                        assignee_id: Some(me.id),
+                        ..ConversationFilter::default()
+                    },
+                    Some(limit),
+                    after.clone(),
+                )
+                .await?;
+            cli::format_conversations(&response, format, &list_display(config, table)?);
+        }
+
+        Commands::Search {
+            query,
+            limit,
+            table,
+        } => {
+            let mut filter = cli::parse_query(query).map_err(|e| anyhow::anyhow!(e))?;
+            let limit = limit
+                .or(config.defaults.limit)
+                .unwrap_or(DEFAULT_CONVERSATION_LIMIT);
+            filter.limit = Some(limit);
+            let keywords = filter.search.as_deref().and_then(cli::Keywords::parse);
+            // Filters only apply to conversations; the other sources are
+            // searched for the keywords alone
+            let contacts = async {
+                match &filter.search {
+                    Some(words) => client.search_contacts(words, limit).await,
+                    None => Ok(Vec::new()),
+                }
+            };
+            let canned_replies = async {
+                let Some(keywords) = &keywords else {
+                    return Ok(Vec::new());
+                };
+                let replies = client.canned_replies().await?;
+                Ok(replies
+                    .into_iter()
+                    .filter(|reply| {
+                        let text = [
+                            Some(reply.name.as_str()),
+                            reply.subject.as_deref(),
+                            reply.body.as_deref(),
+                        ];
+                        keywords.all_in(&text.into_iter().flatten().collect::<Vec<_>>().join("\n"))
+                    })
+                    .take(limit as usize)
+                    .collect())
+            };
+            let articles = async {
+                match &filter.search {
+                    Some(words) => client.kb_articles(words, limit).await,
+                    None => Ok(Vec::new()),
+                }
+            };
+            let (conversations, contacts, canned_replies, articles) = tokio::join!(
+                find_conversations(client, &filter, config),
+                contacts,
+                canned_replies,
+                articles
+            );
+            let results = cli::SearchResults {
+                conversations: conversations?,
+                contacts: search_source("contacts", contacts)?,
+                canned_replies: search_source("canned replies", canned_replies)?,
+                articles: search_source("knowledge base articles", articles)?,
+            };
+            let mut display = list_display(config, table)?;
+            display.search = keywords;
+            cli::format_search_results(&results, format, &display);
+        }
+
+        Commands::Open {
+            numbers,
+            filter,
+            yes,
+        } => {
+            let numbers = if numbers.is_empty() {
+                let matches = find_conversations(client, filter, config).await?;
+                matches.iter().map(|c| c.number).collect()
+            } else {
+                let numbers = cli::expand_numbers(numbers);
+                validate_conversation_numbers(&numbers)?;
+                numbers
+            };
+            if numbers.is_empty() {
+                success_msg(quiet, cli::Msg::NoMatches.text());
+                return Ok(());
+            }
+            let urls = numbers
+                .iter()
+                .map(|n| config.conversation_url(*n))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if urls.len() > OPEN_TABS_WITHOUT_CONFIRM {
+                let prompt = format!("Open {} browser tabs?", urls.len());
+                if !confirm(&prompt, *yes)? {
+                    println!("{}", cli::Msg::Aborted.text());
+                    return Ok(());
+                }
+            }
+            for (number, url) in numbers.iter().zip(&urls) {
+                cli::open_in_browser(url)?;
+                success_msg(quiet, format!("Opened #{} in the browser", number));
+            }
+        }
+
+        Commands::Mentions { unread, limit } => {
+            let mut mentions = client.mentions(*limit).await?;
+            if *unread {
+                mentions.retain(|m| m.is_unread());
+            }
+            cli::format_mentions(&mentions, format);
+        }
+
+        Commands::Watch {
+            interval,
+            metrics,
+            notify,
+        } => {
+            watch(client, *interval, *metrics, *notify, config, quiet).await?;
+        }
+
+        Commands::Outbox { action } => {
+            let OutboxAction::Flush { all } = action else {
+                unreachable!()
+            };
+            let sent = flush_outbox(client, config, quiet, *all).await?;
+            if sent == 0 {
+                success_msg(quiet, "No queued replies are due");
+            }
+        }
+
+        Commands::Limits => {
+            // Any request reports the budget; `me` is the cheapest
+            client.me().await?;
+            match client.rate_limit() {
+                Some(info) => cli::format_rate_limit(&info, format),
+                None => anyhow::bail!("The API did not report rate-limit headers"),
+            }
+        }
+
         Commands::Conversation { action } => {
             handle_conversation(action, client, format, config, quiet).await?;
         }
 
         Commands::Folder { action } => {
-            handle_folder(action, client, format).await?;
+            handle_folder(action, client, config, format).await?;
         }
 
         Commands::Tag { action } => {
             handle_tag(action, client, format).await?;
         }
 
+        Commands::Contact { action } => {
+            handle_contact(action, client, config, format, quiet).await?;
+        }
+
+        Commands::Report { action } => {
+            handle_report(action, client, format).await?;
+        }
+
         Commands::CannedReplies { action } => {
             handle_canned_replies(action, client, format).await?;
         }
 
-        Commands::Config { .. } | Commands::Completions { .. } => unreachable!(),
+        Commands::Rule { action } => {
+            handle_rule(action, client, format, quiet).await?;
+        }
+
+        Commands::Message { action } => match action {
+            MessageAction::View { id, html } => {
+                let message = client.message(id).await?;
+                cli::format_message(&message, format, *html);
+            }
+        },
+
+        Commands::Note { action } => {
+            handle_note(action, client, quiet).await?;
+        }
+
+        Commands::Sync {
+            maildir: Some(dir),
+            full,
+            ..
+        } => {
+            let summary = export::sync_maildir(client, dir, *full).await?;
+            success_msg(
+                quiet,
+                format!(
+                    "Mirrored {} {}conversations ({} new messages) to {}",
+                    summary.conversations,
+                    if summary.incremental { "updated " } else { "" },
+                    summary.messages_written,
+                    dir.display()
+                ),
+            );
+        }
+
+        Commands::Sync { db, full, .. } => {
+            let path = store_path(db.as_ref())?;
+            let store = Store::open(&path)?;
+            let summary = store::sync(client, &store, &SyncOptions { full: *full }).await?;
+            if summary.resumed {
+                success_msg(quiet, "Resumed interrupted sync");
+            }
+            success_msg(
+                quiet,
+                format!(
+                    "Synced {} conversations ({} updated, {} messages, {} notes) and {} tags to {}",
+                    summary.conversations_seen,
+                    summary.conversations_updated,
+                    summary.messages_fetched,
+                    summary.notes_fetched,
+                    summary.tags,
+                    path.display()
+                ),
+            );
+        }
+
+        Commands::Export {
+            dir,
+            to,
+            status,
+            folder,
+            since,
+            until,
+            updated,
+            limit,
+        } => {
+            let folder_id = match folder {
+                Some(name) => Some(resolve_folder_id(client, name).await?),
+                None => None,
+            };
+            let options = BackupOptions {
+                dir: dir.clone(),
+                format: *to,
+                status: *status,
+                folder_id,
+                dates: date_range(since.as_deref(), until.as_deref(), *updated)?,
+                contact: None,
+                limit: limit.map(|l| l as usize),
+                pdf_command: config.pdf_command.clone(),
+            };
+            let summary = export::backup(client, &options).await?;
+            success_msg(
+                quiet,
+                format!(
+                    "Exported {} conversations ({} messages) to {}",
+                    summary.conversations,
+                    summary.messages,
+                    dir.display()
+                ),
+            );
+        }
+
+        Commands::Undo { steps, dry_run } => {
+            let path = Journal::default_path()
+                .ok_or_else(|| anyhow::anyhow!("Could not determine data directory"))?;
+            let journal = Journal::new(path);
+            let batches = journal.last_batches(*steps)?;
+            if batches.is_empty() {
+                success_msg(quiet, cli::Msg::NothingToUndo.text());
+                return Ok(());
+            }
+
+            for batch in &batches {
+                for entry in batch {
+                    let Some(description) = entry.describe_undo() else {
+                        continue;
+                    };
+                    if *dry_run {
+                        println!("{}", description);
+                        continue;
+                    }
+                    entry
+                        .revert(client)
+                        .await
+                        .with_context(|| format!("Failed to undo '{}'", entry.action))?;
+                    success_msg(quiet, description);
+                }
+                if !*dry_run {
+                    journal.remove_batches(&[batch[0].batch.clone()])?;
+                }
+            }
+        }
+
+        Commands::Macro { name, numbers } => {
+            let numbers = cli::expand_numbers(numbers);
+            handle_macro(name, &numbers, client, format, config, quiet).await?;
+        }
+
+        Commands::Config { .. }
+        | Commands::Completions { .. }
+        | Commands::Complete { .. }
+        | Commands::SearchLocal { .. }
+        | Commands::Remind { .. }
+        | Commands::Snippet { .. } => {
+            unreachable!()
+        }
+        #[cfg(feature = "mock-server")]
+        Commands::MockServer { .. } => {
+            unreachable!()
+        }
     }
 
     Ok(())
 }
 
+/// The `--db` path if given, otherwise the default store location
+fn store_path(db: Option<&PathBuf>) -> anyhow::Result<PathBuf> {
+    match db {
+        Some(path) => Ok(path.clone()),
+        None => Store::default_path()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine data directory")),
+    }
+}
+
 async fn handle_conversation(
     action: &ConversationAction,
     client: &GrooveClient,
@@ -193,280 +770,2465 @@ async fn handle_conversation(
     config: &Config,
     quiet: bool,
 ) -> anyhow::Result<()> {
+    // Changes print a structured result instead of a success line with `-o json`
+    let quiet = quiet || matches!(format, OutputFormat::Json);
     match action {
         ConversationAction::List {
             status,
             folder,
             search,
+            search_in,
+            contact,
+            since,
+            until,
+            updated,
+            waking_before,
+            sort,
+            group_by,
             limit,
             after,
+            all,
+            watch,
+            idle_over,
+            breaching_only,
+            table,
         } => {
-            // Apply config defaults: CLI arg > config default > hardcoded default
+            // Apply config defaults: CLI arg > [defaults.list] > [defaults] >
+            // hardcoded default
+            let defaults = &config.defaults.list;
             let limit = limit
+                .or(defaults.limit)
                 .or(config.defaults.limit)
                 .unwrap_or(DEFAULT_CONVERSATION_LIMIT);
-            let folder = folder.as_ref().or(config.defaults.folder.as_ref());
-            let response = client
-                .conversations(
-                    Some(limit),
-                    after.clone(),
-                    status.as_deref(),
-                    folder.map(|s| s.as_str()),
-                    search.as_deref(),
-                )
-                .await?;
-            cli::format_conversations(&response, format);
+            let sort = match sort {
+                Some(sort) => Some(*sort),
+                None => config_default("list", "sort", &defaults.sort, str::parse)?,
+            };
+            let group_by = match group_by {
+                Some(key) => Some(*key),
+                None => config_default("list", "group_by", &defaults.group_by, |s| {
+                    GroupBy::from_str(s, true)
+                })?,
+            };
+            // --waking-before implies snoozed, so it overrides a default status
+            let status = match status {
+                Some(status) => Some(*status),
+                None if waking_before.is_some() => None,
+                None => config_default("list", "status", &defaults.status, str::parse)?,
+            };
+            let folder = folder
+                .as_ref()
+                .or(defaults.folder.as_ref())
+                .or(config.defaults.folder.as_ref());
+            let folder = match folder {
+                Some(name) => Some(resolve_folder_id(client, name).await?),
+                None => None,
+            };
+            let contact = match contact {
+                Some(contact) => Some(resolve_contact_id(client, contact).await?),
+                None => None,
+            };
+            let dates = date_range(since.as_deref(), until.as_deref(), *updated)?;
+            let wake_span = match waking_before {
+                Some(span) => {
+                    if status.is_some_and(|s| s != ConversationState::Snoozed) {
+                        anyhow::bail!("--waking-before only applies to snoozed conversations");
+                    }
+                    Some(parse_duration_span(span)?)
+                }
+                None => None,
+            };
+            let status = match wake_span {
+                Some(_) => Some(ConversationState::Snoozed),
+                None => status,
+            };
+            let filter = ConversationFilter {
+                state: status,
+                folder_id: folder,
+                keywords: search.clone(),
+                contact_id: contact,
+                dates,
+                sort,
+                ..ConversationFilter::default()
+            };
+            let sla = list_sla(config)?;
+            if *breaching_only && sla.is_none() {
+                anyhow::bail!(
+                    "--breaching-only needs an SLA. Set one with: groove config set sla.response_time 4h"
+                );
+            }
+            let idle_span = idle_over.as_deref().map(parse_duration_span).transpose()?;
+            let mut display = list_display(config, table)?;
+            if sla.is_some() && !display.columns.contains(&cli::Column::Waiting) {
+                display.columns.push(cli::Column::Waiting);
+            }
+            if idle_span.is_some() && !display.columns.contains(&cli::Column::Idle) {
+                display.columns.push(cli::Column::Idle);
+            }
+            let search_in = search_in.unwrap_or_default();
+            let keywords = search.as_deref().and_then(cli::Keywords::parse);
+            if search_in == SearchIn::Body && !display.columns.contains(&cli::Column::Match) {
+                display.columns.push(cli::Column::Match);
+            }
+            // Bodies are read for --search-in body, or to fill a configured
+            // match column
+            let read_bodies = keywords.is_some()
+                && (search_in == SearchIn::Body || display.shows(cli::Column::Match));
+            display.search = keywords.clone();
+            let idle = display.shows(cli::Column::Idle);
+            let fetch = || async {
+                let mut response = if *all {
+                    let nodes: Vec<Conversation> =
+                        client.conversations_stream(&filter).try_collect().await?;
+                    ConversationsResponse {
+                        total_count: nodes.len() as i32,
+                        nodes,
+                        page_info: PageInfo::default(),
+                    }
+                } else {
+                    client
+                        .conversations(&filter, Some(limit), after.clone())
+                        .await?
+                };
+                if let Some(span) = wake_span {
+                    // The API can't filter on wake-up time, so this narrows the fetched page
+                    let cutoff = Utc::now() + span;
+                    response
+                        .nodes
+                        .retain(|c| c.snoozed_until.is_some_and(|until| until <= cutoff));
+                    response.nodes.sort_by_key(|c| c.snoozed_until);
+                }
+                // The API's keyword search covers everything, so --search-in
+                // narrows what it found
+                let mut matches = HashMap::new();
+                if let Some(keywords) = &keywords {
+                    if search_in == SearchIn::Subject {
+                        response
+                            .nodes
+                            .retain(|c| keywords.all_in(c.subject.as_deref().unwrap_or("")));
+                    }
+                    if read_bodies {
+                        matches = body_matches(client, &response.nodes, keywords).await?;
+                    }
+                    if search_in == SearchIn::Body {
+                        response.nodes.retain(|c| matches.contains_key(&c.number));
+                    }
+                }
+                let mut sla = sla.clone();
+                let mut last_message = HashMap::new();
+                if sla.is_some() || idle {
+                    // Enough of each thread to find where the customer's
+                    // unanswered messages start, or just the newest one
+                    let count = if sla.is_some() {
+                        sla::RECENT_MESSAGES
+                    } else {
+                        1
+                    };
+                    let wanted = response
+                        .nodes
+                        .iter()
+                        .filter(|c| idle || sla::applies_to(c.state));
+                    let newest = newest_messages(client, wanted, count).await?;
+                    for conv in &response.nodes {
+                        let Some(messages) = newest.get(&conv.number) else {
+                            continue;
+                        };
+                        if let Some(sla) = sla.as_mut().filter(|_| sla::applies_to(conv.state)) {
+                            if let Some(since) = sla::waiting_since(messages) {
+                                sla.waiting.insert(conv.number, since);
+                            }
+                        }
+                        // A conversation without messages has been idle since it began
+                        let last = messages.last().map_or(conv.created_at, |m| m.created_at);
+                        last_message.insert(conv.number, last);
+                    }
+                }
+                let now = Utc::now();
+                if let Some(span) = idle_span {
+                    response
+                        .nodes
+                        .retain(|c| last_message.get(&c.number).is_some_and(|t| now - *t > span));
+                }
+                if let Some(sla) = sla.as_ref().filter(|_| *breaching_only) {
+                    response.nodes.retain(|c| sla.is_breaching(c.number, now));
+                }
+                Ok::<_, error::GrooveError>((response, sla, last_message, matches))
+            };
+            let snoozed = status == Some(ConversationState::Snoozed);
+            let print =
+                |response: &ConversationsResponse, display: &cli::ListDisplay| match group_by {
+                    Some(key) => cli::format_grouped_conversations(response, key, format, display),
+                    None if snoozed => cli::format_snoozed_conversations(response, format, display),
+                    None => cli::format_conversations(response, format, display),
+                };
+
+            let Some(interval) = watch else {
+                let (response, sla, last_message, matches) = fetch().await?;
+                display.sla = sla;
+                display.last_message = last_message;
+                display.matches = matches;
+                print(&response, &display);
+                return Ok(());
+            };
+            // Redraw in place on a terminal; otherwise append each refresh
+            let redraw = io::stdout().is_terminal() && matches!(format, OutputFormat::Table);
+            let mut previous: Option<cli::Snapshot> = None;
+            let mut ticker = tokio::time::interval(std::time::Duration::from_secs(*interval));
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let response = match fetch().await {
+                    Ok((response, sla, last_message, matches)) => {
+                        display.sla = sla;
+                        display.last_message = last_message;
+                        display.matches = matches;
+                        response
+                    }
+                    Err(err @ error::GrooveError::AuthError(_)) => return Err(err.into()),
+                    // Nothing to show yet, so a failed first fetch ends the watch
+                    Err(err) if previous.is_none() => return Err(err.into()),
+                    Err(err) => {
+                        eprintln!("Refresh failed: {}", err);
+                        continue;
+                    }
+                };
+                display.changes = previous
+                    .map(|snapshot| snapshot.changes(&response.nodes))
+                    .unwrap_or_default();
+                previous = Some(cli::Snapshot::of(&response.nodes));
+                // Pick up conversations viewed in another terminal
+                display.seen = open_seen(config);
+                if redraw {
+                    print!("\x1b[2J\x1b[H");
+                }
+                if matches!(format, OutputFormat::Table) {
+                    let changes = display.changes.summary();
+                    println!(
+                        "{} every {}s{}",
+                        Local::now().format("%H:%M:%S"),
+                        interval,
+                        changes.map(|c| format!(" · {}", c)).unwrap_or_default()
+                    );
+                }
+                print(&response, &display);
+            }
+        }
+
+        ConversationAction::View {
+            number,
+            message: Some(message_id),
+            html,
+            ..
+        } => {
+            validate_conversation_number(*number)?;
+            let message = client.message(message_id).await?;
+            cli::format_message(&message, format, *html);
         }
 
-        ConversationAction::View { number, full } => {
+        ConversationAction::View {
+            number,
+            grep: Some(pattern),
+            context,
+            ..
+        } => {
+            let pattern = regex::RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| anyhow::anyhow!("Invalid --grep pattern: {}", e))?;
             let conv = get_conversation(client, *number).await?;
-            let messages = client
-                .messages(&conv.id, Some(DEFAULT_MESSAGE_LIMIT))
-                .await?;
-            cli::format_conversation_detail(&conv, &messages, *full);
+            let messages = client.all_messages(&conv.id).await?;
+            cli::format_message_matches(&messages, &pattern, *context, format);
+        }
+
+        ConversationAction::View {
+            number, raw: true, ..
+        } => {
+            let conv = get_conversation(client, *number).await?;
+            let messages = client.all_messages(&conv.id).await?;
+            print!("{}", export::render(&conv, &messages, ExportFormat::Text));
+        }
+
+        ConversationAction::View {
+            number,
+            stats: true,
+            ..
+        } => {
+            let conv = get_conversation(client, *number).await?;
+            let messages = client.all_messages(&conv.id).await?;
+            cli::format_response_times(&conv, &ResponseTimes::of(&messages), format);
+        }
+
+        ConversationAction::View {
+            number,
+            full,
+            html,
+            show_quoted,
+            reverse,
+            collapse,
+            expand,
+            copy,
+            all_messages,
+            last,
+            before,
+            ..
+        } => {
+            let conv = get_conversation(client, *number).await?;
+            let paging_back = last.is_some() || before.is_some();
+            // JSON has no "more messages" hint, so give scripts the whole thread
+            let whole_thread =
+                *all_messages || (matches!(format, OutputFormat::Json) && !paging_back);
+            let (messages, page_info) = if whole_thread {
+                (client.all_messages(&conv.id).await?, PageInfo::default())
+            } else {
+                let page = if paging_back {
+                    MessagePage::Last {
+                        count: last.map_or(DEFAULT_MESSAGE_LIMIT, |n| n as i32),
+                        before: before.clone(),
+                    }
+                } else {
+                    MessagePage::First {
+                        count: DEFAULT_MESSAGE_LIMIT,
+                        after: None,
+                    }
+                };
+                client.messages(&conv.id, &page).await?
+            };
+            let defaults = &config.defaults.view;
+            let display = MessageDisplay {
+                full: *full || defaults.full.unwrap_or(false),
+                html: *html,
+                show_quoted: *show_quoted || defaults.show_quoted.unwrap_or(false),
+                reverse: *reverse || defaults.reverse.unwrap_or(false),
+                collapse: *collapse,
+                expand: expand.clone(),
+            };
+            cli::format_conversation_detail(&conv, &messages, format, &display);
+            // Scripts reading JSON shouldn't clear the badges
+            if !matches!(format, OutputFormat::Json) {
+                if let Some(mut seen) = open_seen(config) {
+                    if let Err(err) = seen.mark(&conv) {
+                        eprintln!(
+                            "{} could not record #{} as seen: {}",
+                            cli::Msg::Warning.text(),
+                            number,
+                            err
+                        );
+                    }
+                }
+            }
+
+            if !quiet {
+                if page_info.has_next_page && !paging_back {
+                    eprintln!(
+                        "Showing the first {} messages. Use --all-messages or --last N to see the rest.",
+                        messages.len()
+                    );
+                } else if let Some(cursor) = page_info
+                    .start_cursor
+                    .as_deref()
+                    .filter(|_| page_info.has_previous_page)
+                {
+                    eprintln!(
+                        "Older messages: groove conversation view {} --before {}",
+                        number, cursor
+                    );
+                }
+            }
+
+            if *copy {
+                // The page shown may not reach the end of the thread
+                let newest = if page_info.has_next_page {
+                    let page = MessagePage::Last {
+                        count: 1,
+                        before: None,
+                    };
+                    client.messages(&conv.id, &page).await?.0
+                } else {
+                    messages
+                };
+                let body = newest.last().and_then(|m| m.text()).ok_or_else(|| {
+                    anyhow::anyhow!("Conversation #{} has no message body to copy", number)
+                })?;
+                cli::copy_to_clipboard(&body)?;
+                success_msg(quiet, "Copied last message to clipboard");
+            }
+        }
+
+        ConversationAction::Url { number, copy } => {
+            validate_conversation_number(*number)?;
+            let url = config.conversation_url(*number)?;
+            if *copy {
+                cli::copy_to_clipboard(&url)?;
+                success_msg(quiet, format!("Copied {} to clipboard", url));
+            } else {
+                println!("{}", url);
+            }
+        }
+
+        ConversationAction::Copy { number, message } => {
+            let message = match message {
+                Some(id) => {
+                    validate_conversation_number(*number)?;
+                    client.message(id).await?
+                }
+                None => {
+                    let conv = get_conversation(client, *number).await?;
+                    let page = MessagePage::Last {
+                        count: 1,
+                        before: None,
+                    };
+                    let mut newest = client.messages(&conv.id, &page).await?.0;
+                    newest.pop().ok_or_else(|| {
+                        anyhow::anyhow!("Conversation #{} has no messages", number)
+                    })?
+                }
+            };
+            let body = message
+                .text()
+                .ok_or_else(|| anyhow::anyhow!("Message {} has no body to copy", message.id))?;
+            cli::copy_to_clipboard(&body)?;
+            success_msg(quiet, format!("Copied message {} to clipboard", message.id));
         }
 
         ConversationAction::Reply {
             number,
             body,
             canned,
+            snippet,
+            template,
+            assign,
+            preview,
+            send_at,
         } => {
+            if *preview && !io::stdin().is_terminal() {
+                anyhow::bail!("--preview needs an interactive terminal to confirm the reply");
+            }
+            let send_at = send_at.as_deref().map(parse_when).transpose()?;
+            if let Some(at) = send_at {
+                if at <= Utc::now() {
+                    anyhow::bail!("--send-at {} is in the past", cli::format_timestamp(&at));
+                }
+            }
+            let template = match template {
+                Some(name) => Some(load_template(name, config)?),
+                None => None,
+            };
+
+            let conv = get_conversation(client, *number).await?;
+            let mut options = ReplyOptions::default();
             let body = if let Some(canned_name) = canned {
                 let canned_replies = client.canned_replies().await?;
-                let canned_reply = canned_replies
-                    .iter()
-                    .find(|r| r.name.eq_ignore_ascii_case(canned_name) || r.id == *canned_name)
-                    .ok_or_else(|| error::GrooveError::CannedReplyNotFound(canned_name.clone()))?;
+                let canned_reply = find_canned_reply(canned_name, &canned_replies)?;
 
                 let canned_body = canned_reply.body.clone().unwrap_or_default();
                 match body {
                     Some(extra) => format!("{}\n\n{}", canned_body, extra),
                     None => canned_body,
                 }
+            } else if let Some(snippet_name) = snippet {
+                let snippets = Snippets::new(snippets_dir()?).all()?;
+                let snippet = find_snippet(snippet_name, &snippets)?;
+                match body {
+                    Some(extra) => format!("{}\n\n{}", snippet.body.trim_end(), extra),
+                    None => snippet.body.clone(),
+                }
+            } else if let Some(template) = &template {
+                let agent = if template.uses_agent() {
+                    Some(client.me().await?)
+                } else {
+                    None
+                };
+                let (rendered, subject) =
+                    template.render(&Variables::new(&conv, agent.as_ref()))?;
+                options.subject = subject;
+                options.close = template.close;
+                if !template.tags.is_empty() {
+                    options.tag_ids = resolve_tag_ids(&template.tags, &client.tags().await?)?;
+                }
+                match body {
+                    Some(extra) => format!("{}\n\n{}", rendered.trim_end(), extra),
+                    None => rendered,
+                }
             } else {
                 get_body(body.clone())?
             };
 
+            // Resolve the agent first so a typo doesn't leave the reply sent
+            // but the conversation unassigned
+            if let Some(agent) = assign {
+                options.assign = Some(QueuedAssignee {
+                    name: agent.clone(),
+                    id: resolve_agent_id(client, agent).await?,
+                });
+            }
+
+            if *preview {
+                cli::format_reply_preview(&conv, &body, &options, send_at.as_ref());
+                let prompt = match send_at {
+                    Some(_) => "Queue this reply?",
+                    None => "Send this reply?",
+                };
+                if !confirm(prompt, false)? {
+                    println!("{}", cli::Msg::Aborted.text());
+                    return Ok(());
+                }
+            }
+
+            if let Some(send_at) = send_at {
+                let queued = Outbox::new(outbox_path()?).add(*number, body, options, send_at)?;
+                match format {
+                    OutputFormat::Json => cli::format_outbox(&[queued], format),
+                    _ => success_msg(
+                        quiet,
+                        format!(
+                            "Reply to #{} queued for {} (outbox ID {})",
+                            number,
+                            cli::format_timestamp(&send_at),
+                            queued.id
+                        ),
+                    ),
+                }
+                return Ok(());
+            }
+
+            let result = send_reply(client, &conv, &body, &options, config, quiet).await?;
+            let mut result = finish_reply(client, &conv, options, result, config, quiet).await?;
+
+            if let OutputFormat::Json = format {
+                // The mutation doesn't return the reply, so report the newest message
+                let newest = MessagePage::Last {
+                    count: 1,
+                    before: None,
+                };
+                let (messages, _) = client.messages(&conv.id, &newest).await?;
+                result.message_id = messages.into_iter().next().map(|m| m.id);
+                report_json(format, &result);
+            }
+        }
+
+        ConversationAction::Compose {
+            number,
+            canned,
+            snippet,
+            assign,
+            close,
+        } => {
+            if !io::stdin().is_terminal() {
+                anyhow::bail!("compose needs an interactive terminal; use 'groove conversation reply' in scripts");
+            }
+            let conv = get_conversation(client, *number).await?;
+            let canned_replies = client.canned_replies().await?;
+            let prefill = if let Some(canned_name) = canned {
+                find_canned_reply(canned_name, &canned_replies)?
+                    .body
+                    .clone()
+                    .unwrap_or_default()
+            } else if let Some(snippet_name) = snippet {
+                let snippets = Snippets::new(snippets_dir()?).all()?;
+                find_snippet(snippet_name, &snippets)?.body.clone()
+            } else {
+                String::new()
+            };
+            let mut options = ReplyOptions {
+                close: *close,
+                ..ReplyOptions::default()
+            };
+            if let Some(agent) = assign {
+                options.assign = Some(QueuedAssignee {
+                    name: agent.clone(),
+                    id: resolve_agent_id(client, agent).await?,
+                });
+            }
+
+            let messages = client.all_messages(&conv.id).await?;
+            let composer = compose::Composer::new(&conv, &messages, &prefill, canned_replies);
+            let body = match composer.run()? {
+                compose::Outcome::Send { body, close } => {
+                    options.close |= close;
+                    body
+                }
+                compose::Outcome::Cancel { draft } if draft.is_empty() => {
+                    println!("{}", cli::Msg::Aborted.text());
+                    return Ok(());
+                }
+                compose::Outcome::Cancel { draft } => {
+                    let path = compose::draft_path(*number);
+                    std::fs::write(&path, draft)?;
+                    println!("Aborted. Draft kept at {}", path.display());
+                    return Ok(());
+                }
+            };
+
+            let result = match send_reply(client, &conv, &body, &options, config, quiet).await {
+                Ok(result) => result,
+                Err(e) => {
+                    let path = compose::draft_path(*number);
+                    if std::fs::write(&path, &body).is_ok() {
+                        eprintln!("Draft kept at {}", path.display());
+                    }
+                    return Err(e);
+                }
+            };
+            let result = finish_reply(client, &conv, options, result, config, quiet).await?;
+            report_json(format, &result);
+        }
+
+        ConversationAction::Close { bulk } => {
+            let state = ConversationState::Closed;
+            change_states(client, bulk, state, format, config, quiet).await?;
+        }
+
+        ConversationAction::Open { bulk } => {
+            let state = ConversationState::Opened;
+            change_states(client, bulk, state, format, config, quiet).await?;
+        }
+
+        ConversationAction::Snooze { number, duration } => {
+            let until = parse_duration(duration)?;
+            let conv = get_conversation(client, *number).await?;
+            run_hook(config, HookStage::Pre, "snooze", &conv)?;
+            client.snooze(&conv.id, &until).await?;
+            record_undo("snooze", &conv, &[]);
+            success_msg(quiet, cli::tr(cli::Msg::Snoozed, &[number, &until]));
+            run_hook(config, HookStage::Post, "snooze", &conv)?;
+            let mut result = cli::ActionResult::new("snooze", &conv);
+            result.state = Some(ConversationState::Snoozed);
+            result.snoozed_until = Some(until);
+            report_json(format, &result);
+        }
+
+        ConversationAction::Assign { number, agent } => {
+            let conv = get_conversation(client, *number).await?;
+            let agent_id = resolve_agent_id(client, agent).await?;
+
+            run_hook(config, HookStage::Pre, "assign", &conv)?;
+            client.assign(&conv.id, &agent_id).await?;
+            record_undo("assign", &conv, &[]);
+            success_msg(quiet, cli::tr(cli::Msg::Assigned, &[number, agent]));
+            run_hook(config, HookStage::Post, "assign", &conv)?;
+            let mut result = cli::ActionResult::new("assign", &conv);
+            result.agent_id = Some(agent_id);
+            report_json(format, &result);
+        }
+
+        ConversationAction::SetContact { number, contact } => {
+            let conv = get_conversation(client, *number).await?;
+            let contact_id = resolve_contact_id(client, contact).await?;
+
+            run_hook(config, HookStage::Pre, "set-contact", &conv)?;
+            client.change_contact(&conv.id, &contact_id).await?;
+            record_undo("set-contact", &conv, &[]);
+            success_msg(quiet, cli::tr(cli::Msg::ContactChanged, &[number, contact]));
+            run_hook(config, HookStage::Post, "set-contact", &conv)?;
+            let mut result = cli::ActionResult::new("set-contact", &conv);
+            result.contact_id = Some(contact_id);
+            report_json(format, &result);
+        }
+
+        ConversationAction::Unassign { bulk } => {
+            for_each_conversation(client, bulk, format, quiet, |conv| async move {
+                run_hook(config, HookStage::Pre, "unassign", &conv)?;
+                client.unassign(&conv.id).await?;
+                record_undo("unassign", &conv, &[]);
+                success_msg(quiet, cli::tr(cli::Msg::Unassigned, &[&conv.number]));
+                run_hook(config, HookStage::Post, "unassign", &conv)?;
+                let result = cli::ActionResult::new("unassign", &conv);
+                Ok(result)
+            })
+            .await?;
+        }
+
+        ConversationAction::Follow { number, agent } => {
+            let conv = get_conversation(client, *number).await?;
+            let agent_id = resolve_agent_id(client, agent).await?;
+            client.follow(&conv.id, &agent_id).await?;
+            let msg = if agent == "me" {
+                cli::tr(cli::Msg::Following, &[number])
+            } else {
+                cli::tr(cli::Msg::AgentFollowing, &[number, agent])
+            };
+            success_msg(quiet, msg);
+            let mut result = cli::ActionResult::new("follow", &conv);
+            result.agent_id = Some(agent_id);
+            report_json(format, &result);
+        }
+
+        ConversationAction::Unfollow { number, agent } => {
+            let conv = get_conversation(client, *number).await?;
+            let agent_id = resolve_agent_id(client, agent).await?;
+            client.unfollow(&conv.id, &agent_id).await?;
+            let msg = if agent == "me" {
+                cli::tr(cli::Msg::Unfollowed, &[number])
+            } else {
+                cli::tr(cli::Msg::AgentUnfollowed, &[number, agent])
+            };
+            success_msg(quiet, msg);
+            let mut result = cli::ActionResult::new("unfollow", &conv);
+            result.agent_id = Some(agent_id);
+            report_json(format, &result);
+        }
+
+        ConversationAction::Followers { number } => {
+            validate_conversation_number(*number)?;
+            let followers = client.followers(*number).await?;
+            cli::format_followers(&followers, format);
+        }
+
+        ConversationAction::AddTag { targets } => {
+            let action = |tags| BulkAction::AddTag { tags };
+            tag_conversations(client, targets, action, format, config, quiet).await?;
+        }
+
+        ConversationAction::RemoveTag { targets } => {
+            let action = |tags| BulkAction::RemoveTag { tags };
+            tag_conversations(client, targets, action, format, config, quiet).await?;
+        }
+
+        ConversationAction::ClearTags { bulk } => {
+            for_each_conversation(client, bulk, format, quiet, |conv| async move {
+                let tag_ids: Vec<String> = conv.tags.iter().map(|t| t.id.clone()).collect();
+                let mut result = cli::ActionResult::new("clear-tags", &conv);
+                if tag_ids.is_empty() {
+                    success_msg(quiet, cli::tr(cli::Msg::NoTags, &[&conv.number]));
+                    return Ok(result);
+                }
+                run_hook(config, HookStage::Pre, "clear-tags", &conv)?;
+                client.untag(&conv.id, tag_ids.clone()).await?;
+                record_undo("clear-tags", &conv, &tag_ids);
+                success_msg(quiet, cli::tr(cli::Msg::TagsCleared, &[&conv.number]));
+                run_hook(config, HookStage::Post, "clear-tags", &conv)?;
+                result.tag_ids = tag_ids;
+                Ok(result)
+            })
+            .await?;
+        }
+
+        ConversationAction::Bulk {
+            filter,
+            yes,
+            options,
+            action,
+        } => {
+            let tag_ids = match action {
+                BulkAction::AddTag { tags } | BulkAction::RemoveTag { tags } => {
+                    resolve_tag_ids(tags, &client.tags().await?)?
+                }
+                _ => Vec::new(),
+            };
+
+            let matches = find_conversations(client, filter, config).await?;
+            if matches.is_empty() {
+                success_msg(quiet, cli::Msg::NoMatches.text());
+                return Ok(());
+            }
+
+            let prompt = format!(
+                "{} conversations match. Run '{}' on all of them?",
+                matches.len(),
+                action.name()
+            );
+            if !confirm(&prompt, *yes)? {
+                println!("{}", cli::Msg::Aborted.text());
+                return Ok(());
+            }
+
+            let numbers: Vec<i64> = matches.iter().map(|c| c.number).collect();
+            let (matches, tag_ids) = (&matches, &tag_ids);
+            run_bulk(&numbers, options, format, quiet, |number| async move {
+                let conv = matches
+                    .iter()
+                    .find(|c| c.number == number)
+                    .expect("numbers come from matches");
+                apply_bulk_action(action, tag_ids, conv, client, config, quiet).await
+            })
+            .await?;
+        }
+
+        ConversationAction::Note { number, body } => {
+            let body = get_body(body.clone())?;
+            let conv = get_conversation(client, *number).await?;
+            let mentions = resolve_mentions(client, &body).await?;
+            run_hook(config, HookStage::Pre, "note", &conv)?;
+            client.add_note(&conv.id, &body, &mentions).await?;
+            success_msg(quiet, cli::tr(cli::Msg::NoteAdded, &[number]));
+            run_hook(config, HookStage::Post, "note", &conv)?;
+            report_json(format, &cli::ActionResult::new("note", &conv));
+        }
+
+        ConversationAction::Notes { number } => {
             let conv = get_conversation(client, *number).await?;
-            client.reply(&conv.id, &body).await?;
-            success_msg(quiet, format!("Reply sent to conversation #{}", number));
+            let notes = client.all_notes(&conv.id).await?;
+            cli::format_notes(&notes, format);
         }
 
-        ConversationAction::Close { numbers } => {
-            validate_conversation_numbers(numbers)?;
-            for number in numbers {
-                let conv = get_conversation(client, *number).await?;
-                client.close(&conv.id).await?;
-                success_msg(quiet, format!("Closed conversation #{}", number));
+        ConversationAction::Redact {
+            number,
+            message,
+            pattern,
+            yes,
+        } => {
+            let pattern = pattern
+                .as_deref()
+                .map(|p| regex::RegexBuilder::new(p).case_insensitive(true).build())
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid --pattern: {}", e))?;
+            let conv = get_conversation(client, *number).await?;
+            let messages = client.all_messages(&conv.id).await?;
+            let msg = messages.iter().find(|m| m.id == *message).ok_or_else(|| {
+                anyhow::anyhow!("Message {} is not in conversation #{}", message, number)
+            })?;
+            match &pattern {
+                Some(pattern) => {
+                    // The HTML body is what the web app shows, when there is one
+                    let body = msg
+                        .body_html
+                        .as_deref()
+                        .filter(|h| !h.trim().is_empty())
+                        .or(msg.body_text.as_deref())
+                        .unwrap_or_default();
+                    let matches = pattern.find_iter(body).count();
+                    if matches == 0 {
+                        anyhow::bail!("Nothing in message {} matches the pattern", message);
+                    }
+                    let matches = format!(
+                        "{} {}",
+                        matches,
+                        if matches == 1 { "match" } else { "matches" }
+                    );
+                    let prompt = format!(
+                        "Redact {} in message {} of conversation #{}?",
+                        matches, message, number
+                    );
+                    if !confirm(&prompt, *yes)? {
+                        println!("{}", cli::Msg::Aborted.text());
+                        return Ok(());
+                    }
+                    let redacted = pattern.replace_all(body, "[redacted]");
+                    client.redact_message(message, &redacted).await?;
+                    success_msg(
+                        quiet,
+                        format!("Redacted {} in message {}", matches, message),
+                    );
+                }
+                None => {
+                    let prompt = format!(
+                        "Delete message {} from conversation #{}? This can't be undone.",
+                        message, number
+                    );
+                    if !confirm(&prompt, *yes)? {
+                        println!("{}", cli::Msg::Aborted.text());
+                        return Ok(());
+                    }
+                    client.delete_message(message).await?;
+                    success_msg(quiet, format!("Deleted message {}", message));
+                }
             }
+            let mut result = cli::ActionResult::new("redact", &conv);
+            result.message_id = Some(message.clone());
+            report_json(format, &result);
         }
 
-        ConversationAction::Open { numbers } => {
-            validate_conversation_numbers(numbers)?;
-            for number in numbers {
-                let conv = get_conversation(client, *number).await?;
-                client.open(&conv.id).await?;
-                success_msg(quiet, format!("Opened conversation #{}", number));
+        ConversationAction::Export { number, to, out } => {
+            let export_format = to
+                .or_else(|| out.as_deref().and_then(ExportFormat::from_path))
+                .unwrap_or(ExportFormat::Markdown);
+            if export_format == ExportFormat::Pdf && out.is_none() {
+                anyhow::bail!("PDF export needs an output file: use --out FILE.pdf");
+            }
+            let conv = get_conversation(client, *number).await?;
+            let messages = client.all_messages(&conv.id).await?;
+
+            match out {
+                Some(path) => {
+                    export::write_export(
+                        &conv,
+                        &messages,
+                        export_format,
+                        path,
+                        config.pdf_command.as_deref(),
+                    )
+                    .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))?;
+                    success_msg(
+                        quiet,
+                        format!(
+                            "Exported conversation #{} ({} messages) to {}",
+                            number,
+                            messages.len(),
+                            path.display()
+                        ),
+                    );
+                }
+                None => print!("{}", export::render(&conv, &messages, export_format)),
             }
         }
+    }
+
+    Ok(())
+}
+
+async fn handle_macro(
+    name: &str,
+    numbers: &[i64],
+    client: &GrooveClient,
+    format: &OutputFormat,
+    config: &Config,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    let steps = config
+        .macros
+        .get(name)
+        .ok_or_else(|| anyhow::anyhow!("Macro '{}' is not defined in config", name))?;
+    if steps.is_empty() {
+        anyhow::bail!("Macro '{}' has no steps", name);
+    }
+    validate_conversation_numbers(numbers)?;
+
+    // Check every step parses before touching any conversation
+    for step in steps {
+        parse_macro_step(step, numbers[0])?;
+    }
+
+    let mut failures = 0;
+    let mut summary = Vec::new();
+    for number in numbers {
+        let mut completed = Vec::new();
+        let mut failure = None;
+        for step in steps {
+            let action = parse_macro_step(step, *number)?;
+            match handle_conversation(&action, client, format, config, quiet).await {
+                Ok(()) => completed.push(step.as_str()),
+                Err(err) => {
+                    failure = Some(format!("'{}' failed: {}", step, err));
+                    break;
+                }
+            }
+        }
+
+        match failure {
+            None => summary.push(format!("  #{}  ok      {}", number, completed.join(", "))),
+            Some(reason) => {
+                failures += 1;
+                summary.push(format!("  #{}  failed  {}", number, reason));
+            }
+        }
+    }
+
+    if !quiet || failures > 0 {
+        println!("\nMacro '{}' summary:", name);
+        for line in summary {
+            println!("{}", line);
+        }
+    }
+
+    if failures > 0 {
+        return Err(error::GrooveError::PartialFailure(format!(
+            "Macro '{}' failed for {} of {} conversations",
+            name,
+            failures,
+            numbers.len()
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+fn parse_macro_step(step: &str, number: i64) -> anyhow::Result<ConversationAction> {
+    let args = cli::macro_step_args(step, number)?;
+    let parsed = Cli::try_parse_from(args).map_err(|e| {
+        let msg = e.to_string();
+        let first_line = msg.lines().next().unwrap_or_default();
+        anyhow::anyhow!(
+            "Invalid macro step '{}': {}",
+            step,
+            first_line.trim_start_matches("error: ")
+        )
+    })?;
+    match parsed.command {
+        Commands::Conversation { action } => Ok(action),
+        _ => unreachable!("macro steps are always conversation subcommands"),
+    }
+}
+
+async fn handle_folder(
+    action: &FolderAction,
+    client: &GrooveClient,
+    config: &Config,
+    format: &OutputFormat,
+) -> anyhow::Result<()> {
+    match action {
+        FolderAction::List => {
+            let folders = client.folders().await?;
+            cli::format_folders(&folders, format);
+            if folders.len() >= MAX_ITEMS_PER_PAGE {
+                eprintln!(
+                    "{} Results may be truncated (showing {} items)",
+                    cli::Msg::Warning.text(),
+                    MAX_ITEMS_PER_PAGE
+                );
+            }
+        }
+
+        FolderAction::View {
+            name,
+            status,
+            limit,
+            after,
+            table,
+        } => {
+            let folder = resolve_folder(client, name).await?;
+            let limit = limit
+                .or(config.defaults.limit)
+                .unwrap_or(DEFAULT_CONVERSATION_LIMIT);
+            let in_folder = |state| ConversationFilter {
+                state,
+                folder_id: Some(folder.id.clone()),
+                ..ConversationFilter::default()
+            };
+            let count = |state| async move {
+                let page = client
+                    .conversations(&in_folder(state), Some(1), None)
+                    .await?;
+                Ok::<_, error::GrooveError>(page.total_count)
+            };
+            let filter = in_folder(*status);
+            let (response, total, unread, opened) = futures::try_join!(
+                client.conversations(&filter, Some(limit), after.clone()),
+                count(None),
+                count(Some(ConversationState::Unread)),
+                count(Some(ConversationState::Opened)),
+            )?;
+            cli::format_folder_view(
+                &folder,
+                &cli::FolderCounts {
+                    total,
+                    unread,
+                    opened,
+                },
+                &response,
+                format,
+                &list_display(config, table)?,
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn handle_tag(
+    action: &TagAction,
+    client: &GrooveClient,
+    format: &OutputFormat,
+) -> anyhow::Result<()> {
+    match action {
+        TagAction::List => {
+            let tags = client.tags().await?;
+            cli::format_tags(&tags, format);
+            if tags.len() >= MAX_ITEMS_PER_PAGE {
+                eprintln!(
+                    "{} Results may be truncated (showing {} items)",
+                    cli::Msg::Warning.text(),
+                    MAX_ITEMS_PER_PAGE
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn handle_note(
+    action: &NoteAction,
+    client: &GrooveClient,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    match action {
+        NoteAction::Edit { id, body } => {
+            let body = get_body(body.clone())?;
+            client.update_note(id, &body).await?;
+            success_msg(quiet, format!("Updated note {}", id));
+        }
+        NoteAction::Delete { id, yes } => {
+            if !confirm(&format!("Delete note {}?", id), *yes)? {
+                println!("{}", cli::Msg::Aborted.text());
+                return Ok(());
+            }
+            client.delete_note(id).await?;
+            success_msg(quiet, format!("Deleted note {}", id));
+        }
+    }
+    Ok(())
+}
+
+async fn handle_contact(
+    action: &ContactAction,
+    client: &GrooveClient,
+    config: &Config,
+    format: &OutputFormat,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    match action {
+        ContactAction::View { email, limit } => {
+            let profile = client
+                .contact_profile(email, *limit)
+                .await?
+                .ok_or_else(|| error::GrooveError::ContactNotFound(email.to_string()))?;
+            cli::format_contact_profile(&profile, format);
+        }
+        ContactAction::Merge {
+            primary,
+            duplicate,
+            yes,
+        } => {
+            let primary_id = resolve_contact_id(client, primary).await?;
+            let duplicate_id = resolve_contact_id(client, duplicate).await?;
+            if primary_id == duplicate_id {
+                anyhow::bail!("{} and {} are the same contact", primary, duplicate);
+            }
+            let prompt = format!(
+                "Merge {} into {}? Its conversations move to {} and it is removed.",
+                duplicate, primary, primary
+            );
+            if !confirm(&prompt, *yes)? {
+                println!("{}", cli::Msg::Aborted.text());
+                return Ok(());
+            }
+            client.merge_contacts(&primary_id, &duplicate_id).await?;
+            success_msg(quiet, format!("Merged {} into {}", duplicate, primary));
+        }
+        ContactAction::Export { email, dir, to } => {
+            let contact = contact_record(client, email).await?;
+            let options = BackupOptions {
+                dir: dir.clone(),
+                format: *to,
+                status: None,
+                folder_id: None,
+                dates: None,
+                contact: Some(contact),
+                limit: None,
+                pdf_command: config.pdf_command.clone(),
+            };
+            let summary = export::backup(client, &options).await?;
+            success_msg(
+                quiet,
+                format!(
+                    "Exported {} and {} conversations ({} messages) to {}",
+                    email,
+                    summary.conversations,
+                    summary.messages,
+                    dir.display()
+                ),
+            );
+        }
+        ContactAction::Delete { contact, yes } => {
+            let record = contact_record(client, contact).await?;
+            let prompt = format!(
+                "Permanently delete contact {} ({})?",
+                record.contact.display_name(),
+                record.contact.id
+            );
+            if !confirm(&prompt, *yes)? {
+                println!("{}", cli::Msg::Aborted.text());
+                return Ok(());
+            }
+            client.delete_contact(&record.contact.id).await?;
+            success_msg(quiet, format!("Deleted contact {}", contact));
+        }
+    }
+    Ok(())
+}
+
+async fn handle_report(
+    action: &ReportAction,
+    client: &GrooveClient,
+    format: &OutputFormat,
+) -> anyhow::Result<()> {
+    match action {
+        ReportAction::Channels { since, until } => {
+            let filter = ConversationFilter {
+                dates: date_range(Some(since), until.as_deref(), false)?,
+                ..ConversationFilter::default()
+            };
+            let conversations: Vec<Conversation> =
+                client.conversations_stream(&filter).try_collect().await?;
+            let measured: Vec<(Conversation, ResponseTimes)> = stream::iter(conversations)
+                .map(|conv| async move {
+                    let messages = client.all_messages(&conv.id).await?;
+                    Ok::<_, error::GrooveError>((conv, ResponseTimes::of(&messages)))
+                })
+                .buffer_unordered(cli::DEFAULT_CONCURRENCY)
+                .try_collect()
+                .await?;
+            cli::format_channel_stats(&report::channel_stats(&measured), format);
+        }
+    }
+    Ok(())
+}
+
+async fn handle_canned_replies(
+    action: &CannedRepliesAction,
+    client: &GrooveClient,
+    format: &OutputFormat,
+) -> anyhow::Result<()> {
+    match action {
+        CannedRepliesAction::List => {
+            let replies = client.canned_replies().await?;
+            cli::format_canned_replies(&replies, format);
+            if replies.len() >= MAX_ITEMS_PER_PAGE {
+                eprintln!(
+                    "{} Results may be truncated (showing {} items)",
+                    cli::Msg::Warning.text(),
+                    MAX_ITEMS_PER_PAGE
+                );
+            }
+        }
+        CannedRepliesAction::Show { name } => {
+            let replies = client.canned_replies().await?;
+            let reply = find_canned_reply(name, &replies)?;
+            cli::format_canned_reply(reply);
+        }
+    }
+    Ok(())
+}
+
+async fn handle_rule(
+    action: &RuleAction,
+    client: &GrooveClient,
+    format: &OutputFormat,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    let rules = client.rules().await?;
+    let (name, enable) = match action {
+        RuleAction::List => {
+            cli::format_rules(&rules, format);
+            if rules.len() >= MAX_ITEMS_PER_PAGE {
+                eprintln!(
+                    "{} Results may be truncated (showing {} items)",
+                    cli::Msg::Warning.text(),
+                    MAX_ITEMS_PER_PAGE
+                );
+            }
+            return Ok(());
+        }
+        RuleAction::Show { name } => {
+            cli::format_rule(find_rule(name, &rules)?, format);
+            return Ok(());
+        }
+        RuleAction::Enable { name } => (name, true),
+        RuleAction::Disable { name } => (name, false),
+    };
+
+    let rule = find_rule(name, &rules)?;
+    let state = if enable { "enabled" } else { "disabled" };
+    if rule.enabled == enable {
+        success_msg(quiet, format!("Rule '{}' is already {}", rule.name, state));
+        return Ok(());
+    }
+    client.set_rule_enabled(&rule.id, enable).await?;
+    success_msg(quiet, format!("Rule '{}' {}", rule.name, state));
+    Ok(())
+}
+
+fn validate_conversation_number(number: i64) -> anyhow::Result<()> {
+    if number <= 0 {
+        anyhow::bail!("Conversation number must be positive, got: {}", number);
+    }
+    Ok(())
+}
+
+fn validate_conversation_numbers(numbers: &[i64]) -> anyhow::Result<()> {
+    for number in numbers {
+        validate_conversation_number(*number)?;
+    }
+    Ok(())
+}
+
+/// Fetch each conversation in `bulk` and run `op` on it; see `run_bulk`.
+/// Several conversations are looked up in batched requests first.
+async fn for_each_conversation<F, Fut>(
+    client: &GrooveClient,
+    bulk: &BulkArgs,
+    format: &OutputFormat,
+    quiet: bool,
+    op: F,
+) -> anyhow::Result<()>
+where
+    F: Fn(Conversation) -> Fut,
+    Fut: Future<Output = anyhow::Result<cli::ActionResult>>,
+{
+    let numbers = bulk.numbers();
+    let found = if numbers.len() > 1 {
+        Some(lookup_conversations(client, &numbers).await?)
+    } else {
+        None
+    };
+
+    let (op, found) = (&op, &found);
+    run_bulk(
+        &numbers,
+        &bulk.options,
+        format,
+        quiet,
+        |number| async move {
+            let conv = match found {
+                Some(found) => found
+                    .iter()
+                    .find(|c| c.number == number)
+                    .cloned()
+                    .ok_or(error::GrooveError::ConversationNotFound(number))?,
+                None => get_conversation(client, number).await?,
+            };
+            op(conv).await
+        },
+    )
+    .await
+}
+
+/// The conversations and tag names given to `add-tag` or `remove-tag`,
+/// reading numbers from stdin for `-`
+fn tag_targets(targets: &cli::TagArgs) -> anyhow::Result<(BulkArgs, Vec<String>)> {
+    let (numbers, tags) = cli::split_numbers(&targets.args);
+    if numbers.is_empty() {
+        anyhow::bail!("Invalid conversation number: '{}'", targets.args[0]);
+    }
+    if tags.is_empty() {
+        anyhow::bail!("No tags given after the conversation numbers");
+    }
+    let mut lists = Vec::with_capacity(numbers.len());
+    for arg in numbers {
+        let list = match arg.as_str() {
+            "-" => cli::NumberList(
+                cli::read_numbers(io::stdin().lock()).map_err(|e| anyhow::anyhow!(e))?,
+            ),
+            arg => arg.parse().map_err(|e: String| anyhow::anyhow!(e))?,
+        };
+        lists.push(list);
+    }
+    if lists.iter().all(|list| list.0.is_empty()) {
+        anyhow::bail!("No conversation numbers on stdin");
+    }
+    let bulk = BulkArgs {
+        numbers: lists,
+        options: targets.options.clone(),
+    };
+    Ok((bulk, tags.to_vec()))
+}
+
+/// Run the `add-tag` or `remove-tag` action built by `action` on each
+/// conversation in `targets`
+async fn tag_conversations(
+    client: &GrooveClient,
+    targets: &cli::TagArgs,
+    action: impl FnOnce(Vec<String>) -> BulkAction,
+    format: &OutputFormat,
+    config: &Config,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    let (bulk, tags) = tag_targets(targets)?;
+    let tag_ids = resolve_tag_ids(&tags, &client.tags().await?)?;
+    let (action, tag_ids) = (&action(tags), &tag_ids);
+    for_each_conversation(client, &bulk, format, quiet, |conv| async move {
+        apply_bulk_action(action, tag_ids, &conv, client, config, quiet).await
+    })
+    .await
+}
+
+/// The conversations among `numbers` that exist, looked up in batches
+async fn lookup_conversations(
+    client: &GrooveClient,
+    numbers: &[i64],
+) -> anyhow::Result<Vec<Conversation>> {
+    validate_conversation_numbers(numbers)?;
+    let found = client.conversations_by_number(numbers).await?;
+    Ok(found.into_iter().flatten().collect())
+}
+
+/// Close or open the conversations in `bulk` with batched requests: one
+/// lookup per `BATCH_LOOKUP_SIZE` numbers and one mutation per
+/// `BATCH_MUTATION_SIZE` conversations, instead of two requests each.
+///
+/// Pre hooks run for a batch before its mutation is sent, post hooks after.
+/// Failures are reported as in `run_bulk`; `--fail-fast` stops before the
+/// next batch. A batch whose request fails counts as a failure for each of
+/// its conversations, keeping the results of the batches before it.
+async fn change_states(
+    client: &GrooveClient,
+    bulk: &BulkArgs,
+    state: ConversationState,
+    format: &OutputFormat,
+    config: &Config,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    let (action, done) = match state {
+        ConversationState::Closed => ("close", cli::Msg::Closed),
+        _ => ("open", cli::Msg::Opened),
+    };
+    let numbers = bulk.numbers();
+    let found = lookup_conversations(client, &numbers).await?;
+    // A single conversation fails with its own error, as in `run_bulk`
+    let stop_early = bulk.options.fail_fast || numbers.len() == 1;
+
+    let mut results = Vec::with_capacity(numbers.len());
+    let mut ready = Vec::new();
+    for number in &numbers {
+        let checked = match found.iter().find(|c| c.number == *number) {
+            Some(conv) => run_hook(config, HookStage::Pre, action, conv).map(|()| conv),
+            None => Err(error::GrooveError::ConversationNotFound(*number).into()),
+        };
+        match checked {
+            Ok(conv) => ready.push(conv),
+            Err(err) if stop_early => return Err(err),
+            Err(err) => results.push(cli::BulkResult::failed(*number, &err)),
+        }
+    }
+
+    // Up to --concurrency batches in flight
+    let mut batches = stream::iter(ready.chunks(BATCH_MUTATION_SIZE))
+        .map(|batch| async move {
+            let ids: Vec<&str> = batch.iter().map(|c| c.id.as_str()).collect();
+            let outcomes = match state {
+                ConversationState::Closed => client.close_many(&ids).await,
+                _ => client.open_many(&ids).await,
+            };
+            (batch, outcomes)
+        })
+        .buffer_unordered(bulk.options.concurrency);
+
+    while let Some((batch, outcomes)) = batches.next().await {
+        let outcomes = match outcomes {
+            Ok(outcomes) => outcomes,
+            Err(err) if numbers.len() == 1 => return Err(err.into()),
+            Err(err) => {
+                results.extend(
+                    batch
+                        .iter()
+                        .map(|conv| cli::BulkResult::failed(conv.number, &err)),
+                );
+                if bulk.options.fail_fast {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        let mut failed = None;
+        for (conv, outcome) in batch.iter().zip(outcomes) {
+            let outcome = outcome.map_err(anyhow::Error::from).and_then(|()| {
+                record_undo(action, conv, &[]);
+                success_msg(quiet, cli::tr(done, &[&conv.number]));
+                run_hook(config, HookStage::Post, action, conv)?;
+                let mut result = cli::ActionResult::new(action, conv);
+                result.state = Some(state);
+                Ok(result)
+            });
+            match outcome {
+                Ok(result) => results.push(cli::BulkResult::succeeded(conv.number, result)),
+                Err(err) if stop_early => {
+                    failed.get_or_insert(err);
+                }
+                Err(err) => results.push(cli::BulkResult::failed(conv.number, &err)),
+            }
+        }
+        if let Some(err) = failed {
+            return Err(err);
+        }
+    }
+
+    report_bulk(&numbers, results, format, quiet)
+}
+
+/// Run `job` for each conversation number, with up to `options.concurrency`
+/// jobs in flight.
+///
+/// Failures don't stop the run unless `--fail-fast` is set; a summary is
+/// printed for multiple conversations and an error returned if any failed.
+/// With `-o json` the result of a single conversation is printed instead.
+async fn run_bulk<F, Fut>(
+    numbers: &[i64],
+    options: &BulkOptions,
+    format: &OutputFormat,
+    quiet: bool,
+    job: F,
+) -> anyhow::Result<()>
+where
+    F: Fn(i64) -> Fut,
+    Fut: Future<Output = anyhow::Result<cli::ActionResult>>,
+{
+    let job = &job;
+    let mut outcomes = stream::iter(numbers)
+        .map(|number| async move { (*number, job(*number).await) })
+        .buffer_unordered(options.concurrency);
+
+    let mut results = Vec::with_capacity(numbers.len());
+    while let Some((number, result)) = outcomes.next().await {
+        match result {
+            Err(err) if options.fail_fast || numbers.len() == 1 => return Err(err),
+            Err(err) => results.push(cli::BulkResult::failed(number, &err)),
+            Ok(result) => results.push(cli::BulkResult::succeeded(number, result)),
+        }
+    }
+
+    report_bulk(numbers, results, format, quiet)
+}
+
+/// Print the outcome of a bulk change and fail if any conversation failed
+fn report_bulk(
+    numbers: &[i64],
+    mut results: Vec<cli::BulkResult>,
+    format: &OutputFormat,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    // Report in the order the numbers were given
+    results.sort_by_key(|r| numbers.iter().position(|n| *n == r.number));
+    let failed: Vec<_> = results.iter().filter(|r| r.error.is_some()).collect();
+
+    let json = matches!(format, OutputFormat::Json);
+    if numbers.len() == 1 {
+        if let Some(result) = &results[0].result {
+            report_json(format, result);
+        }
+    } else if !quiet || json || !failed.is_empty() {
+        cli::format_bulk_summary(&results, format);
+    }
+
+    if failed.is_empty() {
+        return Ok(());
+    }
+    Err(error::GrooveError::PartialFailure(format!(
+        "{} of {} conversations failed: {}",
+        failed.len(),
+        results.len(),
+        failed
+            .iter()
+            .map(|r| format!("#{}", r.number))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+    .into())
+}
+
+/// Page through conversations matching `filter`, applying the filters the
+/// API can't (`--older-than`, `--tag`) client-side
+async fn find_conversations(
+    client: &GrooveClient,
+    filter: &BulkFilter,
+    config: &Config,
+) -> anyhow::Result<Vec<Conversation>> {
+    let cutoff = match &filter.older_than {
+        Some(age) => Some(Utc::now() - parse_duration_span(age)?),
+        None => None,
+    };
+    let folder = match filter.folder.as_ref().or(config.defaults.folder.as_ref()) {
+        Some(name) => Some(resolve_folder_id(client, name).await?),
+        None => None,
+    };
+    let assignee = match &filter.assignee {
+        Some(agent) => Some(resolve_agent_id(client, agent).await?),
+        None => None,
+    };
+    let limit = filter.limit.map(|l| l as usize).unwrap_or(usize::MAX);
+    let conversation_filter = ConversationFilter {
+        state: filter.status,
+        folder_id: folder,
+        assignee_id: assignee,
+        keywords: filter.search.clone(),
+        ..ConversationFilter::default()
+    };
+
+    let matches = client
+        .conversations_stream(&conversation_filter)
+        .try_filter(|conv| {
+            let old_enough = cutoff.is_none_or(|cutoff| conv.updated_at < cutoff);
+            let tagged = filter
+                .tag
+                .iter()
+                .all(|name| conv.tags.iter().any(|t| t.name.eq_ignore_ascii_case(name)));
+            future::ready(old_enough && tagged)
+        })
+        .take(limit)
+        .try_collect()
+        .await?;
+    Ok(matches)
+}
+
+async fn apply_bulk_action(
+    action: &BulkAction,
+    tag_ids: &[String],
+    conv: &Conversation,
+    client: &GrooveClient,
+    config: &Config,
+    quiet: bool,
+) -> anyhow::Result<cli::ActionResult> {
+    run_hook(config, HookStage::Pre, action.name(), conv)?;
+    let done = match action {
+        BulkAction::Close => {
+            client.close(&conv.id).await?;
+            cli::Msg::Closed
+        }
+        BulkAction::Open => {
+            client.open(&conv.id).await?;
+            cli::Msg::Opened
+        }
+        BulkAction::Unassign => {
+            client.unassign(&conv.id).await?;
+            cli::Msg::Unassigned
+        }
+        BulkAction::AddTag { .. } => {
+            client.tag(&conv.id, tag_ids.to_vec()).await?;
+            cli::Msg::TagsAdded
+        }
+        BulkAction::RemoveTag { .. } => {
+            client.untag(&conv.id, tag_ids.to_vec()).await?;
+            cli::Msg::TagsRemoved
+        }
+    };
+    record_undo(action.name(), conv, tag_ids);
+    success_msg(quiet, cli::tr(done, &[&conv.number]));
+    run_hook(config, HookStage::Post, action.name(), conv)?;
+
+    let mut result = cli::ActionResult::new(action.name(), conv);
+    match action {
+        BulkAction::Close => result.state = Some(ConversationState::Closed),
+        BulkAction::Open => result.state = Some(ConversationState::Opened),
+        BulkAction::AddTag { .. } | BulkAction::RemoveTag { .. } => {
+            result.tag_ids = tag_ids.to_vec()
+        }
+        BulkAction::Unassign => {}
+    }
+    Ok(result)
+}
+
+/// Ask a yes/no question on the terminal; `assume_yes` skips the prompt
+fn confirm(prompt: &str, assume_yes: bool) -> anyhow::Result<bool> {
+    if assume_yes {
+        return Ok(true);
+    }
+    if !io::stdin().is_terminal() {
+        anyhow::bail!("Refusing to continue without confirmation; pass --yes to proceed");
+    }
+
+    print!("{} [y/N] ", prompt);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+async fn get_conversation(
+    client: &GrooveClient,
+    number: i64,
+) -> anyhow::Result<groovehq_cli::types::Conversation> {
+    validate_conversation_number(number)?;
+    Ok(client.conversation(number).await?)
+}
+
+fn resolve_tag_ids(
+    tag_names: &[String],
+    all_tags: &[groovehq_cli::types::Tag],
+) -> anyhow::Result<Vec<String>> {
+    tag_names
+        .iter()
+        .map(|name| {
+            resolve_name("tag", name, all_tags, |t| vec![t.name.as_str()])
+                .map(|t| t.id.clone())
+                .map_err(|suggestions| {
+                    anyhow::anyhow!(error::GrooveError::TagNotFound(name.clone(), suggestions))
+                })
+        })
+        .collect()
+}
+
+/// ID of the agent given by email or name, or of the current agent for "me"
+async fn resolve_agent_id(client: &GrooveClient, agent: &str) -> anyhow::Result<String> {
+    if agent == "me" {
+        return Ok(client.me().await?.id);
+    }
+    let agents = client.agents().await?;
+    resolve_name("agent", agent, &agents, |a| {
+        std::iter::once(a.email.as_str())
+            .chain(a.name.as_deref())
+            .collect()
+    })
+    .map(|a| a.id.clone())
+    .map_err(|suggestions| error::GrooveError::AgentNotFound(agent.to_string(), suggestions).into())
+}
+
+/// Agent IDs for the `@name`/`@email` mentions in a note body. Mentions
+/// match an agent's email, name, or the part of the email before the `@`.
+async fn resolve_mentions(client: &GrooveClient, body: &str) -> anyhow::Result<Vec<String>> {
+    let tokens = cli::mention_tokens(body);
+    if tokens.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let agents = client.agents().await?;
+    let mut ids: Vec<String> = Vec::new();
+    for token in tokens {
+        let agent = resolve_name("agent", token, &agents, |a| {
+            let local_part = a.email.split('@').next().unwrap_or_default();
+            std::iter::once(a.email.as_str())
+                .chain(a.name.as_deref())
+                .chain(std::iter::once(local_part))
+                .collect()
+        })
+        .map_err(|suggestions| error::GrooveError::AgentNotFound(token.to_string(), suggestions))?;
+        if !ids.contains(&agent.id) {
+            ids.push(agent.id.clone());
+        }
+    }
+    Ok(ids)
+}
+
+/// Resolve a `--folder` value to a folder ID. Names are matched like tags;
+/// an unknown value is passed through as an ID only when the folder list may
+/// be truncated, otherwise the available folders are listed.
+async fn resolve_folder_id(client: &GrooveClient, folder: &str) -> anyhow::Result<String> {
+    Ok(resolve_folder(client, folder).await?.id)
+}
+
+/// The folder with this name or ID. When the folder list may be truncated,
+/// an unknown name is passed through as an ID, without a count.
+async fn resolve_folder(client: &GrooveClient, folder: &str) -> anyhow::Result<Folder> {
+    let folders = client.folders().await?;
+    if let Some(found) = folders.iter().find(|f| f.id == folder) {
+        return Ok(found.clone());
+    }
+    match resolve_name("folder", folder, &folders, |f| vec![f.name.as_str()]) {
+        Ok(found) => Ok(found.clone()),
+        Err(_) if folders.len() >= MAX_ITEMS_PER_PAGE => Ok(Folder {
+            id: folder.to_string(),
+            name: folder.to_string(),
+        }),
+        Err(_) => Err(error::GrooveError::FolderNotFound(
+            folder.to_string(),
+            folders.iter().map(|f| f.name.clone()).collect(),
+        )
+        .into()),
+    }
+}
+
+/// Resolve a `--contact` value to a contact ID; anything that isn't an email
+/// address is taken to be an ID already
+async fn resolve_contact_id(client: &GrooveClient, contact: &str) -> anyhow::Result<String> {
+    if !contact.contains('@') {
+        return Ok(contact.to_string());
+    }
+    client
+        .contact_by_email(contact)
+        .await?
+        .map(|c| c.id)
+        .ok_or_else(|| error::GrooveError::ContactNotFound(contact.to_string()).into())
+}
+
+/// A contact's full profile, by email or contact ID
+async fn contact_record(
+    client: &GrooveClient,
+    contact: &str,
+) -> anyhow::Result<groovehq_cli::types::ContactRecord> {
+    let contact_id = resolve_contact_id(client, contact).await?;
+    client
+        .contact(&contact_id)
+        .await?
+        .ok_or_else(|| error::GrooveError::ContactNotFound(contact.to_string()).into())
+}
+
+/// Find a canned reply by ID, or by name allowing for typos
+fn find_canned_reply<'a>(
+    name: &str,
+    replies: &'a [groovehq_cli::types::CannedReply],
+) -> anyhow::Result<&'a groovehq_cli::types::CannedReply> {
+    if let Some(reply) = replies.iter().find(|r| r.id == name) {
+        return Ok(reply);
+    }
+    resolve_name("canned reply", name, replies, |r| vec![r.name.as_str()]).map_err(|suggestions| {
+        anyhow::anyhow!(error::GrooveError::CannedReplyNotFound(
+            name.to_string(),
+            suggestions
+        ))
+    })
+}
+
+fn find_rule<'a>(
+    name: &str,
+    rules: &'a [groovehq_cli::types::Rule],
+) -> anyhow::Result<&'a groovehq_cli::types::Rule> {
+    if let Some(rule) = rules.iter().find(|r| r.id == name) {
+        return Ok(rule);
+    }
+    resolve_name("rule", name, rules, |r| vec![r.name.as_str()]).map_err(|suggestions| {
+        anyhow::anyhow!(error::GrooveError::RuleNotFound(
+            name.to_string(),
+            suggestions
+        ))
+    })
+}
+
+fn find_snippet<'a>(name: &str, snippets: &'a [Snippet]) -> anyhow::Result<&'a Snippet> {
+    resolve_name("snippet", name, snippets, |s| vec![s.name.as_str()]).map_err(|suggestions| {
+        anyhow::anyhow!(error::GrooveError::SnippetNotFound(
+            name.to_string(),
+            suggestions
+        ))
+    })
+}
+
+/// Look up an agent, tag, canned reply, rule, or snippet by name. An unambiguous near-match
+/// is used with a note on stderr; otherwise the closest names are returned.
+fn resolve_name<'a, T>(
+    kind: &str,
+    query: &str,
+    items: &'a [T],
+    keys: impl Fn(&'a T) -> Vec<&'a str>,
+) -> Result<&'a T, Vec<String>> {
+    match cli::lookup(query, items, keys) {
+        cli::Lookup::Exact(item) => Ok(item),
+        cli::Lookup::Corrected(item, name) => {
+            eprintln!("Note: using {} '{}' for '{}'", kind, name, query);
+            Ok(item)
+        }
+        cli::Lookup::NotFound(suggestions) => Err(suggestions),
+    }
+}
+
+/// Build the process for an external plugin, exporting the resolved token,
+/// endpoint, and profile so the plugin can talk to the API directly
+fn plugin_command(plugin: &cli::Plugin, config: Option<&Config>) -> std::process::Command {
+    let mut cmd = std::process::Command::new(&plugin.path);
+    cmd.args(&plugin.args);
+
+    let profile = cli::global_option_value(&plugin.global_args, "profile")
+        .or_else(|| std::env::var("GROOVE_PROFILE").ok());
+    let config = config.and_then(|c| c.with_profile(profile.as_deref()).ok()?.with_env().ok());
+    let token = cli::global_option_value(&plugin.global_args, "token");
+    let endpoint = cli::global_option_value(&plugin.global_args, "endpoint")
+        .or_else(|| std::env::var("GROOVEHQ_API_ENDPOINT").ok())
+        .filter(|endpoint| !endpoint.is_empty());
+
+    if let Some(config) = &config {
+        if let Ok(token) = config::resolve_token(token.as_deref(), config) {
+            cmd.env("GROOVEHQ_API_TOKEN", token);
+        }
+        let endpoint = endpoint.as_deref().or(config.api_endpoint.as_deref());
+        cmd.env(
+            "GROOVEHQ_API_ENDPOINT",
+            endpoint.unwrap_or(DEFAULT_ENDPOINT),
+        );
+    }
+    if let Some(profile) = profile {
+        cmd.env("GROOVE_PROFILE", profile);
+    }
+    if let Some(path) = cli::global_option_value(&plugin.global_args, "config") {
+        cmd.env("GROOVE_CONFIG", path);
+    }
+    cmd
+}
+
+/// Run a configured hook. Failing pre-hooks abort the action; failing
+/// post-hooks only warn since the action has already happened.
+fn run_hook(
+    config: &Config,
+    stage: HookStage,
+    action: &str,
+    conv: &groovehq_cli::types::Conversation,
+) -> anyhow::Result<()> {
+    match hooks::run_hook(&config.hooks, stage, action, conv) {
+        Err(err) if stage != HookStage::Pre => {
+            eprintln!("{} {}", cli::Msg::Warning.text(), err);
+            Ok(())
+        }
+        result => Ok(result?),
+    }
+}
+
+/// The response-time target from `[sla] response_time`, if set
+fn list_sla(config: &Config) -> anyhow::Result<Option<Sla>> {
+    let Some(response_time) = &config.sla.response_time else {
+        return Ok(None);
+    };
+    let response_time = parse_duration_span(response_time)
+        .map_err(|e| error::GrooveError::Config(format!("[sla] response_time: {}", e)))?;
+    Ok(Some(Sla::new(response_time)))
+}
+
+/// The newest `count` messages of each conversation, oldest first, by
+/// conversation number
+async fn newest_messages<'a>(
+    client: &GrooveClient,
+    conversations: impl Iterator<Item = &'a Conversation>,
+    count: i32,
+) -> error::Result<HashMap<i64, Vec<Message>>> {
+    let mut pages = stream::iter(conversations)
+        .map(|conv| async move {
+            let page = MessagePage::Last {
+                count,
+                before: None,
+            };
+            let (messages, _) = client.messages(&conv.id, &page).await?;
+            Ok::<_, error::GrooveError>((conv.number, messages))
+        })
+        .buffer_unordered(cli::DEFAULT_CONCURRENCY);
+    let mut newest = HashMap::new();
+    while let Some(page) = pages.next().await {
+        let (number, messages) = page?;
+        newest.insert(number, messages);
+    }
+    Ok(newest)
+}
+
+/// For each conversation with a message containing every keyword, the
+/// first line of it that matched
+async fn body_matches(
+    client: &GrooveClient,
+    conversations: &[Conversation],
+    keywords: &cli::Keywords,
+) -> error::Result<HashMap<i64, String>> {
+    let mut threads = stream::iter(conversations)
+        .map(|conv| async move {
+            let messages = client.all_messages(&conv.id).await?;
+            Ok::<_, error::GrooveError>((conv.number, messages))
+        })
+        .buffer_unordered(cli::DEFAULT_CONCURRENCY);
+    let mut matches = HashMap::new();
+    while let Some(thread) = threads.next().await {
+        let (number, messages) = thread?;
+        let line = messages.iter().find_map(|message| {
+            let text = message.text()?;
+            if !keywords.all_in(&text) {
+                return None;
+            }
+            keywords.snippet(&text)
+        });
+        if let Some(line) = line {
+            matches.insert(number, line);
+        }
+    }
+    Ok(matches)
+}
+
+/// Results from one of `groove search`'s sources. Failures are warnings, so
+/// the other sources still show, unless the token was rejected.
+fn search_source<T>(source: &str, result: error::Result<Vec<T>>) -> anyhow::Result<Vec<T>> {
+    match result {
+        Ok(items) => Ok(items),
+        Err(err @ error::GrooveError::AuthError(_)) => Err(err.into()),
+        Err(err) => {
+            eprintln!(
+                "{} could not search {}: {}",
+                cli::Msg::Warning.text(),
+                source,
+                err
+            );
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// The record of conversations viewed with this profile. Problems reading
+/// it are warnings, since it only adds "NEW" badges.
+fn open_seen(config: &Config) -> Option<Seen> {
+    let path = Seen::default_path(config.active_profile.as_deref())?;
+    Seen::open(&path)
+        .map_err(|err| {
+            eprintln!(
+                "{} could not read {}: {}",
+                cli::Msg::Warning.text(),
+                path.display(),
+                err
+            )
+        })
+        .ok()
+}
+
+/// Journal a change so `groove undo` can revert it. All changes made by
+/// one invocation share a batch and are undone together.
+fn record_undo(action: &str, conv: &Conversation, tag_ids: &[String]) {
+    static BATCH: OnceLock<String> = OnceLock::new();
+    let Some(path) = Journal::default_path() else {
+        return;
+    };
+    let batch = BATCH.get_or_init(journal::new_batch_id);
+    if let Err(err) = Journal::new(path).record(&JournalEntry::new(batch, action, conv, tag_ids)) {
+        eprintln!(
+            "{} could not record change for undo: {}",
+            cli::Msg::Warning.text(),
+            err
+        );
+    }
+}
+
+/// With `-o json`, print what a change did
+fn report_json(format: &OutputFormat, result: &cli::ActionResult) {
+    if let OutputFormat::Json = format {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(result).expect("serialization should not fail")
+        );
+    }
+}
+
+fn success_msg(quiet: bool, msg: impl std::fmt::Display) {
+    if !quiet {
+        println!("{}", msg);
+    }
+}
+
+fn get_body(body_arg: Option<String>) -> anyhow::Result<String> {
+    if let Some(body) = body_arg {
+        return Ok(body);
+    }
+
+    // Check if stdin has data (not a TTY)
+    if io::stdin().is_terminal() {
+        anyhow::bail!("No body provided. Pass as argument or pipe content via stdin");
+    }
 
-        ConversationAction::Snooze { number, duration } => {
-            let until = parse_duration(duration)?;
-            let conv = get_conversation(client, *number).await?;
-            client.snooze(&conv.id, &until).await?;
-            success_msg(
-                quiet,
-                format!("Snoozed conversation #{} until {}", number, until),
-            );
-        }
+    let mut buffer = String::new();
+    io::stdin().read_to_string(&mut buffer)?;
 
-        ConversationAction::Assign { number, agent } => {
-            let conv = get_conversation(client, *number).await?;
+    if buffer.trim().is_empty() {
+        anyhow::bail!("Empty body provided");
+    }
 
-            let agent_id = if agent == "me" {
-                client.me().await?.id
-            } else {
-                let agents = client.agents().await?;
-                agents
-                    .iter()
-                    .find(|a| a.email == *agent || a.name.as_deref() == Some(agent))
-                    .map(|a| a.id.clone())
-                    .ok_or_else(|| error::GrooveError::AgentNotFound(agent.clone()))?
-            };
+    Ok(buffer)
+}
 
-            client.assign(&conv.id, &agent_id).await?;
-            success_msg(
-                quiet,
-                format!("Assigned conversation #{} to {}", number, agent),
-            );
-        }
+/// Run the pre hooks and send a reply. Nothing has changed if this fails.
+async fn send_reply(
+    client: &GrooveClient,
+    conv: &Conversation,
+    body: &str,
+    options: &ReplyOptions,
+    config: &Config,
+    quiet: bool,
+) -> anyhow::Result<cli::ActionResult> {
+    run_hook(config, HookStage::Pre, "reply", conv)?;
+    if options.assign.is_some() {
+        run_hook(config, HookStage::Pre, "assign", conv)?;
+    }
+    if !options.tag_ids.is_empty() {
+        run_hook(config, HookStage::Pre, "add-tag", conv)?;
+    }
+    if options.close {
+        run_hook(config, HookStage::Pre, "close", conv)?;
+    }
+    match &options.subject {
+        Some(subject) => client.reply_with_subject(&conv.id, body, subject).await?,
+        None => client.reply(&conv.id, body).await?,
+    }
+    success_msg(quiet, cli::tr(cli::Msg::ReplySent, &[&conv.number]));
+    Ok(cli::ActionResult::new("reply", conv))
+}
 
-        ConversationAction::Unassign { numbers } => {
-            validate_conversation_numbers(numbers)?;
-            for number in numbers {
-                let conv = get_conversation(client, *number).await?;
-                client.unassign(&conv.id).await?;
-                success_msg(quiet, format!("Unassigned conversation #{}", number));
-            }
-        }
+/// Assign, tag, and close the conversation as asked and run the post hooks,
+/// once [`send_reply`] has sent the reply
+async fn finish_reply(
+    client: &GrooveClient,
+    conv: &Conversation,
+    options: ReplyOptions,
+    mut result: cli::ActionResult,
+    config: &Config,
+    quiet: bool,
+) -> anyhow::Result<cli::ActionResult> {
+    let failed = |what: &str, e: error::GrooveError| {
+        anyhow::anyhow!(
+            "Reply sent, but {} conversation #{} failed: {}",
+            what,
+            conv.number,
+            e
+        )
+    };
+    if let Some(agent) = options.assign {
+        client
+            .assign(&conv.id, &agent.id)
+            .await
+            .map_err(|e| failed("assigning", e))?;
+        record_undo("assign", conv, &[]);
+        success_msg(
+            quiet,
+            cli::tr(cli::Msg::Assigned, &[&conv.number, &agent.name]),
+        );
+        run_hook(config, HookStage::Post, "assign", conv)?;
+        result.agent_id = Some(agent.id);
+    }
+    if !options.tag_ids.is_empty() {
+        client
+            .tag(&conv.id, options.tag_ids.clone())
+            .await
+            .map_err(|e| failed("tagging", e))?;
+        record_undo("add-tag", conv, &options.tag_ids);
+        success_msg(quiet, cli::tr(cli::Msg::TagsAdded, &[&conv.number]));
+        run_hook(config, HookStage::Post, "add-tag", conv)?;
+        result.tag_ids = options.tag_ids;
+    }
+    if options.close {
+        client
+            .close(&conv.id)
+            .await
+            .map_err(|e| failed("closing", e))?;
+        record_undo("close", conv, &[]);
+        success_msg(quiet, cli::tr(cli::Msg::Closed, &[&conv.number]));
+        run_hook(config, HookStage::Post, "close", conv)?;
+        result.state = Some(ConversationState::Closed);
+    }
+    run_hook(config, HookStage::Post, "reply", conv)?;
+    Ok(result)
+}
 
-        ConversationAction::AddTag { number, tags } => {
-            let conv = get_conversation(client, *number).await?;
-            let all_tags = client.tags().await?;
-            let tag_ids = resolve_tag_ids(tags, &all_tags)?;
-            client.tag(&conv.id, tag_ids).await?;
-            success_msg(quiet, format!("Added tags to conversation #{}", number));
-        }
+/// Read the template named by `reply --template`
+fn load_template(name: &str, config: &Config) -> anyhow::Result<Template> {
+    let dir = match &config.templates_dir {
+        Some(dir) => dir.clone(),
+        None => Template::default_dir()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?,
+    };
+    Ok(Template::load(&Template::find(name, &dir)?)?)
+}
 
-        ConversationAction::RemoveTag { number, tags } => {
-            let conv = get_conversation(client, *number).await?;
-            let all_tags = client.tags().await?;
-            let tag_ids = resolve_tag_ids(tags, &all_tags)?;
-            client.untag(&conv.id, tag_ids).await?;
-            success_msg(quiet, format!("Removed tags from conversation #{}", number));
-        }
+fn outbox_path() -> anyhow::Result<PathBuf> {
+    Outbox::default_path().ok_or_else(|| anyhow::anyhow!("Could not determine data directory"))
+}
 
-        ConversationAction::Note { number, body } => {
-            let body = get_body(body.clone())?;
-            let conv = get_conversation(client, *number).await?;
-            client.add_note(&conv.id, &body).await?;
-            success_msg(quiet, format!("Note added to conversation #{}", number));
-        }
+fn handle_outbox(action: &OutboxAction, format: &OutputFormat, quiet: bool) -> anyhow::Result<()> {
+    let outbox = Outbox::new(outbox_path()?);
+    match action {
+        OutboxAction::List => cli::format_outbox(&outbox.all()?, format),
+        OutboxAction::Cancel { id } => match outbox.remove(*id)? {
+            Some(queued) => success_msg(
+                quiet,
+                format!("Cancelled queued reply {} to #{}", id, queued.number),
+            ),
+            None => anyhow::bail!("No queued reply with ID {}", id),
+        },
+        OutboxAction::Flush { .. } => unreachable!("flushing needs a client"),
     }
-
     Ok(())
 }
 
-async fn handle_folder(
-    action: &FolderAction,
+/// Send the queued replies that are due, or all of them. Replies that
+/// couldn't be sent go back in the outbox for the next flush; the count sent
+/// is returned.
+async fn flush_outbox(
     client: &GrooveClient,
-    format: &OutputFormat,
-) -> anyhow::Result<()> {
-    match action {
-        FolderAction::List => {
-            let folders = client.folders().await?;
-            cli::format_folders(&folders, format);
-            if folders.len() >= MAX_ITEMS_PER_PAGE {
+    config: &Config,
+    quiet: bool,
+    all: bool,
+) -> anyhow::Result<usize> {
+    let outbox = Outbox::new(outbox_path()?);
+    let due = outbox.take_due(if all {
+        DateTime::<Utc>::MAX_UTC
+    } else {
+        Utc::now()
+    })?;
+    let total = due.len();
+    let mut failed = 0;
+    for queued in due {
+        let sent = async {
+            let conv = get_conversation(client, queued.number).await?;
+            send_reply(client, &conv, &queued.body, &queued.options, config, quiet)
+                .await
+                .map(|result| (conv, result))
+        };
+        let (conv, result) = match sent.await {
+            Ok(sent) => sent,
+            Err(err) => {
                 eprintln!(
-                    "Warning: Results may be truncated (showing {} items)",
-                    MAX_ITEMS_PER_PAGE
+                    "Queued reply {} to #{} failed, will retry: {}",
+                    queued.id, queued.number, err
                 );
+                outbox.restore(queued)?;
+                failed += 1;
+                continue;
             }
+        };
+        // The reply is out, so it isn't retried even if this fails
+        if let Err(err) = finish_reply(client, &conv, queued.options, result, config, quiet).await {
+            eprintln!("{}", err);
+            failed += 1;
         }
     }
-    Ok(())
+    if failed > 0 {
+        return Err(error::GrooveError::PartialFailure(format!(
+            "{} of {} queued replies failed",
+            failed, total
+        ))
+        .into());
+    }
+    Ok(total)
 }
 
-async fn handle_tag(
-    action: &TagAction,
-    client: &GrooveClient,
+fn handle_remind(
+    action: Option<&RemindAction>,
+    number: Option<i64>,
+    when: Option<&str>,
+    note: &Option<String>,
     format: &OutputFormat,
+    quiet: bool,
 ) -> anyhow::Result<()> {
+    let path = Reminders::default_path()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine data directory"))?;
+    let reminders = Reminders::new(path);
     match action {
-        TagAction::List => {
-            let tags = client.tags().await?;
-            cli::format_tags(&tags, format);
-            if tags.len() >= MAX_ITEMS_PER_PAGE {
-                eprintln!(
-                    "Warning: Results may be truncated (showing {} items)",
-                    MAX_ITEMS_PER_PAGE
-                );
+        Some(RemindAction::List { due }) => {
+            let mut all = reminders.all()?;
+            if *due {
+                all.retain(|r| r.is_due(Utc::now()));
+            }
+            cli::format_reminders(&all, format);
+        }
+        Some(RemindAction::Done { id }) => match reminders.remove(*id)? {
+            Some(reminder) => success_msg(
+                quiet,
+                format!("Removed reminder {} for #{}", id, reminder.number),
+            ),
+            None => anyhow::bail!("No reminder with ID {}", id),
+        },
+        None => {
+            // clap requires both when there's no subcommand
+            let (Some(number), Some(when)) = (number, when) else {
+                unreachable!()
+            };
+            validate_conversation_number(number)?;
+            let reminder = reminders.add(number, parse_when(when)?, note.clone())?;
+            match format {
+                OutputFormat::Json => cli::format_reminders(&[reminder], format),
+                _ => success_msg(
+                    quiet,
+                    format!(
+                        "Reminder {} set for #{} at {}",
+                        reminder.id,
+                        number,
+                        cli::format_timestamp(&reminder.due)
+                    ),
+                ),
             }
         }
     }
     Ok(())
 }
 
-async fn handle_canned_replies(
-    action: &CannedRepliesAction,
-    client: &GrooveClient,
+fn snippets_dir() -> anyhow::Result<PathBuf> {
+    Snippets::default_dir().ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))
+}
+
+fn handle_snippet(
+    action: &SnippetAction,
     format: &OutputFormat,
+    quiet: bool,
 ) -> anyhow::Result<()> {
+    let snippets = Snippets::new(snippets_dir()?);
     match action {
-        CannedRepliesAction::List => {
-            let replies = client.canned_replies().await?;
-            cli::format_canned_replies(&replies, format);
-            if replies.len() >= MAX_ITEMS_PER_PAGE {
-                eprintln!(
-                    "Warning: Results may be truncated (showing {} items)",
-                    MAX_ITEMS_PER_PAGE
+        SnippetAction::Add { name, body } => {
+            let body = get_body(body.clone())?;
+            snippets.add(name, &body)?;
+            success_msg(quiet, format!("Saved snippet '{}'", name));
+        }
+        SnippetAction::List => cli::format_snippets(&snippets.all()?, format),
+        SnippetAction::Use { name, copy } => {
+            let all = snippets.all()?;
+            let snippet = find_snippet(name, &all)?;
+            if *copy {
+                cli::copy_to_clipboard(&snippet.body)?;
+                success_msg(
+                    quiet,
+                    format!("Copied snippet '{}' to clipboard", snippet.name),
                 );
+            } else {
+                print!("{}", snippet.body);
+                if !snippet.body.ends_with('\n') {
+                    println!();
+                }
             }
         }
-        CannedRepliesAction::Show { name } => {
-            let replies = client.canned_replies().await?;
-            let reply = replies
-                .iter()
-                .find(|r| r.name.eq_ignore_ascii_case(name) || r.id == *name)
-                .ok_or_else(|| error::GrooveError::CannedReplyNotFound(name.clone()))?;
-            cli::format_canned_reply(reply);
+        SnippetAction::Remove { name } => {
+            if !snippets.remove(name)? {
+                return Err(error::GrooveError::SnippetNotFound(name.clone(), Vec::new()).into());
+            }
+            success_msg(quiet, format!("Removed snippet '{}'", name));
         }
     }
     Ok(())
 }
 
-fn validate_conversation_number(number: i64) -> anyhow::Result<()> {
-    if number <= 0 {
-        anyhow::bail!("Conversation number must be positive, got: {}", number);
+/// Announce open conversations assigned to `agent_id` since the last call,
+/// and run the `on-assigned` hook for each. `assigned` holds the numbers
+/// seen so far; the first call only fills it in.
+async fn announce_assignments(
+    client: &GrooveClient,
+    agent_id: &str,
+    assigned: &mut Option<HashSet<i64>>,
+    notify: bool,
+    config: &Config,
+) -> Result<(), error::GrooveError> {
+    let filter = ConversationFilter {
+        state: Some(ConversationState::Opened),
+        assignee_id: Some(agent_id.to_string()),
+        ..ConversationFilter::default()
+    };
+    let mine: Vec<Conversation> = client.conversations_stream(&filter).try_collect().await?;
+    let numbers = mine.iter().map(|c| c.number).collect();
+    let Some(previous) = assigned.replace(numbers) else {
+        return Ok(());
+    };
+    for conv in mine.iter().filter(|c| !previous.contains(&c.number)) {
+        let title = format!("Assigned to you: conversation #{}", conv.number);
+        let subject = conv.subject.as_deref().unwrap_or("(no subject)");
+        println!("{} {}: {}", Local::now().format("%H:%M:%S"), title, subject);
+        if notify {
+            if let Err(err) = groovehq_cli::notify::desktop(&title, subject) {
+                eprintln!("{} {}", cli::Msg::Warning.text(), err);
+            }
+        }
+        // Hook failures here are only warnings
+        let _ = run_hook(config, HookStage::On, "assigned", conv);
     }
     Ok(())
 }
 
-fn validate_conversation_numbers(numbers: &[i64]) -> anyhow::Result<()> {
-    for number in numbers {
-        validate_conversation_number(*number)?;
+/// Announce reminders that have come due, as `watch` polls
+fn announce_reminders(notify: bool) {
+    let Some(path) = Reminders::default_path() else {
+        return;
+    };
+    let due = match Reminders::new(path).take_due(Utc::now()) {
+        Ok(due) => due,
+        Err(err) => {
+            eprintln!("Could not read reminders: {}", err);
+            return;
+        }
+    };
+    for reminder in due {
+        let title = format!("Reminder: conversation #{}", reminder.number);
+        let note = reminder.note.as_deref().unwrap_or("Follow up");
+        println!("{} {}: {}", Local::now().format("%H:%M:%S"), title, note);
+        if notify {
+            if let Err(err) = groovehq_cli::notify::desktop(&title, note) {
+                eprintln!("{} {}", cli::Msg::Warning.text(), err);
+            }
+        }
     }
-    Ok(())
 }
 
-async fn get_conversation(
-    client: &GrooveClient,
-    number: i64,
-) -> anyhow::Result<groovehq_cli::types::Conversation> {
-    validate_conversation_number(number)?;
-    Ok(client.conversation(number).await?)
-}
+/// Fetch the numbers for `groove inbox`, concurrently
+async fn inbox_summary(client: &GrooveClient) -> anyhow::Result<InboxSummary> {
+    // Sorting by waiting time makes the first conversation the oldest, so one
+    // request per state gives both its count and its oldest conversation
+    let waiting = |state| async move {
+        let filter = ConversationFilter::new()
+            .state(state)
+            .sort(ConversationSort::WaitingLongest);
+        let page = client.conversations(&filter, Some(1), None).await?;
+        Ok::<_, error::GrooveError>((page.total_count, page.nodes.into_iter().next()))
+    };
+    let mine = async {
+        let me = client.me().await?;
+        let filter = ConversationFilter {
+            state: Some(ConversationState::Opened),
+            assignee_id: Some(me.id),
+            ..ConversationFilter::default()
+        };
+        let page = client.conversations(&filter, Some(1), None).await?;
+        Ok(page.total_count)
+    };
+    let snoozed = async {
+        let filter = ConversationFilter::new().state(ConversationState::Snoozed);
+        client
+            .conversations_stream(&filter)
+            .try_collect::<Vec<_>>()
+            .await
+    };
 
-fn resolve_tag_ids(
-    tag_names: &[String],
-    all_tags: &[groovehq_cli::types::Tag],
-) -> anyhow::Result<Vec<String>> {
-    tag_names
+    let ((unread, oldest_unread), (opened, oldest_open), mine, snoozed) = futures::try_join!(
+        waiting(ConversationState::Unread),
+        waiting(ConversationState::Opened),
+        mine,
+        snoozed
+    )?;
+
+    let midnight = (Local::now().date_naive() + Duration::days(1))
+        .and_time(NaiveTime::MIN)
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc));
+    let mut waking_today: Vec<Conversation> = snoozed
         .iter()
-        .map(|name| {
-            all_tags
-                .iter()
-                .find(|t| t.name.eq_ignore_ascii_case(name))
-                .map(|t| t.id.clone())
-                .ok_or_else(|| anyhow::anyhow!(error::GrooveError::TagNotFound(name.clone())))
+        .filter(|c| {
+            c.snoozed_until
+                .zip(midnight)
+                .is_some_and(|(at, end)| at < end)
         })
-        .collect()
+        .cloned()
+        .collect();
+    waking_today.sort_by_key(|c| c.snoozed_until);
+
+    Ok(InboxSummary {
+        unread,
+        opened,
+        snoozed: snoozed.len() as i32,
+        mine,
+        oldest_waiting: oldest_unread
+            .into_iter()
+            .chain(oldest_open)
+            .min_by_key(|c| c.updated_at),
+        waking_today,
+    })
 }
 
-fn success_msg(quiet: bool, msg: impl std::fmt::Display) {
-    if !quiet {
-        println!("{}", msg);
-    }
+/// Metrics served by `watch --metrics`, shared with the client's
+/// [`RecordLatency`] middleware
+fn watch_metrics() -> &'static Arc<Metrics> {
+    static METRICS: OnceLock<Arc<Metrics>> = OnceLock::new();
+    METRICS.get_or_init(|| Arc::new(Metrics::new()))
 }
 
-fn get_body(body_arg: Option<String>) -> anyhow::Result<String> {
-    if let Some(body) = body_arg {
-        return Ok(body);
-    }
-
-    // Check if stdin has data (not a TTY)
-    if io::stdin().is_terminal() {
-        anyhow::bail!("No body provided. Pass as argument or pipe content via stdin");
+/// Count the inbox, announce due reminders and conversations newly assigned
+/// to the current agent, and send due queued replies every `interval`
+/// seconds until interrupted. Failed polls are reported and retried at the
+/// next tick, except for authentication errors, which won't fix themselves.
+async fn watch(
+    client: &GrooveClient,
+    interval: u64,
+    metrics_addr: Option<SocketAddr>,
+    notify: bool,
+    config: &Config,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    let metrics = watch_metrics();
+    if let Some(addr) = metrics_addr {
+        let listener =
+            TcpListener::bind(addr).with_context(|| format!("Failed to listen on {}", addr))?;
+        eprintln!(
+            "Serving metrics on http://{}/metrics",
+            listener.local_addr()?
+        );
+        let metrics = metrics.clone();
+        std::thread::spawn(move || groovehq_cli::metrics::serve(listener, metrics));
     }
 
-    let mut buffer = String::new();
-    io::stdin().read_to_string(&mut buffer)?;
-
-    if buffer.trim().is_empty() {
-        anyhow::bail!("Empty body provided");
+    let me = client.me().await?;
+    let mut assigned = None;
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(interval));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        announce_reminders(notify);
+        match announce_assignments(client, &me.id, &mut assigned, notify, config).await {
+            Err(err @ error::GrooveError::AuthError(_)) => return Err(err.into()),
+            Err(err) => eprintln!("Could not check assignments: {}", err),
+            Ok(()) => {}
+        }
+        // Failures are reported by the flush and retried next tick
+        let _ = flush_outbox(client, config, quiet, false).await;
+        match InboxCounts::fetch(client).await {
+            Ok(counts) => {
+                metrics.set_inbox(counts);
+                if !quiet {
+                    println!("{} {}", Local::now().format("%H:%M:%S"), counts);
+                }
+            }
+            Err(err @ error::GrooveError::AuthError(_)) => return Err(err.into()),
+            Err(err) => {
+                metrics.poll_failed();
+                eprintln!("Poll failed: {}", err);
+            }
+        }
     }
-
-    Ok(buffer)
 }
 
 fn parse_duration(s: &str) -> anyhow::Result<String> {
@@ -480,6 +3242,110 @@ fn parse_duration(s: &str) -> anyhow::Result<String> {
         return Ok(s.to_string());
     }
 
+    let duration = parse_duration_span(s)?;
+    let until = Utc::now() + duration;
+    Ok(until.to_rfc3339())
+}
+
+/// Parse when something should happen: a delay from now ("2d"), a day with
+/// an optional local time ("tomorrow 9am", "fri 17:00", "2024-06-03"; 09:00
+/// when no time is given), a bare time ("5pm", the next one), or an RFC 3339
+/// timestamp
+fn parse_when(s: &str) -> anyhow::Result<DateTime<Utc>> {
+    parse_when_from(s, Local::now()).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Invalid time: {}. Use a delay like 2d, a day and time like \"tomorrow 9am\", \
+             YYYY-MM-DD, or an RFC 3339 timestamp",
+            s
+        )
+    })
+}
+
+fn parse_when_from(s: &str, now: DateTime<Local>) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(s) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+    if let Ok(delay) = parse_duration_span(s) {
+        return Some(now.with_timezone(&Utc) + delay);
+    }
+
+    let lower = s.to_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+    let at_local = |date: NaiveDate, time: NaiveTime| {
+        date.and_time(time)
+            .and_local_timezone(Local)
+            .earliest()
+            .map(|t| t.with_timezone(&Utc))
+    };
+    let today = now.date_naive();
+    let nine = NaiveTime::from_hms_opt(9, 0, 0).expect("valid time");
+
+    // A bare time means the next time the clock reads it
+    if let [word] = words[..] {
+        if let Some(time) = parse_clock_time(word) {
+            let when = at_local(today, time)?;
+            return Some(if when > now {
+                when
+            } else {
+                at_local(today + Duration::days(1), time)?
+            });
+        }
+    }
+
+    let (day, time) = match words[..] {
+        [day] => (day, nine),
+        [day, time] => (day, parse_clock_time(time)?),
+        [day, "at", time] => (day, parse_clock_time(time)?),
+        _ => return None,
+    };
+    if let Ok(date) = NaiveDate::parse_from_str(day, "%Y-%m-%d") {
+        return at_local(date, time);
+    }
+    match day {
+        "today" => at_local(today, time),
+        "tomorrow" => at_local(today + Duration::days(1), time),
+        _ => {
+            // The next such weekday, which is today only if the time is
+            // still ahead
+            let weekday: chrono::Weekday = day.parse().ok()?;
+            let ahead =
+                (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+            let when = at_local(today + Duration::days(ahead.into()), time)?;
+            if when > now {
+                Some(when)
+            } else {
+                at_local(today + Duration::days(i64::from(ahead) + 7), time)
+            }
+        }
+    }
+}
+
+/// Parse a clock time like "9am", "5:30pm", or "17:00"
+fn parse_clock_time(s: &str) -> Option<NaiveTime> {
+    let (digits, offset) = if let Some(digits) = s.strip_suffix("am") {
+        (digits, Some(0))
+    } else if let Some(digits) = s.strip_suffix("pm") {
+        (digits, Some(12))
+    } else {
+        (s, None)
+    };
+    let (hour, minute) = match digits.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse().ok()?, minute.parse().ok()?),
+        // A lone number is too easily a typo for a date or delay
+        None if offset.is_some() => (digits.parse().ok()?, 0),
+        _ => return None,
+    };
+    let hour: u32 = match offset {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// Parse a relative duration like "30m", "12h", "30d", or "2w"
+fn parse_duration_span(s: &str) -> anyhow::Result<Duration> {
     let len = s.len();
     if len < 2 {
         anyhow::bail!("Invalid duration: {}", s);
@@ -494,21 +3360,167 @@ fn parse_duration(s: &str) -> anyhow::Result<String> {
         anyhow::bail!("Duration must be positive, got: {}", num);
     }
 
-    let duration = match unit {
+    Ok(match unit {
         "m" => Duration::minutes(num),
         "h" => Duration::hours(num),
         "d" => Duration::days(num),
         "w" => Duration::weeks(num),
         _ => anyhow::bail!("Invalid duration unit: {}. Use m, h, d, or w", unit),
+    })
+}
+
+/// Conversation list layout from `--wide`/`--narrow`, `[defaults.list]
+/// columns`, and `defaults.compact_format`
+fn list_display(config: &Config, table: &cli::TableArgs) -> anyhow::Result<cli::ListDisplay> {
+    let width = if table.wide {
+        cli::TableWidth::Wide
+    } else if table.narrow {
+        cli::TableWidth::Narrow
+    } else {
+        cli::TableWidth::Auto
     };
+    let compact_format = config
+        .defaults
+        .compact_format
+        .as_deref()
+        .map(str::parse)
+        .transpose()
+        .map_err(|e| error::GrooveError::Config(format!("[defaults] compact_format: {}", e)))?;
+    Ok(cli::ListDisplay {
+        columns: list_columns(config)?,
+        width,
+        compact_format,
+        seen: open_seen(config),
+        changes: cli::Changes::default(),
+        sla: None,
+        last_message: HashMap::new(),
+        search: None,
+        matches: HashMap::new(),
+    })
+}
 
-    let until = Utc::now() + duration;
-    Ok(until.to_rfc3339())
+/// Table style and state colors from `[theme]`, and the date format from
+/// `--iso-dates` or `defaults.date_format`
+fn theme(config: &Config, iso_dates: bool) -> anyhow::Result<cli::Theme> {
+    let settings = &config.theme;
+    let mut theme = cli::Theme::default();
+    if iso_dates {
+        theme.date_format = cli::DateFormat::Iso;
+    } else if let Some(format) = &config.defaults.date_format {
+        theme.date_format = format
+            .parse()
+            .map_err(|e| error::GrooveError::Config(format!("[defaults] date_format: {}", e)))?;
+    }
+    if let Some(style) = &settings.table_style {
+        theme.table_style = style
+            .parse()
+            .map_err(|e| error::GrooveError::Config(format!("[theme] table_style: {}", e)))?;
+    }
+    for (state, color) in &settings.colors {
+        theme
+            .set_state_color(state, color)
+            .map_err(|e| error::GrooveError::Config(format!("[theme.colors] {}: {}", state, e)))?;
+    }
+    // Terminals without OSC 8 support print just the text, but pipes and
+    // files would get the escape codes
+    theme.hyperlinks = settings.hyperlinks.unwrap_or(true)
+        && io::stdout().is_terminal()
+        && std::env::var("TERM").map_or(true, |term| term != "dumb");
+    theme.web_url = config.web_url.clone();
+    theme.icons = settings.icons.unwrap_or(false);
+    Ok(theme)
+}
+
+/// Conversation table columns from `[defaults.list] columns`
+fn list_columns(config: &Config) -> anyhow::Result<Vec<cli::Column>> {
+    let Some(names) = &config.defaults.list.columns else {
+        return Ok(cli::Column::DEFAULT.to_vec());
+    };
+    let columns = names
+        .iter()
+        .map(|name| name.parse())
+        .collect::<Result<Vec<_>, String>>()
+        .map_err(|e| error::GrooveError::Config(format!("[defaults.list] columns: {}", e)))?;
+    if columns.is_empty() {
+        return Err(
+            error::GrooveError::Config("[defaults.list] columns is empty".to_string()).into(),
+        );
+    }
+    Ok(columns)
+}
+
+/// A `[defaults.<command>]` value from the config, parsed like its flag
+fn config_default<T>(
+    command: &str,
+    key: &str,
+    value: &Option<String>,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> anyhow::Result<Option<T>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let parsed = parse(value).map_err(|e| {
+        error::GrooveError::Config(format!("[defaults.{}] {}: {}", command, key, e))
+    })?;
+    Ok(Some(parsed))
+}
+
+/// Build the `--since`/`--until` filter, or `None` if neither was given
+fn date_range(
+    since: Option<&str>,
+    until: Option<&str>,
+    updated: bool,
+) -> anyhow::Result<Option<DateRange>> {
+    if since.is_none() && until.is_none() {
+        return Ok(None);
+    }
+    let range = DateRange {
+        field: if updated {
+            DateField::Updated
+        } else {
+            DateField::Created
+        },
+        since: since.map(|s| parse_date_bound(s, false)).transpose()?,
+        until: until.map(|s| parse_date_bound(s, true)).transpose()?,
+    };
+    if let (Some(since), Some(until)) = (range.since, range.until) {
+        if since > until {
+            anyhow::bail!("--since must be before --until");
+        }
+    }
+    Ok(Some(range))
+}
+
+/// Parse a point in time given as a relative age ("7d" = seven days ago), a
+/// date, or an RFC 3339 timestamp. A bare date means the start of that day,
+/// or its end when `end_of_day` is set, so `--until 2024-01-31` includes the
+/// 31st.
+fn parse_date_bound(s: &str, end_of_day: bool) -> anyhow::Result<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(s) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        let time = if end_of_day {
+            NaiveTime::from_hms_opt(23, 59, 59).expect("valid time")
+        } else {
+            NaiveTime::MIN
+        };
+        return Ok(date.and_time(time).and_utc());
+    }
+    parse_duration_span(s)
+        .map(|age| Utc::now() - age)
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Invalid date: {}. Use a relative age like 7d, YYYY-MM-DD, or an RFC 3339 timestamp",
+                s
+            )
+        })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
 
     #[test]
     fn test_parse_duration_minutes() {
@@ -594,6 +3606,63 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("must be positive"));
     }
 
+    #[test]
+    fn test_parse_duration_span() {
+        assert_eq!(parse_duration_span("30d").unwrap(), Duration::days(30));
+        assert_eq!(parse_duration_span("2w").unwrap(), Duration::weeks(2));
+        assert!(parse_duration_span("2025-01-15").is_err());
+    }
+
+    #[test]
+    fn test_parse_clock_time() {
+        let at = |h, m| NaiveTime::from_hms_opt(h, m, 0);
+        assert_eq!(parse_clock_time("9am"), at(9, 0));
+        assert_eq!(parse_clock_time("12am"), at(0, 0));
+        assert_eq!(parse_clock_time("12pm"), at(12, 0));
+        assert_eq!(parse_clock_time("5:30pm"), at(17, 30));
+        assert_eq!(parse_clock_time("17:00"), at(17, 0));
+        assert_eq!(parse_clock_time("17"), None);
+        assert_eq!(parse_clock_time("13pm"), None);
+        assert_eq!(parse_clock_time("9:5am"), None);
+    }
+
+    #[test]
+    fn test_parse_when() {
+        // Wednesday 2024-06-05, 10:30 local time
+        let now = Local.with_ymd_and_hms(2024, 6, 5, 10, 30, 0).unwrap();
+        let local =
+            |s: &str| parse_when_from(s, now).map(|t| t.with_timezone(&Local).naive_local());
+        let at = |d, h, m| {
+            NaiveDate::from_ymd_opt(2024, 6, d)
+                .unwrap()
+                .and_hms_opt(h, m, 0)
+        };
+
+        assert_eq!(local("tomorrow 9am"), at(6, 9, 0));
+        assert_eq!(local("Tomorrow"), at(6, 9, 0));
+        assert_eq!(local("today 5:30pm"), at(5, 17, 30));
+        assert_eq!(local("2024-06-10 at 14:00"), at(10, 14, 0));
+        assert_eq!(local("2024-06-10"), at(10, 9, 0));
+        assert_eq!(local("fri"), at(7, 9, 0));
+        // Today's 9am has passed, so the next Wednesday
+        assert_eq!(local("wednesday 9am"), at(12, 9, 0));
+        assert_eq!(local("wed 4pm"), at(5, 16, 0));
+        assert_eq!(local("9am"), at(6, 9, 0));
+        assert_eq!(local("11am"), at(5, 11, 0));
+        assert_eq!(local("2h"), at(5, 12, 30));
+        assert_eq!(
+            parse_when_from("2024-06-03T09:00:00Z", now),
+            Some(Utc.with_ymd_and_hms(2024, 6, 3, 9, 0, 0).unwrap())
+        );
+
+        assert_eq!(local("someday"), None);
+        assert_eq!(local("tomorrow 25:00"), None);
+        assert!(parse_when("soon")
+            .unwrap_err()
+            .to_string()
+            .starts_with("Invalid time"));
+    }
+
     #[test]
     fn test_validate_conversation_number_valid() {
         assert!(validate_conversation_number(1).is_ok());
@@ -630,4 +3699,42 @@ mod tests {
         let result = validate_conversation_numbers(&[-1, 2, 3]);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_parse_date_bound_date() {
+        let start = parse_date_bound("2024-01-31", false).unwrap();
+        assert_eq!(start.to_rfc3339(), "2024-01-31T00:00:00+00:00");
+        let end = parse_date_bound("2024-01-31", true).unwrap();
+        assert_eq!(end.to_rfc3339(), "2024-01-31T23:59:59+00:00");
+    }
+
+    #[test]
+    fn test_parse_date_bound_timestamp() {
+        let at = parse_date_bound("2024-01-31T12:00:00+02:00", true).unwrap();
+        assert_eq!(at.to_rfc3339(), "2024-01-31T10:00:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_date_bound_relative() {
+        let at = parse_date_bound("7d", false).unwrap();
+        let expected = Utc::now() - Duration::days(7);
+        assert!((at - expected).num_seconds().abs() < 5);
+    }
+
+    #[test]
+    fn test_parse_date_bound_invalid() {
+        assert!(parse_date_bound("yesterday", false).is_err());
+        assert!(parse_date_bound("2024-13-01", false).is_err());
+    }
+
+    #[test]
+    fn test_date_range() {
+        assert_eq!(date_range(None, None, false).unwrap(), None);
+
+        let range = date_range(Some("2024-01-01"), None, true).unwrap().unwrap();
+        assert_eq!(range.field, DateField::Updated);
+        assert!(range.until.is_none());
+
+        assert!(date_range(Some("2024-02-01"), Some("2024-01-01"), false).is_err());
+    }
 }
@@ -0,0 +1,359 @@
+//! Inbox gauges and API latency histograms for `groove watch --metrics`, in
+//! the Prometheus text exposition format.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use futures::TryStreamExt;
+
+use crate::api::{GrooveClient, Middleware, Next, Request, Response};
+use crate::error::Result;
+use crate::types::{ConversationFilter, ConversationState};
+
+/// Upper bounds, in seconds, of the latency histogram buckets
+const LATENCY_BUCKETS: [f64; 9] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// How long a scraper gets to send its request and read the response, so a
+/// stalled connection can't hold up the next scrape
+const SCRAPE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pause after a failed accept, e.g. when out of file descriptors, so the
+/// retry doesn't spin
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Open, unassigned, and snoozed conversation counts from one poll
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InboxCounts {
+    pub open: u64,
+    pub unassigned: u64,
+    pub snoozed: u64,
+}
+
+impl InboxCounts {
+    /// Count the inbox. Finding unassigned conversations means listing every
+    /// open one, so this takes one request per
+    /// [`STREAM_PAGE_SIZE`](crate::api::STREAM_PAGE_SIZE) open conversations.
+    pub async fn fetch(client: &GrooveClient) -> Result<Self> {
+        let mut counts = Self::default();
+        let open = ConversationFilter::new().state(ConversationState::Opened);
+        client
+            .conversations_stream(&open)
+            .try_for_each(|conv| {
+                counts.open += 1;
+                if conv.assigned.is_none() {
+                    counts.unassigned += 1;
+                }
+                futures::future::ok(())
+            })
+            .await?;
+
+        let snoozed = ConversationFilter::new().state(ConversationState::Snoozed);
+        let page = client.conversations(&snoozed, Some(1), None).await?;
+        counts.snoozed = page.total_count.max(0) as u64;
+        Ok(counts)
+    }
+}
+
+impl fmt::Display for InboxCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} open ({} unassigned), {} snoozed",
+            self.open, self.unassigned, self.snoozed
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Observations at or below each of [`LATENCY_BUCKETS`]
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    inbox: Option<InboxCounts>,
+    last_poll: Option<DateTime<Utc>>,
+    poll_errors: u64,
+    /// Latency by GraphQL operation name
+    latency: BTreeMap<String, Histogram>,
+}
+
+/// Metrics shared between the poll loop, the API client, and the HTTP
+/// endpoint
+#[derive(Debug, Default)]
+pub struct Metrics {
+    state: Mutex<State>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a successful poll
+    pub fn set_inbox(&self, counts: InboxCounts) {
+        if let Ok(mut state) = self.state.lock() {
+            state.inbox = Some(counts);
+            state.last_poll = Some(Utc::now());
+        }
+    }
+
+    pub fn poll_failed(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.poll_errors += 1;
+        }
+    }
+
+    pub fn observe_latency(&self, operation: &str, elapsed: Duration) {
+        if let Ok(mut state) = self.state.lock() {
+            state
+                .latency
+                .entry(operation.to_string())
+                .or_default()
+                .observe(elapsed.as_secs_f64());
+        }
+    }
+
+    /// Everything recorded so far, in the Prometheus text format. Inbox
+    /// gauges are left out until the first poll succeeds.
+    pub fn render(&self) -> String {
+        let Ok(state) = self.state.lock() else {
+            return String::new();
+        };
+        let mut out = String::new();
+
+        if let Some(inbox) = state.inbox {
+            out.push_str("# HELP groove_conversations Conversations by state\n");
+            out.push_str("# TYPE groove_conversations gauge\n");
+            let _ = writeln!(
+                out,
+                "groove_conversations{{state=\"opened\"}} {}",
+                inbox.open
+            );
+            let _ = writeln!(
+                out,
+                "groove_conversations{{state=\"snoozed\"}} {}",
+                inbox.snoozed
+            );
+            out.push_str(
+                "# HELP groove_conversations_unassigned Open conversations with no assignee\n",
+            );
+            out.push_str("# TYPE groove_conversations_unassigned gauge\n");
+            let _ = writeln!(out, "groove_conversations_unassigned {}", inbox.unassigned);
+        }
+        if let Some(last_poll) = state.last_poll {
+            out.push_str(
+                "# HELP groove_last_poll_timestamp_seconds When the inbox was last counted\n",
+            );
+            out.push_str("# TYPE groove_last_poll_timestamp_seconds gauge\n");
+            let _ = writeln!(
+                out,
+                "groove_last_poll_timestamp_seconds {}",
+                last_poll.timestamp()
+            );
+        }
+
+        out.push_str("# HELP groove_poll_errors_total Polls that failed\n");
+        out.push_str("# TYPE groove_poll_errors_total counter\n");
+        let _ = writeln!(out, "groove_poll_errors_total {}", state.poll_errors);
+
+        if !state.latency.is_empty() {
+            out.push_str(
+                "# HELP groove_api_request_duration_seconds GraphQL request latency by operation\n",
+            );
+            out.push_str("# TYPE groove_api_request_duration_seconds histogram\n");
+        }
+        for (operation, histogram) in &state.latency {
+            for (count, bound) in histogram.buckets.iter().zip(LATENCY_BUCKETS) {
+                let _ = writeln!(
+                    out,
+                    "groove_api_request_duration_seconds_bucket{{operation=\"{}\",le=\"{}\"}} {}",
+                    operation, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "groove_api_request_duration_seconds_bucket{{operation=\"{}\",le=\"+Inf\"}} {}",
+                operation, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "groove_api_request_duration_seconds_sum{{operation=\"{}\"}} {}",
+                operation, histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "groove_api_request_duration_seconds_count{{operation=\"{}\"}} {}",
+                operation, histogram.count
+            );
+        }
+        out
+    }
+}
+
+/// Times each request into [`Metrics`], by GraphQL operation name
+pub struct RecordLatency(pub Arc<Metrics>);
+
+impl Middleware for RecordLatency {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            let operation = request.operation_name().unwrap_or("anonymous").to_string();
+            let started = Instant::now();
+            let response = next.run(request).await;
+            self.0.observe_latency(&operation, started.elapsed());
+            response
+        })
+    }
+}
+
+/// Answer `GET /metrics` on `listener`, one scrape at a time. Connection
+/// errors are logged and the next connection is served.
+pub fn serve(listener: TcpListener, metrics: Arc<Metrics>) -> io::Result<()> {
+    serve_with_timeout(listener, metrics, SCRAPE_TIMEOUT)
+}
+
+fn serve_with_timeout(
+    listener: TcpListener,
+    metrics: Arc<Metrics>,
+    timeout: Duration,
+) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("metrics: {}", e);
+                std::thread::sleep(ACCEPT_BACKOFF);
+                continue;
+            }
+        };
+        if let Err(e) = stream
+            .set_read_timeout(Some(timeout))
+            .and_then(|_| stream.set_write_timeout(Some(timeout)))
+        {
+            eprintln!("metrics: {}", e);
+            continue;
+        }
+        let mut request_line = String::new();
+        let mut reader = BufReader::new(&stream);
+        if reader.read_line(&mut request_line).is_err() {
+            continue;
+        }
+        // Drain the headers; the request has no body
+        let mut line = String::new();
+        while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+            line.clear();
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let (status, body) = match (parts.next(), parts.next()) {
+            (Some("GET"), Some("/metrics")) => ("200 OK", metrics.render()),
+            _ => (
+                "404 Not Found",
+                "Metrics are served at /metrics\n".to_string(),
+            ),
+        };
+        // A scraper that hangs up early isn't worth stopping for
+        let _ = write!(
+            stream,
+            "HTTP/1.1 {}\r\n\
+             Content-Type: text/plain; version=0.0.4\r\n\
+             Content-Length: {}\r\n\
+             Connection: close\r\n\
+             \r\n\
+             {}",
+            status,
+            body.len(),
+            body
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_before_first_poll() {
+        let metrics = Metrics::new();
+        let out = metrics.render();
+        assert!(out.contains("groove_poll_errors_total 0"));
+        assert!(!out.contains("groove_conversations"));
+    }
+
+    #[test]
+    fn test_render_inbox_and_latency() {
+        let metrics = Metrics::new();
+        metrics.set_inbox(InboxCounts {
+            open: 12,
+            unassigned: 3,
+            snoozed: 4,
+        });
+        metrics.observe_latency("Conversations", Duration::from_millis(80));
+        metrics.observe_latency("Conversations", Duration::from_millis(700));
+
+        let out = metrics.render();
+        assert!(out.contains("groove_conversations{state=\"opened\"} 12\n"));
+        assert!(out.contains("groove_conversations{state=\"snoozed\"} 4\n"));
+        assert!(out.contains("groove_conversations_unassigned 3\n"));
+        // Buckets are cumulative
+        assert!(out.contains(
+            "groove_api_request_duration_seconds_bucket{operation=\"Conversations\",le=\"0.05\"} 0\n"
+        ));
+        assert!(out.contains(
+            "groove_api_request_duration_seconds_bucket{operation=\"Conversations\",le=\"0.1\"} 1\n"
+        ));
+        assert!(out.contains(
+            "groove_api_request_duration_seconds_bucket{operation=\"Conversations\",le=\"1\"} 2\n"
+        ));
+        assert!(out.contains(
+            "groove_api_request_duration_seconds_count{operation=\"Conversations\"} 2\n"
+        ));
+    }
+
+    #[test]
+    fn test_stalled_scraper_times_out() {
+        use std::io::Read;
+        use std::net::TcpStream;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let metrics = Arc::new(Metrics::new());
+        std::thread::spawn(move || {
+            serve_with_timeout(listener, metrics, Duration::from_millis(100))
+        });
+
+        // Connects but never sends a request
+        let _stalled = TcpStream::connect(addr).unwrap();
+
+        let mut scraper = TcpStream::connect(addr).unwrap();
+        scraper
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        scraper.write_all(b"GET /metrics HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = String::new();
+        scraper.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+    }
+}
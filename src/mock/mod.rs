@@ -0,0 +1,634 @@
+//! A local stand-in for the Groove API, served by `groove mock-server`.
+//! Enabled by the `mock-server` feature.
+//!
+//! Queries are answered from a fixed set of fixture conversations, messages,
+//! notes, tags, folders, agents, canned replies, and knowledge base articles,
+//! shaped like the real API's responses, so scripts built on `groove` can be
+//! developed and tested without a Groove account. Mutations succeed (or
+//! report "Conversation not found") but don't change the fixtures, so every
+//! run starts from the same data. Any token is accepted; requests without one
+//! get a 401.
+//!
+//! ```no_run
+//! use std::net::TcpListener;
+//!
+//! let listener = TcpListener::bind("127.0.0.1:0")?;
+//! let endpoint = format!("http://{}", listener.local_addr()?);
+//! std::thread::spawn(move || groovehq_cli::mock::serve(listener));
+//! let client = groovehq_cli::GrooveClient::new("any-token", Some(&endpoint))?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde_json::{json, Map, Value};
+
+/// Port `groove mock-server` listens on unless told otherwise
+pub const DEFAULT_PORT: u16 = 8765;
+
+/// Requests allowed per window, as reported in the rate-limit headers
+const RATE_LIMIT: u64 = 1000;
+
+/// Page size when a query doesn't give `first` or `last`
+const DEFAULT_PAGE_SIZE: usize = 25;
+
+static REQUESTS: AtomicU64 = AtomicU64::new(0);
+
+fn fixtures() -> &'static Value {
+    static FIXTURES: OnceLock<Value> = OnceLock::new();
+    FIXTURES.get_or_init(|| {
+        serde_json::from_str(include_str!("fixtures.json")).expect("fixtures.json is valid JSON")
+    })
+}
+
+/// Answer requests on `listener` until it fails, one thread per connection
+pub fn serve(listener: TcpListener) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let stream = stream?;
+        std::thread::spawn(move || {
+            if let Err(e) = handle_connection(stream) {
+                eprintln!("mock-server: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    // Keep-alive: serve requests until the client closes the connection
+    loop {
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line)? == 0 {
+            return Ok(());
+        }
+        let method = request_line.split_whitespace().next().unwrap_or_default();
+
+        let mut content_length = 0;
+        let mut authorized = false;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                match name.trim().to_ascii_lowercase().as_str() {
+                    "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                    "authorization" => authorized = !value.trim().is_empty(),
+                    _ => {}
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+
+        let (status, response) = if method != "POST" {
+            (
+                "405 Method Not Allowed",
+                json!({ "error": "POST GraphQL requests to /" }),
+            )
+        } else if !authorized {
+            (
+                "401 Unauthorized",
+                json!({ "error": "Missing Authorization header" }),
+            )
+        } else {
+            match serde_json::from_slice::<Value>(&body) {
+                Ok(request) => {
+                    let query = request["query"].as_str().unwrap_or_default();
+                    eprintln!(
+                        "mock-server: {}",
+                        operation_name(query).unwrap_or("anonymous")
+                    );
+                    ("200 OK", respond(&request))
+                }
+                Err(e) => (
+                    "400 Bad Request",
+                    json!({ "errors": [{ "message": format!("Invalid JSON: {}", e) }] }),
+                ),
+            }
+        };
+
+        let count = REQUESTS.fetch_add(1, Ordering::Relaxed) + 1;
+        let body = response.to_string();
+        write!(
+            writer,
+            "HTTP/1.1 {}\r\n\
+             Content-Type: application/json\r\n\
+             Content-Length: {}\r\n\
+             X-Request-Id: mock-{}\r\n\
+             X-RateLimit-Limit: {}\r\n\
+             X-RateLimit-Remaining: {}\r\n\
+             X-RateLimit-Reset: 60\r\n\
+             \r\n\
+             {}",
+            status,
+            body.len(),
+            count,
+            RATE_LIMIT,
+            RATE_LIMIT - count % RATE_LIMIT,
+            body
+        )?;
+        writer.flush()?;
+    }
+}
+
+/// `Conversations` from `query Conversations(...) { ... }`
+fn operation_name(query: &str) -> Option<&str> {
+    static OPERATION: OnceLock<Regex> = OnceLock::new();
+    let re = OPERATION.get_or_init(|| Regex::new(r"^\s*(?:query|mutation)\s+(\w+)").unwrap());
+    re.captures(query)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str())
+}
+
+/// The GraphQL response to a `{"query": ..., "variables": ...}` request body
+pub fn respond(request: &Value) -> Value {
+    let query = request["query"].as_str().unwrap_or_default();
+    let vars = &request["variables"];
+
+    if query.trim_start().starts_with("mutation") {
+        return json!({ "data": mutations(query, vars) });
+    }
+
+    let data = match operation_name(query) {
+        None if query.contains("me {") => json!({ "me": fixtures()["me"] }),
+        Some("Conversations") => json!({ "conversations": conversations(vars) }),
+        Some("Conversation") => json!({ "conversation": conversation(&vars["number"]) }),
+        Some("ConversationsByNumber") => conversations_by_number(query),
+        Some("Followers") => json!({ "conversation": followers(&vars["number"]) }),
+        Some("Messages") => json!({ "events": events(vars, "EmailMessage|Reply") }),
+        Some("Notes") => json!({ "events": events(vars, "Note") }),
+        Some("Message") => json!({ "node": message(&vars["id"]) }),
+        Some("Mentions") => {
+            json!({ "mentions": { "nodes": first(&fixtures()["mentions"], vars) } })
+        }
+        Some("Folders") => json!({ "folders": { "nodes": first(&fixtures()["folders"], vars) } }),
+        Some("Tags") => json!({ "tags": { "nodes": first(&fixtures()["tags"], vars) } }),
+        Some("Agents") => json!({ "agents": { "nodes": first(&fixtures()["agents"], vars) } }),
+        Some("CannedReplies") => {
+            json!({ "cannedReplies": { "nodes": first(&fixtures()["cannedReplies"], vars) } })
+        }
+        Some("Rules") => json!({ "rules": { "nodes": first(&fixtures()["rules"], vars) } }),
+        Some("KbArticles") => json!({ "kbArticles": { "nodes": kb_articles(vars) } }),
+        Some("Contacts") => json!({ "contacts": { "nodes": contacts(vars) } }),
+        Some("ContactProfile") => json!({ "contacts": { "nodes": contact_profiles(vars) } }),
+        Some("ContactRecord") => json!({ "node": contact(&vars["id"]) }),
+        name => {
+            return json!({
+                "errors": [{
+                    "message": format!(
+                        "The mock server doesn't support the {} query",
+                        name.unwrap_or("anonymous")
+                    )
+                }]
+            })
+        }
+    };
+    json!({ "data": data })
+}
+
+fn all_conversations() -> &'static [Value] {
+    fixtures()["conversations"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn find_conversation(number: &Value) -> Option<&'static Value> {
+    let number = number.as_i64()?;
+    all_conversations()
+        .iter()
+        .find(|c| c["number"].as_i64() == Some(number))
+}
+
+/// A fixture conversation without the `_`-prefixed fields the mock uses
+/// for folders, followers, and events
+fn public(conversation: &Value) -> Value {
+    let mut fields = conversation.as_object().cloned().unwrap_or_default();
+    let messages = conversation["_events"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|event| {
+            matches!(
+                event["change"]["__typename"].as_str(),
+                Some("EmailMessage" | "Reply")
+            )
+        })
+        .count();
+    fields.retain(|key, _| !key.starts_with('_'));
+    fields.insert("messagesCount".to_string(), json!(messages));
+    Value::Object(fields)
+}
+
+fn conversation(number: &Value) -> Value {
+    find_conversation(number).map(public).unwrap_or(Value::Null)
+}
+
+/// `c0: conversation(number: 12) { ... }` aliases, looked up one by one
+fn conversations_by_number(query: &str) -> Value {
+    static ALIAS: OnceLock<Regex> = OnceLock::new();
+    let re =
+        ALIAS.get_or_init(|| Regex::new(r"(\w+)\s*:\s*conversation\(number:\s*(\d+)\)").unwrap());
+    let data: Map<String, Value> = re
+        .captures_iter(query)
+        .map(|caps| {
+            let number = json!(caps[2].parse::<i64>().unwrap_or_default());
+            (caps[1].to_string(), conversation(&number))
+        })
+        .collect();
+    Value::Object(data)
+}
+
+fn conversations(vars: &Value) -> Value {
+    let filter = &vars["filter"];
+    let mut matches: Vec<&Value> = all_conversations()
+        .iter()
+        .filter(|c| matches_filter(c, filter))
+        .collect();
+
+    if let Some(field) = vars["orderBy"]["field"].as_str() {
+        let key = if field == "UPDATED_AT" {
+            "updatedAt"
+        } else {
+            "createdAt"
+        };
+        matches.sort_by_key(|c| timestamp(&c[key]));
+        if vars["orderBy"]["direction"] == "DESC" {
+            matches.reverse();
+        }
+    }
+
+    let total = matches.len();
+    let start = cursor(&vars["after"]).unwrap_or(0).min(total);
+    let end = (start + page_size(&vars["first"])).min(total);
+    json!({
+        "nodes": matches[start..end].iter().map(|c| public(c)).collect::<Vec<_>>(),
+        "pageInfo": {
+            "hasNextPage": end < total,
+            "endCursor": (end > start).then(|| end.to_string())
+        },
+        "totalCount": total
+    })
+}
+
+fn matches_filter(conversation: &Value, filter: &Value) -> bool {
+    if let Some(state) = filter["state"].as_str() {
+        if conversation["state"] != state {
+            return false;
+        }
+    }
+    if let Some(folder) = filter["folderId"].as_str() {
+        let folders = conversation["_folders"].as_array();
+        if !folders.is_some_and(|f| f.iter().any(|id| id == folder)) {
+            return false;
+        }
+    }
+    if let Some(keywords) = filter["keywords"].as_str() {
+        let keywords = keywords.to_lowercase();
+        let mut text = conversation["subject"]
+            .as_str()
+            .unwrap_or_default()
+            .to_lowercase();
+        for event in conversation["_events"].as_array().into_iter().flatten() {
+            for field in ["bodyPlainText", "body"] {
+                if let Some(body) = event["change"][field].as_str() {
+                    text.push('\n');
+                    text.push_str(&body.to_lowercase());
+                }
+            }
+        }
+        if !keywords.split_whitespace().all(|word| text.contains(word)) {
+            return false;
+        }
+    }
+    if let Some(contact) = filter["contactId"].as_str() {
+        if conversation["contact"]["id"] != contact {
+            return false;
+        }
+    }
+    if let Some(assignee) = filter["assigneeId"].as_str() {
+        if conversation["assigned"]["agent"]["id"] != assignee {
+            return false;
+        }
+    }
+    for key in ["createdAt", "updatedAt"] {
+        let Some(at) = timestamp(&conversation[key]) else {
+            continue;
+        };
+        if timestamp(&filter[key]["gte"]).is_some_and(|since| at < since)
+            || timestamp(&filter[key]["lte"]).is_some_and(|until| at > until)
+        {
+            return false;
+        }
+    }
+    true
+}
+
+fn followers(number: &Value) -> Value {
+    let Some(conversation) = find_conversation(number) else {
+        return Value::Null;
+    };
+    let agents = fixtures()["agents"].as_array().into_iter().flatten();
+    let ids = conversation["_followers"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let nodes: Vec<&Value> = agents.filter(|a| ids.contains(&a["id"])).collect();
+    json!({ "followers": { "nodes": nodes } })
+}
+
+/// A page of a conversation's events. Changes whose type isn't in `types`
+/// (`|`-separated) come back with only `__typename`, as they do from the
+/// real API when the query has no fragment for them.
+fn events(vars: &Value, types: &str) -> Value {
+    let id = vars["conversationId"].as_str().unwrap_or_default();
+    let all: Vec<Value> = all_conversations()
+        .iter()
+        .find(|c| c["id"] == id)
+        .and_then(|c| c["_events"].as_array())
+        .into_iter()
+        .flatten()
+        .map(|event| {
+            let change = &event["change"];
+            let typename = change["__typename"].as_str().unwrap_or_default();
+            let change = if types.split('|').any(|t| t == typename) {
+                change.clone()
+            } else {
+                json!({ "__typename": typename })
+            };
+            json!({ "createdAt": event["createdAt"], "change": change })
+        })
+        .collect();
+
+    let total = all.len();
+    let (start, end) = if vars["last"].is_null() {
+        let start = cursor(&vars["after"]).unwrap_or(0).min(total);
+        (start, (start + page_size(&vars["first"])).min(total))
+    } else {
+        let end = cursor(&vars["before"]).unwrap_or(total).min(total);
+        (end.saturating_sub(page_size(&vars["last"])), end)
+    };
+    json!({
+        "pageInfo": {
+            "hasNextPage": end < total,
+            "endCursor": (end > start).then(|| end.to_string()),
+            "hasPreviousPage": start > 0,
+            "startCursor": (end > start).then(|| start.to_string())
+        },
+        "nodes": all[start..end]
+    })
+}
+
+fn message(id: &Value) -> Value {
+    all_conversations()
+        .iter()
+        .filter_map(|c| c["_events"].as_array())
+        .flatten()
+        .find(|event| {
+            event["change"]["id"] == *id
+                && matches!(
+                    event["change"]["__typename"].as_str(),
+                    Some("EmailMessage" | "Reply")
+                )
+        })
+        .map(|event| {
+            let mut node = event["change"].clone();
+            node["createdAt"] = event["createdAt"].clone();
+            node
+        })
+        .unwrap_or(Value::Null)
+}
+
+fn contacts(vars: &Value) -> Vec<Value> {
+    let email = vars["filter"]["email"].as_str();
+    let keywords = vars["filter"]["keywords"].as_str();
+    let contacts = fixtures()["contacts"].as_array().into_iter().flatten();
+    let matches: Vec<Value> = contacts
+        .filter(|c| {
+            email.is_none_or(|email| {
+                c["email"]
+                    .as_str()
+                    .is_some_and(|e| e.eq_ignore_ascii_case(email))
+            })
+        })
+        .filter(|c| keywords.is_none_or(|k| has_keywords(&[&c["name"], &c["email"]], k)))
+        .cloned()
+        .collect();
+    first(&Value::Array(matches), vars)
+}
+
+fn contact(id: &Value) -> Value {
+    fixtures()["contacts"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|c| c["id"] == *id)
+        .map(|c| {
+            let mut node = c.clone();
+            node["__typename"] = json!("Contact");
+            node
+        })
+        .unwrap_or(Value::Null)
+}
+
+/// Articles whose title or body has every keyword, without their bodies
+fn kb_articles(vars: &Value) -> Vec<Value> {
+    let keywords = vars["filter"]["keywords"].as_str();
+    let articles = fixtures()["kbArticles"].as_array().into_iter().flatten();
+    let matches: Vec<Value> = articles
+        .filter(|a| keywords.is_none_or(|k| has_keywords(&[&a["title"], &a["body"]], k)))
+        .map(|a| json!({ "id": a["id"], "title": a["title"], "url": a["url"] }))
+        .collect();
+    first(&Value::Array(matches), vars)
+}
+
+/// Whether the text in `fields` contains every word of `keywords`, ignoring
+/// case
+fn has_keywords(fields: &[&Value], keywords: &str) -> bool {
+    let text: String = fields
+        .iter()
+        .filter_map(|field| field.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    keywords
+        .to_lowercase()
+        .split_whitespace()
+        .all(|word| text.contains(word))
+}
+
+/// Matching contacts, each with their newest conversations
+fn contact_profiles(vars: &Value) -> Vec<Value> {
+    contacts(vars)
+        .into_iter()
+        .map(|mut contact| {
+            let mut conversations: Vec<&Value> = all_conversations()
+                .iter()
+                .filter(|c| c["contact"]["id"] == contact["id"])
+                .collect();
+            conversations.sort_by_key(|c| std::cmp::Reverse(timestamp(&c["createdAt"])));
+            let nodes: Vec<Value> = conversations
+                .into_iter()
+                .take(page_size(&vars["conversations"]))
+                .map(public)
+                .collect();
+            contact["conversations"] = json!({ "nodes": nodes });
+            contact
+        })
+        .collect()
+}
+
+/// Up to `first` items of a fixture list
+fn first(items: &Value, vars: &Value) -> Vec<Value> {
+    let items = items.as_array().cloned().unwrap_or_default();
+    items.into_iter().take(page_size(&vars["first"])).collect()
+}
+
+/// Every field of a mutation document, keyed by alias, succeeds unless its
+/// input names a conversation that doesn't exist
+fn mutations(query: &str, vars: &Value) -> Value {
+    static FIELD: OnceLock<Regex> = OnceLock::new();
+    let re = FIELD.get_or_init(|| {
+        Regex::new(r"(?:(\w+)\s*:\s*)?(\w+)\s*\(\s*input:\s*\$(\w+)\s*\)").unwrap()
+    });
+    let data: Map<String, Value> = re
+        .captures_iter(query)
+        .map(|caps| {
+            let key = caps.get(1).unwrap_or_else(|| caps.get(2).unwrap()).as_str();
+            let input = &vars[&caps[3]];
+            let errors = match (input["conversationId"].as_str(), input["ruleId"].as_str()) {
+                (Some(id), _) if !all_conversations().iter().any(|c| c["id"] == id) => {
+                    json!([{ "message": "Conversation not found" }])
+                }
+                (_, Some(id))
+                    if !fixtures()["rules"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .any(|r| r["id"] == id) =>
+                {
+                    json!([{ "message": "Rule not found" }])
+                }
+                _ => json!([]),
+            };
+            (key.to_string(), json!({ "errors": errors }))
+        })
+        .collect();
+    Value::Object(data)
+}
+
+fn timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    value.as_str()?.parse().ok()
+}
+
+/// Page cursors are the index of the next item
+fn cursor(value: &Value) -> Option<usize> {
+    value.as_str()?.parse().ok()
+}
+
+fn page_size(value: &Value) -> usize {
+    value
+        .as_u64()
+        .map(|n| n as usize)
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(query: &str, variables: Value) -> Value {
+        respond(&json!({ "query": query, "variables": variables }))
+    }
+
+    #[test]
+    fn test_fixtures_strip_internal_fields() {
+        let response = query(
+            "query Conversation($number: Int!) { conversation(number: $number) { id } }",
+            json!({ "number": 1001 }),
+        );
+        let conversation = &response["data"]["conversation"];
+        assert_eq!(conversation["subject"], "Refund for order #4521");
+        assert!(conversation.get("_events").is_none());
+        assert_eq!(conversation["messagesCount"], 2);
+
+        let missing = query("query Conversation { }", json!({ "number": 1 }));
+        assert!(missing["data"]["conversation"].is_null());
+    }
+
+    #[test]
+    fn test_conversations_filter_and_paging() {
+        let q = "query Conversations($first: Int) { conversations { nodes { id } } }";
+        let opened = query(q, json!({ "first": 2, "filter": { "state": "OPENED" } }));
+        let page = &opened["data"]["conversations"];
+        assert_eq!(page["totalCount"], 3);
+        assert_eq!(page["nodes"].as_array().unwrap().len(), 2);
+        assert_eq!(page["pageInfo"]["hasNextPage"], true);
+
+        let rest = query(
+            q,
+            json!({ "first": 2, "after": page["pageInfo"]["endCursor"], "filter": { "state": "OPENED" } }),
+        );
+        let page = &rest["data"]["conversations"];
+        assert_eq!(page["nodes"].as_array().unwrap().len(), 1);
+        assert_eq!(page["pageInfo"]["hasNextPage"], false);
+
+        let search = query(q, json!({ "filter": { "keywords": "VAT" } }));
+        assert_eq!(search["data"]["conversations"]["nodes"][0]["number"], 1004);
+    }
+
+    #[test]
+    fn test_messages_hide_notes() {
+        let response = query(
+            "query Messages($conversationId: ID!) { events { nodes { change { __typename } } } }",
+            json!({ "conversationId": "conv-1001", "last": 2 }),
+        );
+        let events = &response["data"]["events"];
+        assert_eq!(events["pageInfo"]["hasPreviousPage"], true);
+        assert_eq!(
+            events["nodes"][0]["change"],
+            json!({ "__typename": "Note" })
+        );
+        assert_eq!(events["nodes"][1]["change"]["id"], "msg-1001-2");
+    }
+
+    #[test]
+    fn test_mutations_by_alias() {
+        let response = query(
+            "mutation UpdateStates($input0: X!, $input1: X!) {\n\
+             m0: conversationClose(input: $input0) { errors { message } }\n\
+             m1: conversationClose(input: $input1) { errors { message } }\n}",
+            json!({
+                "input0": { "conversationId": "conv-1001" },
+                "input1": { "conversationId": "conv-9" }
+            }),
+        );
+        assert_eq!(response["data"]["m0"]["errors"], json!([]));
+        assert_eq!(
+            response["data"]["m1"]["errors"][0]["message"],
+            "Conversation not found"
+        );
+    }
+
+    #[test]
+    fn test_unknown_operation() {
+        let response = query("query Reports { reports { id } }", json!({}));
+        assert!(response["errors"][0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Reports"));
+    }
+}
@@ -0,0 +1,40 @@
+use crate::error::{GrooveError, Result};
+use std::process::{Command, Stdio};
+
+/// Show a desktop notification with `notify-send` (Linux and BSD) or
+/// `osascript` (macOS)
+pub fn desktop(title: &str, body: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        // {:?} quotes and escapes the strings the way AppleScript expects
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification {:?} with title {:?}",
+            body, title
+        ));
+        command
+    } else {
+        let mut command = Command::new("notify-send");
+        command.args(["--app-name", "groove", title, body]);
+        command
+    };
+
+    let status = command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| {
+            GrooveError::Notification(format!(
+                "{}: {}",
+                command.get_program().to_string_lossy(),
+                e
+            ))
+        })?;
+    if !status.success() {
+        return Err(GrooveError::Notification(format!(
+            "{} exited with {}",
+            command.get_program().to_string_lossy(),
+            status
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,257 @@
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// A reply waiting to be sent by `groove outbox flush` or `groove watch`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedReply {
+    /// Short ID for `groove outbox cancel`
+    pub id: u64,
+    pub number: i64,
+    pub body: String,
+    #[serde(flatten)]
+    pub options: ReplyOptions,
+    pub send_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What to do along with sending a reply. Names are resolved when the reply
+/// is written, so a typo is caught then rather than when a queued reply is
+/// sent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplyOptions {
+    /// Subject line, if not the conversation's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// Agent to assign the conversation to once the reply is sent
+    #[serde(default)]
+    pub assign: Option<QueuedAssignee>,
+    /// Tags to add once the reply is sent
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tag_ids: Vec<String>,
+    /// Close the conversation once the reply is sent
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub close: bool,
+}
+
+/// An agent to assign, as given on the command line and as resolved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedAssignee {
+    /// As given on the command line, for messages
+    pub name: String,
+    pub id: String,
+}
+
+impl QueuedReply {
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.send_at <= now
+    }
+}
+
+/// Queued replies stored one JSON object per line. Changes hold an exclusive
+/// lock on a sibling `.lock` file and replace the file by renaming, so
+/// concurrent `groove` processes neither lose updates nor read a half
+/// written queue.
+pub struct Outbox {
+    path: PathBuf,
+}
+
+impl Outbox {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Default location in the platform data directory
+    pub fn default_path() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "groove-cli").map(|dirs| dirs.data_dir().join("outbox.jsonl"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every queued reply, soonest first
+    pub fn all(&self) -> Result<Vec<QueuedReply>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut replies = contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect::<Result<Vec<QueuedReply>>>()?;
+        replies.sort_by_key(|r| (r.send_at, r.id));
+        Ok(replies)
+    }
+
+    pub fn add(
+        &self,
+        number: i64,
+        body: String,
+        options: ReplyOptions,
+        send_at: DateTime<Utc>,
+    ) -> Result<QueuedReply> {
+        let _lock = self.lock()?;
+        let mut replies = self.all()?;
+        let reply = QueuedReply {
+            id: replies.iter().map(|r| r.id).max().unwrap_or(0) + 1,
+            number,
+            body,
+            options,
+            send_at,
+            created_at: Utc::now(),
+        };
+        replies.push(reply.clone());
+        self.save(&replies)?;
+        Ok(reply)
+    }
+
+    /// Put back a reply taken with [`take_due`](Self::take_due), e.g. after
+    /// sending it failed
+    pub fn restore(&self, reply: QueuedReply) -> Result<()> {
+        let _lock = self.lock()?;
+        let mut replies = self.all()?;
+        replies.push(reply);
+        self.save(&replies)
+    }
+
+    /// Delete a queued reply, returning it if it existed
+    pub fn remove(&self, id: u64) -> Result<Option<QueuedReply>> {
+        let _lock = self.lock()?;
+        let mut replies = self.all()?;
+        let Some(index) = replies.iter().position(|r| r.id == id) else {
+            return Ok(None);
+        };
+        let removed = replies.remove(index);
+        self.save(&replies)?;
+        Ok(Some(removed))
+    }
+
+    /// Remove and return the replies due by `now`. They're taken under the
+    /// outbox lock before sending, so a second dispatcher can't send them
+    /// again; failures should be [`restore`](Self::restore)d.
+    pub fn take_due(&self, now: DateTime<Utc>) -> Result<Vec<QueuedReply>> {
+        let _lock = self.lock()?;
+        let (due, pending): (Vec<_>, Vec<_>) = self.all()?.into_iter().partition(|r| r.is_due(now));
+        if !due.is_empty() {
+            self.save(&pending)?;
+        }
+        Ok(due)
+    }
+
+    /// Block until no other process is changing the outbox. The lock is
+    /// released when the returned file is dropped.
+    fn lock(&self) -> Result<File> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = File::create(self.sibling("lock"))?;
+        file.lock()?;
+        Ok(file)
+    }
+
+    /// Replace the queue with `replies`. Callers hold [`lock`](Self::lock).
+    fn save(&self, replies: &[QueuedReply]) -> Result<()> {
+        let mut contents = String::new();
+        for reply in replies {
+            contents.push_str(&serde_json::to_string(reply)?);
+            contents.push('\n');
+        }
+        let tmp = self.sibling("tmp");
+        std::fs::write(&tmp, contents)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// `outbox.jsonl.<extension>`, next to the queue
+    fn sibling(&self, extension: &str) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(extension);
+        self.path.with_file_name(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_take_due_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let outbox = Outbox::new(dir.path().join("outbox.jsonl"));
+        assert!(outbox.all().unwrap().is_empty());
+
+        let later = outbox
+            .add(
+                7,
+                "Later".into(),
+                ReplyOptions::default(),
+                Utc::now() + Duration::days(1),
+            )
+            .unwrap();
+        let options = ReplyOptions {
+            tag_ids: vec!["tag-1".into()],
+            close: true,
+            ..ReplyOptions::default()
+        };
+        outbox
+            .add(
+                8,
+                "Now".into(),
+                options.clone(),
+                Utc::now() - Duration::minutes(1),
+            )
+            .unwrap();
+
+        let due = outbox.take_due(Utc::now()).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].number, 8);
+        assert_eq!(due[0].options, options);
+        assert_eq!(outbox.all().unwrap(), vec![later.clone()]);
+
+        // A failed send goes back in the queue, soonest first
+        outbox.restore(due[0].clone()).unwrap();
+        let all = outbox.all().unwrap();
+        assert_eq!(all.iter().map(|r| r.number).collect::<Vec<_>>(), [8, 7]);
+
+        assert_eq!(outbox.remove(later.id).unwrap(), Some(later));
+        assert!(outbox.remove(99).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_concurrent_adds_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("outbox.jsonl");
+        let threads: Vec<_> = (0..8)
+            .map(|number| {
+                let outbox = Outbox::new(&path);
+                std::thread::spawn(move || {
+                    outbox
+                        .add(number, "Hi".into(), ReplyOptions::default(), Utc::now())
+                        .unwrap();
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let mut ids: Vec<_> = Outbox::new(&path)
+            .all()
+            .unwrap()
+            .iter()
+            .map(|r| r.id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, (1..=8).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,172 @@
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A private follow-up on a conversation, kept locally so the conversation
+/// itself doesn't have to be snoozed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reminder {
+    /// Short ID for `groove remind done`
+    pub id: u64,
+    pub number: i64,
+    pub due: DateTime<Utc>,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Set once `groove watch` has announced the reminder
+    #[serde(default)]
+    pub notified: bool,
+}
+
+impl Reminder {
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.due <= now
+    }
+}
+
+/// Reminders stored one JSON object per line
+pub struct Reminders {
+    path: PathBuf,
+}
+
+impl Reminders {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Default location in the platform data directory
+    pub fn default_path() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "groove-cli").map(|dirs| dirs.data_dir().join("reminders.jsonl"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every reminder, soonest first
+    pub fn all(&self) -> Result<Vec<Reminder>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut reminders = contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect::<Result<Vec<Reminder>>>()?;
+        reminders.sort_by_key(|r| (r.due, r.id));
+        Ok(reminders)
+    }
+
+    pub fn add(&self, number: i64, due: DateTime<Utc>, note: Option<String>) -> Result<Reminder> {
+        let mut reminders = self.all()?;
+        let reminder = Reminder {
+            id: reminders.iter().map(|r| r.id).max().unwrap_or(0) + 1,
+            number,
+            due,
+            note,
+            created_at: Utc::now(),
+            notified: false,
+        };
+        reminders.push(reminder.clone());
+        self.save(&reminders)?;
+        Ok(reminder)
+    }
+
+    /// Delete a reminder, returning it if it existed
+    pub fn remove(&self, id: u64) -> Result<Option<Reminder>> {
+        let mut reminders = self.all()?;
+        let Some(index) = reminders.iter().position(|r| r.id == id) else {
+            return Ok(None);
+        };
+        let removed = reminders.remove(index);
+        self.save(&reminders)?;
+        Ok(Some(removed))
+    }
+
+    /// Reminders that have come due since they were last announced, marking
+    /// them as announced
+    pub fn take_due(&self, now: DateTime<Utc>) -> Result<Vec<Reminder>> {
+        let mut reminders = self.all()?;
+        let mut due = Vec::new();
+        for reminder in reminders.iter_mut() {
+            if reminder.is_due(now) && !reminder.notified {
+                reminder.notified = true;
+                due.push(reminder.clone());
+            }
+        }
+        if !due.is_empty() {
+            self.save(&reminders)?;
+        }
+        Ok(due)
+    }
+
+    fn save(&self, reminders: &[Reminder]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut contents = String::new();
+        for reminder in reminders {
+            contents.push_str(&serde_json::to_string(reminder)?);
+            contents.push('\n');
+        }
+        std::fs::write(&self.path, contents)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_add_list_and_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let reminders = Reminders::new(dir.path().join("reminders.jsonl"));
+        assert!(reminders.all().unwrap().is_empty());
+
+        let later = reminders
+            .add(
+                7,
+                Utc::now() + Duration::days(2),
+                Some("check deploy".into()),
+            )
+            .unwrap();
+        let sooner = reminders
+            .add(8, Utc::now() + Duration::hours(1), None)
+            .unwrap();
+        assert_eq!((later.id, sooner.id), (1, 2));
+
+        // Soonest first
+        let all = reminders.all().unwrap();
+        assert_eq!(all.iter().map(|r| r.number).collect::<Vec<_>>(), [8, 7]);
+
+        assert_eq!(reminders.remove(1).unwrap().unwrap().number, 7);
+        assert!(reminders.remove(1).unwrap().is_none());
+        assert_eq!(reminders.all().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_take_due_announces_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let reminders = Reminders::new(dir.path().join("reminders.jsonl"));
+        reminders
+            .add(7, Utc::now() - Duration::minutes(5), None)
+            .unwrap();
+        reminders
+            .add(8, Utc::now() + Duration::days(1), None)
+            .unwrap();
+
+        let due = reminders.take_due(Utc::now()).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].number, 7);
+        assert!(reminders.take_due(Utc::now()).unwrap().is_empty());
+
+        // Still listed until marked done
+        assert!(reminders.all().unwrap()[0].notified);
+    }
+}
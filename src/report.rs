@@ -0,0 +1,142 @@
+//! Figures for `groove report`, aggregated from conversations and their
+//! response times.
+
+use chrono::Duration;
+
+use crate::sla::ResponseTimes;
+use crate::types::{Conversation, ConversationState};
+
+/// Name used for conversations that didn't arrive through a channel
+pub const NO_CHANNEL: &str = "(no channel)";
+
+/// Volume and response times of one channel (mailbox or widget)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelStats {
+    pub channel: String,
+    pub conversations: usize,
+    /// Unread or open, i.e. still waiting on an agent
+    pub open: usize,
+    pub median_first_response: Option<Duration>,
+    /// Median over every agent reply, not just the first
+    pub median_response: Option<Duration>,
+}
+
+/// Per-channel figures for `conversations`, busiest channel first
+pub fn channel_stats(conversations: &[(Conversation, ResponseTimes)]) -> Vec<ChannelStats> {
+    let mut channels: Vec<(String, Vec<&(Conversation, ResponseTimes)>)> = Vec::new();
+    for entry in conversations {
+        let name = entry
+            .0
+            .channel
+            .as_ref()
+            .map(|c| c.name.clone().unwrap_or_else(|| c.id.clone()))
+            .unwrap_or_else(|| NO_CHANNEL.to_string());
+        match channels.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, entries)) => entries.push(entry),
+            None => channels.push((name, vec![entry])),
+        }
+    }
+
+    let mut stats: Vec<ChannelStats> = channels
+        .into_iter()
+        .map(|(channel, entries)| ChannelStats {
+            channel,
+            conversations: entries.len(),
+            open: entries
+                .iter()
+                .filter(|(c, _)| {
+                    matches!(
+                        c.state,
+                        ConversationState::Unread | ConversationState::Opened
+                    )
+                })
+                .count(),
+            median_first_response: median(
+                entries
+                    .iter()
+                    .filter_map(|(_, t)| t.first_response())
+                    .collect(),
+            ),
+            median_response: median(
+                entries
+                    .iter()
+                    .flat_map(|(_, t)| t.responses.iter().copied())
+                    .collect(),
+            ),
+        })
+        .collect();
+    stats.sort_by(|a, b| {
+        b.conversations
+            .cmp(&a.conversations)
+            .then_with(|| a.channel.cmp(&b.channel))
+    });
+    stats
+}
+
+fn median(mut durations: Vec<Duration>) -> Option<Duration> {
+    durations.sort();
+    let mid = durations.len() / 2;
+    match durations.len() {
+        0 => None,
+        n if n % 2 == 1 => Some(durations[mid]),
+        _ => Some((durations[mid - 1] + durations[mid]) / 2),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation(channel: Option<&str>, state: ConversationState) -> Conversation {
+        Conversation::builder(1)
+            .id("conv")
+            .state(state)
+            .channel(channel)
+            .build()
+    }
+
+    fn replied_in(minutes: &[i64]) -> ResponseTimes {
+        ResponseTimes {
+            responses: minutes.iter().map(|m| Duration::minutes(*m)).collect(),
+            waiting_since: None,
+        }
+    }
+
+    #[test]
+    fn test_channel_stats() {
+        let conversations = [
+            (
+                conversation(Some("Billing"), ConversationState::Closed),
+                replied_in(&[60, 10]),
+            ),
+            (
+                conversation(Some("Support"), ConversationState::Opened),
+                replied_in(&[30]),
+            ),
+            (
+                conversation(Some("Support"), ConversationState::Unread),
+                replied_in(&[]),
+            ),
+            (
+                conversation(Some("Support"), ConversationState::Closed),
+                replied_in(&[90, 20]),
+            ),
+            (
+                conversation(None, ConversationState::Opened),
+                replied_in(&[]),
+            ),
+        ];
+        let stats = channel_stats(&conversations);
+        let channels: Vec<&str> = stats.iter().map(|s| s.channel.as_str()).collect();
+        assert_eq!(channels, ["Support", NO_CHANNEL, "Billing"]);
+
+        let support = &stats[0];
+        assert_eq!(support.conversations, 3);
+        assert_eq!(support.open, 2);
+        assert_eq!(support.median_first_response, Some(Duration::minutes(60)));
+        assert_eq!(support.median_response, Some(Duration::minutes(30)));
+
+        assert_eq!(stats[1].median_first_response, None);
+        assert_eq!(stats[2].median_response, Some(Duration::minutes(35)));
+    }
+}
@@ -0,0 +1,124 @@
+use crate::error::Result;
+use crate::types::Conversation;
+use chrono::{DateTime, Utc};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// How a conversation looked when it was last viewed here
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastSeen {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages_count: Option<u32>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Conversations viewed with `groove conversation view`, kept locally so
+/// lists can mark new activity regardless of Groove's shared unread state
+#[derive(Debug, Clone, PartialEq)]
+pub struct Seen {
+    path: PathBuf,
+    conversations: HashMap<i64, LastSeen>,
+}
+
+impl Seen {
+    /// Read the record at `path`, starting empty if there isn't one
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let conversations = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path,
+            conversations,
+        })
+    }
+
+    /// Default location in the platform data directory, one file per profile
+    /// since conversation numbers are per account
+    pub fn default_path(profile: Option<&str>) -> Option<PathBuf> {
+        ProjectDirs::from("", "", "groove-cli").map(|dirs| {
+            dirs.data_dir()
+                .join("seen")
+                .join(format!("{}.json", profile.unwrap_or("default")))
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether `conv` has new messages since it was last viewed, or has
+    /// never been viewed. Without message counts to compare, any update
+    /// counts.
+    pub fn has_news(&self, conv: &Conversation) -> bool {
+        let Some(seen) = self.conversations.get(&conv.number) else {
+            return true;
+        };
+        match (conv.messages_count, seen.messages_count) {
+            (Some(now), Some(then)) => now > then,
+            _ => conv.updated_at > seen.updated_at,
+        }
+    }
+
+    /// Record `conv` as viewed as it is now
+    pub fn mark(&mut self, conv: &Conversation) -> Result<()> {
+        self.conversations.insert(
+            conv.number,
+            LastSeen {
+                messages_count: conv.messages_count,
+                updated_at: conv.updated_at,
+            },
+        );
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string(&self.conversations)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn conversation(number: i64, messages_count: Option<u32>) -> Conversation {
+        Conversation::builder(number)
+            .messages_count(messages_count)
+            .build()
+    }
+
+    #[test]
+    fn test_news_since_last_view() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("seen").join("default.json");
+        let mut seen = Seen::open(&path).unwrap();
+
+        let mut conv = conversation(7, Some(2));
+        assert!(seen.has_news(&conv));
+        seen.mark(&conv).unwrap();
+        assert!(!seen.has_news(&conv));
+
+        // Tagging or assigning touches the conversation but isn't news
+        conv.updated_at += Duration::minutes(5);
+        assert!(!seen.has_news(&conv));
+        conv.messages_count = Some(3);
+        assert!(seen.has_news(&conv));
+
+        // Kept across runs
+        let reopened = Seen::open(&path).unwrap();
+        assert!(!reopened.has_news(&conversation(7, Some(2))));
+
+        // Falls back to the update time without message counts
+        let mut conv = conversation(8, None);
+        seen.mark(&conv).unwrap();
+        assert!(!seen.has_news(&conv));
+        conv.updated_at += Duration::minutes(5);
+        assert!(seen.has_news(&conv));
+    }
+}
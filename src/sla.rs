@@ -0,0 +1,185 @@
+//! Response times: how long a customer has been waiting for a reply,
+//! counted from their first message since an agent last replied, and how
+//! long agents took to reply in the past.
+
+use crate::types::{ConversationState, Message};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+/// Newest messages fetched per conversation to find when the customer
+/// started waiting
+pub const RECENT_MESSAGES: i32 = 5;
+
+/// When the customer started waiting for a reply, if the newest message is
+/// theirs. `messages` are oldest first. If every one is from the customer,
+/// the earliest given is used.
+pub fn waiting_since(messages: &[Message]) -> Option<DateTime<Utc>> {
+    messages
+        .iter()
+        .rev()
+        .take_while(|m| is_from_customer(m))
+        .last()
+        .map(|m| m.created_at)
+}
+
+fn is_from_customer(message: &Message) -> bool {
+    author_type(message) == Some("Contact")
+}
+
+fn is_from_agent(message: &Message) -> bool {
+    author_type(message) == Some("Agent")
+}
+
+fn author_type(message: &Message) -> Option<&str> {
+    message.author.as_ref().and_then(|a| a.typename.as_deref())
+}
+
+/// How long agents took to answer the customer in one conversation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResponseTimes {
+    /// From the first message of each run of customer messages to the agent
+    /// reply that ended it, oldest first
+    pub responses: Vec<Duration>,
+    /// When the customer started waiting, if the last run is unanswered
+    pub waiting_since: Option<DateTime<Utc>>,
+}
+
+impl ResponseTimes {
+    /// Measure a whole thread, oldest message first
+    pub fn of(messages: &[Message]) -> Self {
+        let mut times = ResponseTimes::default();
+        for message in messages {
+            if is_from_customer(message) {
+                times.waiting_since.get_or_insert(message.created_at);
+            } else if is_from_agent(message) {
+                if let Some(since) = times.waiting_since.take() {
+                    times.responses.push(message.created_at - since);
+                }
+            }
+        }
+        times
+    }
+
+    /// Time to the first agent reply
+    pub fn first_response(&self) -> Option<Duration> {
+        self.responses.first().copied()
+    }
+
+    /// Mean time to every agent reply, the first included
+    pub fn average_response(&self) -> Option<Duration> {
+        let count = i32::try_from(self.responses.len())
+            .ok()
+            .filter(|n| *n > 0)?;
+        Some(self.responses.iter().sum::<Duration>() / count)
+    }
+}
+
+/// Conversations in these states are waiting on an agent
+pub fn applies_to(state: ConversationState) -> bool {
+    matches!(state, ConversationState::Unread | ConversationState::Opened)
+}
+
+/// A response-time target and how long each listed conversation has waited
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sla {
+    pub response_time: Duration,
+    /// When the customer started waiting, by conversation number; missing
+    /// when nobody is waiting
+    pub waiting: HashMap<i64, DateTime<Utc>>,
+}
+
+impl Sla {
+    pub fn new(response_time: Duration) -> Self {
+        Self {
+            response_time,
+            waiting: HashMap::new(),
+        }
+    }
+
+    /// How long conversation `number` has waited, if it is waiting
+    pub fn waited(&self, number: i64, now: DateTime<Utc>) -> Option<Duration> {
+        self.waiting.get(&number).map(|since| now - *since)
+    }
+
+    /// Whether conversation `number` has waited longer than the target
+    pub fn is_breaching(&self, number: i64, now: DateTime<Utc>) -> bool {
+        self.waited(number, now)
+            .is_some_and(|waited| waited > self.response_time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MessageAuthor;
+
+    fn message(minutes_ago: i64, typename: &str) -> Message {
+        Message {
+            id: format!("msg-{}", minutes_ago),
+            created_at: DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+                - Duration::minutes(minutes_ago),
+            body_text: None,
+            body_html: None,
+            author: Some(MessageAuthor {
+                typename: Some(typename.to_string()),
+                id: "author".to_string(),
+                email: None,
+                name: None,
+            }),
+            attachments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_waiting_since_first_unanswered_message() {
+        let thread = [
+            message(600, "Contact"),
+            message(500, "Agent"),
+            message(300, "Contact"),
+            message(120, "Contact"),
+        ];
+        assert_eq!(waiting_since(&thread), Some(thread[2].created_at));
+
+        // Answered
+        assert_eq!(waiting_since(&thread[..2]), None);
+        assert_eq!(waiting_since(&[]), None);
+    }
+
+    #[test]
+    fn test_response_times() {
+        let thread = [
+            message(600, "Contact"),
+            message(590, "Contact"),
+            message(480, "Agent"),
+            message(470, "Agent"),
+            message(300, "Contact"),
+            message(240, "Agent"),
+            message(60, "Contact"),
+        ];
+        let times = ResponseTimes::of(&thread);
+        assert_eq!(
+            times.responses,
+            vec![Duration::minutes(120), Duration::minutes(60)]
+        );
+        assert_eq!(times.first_response(), Some(Duration::minutes(120)));
+        assert_eq!(times.average_response(), Some(Duration::minutes(90)));
+        assert_eq!(times.waiting_since, Some(thread[6].created_at));
+
+        // Outbound: nothing to respond to until the customer writes
+        let times = ResponseTimes::of(&thread[2..4]);
+        assert_eq!(times, ResponseTimes::default());
+        assert_eq!(times.average_response(), None);
+    }
+
+    #[test]
+    fn test_breaching() {
+        let now = Utc::now();
+        let mut sla = Sla::new(Duration::hours(4));
+        sla.waiting.insert(1, now - Duration::hours(5));
+        sla.waiting.insert(2, now - Duration::hours(1));
+        assert!(sla.is_breaching(1, now));
+        assert!(!sla.is_breaching(2, now));
+        assert!(!sla.is_breaching(3, now));
+        assert_eq!(sla.waited(2, now), Some(Duration::hours(1)));
+    }
+}
@@ -0,0 +1,123 @@
+use crate::error::{GrooveError, Result};
+use directories::ProjectDirs;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// A personal piece of boilerplate, unlike the team's shared canned replies
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Snippet {
+    pub name: String,
+    pub body: String,
+}
+
+/// Snippets stored as one `<name>.txt` file each, so they can also be
+/// written with an editor
+pub struct Snippets {
+    dir: PathBuf,
+}
+
+impl Snippets {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Default location in the platform config directory, next to
+    /// `config.toml`
+    pub fn default_dir() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "groove-cli").map(|dirs| dirs.config_dir().join("snippets"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Every snippet, by name
+    pub fn all(&self) -> Result<Vec<Snippet>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut snippets = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "txt") {
+                if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                    snippets.push(Snippet {
+                        name: name.to_string(),
+                        body: std::fs::read_to_string(&path)?,
+                    });
+                }
+            }
+        }
+        snippets.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(snippets)
+    }
+
+    /// Save a snippet, replacing any with the same name
+    pub fn add(&self, name: &str, body: &str) -> Result<()> {
+        let path = self.path(name)?;
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(path, body)?;
+        Ok(())
+    }
+
+    /// Delete a snippet, returning whether it existed
+    pub fn remove(&self, name: &str) -> Result<bool> {
+        match std::fs::remove_file(self.path(name)?) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf> {
+        let valid = !name.is_empty()
+            && !name.starts_with('.')
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid {
+            return Err(GrooveError::Config(format!(
+                "Invalid snippet name '{}': use letters, digits, '-', '_', and '.'",
+                name
+            )));
+        }
+        Ok(self.dir.join(format!("{}.txt", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_list_and_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let snippets = Snippets::new(dir.path().join("snippets"));
+        assert!(snippets.all().unwrap().is_empty());
+
+        snippets.add("sig", "Cheers,\nAlex").unwrap();
+        snippets.add("apology", "Sorry for the wait!").unwrap();
+        snippets.add("sig", "Thanks,\nAlex").unwrap();
+        std::fs::write(dir.path().join("snippets/notes.md"), "ignored").unwrap();
+
+        let all = snippets.all().unwrap();
+        assert_eq!(
+            all.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+            ["apology", "sig"]
+        );
+        assert_eq!(all[1].body, "Thanks,\nAlex");
+
+        assert!(snippets.remove("sig").unwrap());
+        assert!(!snippets.remove("sig").unwrap());
+    }
+
+    #[test]
+    fn test_rejects_path_like_names() {
+        let snippets = Snippets::new("/tmp/snippets");
+        for name in ["", "../secret", "a/b", ".hidden"] {
+            assert!(snippets.add(name, "x").is_err(), "{:?}", name);
+        }
+    }
+}
@@ -0,0 +1,612 @@
+mod sync;
+
+pub use sync::{sync, SyncOptions, SyncSummary};
+
+use crate::error::Result;
+use crate::types::*;
+use chrono::{DateTime, Utc};
+use directories::ProjectDirs;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS conversations (
+    id TEXT PRIMARY KEY,
+    number INTEGER NOT NULL UNIQUE,
+    subject TEXT,
+    state TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    assigned_agent_id TEXT,
+    contact_id TEXT,
+    channel_id TEXT,
+    data TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS messages (
+    id TEXT PRIMARY KEY,
+    conversation_id TEXT NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL,
+    author_type TEXT,
+    author_id TEXT,
+    author_email TEXT,
+    author_name TEXT,
+    body_text TEXT,
+    body_html TEXT
+);
+CREATE INDEX IF NOT EXISTS messages_conversation ON messages(conversation_id);
+
+CREATE TABLE IF NOT EXISTS notes (
+    id TEXT PRIMARY KEY,
+    conversation_id TEXT NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL,
+    author_id TEXT,
+    author_email TEXT,
+    author_name TEXT,
+    body TEXT
+);
+CREATE INDEX IF NOT EXISTS notes_conversation ON notes(conversation_id);
+
+CREATE TABLE IF NOT EXISTS contacts (
+    id TEXT PRIMARY KEY,
+    email TEXT,
+    name TEXT
+);
+
+CREATE TABLE IF NOT EXISTS tags (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    color TEXT
+);
+
+CREATE TABLE IF NOT EXISTS conversation_tags (
+    conversation_id TEXT NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    tag_id TEXT NOT NULL,
+    PRIMARY KEY (conversation_id, tag_id)
+);
+
+CREATE TABLE IF NOT EXISTS sync_state (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+    kind UNINDEXED,
+    conversation_id UNINDEXED,
+    item_id UNINDEXED,
+    content,
+    tokenize = 'porter unicode61'
+);
+"#;
+
+/// Rebuilds the full-text index from the stored rows
+const REBUILD_SEARCH_INDEX: &str = r#"
+DELETE FROM search_index;
+INSERT INTO search_index (kind, conversation_id, item_id, content)
+    SELECT 'subject', id, id, subject FROM conversations WHERE subject IS NOT NULL;
+INSERT INTO search_index (kind, conversation_id, item_id, content)
+    SELECT 'message', conversation_id, id, body_text FROM messages WHERE body_text IS NOT NULL;
+INSERT INTO search_index (kind, conversation_id, item_id, content)
+    SELECT 'note', conversation_id, id, body FROM notes WHERE body IS NOT NULL;
+"#;
+
+/// Where in a conversation a search match was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchHitKind {
+    Subject,
+    Message,
+    Note,
+}
+
+impl std::fmt::Display for SearchHitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchHitKind::Subject => write!(f, "subject"),
+            SearchHitKind::Message => write!(f, "message"),
+            SearchHitKind::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// A full-text match in the local store
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub number: i64,
+    pub subject: Option<String>,
+    pub state: String,
+    pub kind: SearchHitKind,
+    pub item_id: String,
+    /// Matching excerpt with matches wrapped in `SEARCH_MATCH_START`/`END`
+    pub snippet: String,
+}
+
+/// Markers wrapped around matched terms in `SearchHit::snippet`
+pub const SEARCH_MATCH_START: &str = "\u{2}";
+pub const SEARCH_MATCH_END: &str = "\u{3}";
+
+/// Row counts for the local mirror
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StoreCounts {
+    pub conversations: i64,
+    pub messages: i64,
+    pub notes: i64,
+    pub contacts: i64,
+    pub tags: i64,
+}
+
+/// Local SQLite mirror of conversations, messages, tags, and contacts
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Default database location in the platform data directory
+    pub fn default_path() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "groove-cli").map(|dirs| dirs.data_dir().join("groove.db"))
+    }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        conn.execute_batch(SCHEMA)?;
+        let store = Self { conn };
+
+        // Databases synced before the index existed get it populated once
+        let indexed: i64 =
+            store
+                .conn
+                .query_row("SELECT COUNT(*) FROM search_index", [], |row| row.get(0))?;
+        if indexed == 0 && store.counts()?.conversations > 0 {
+            store.rebuild_search_index()?;
+        }
+        Ok(store)
+    }
+
+    pub fn rebuild_search_index(&self) -> Result<()> {
+        self.conn.execute_batch(REBUILD_SEARCH_INDEX)?;
+        Ok(())
+    }
+
+    fn reindex(
+        tx: &rusqlite::Transaction<'_>,
+        kind: SearchHitKind,
+        conversation_id: &str,
+        items: &[(&str, Option<&str>)],
+    ) -> Result<()> {
+        tx.execute(
+            "DELETE FROM search_index WHERE kind = ?1 AND conversation_id = ?2",
+            params![kind.to_string(), conversation_id],
+        )?;
+        for (item_id, content) in items {
+            if let Some(content) = content {
+                tx.execute(
+                    "INSERT INTO search_index (kind, conversation_id, item_id, content)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![kind.to_string(), conversation_id, item_id, content],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn upsert_conversation(&self, conv: &Conversation) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        Self::write_conversation(&tx, conv)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Store a conversation together with its messages and notes, in one
+    /// transaction so a conversation is never recorded as up to date
+    /// without its thread
+    pub fn store_thread(
+        &self,
+        conv: &Conversation,
+        messages: &[Message],
+        notes: &[Note],
+    ) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        Self::write_conversation(&tx, conv)?;
+        Self::write_messages(&tx, &conv.id, messages)?;
+        Self::write_notes(&tx, &conv.id, notes)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn write_conversation(tx: &rusqlite::Transaction<'_>, conv: &Conversation) -> Result<()> {
+        if let Some(contact) = &conv.contact {
+            tx.execute(
+                "INSERT INTO contacts (id, email, name) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(id) DO UPDATE SET email = excluded.email, name = excluded.name",
+                params![contact.id, contact.email, contact.name],
+            )?;
+        }
+
+        tx.execute(
+            "INSERT INTO conversations
+                (id, number, subject, state, created_at, updated_at,
+                 assigned_agent_id, contact_id, channel_id, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(id) DO UPDATE SET
+                number = excluded.number,
+                subject = excluded.subject,
+                state = excluded.state,
+                created_at = excluded.created_at,
+                updated_at = excluded.updated_at,
+                assigned_agent_id = excluded.assigned_agent_id,
+                contact_id = excluded.contact_id,
+                channel_id = excluded.channel_id,
+                data = excluded.data",
+            params![
+                conv.id,
+                conv.number,
+                conv.subject,
+                conv.state.to_string(),
+                conv.created_at.to_rfc3339(),
+                conv.updated_at.to_rfc3339(),
+                conv.assigned.as_ref().map(|a| &a.id),
+                conv.contact.as_ref().map(|c| &c.id),
+                conv.channel.as_ref().map(|c| &c.id),
+                serde_json::to_string(conv)?,
+            ],
+        )?;
+
+        tx.execute(
+            "DELETE FROM conversation_tags WHERE conversation_id = ?1",
+            params![conv.id],
+        )?;
+        for tag in &conv.tags {
+            tx.execute(
+                "INSERT INTO tags (id, name, color) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(id) DO UPDATE SET name = excluded.name, color = excluded.color",
+                params![tag.id, tag.name, tag.color],
+            )?;
+            tx.execute(
+                "INSERT OR IGNORE INTO conversation_tags (conversation_id, tag_id) VALUES (?1, ?2)",
+                params![conv.id, tag.id],
+            )?;
+        }
+
+        Self::reindex(
+            tx,
+            SearchHitKind::Subject,
+            &conv.id,
+            &[(conv.id.as_str(), conv.subject.as_deref())],
+        )
+    }
+
+    /// Last `updatedAt` recorded for a conversation, if it has been synced
+    pub fn conversation_updated_at(&self, id: &str) -> Result<Option<DateTime<Utc>>> {
+        let value: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT updated_at FROM conversations WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value
+            .and_then(|v| DateTime::parse_from_rfc3339(&v).ok())
+            .map(|dt| dt.with_timezone(&Utc)))
+    }
+
+    pub fn conversation_by_number(&self, number: i64) -> Result<Option<Conversation>> {
+        let data: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT data FROM conversations WHERE number = ?1",
+                params![number],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(data.map(|d| serde_json::from_str(&d)).transpose()?)
+    }
+
+    /// Replace all stored messages of a conversation
+    pub fn replace_messages(&self, conversation_id: &str, messages: &[Message]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        Self::write_messages(&tx, conversation_id, messages)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn write_messages(
+        tx: &rusqlite::Transaction<'_>,
+        conversation_id: &str,
+        messages: &[Message],
+    ) -> Result<()> {
+        tx.execute(
+            "DELETE FROM messages WHERE conversation_id = ?1",
+            params![conversation_id],
+        )?;
+        for msg in messages {
+            let author = msg.author.as_ref();
+            tx.execute(
+                "INSERT OR REPLACE INTO messages
+                    (id, conversation_id, created_at, author_type, author_id,
+                     author_email, author_name, body_text, body_html)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    msg.id,
+                    conversation_id,
+                    msg.created_at.to_rfc3339(),
+                    author.and_then(|a| a.typename.as_deref()),
+                    author.map(|a| &a.id),
+                    author.and_then(|a| a.email.as_deref()),
+                    author.and_then(|a| a.name.as_deref()),
+                    msg.body_text,
+                    msg.body_html,
+                ],
+            )?;
+        }
+
+        let items: Vec<_> = messages
+            .iter()
+            .map(|m| (m.id.as_str(), m.body_text.as_deref()))
+            .collect();
+        Self::reindex(tx, SearchHitKind::Message, conversation_id, &items)
+    }
+
+    /// Replace all stored notes of a conversation
+    pub fn replace_notes(&self, conversation_id: &str, notes: &[Note]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        Self::write_notes(&tx, conversation_id, notes)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn write_notes(
+        tx: &rusqlite::Transaction<'_>,
+        conversation_id: &str,
+        notes: &[Note],
+    ) -> Result<()> {
+        tx.execute(
+            "DELETE FROM notes WHERE conversation_id = ?1",
+            params![conversation_id],
+        )?;
+        for note in notes {
+            let author = note.author.as_ref();
+            tx.execute(
+                "INSERT OR REPLACE INTO notes
+                    (id, conversation_id, created_at, author_id, author_email, author_name, body)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    note.id,
+                    conversation_id,
+                    note.created_at.to_rfc3339(),
+                    author.map(|a| &a.id),
+                    author.and_then(|a| a.email.as_deref()),
+                    author.and_then(|a| a.name.as_deref()),
+                    note.body,
+                ],
+            )?;
+        }
+
+        let items: Vec<_> = notes
+            .iter()
+            .map(|n| (n.id.as_str(), n.body.as_deref()))
+            .collect();
+        Self::reindex(tx, SearchHitKind::Note, conversation_id, &items)
+    }
+
+    /// Full-text search over subjects, message bodies, and notes.
+    ///
+    /// Uses SQLite FTS5 query syntax: `"exact phrase"`, `AND`/`OR`/`NOT`,
+    /// and `prefix*`. Results are ordered by relevance.
+    pub fn search(&self, query: &str, limit: u32) -> Result<Vec<SearchHit>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT c.number, c.subject, c.state, s.kind, s.item_id,
+                    snippet(search_index, 3, ?2, ?3, '…', 16)
+             FROM search_index s
+             JOIN conversations c ON c.id = s.conversation_id
+             WHERE search_index MATCH ?1
+             ORDER BY bm25(search_index)
+             LIMIT ?4",
+        )?;
+
+        let hits = stmt
+            .query_map(
+                params![query, SEARCH_MATCH_START, SEARCH_MATCH_END, limit],
+                |row| {
+                    let kind: String = row.get(3)?;
+                    Ok(SearchHit {
+                        number: row.get(0)?,
+                        subject: row.get(1)?,
+                        state: row.get(2)?,
+                        kind: match kind.as_str() {
+                            "subject" => SearchHitKind::Subject,
+                            "note" => SearchHitKind::Note,
+                            _ => SearchHitKind::Message,
+                        },
+                        item_id: row.get(4)?,
+                        snippet: row.get(5)?,
+                    })
+                },
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(hits)
+    }
+
+    pub fn upsert_tags(&self, tags: &[Tag]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for tag in tags {
+            tx.execute(
+                "INSERT INTO tags (id, name, color) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(id) DO UPDATE SET name = excluded.name, color = excluded.color",
+                params![tag.id, tag.name, tag.color],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_state(&self, key: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT value FROM sync_state WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn set_state(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO sync_state (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    pub fn clear_state(&self, key: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM sync_state WHERE key = ?1", params![key])?;
+        Ok(())
+    }
+
+    pub fn counts(&self) -> Result<StoreCounts> {
+        let count = |table: &str| -> Result<i64> {
+            Ok(self
+                .conn
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                    row.get(0)
+                })?)
+        };
+        Ok(StoreCounts {
+            conversations: count("conversations")?,
+            messages: count("messages")?,
+            notes: count("notes")?,
+            contacts: count("contacts")?,
+            tags: count("tags")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation(id: &str, number: i64) -> Conversation {
+        Conversation::builder(number)
+            .id(id)
+            .subject("Subject")
+            .contact("customer@example.com", None)
+            .tags(&["urgent"])
+            .build()
+    }
+
+    fn message(id: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            created_at: Utc::now(),
+            body_text: Some("Hello".to_string()),
+            body_html: None,
+            author: None,
+            attachments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_upsert_conversation() {
+        let store = Store::open_in_memory().unwrap();
+        let mut conv = conversation("conv-1", 1);
+        store.upsert_conversation(&conv).unwrap();
+
+        conv.subject = Some("Updated".to_string());
+        store.upsert_conversation(&conv).unwrap();
+
+        let stored = store.conversation_by_number(1).unwrap().unwrap();
+        assert_eq!(stored.subject, Some("Updated".to_string()));
+        assert!(store.conversation_updated_at("conv-1").unwrap().is_some());
+
+        let counts = store.counts().unwrap();
+        assert_eq!(counts.conversations, 1);
+        assert_eq!(counts.contacts, 1);
+        assert_eq!(counts.tags, 1);
+    }
+
+    #[test]
+    fn test_replace_messages() {
+        let store = Store::open_in_memory().unwrap();
+        store
+            .upsert_conversation(&conversation("conv-1", 1))
+            .unwrap();
+
+        store
+            .replace_messages("conv-1", &[message("m1"), message("m2")])
+            .unwrap();
+        assert_eq!(store.counts().unwrap().messages, 2);
+
+        store.replace_messages("conv-1", &[message("m3")]).unwrap();
+        assert_eq!(store.counts().unwrap().messages, 1);
+    }
+
+    #[test]
+    fn test_search() {
+        let store = Store::open_in_memory().unwrap();
+        store
+            .upsert_conversation(&conversation("conv-1", 1))
+            .unwrap();
+        let mut msg = message("m1");
+        msg.body_text = Some("My order number is 12345 and the invoice is wrong".to_string());
+        store.replace_messages("conv-1", &[msg]).unwrap();
+        store
+            .replace_notes(
+                "conv-1",
+                &[Note {
+                    id: "n1".to_string(),
+                    created_at: Utc::now(),
+                    body: Some("Escalated to billing team".to_string()),
+                    author: None,
+                }],
+            )
+            .unwrap();
+
+        let hits = store.search("invoice", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].number, 1);
+        assert_eq!(hits[0].kind, SearchHitKind::Message);
+        assert!(hits[0].snippet.contains(SEARCH_MATCH_START));
+
+        let hits = store.search("billing", 10).unwrap();
+        assert_eq!(hits[0].kind, SearchHitKind::Note);
+
+        assert_eq!(store.search("\"invoice is wrong\"", 10).unwrap().len(), 1);
+        assert_eq!(store.search("invoice NOT order", 10).unwrap().len(), 0);
+
+        // Replacing messages drops stale index entries
+        store.replace_messages("conv-1", &[]).unwrap();
+        assert!(store.search("invoice", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_invalid_query() {
+        let store = Store::open_in_memory().unwrap();
+        assert!(store.search("\"unterminated", 10).is_err());
+    }
+
+    #[test]
+    fn test_sync_state() {
+        let store = Store::open_in_memory().unwrap();
+        assert!(store.get_state("cursor").unwrap().is_none());
+        store.set_state("cursor", "abc").unwrap();
+        assert_eq!(store.get_state("cursor").unwrap(), Some("abc".to_string()));
+        store.clear_state("cursor").unwrap();
+        assert!(store.get_state("cursor").unwrap().is_none());
+    }
+}
@@ -0,0 +1,137 @@
+use super::Store;
+use crate::api::GrooveClient;
+use crate::error::Result;
+use crate::types::{ConversationFilter, DateField, DateRange};
+use chrono::{DateTime, Utc};
+
+/// Conversations fetched per page while syncing
+const SYNC_PAGE_SIZE: u32 = 50;
+
+/// Cursor of the last completed conversations page, for resuming
+const CURSOR_KEY: &str = "conversations_cursor";
+
+/// Start time of the last sync that ran to completion; the next one lists
+/// only conversations updated since
+const LAST_SYNC_KEY: &str = "last_sync_at";
+
+/// Start time of the sync in progress, recorded as the last sync once it
+/// completes, even if it was interrupted and resumed
+const RUN_STARTED_KEY: &str = "run_started_at";
+
+/// `updatedAt` lower bound of the sync in progress (empty for none), so a
+/// resumed run lists the same conversations its cursor came from
+const RUN_SINCE_KEY: &str = "run_since";
+
+#[derive(Debug, Default, Clone)]
+pub struct SyncOptions {
+    /// List every conversation and refetch its messages, not just the ones
+    /// updated since the last sync
+    pub full: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SyncSummary {
+    pub conversations_seen: usize,
+    pub conversations_updated: usize,
+    pub messages_fetched: usize,
+    pub notes_fetched: usize,
+    pub tags: usize,
+    /// Whether the sync picked up from an interrupted run
+    pub resumed: bool,
+}
+
+/// Mirror conversations, messages, notes, tags, and contacts into the local store.
+///
+/// Progress is checkpointed after each page, so an interrupted sync resumes
+/// where it stopped. After the first sync only conversations updated since
+/// the previous one started are listed, and messages are only refetched for
+/// conversations whose `updatedAt` changed since they were last stored
+/// (unless `full` is set).
+/// The full-text search index is kept up to date as rows are written.
+pub async fn sync(
+    client: &GrooveClient,
+    store: &Store,
+    options: &SyncOptions,
+) -> Result<SyncSummary> {
+    let mut summary = SyncSummary::default();
+
+    let tags = client.tags().await?;
+    store.upsert_tags(&tags)?;
+    summary.tags = tags.len();
+
+    let mut cursor = store.get_state(CURSOR_KEY)?;
+    summary.resumed = cursor.is_some();
+    if !summary.resumed {
+        let since = if options.full {
+            None
+        } else {
+            store.get_state(LAST_SYNC_KEY)?
+        };
+        store.set_state(RUN_STARTED_KEY, &Utc::now().to_rfc3339())?;
+        store.set_state(RUN_SINCE_KEY, since.as_deref().unwrap_or(""))?;
+    }
+    let filter = ConversationFilter {
+        dates: store
+            .get_state(RUN_SINCE_KEY)?
+            .as_deref()
+            .and_then(parse_time)
+            .map(|since| DateRange {
+                field: DateField::Updated,
+                since: Some(since),
+                until: None,
+            }),
+        ..ConversationFilter::default()
+    };
+
+    loop {
+        let page = client
+            .conversations(&filter, Some(SYNC_PAGE_SIZE), cursor.clone())
+            .await?;
+
+        for conv in &page.nodes {
+            summary.conversations_seen += 1;
+
+            let changed = options.full
+                || store
+                    .conversation_updated_at(&conv.id)?
+                    .is_none_or(|stored| stored < conv.updated_at);
+            if !changed {
+                store.upsert_conversation(conv)?;
+                continue;
+            }
+
+            // Fetch the whole thread before writing anything, so a failed
+            // fetch leaves the stored `updatedAt` behind and the next run
+            // tries this conversation again
+            let messages = client.all_messages(&conv.id).await?;
+            let notes = client.all_notes(&conv.id).await?;
+            store.store_thread(conv, &messages, &notes)?;
+            summary.conversations_updated += 1;
+            summary.messages_fetched += messages.len();
+            summary.notes_fetched += notes.len();
+        }
+
+        match page.page_info.end_cursor {
+            Some(next) if page.page_info.has_next_page => {
+                store.set_state(CURSOR_KEY, &next)?;
+                cursor = Some(next);
+            }
+            _ => break,
+        }
+    }
+
+    let started_at = store
+        .get_state(RUN_STARTED_KEY)?
+        .unwrap_or_else(|| Utc::now().to_rfc3339());
+    store.set_state(LAST_SYNC_KEY, &started_at)?;
+    for key in [CURSOR_KEY, RUN_STARTED_KEY, RUN_SINCE_KEY] {
+        store.clear_state(key)?;
+    }
+    Ok(summary)
+}
+
+fn parse_time(text: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(text)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
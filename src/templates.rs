@@ -0,0 +1,340 @@
+//! Reply templates: Markdown files whose front matter says what else to do
+//! with the conversation, and whose body can use `{{ variables }}` filled in
+//! from it.
+//!
+//! ```text
+//! ---
+//! subject: "Re: {{ conversation.subject }}"
+//! tags: [billing, refund]
+//! close: true
+//! ---
+//! Hi {{ contact.first_name | there }},
+//!
+//! We've issued your refund.
+//! ```
+//!
+//! Front matter is YAML; `tags` may be a single name or a list.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use directories::ProjectDirs;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::error::{GrooveError, Result};
+use crate::types::{Conversation, CurrentAgent};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Template {
+    pub subject: Option<String>,
+    /// Tag names to add once the reply is sent
+    pub tags: Vec<String>,
+    /// Close the conversation once the reply is sent
+    pub close: bool,
+    pub body: String,
+}
+
+/// The keys a template's front matter may set
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FrontMatter {
+    subject: Option<String>,
+    tags: Tags,
+    close: bool,
+}
+
+/// `tags: billing` or `tags: [billing, refund]`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Tags {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Default for Tags {
+    fn default() -> Self {
+        Tags::Many(Vec::new())
+    }
+}
+
+/// Values for a template's `{{ variables }}`. Every known name is present;
+/// `None` means the conversation doesn't have that value.
+#[derive(Debug, Clone, Default)]
+pub struct Variables(BTreeMap<&'static str, Option<String>>);
+
+impl Template {
+    /// Default location in the platform config directory, next to
+    /// `config.toml`
+    pub fn default_dir() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "groove-cli").map(|dirs| dirs.config_dir().join("templates"))
+    }
+
+    /// The template file for `name`: a path to an existing file, otherwise a
+    /// file in `dir`, with `.md` added if `name` has no extension
+    pub fn find(name: &str, dir: &Path) -> Result<PathBuf> {
+        let given = Path::new(name);
+        if given.is_file() {
+            return Ok(given.to_path_buf());
+        }
+        let mut path = dir.join(name);
+        if path.extension().is_none() {
+            path.set_extension("md");
+        }
+        if path.is_file() {
+            Ok(path)
+        } else {
+            Err(GrooveError::TemplateNotFound(path.display().to_string()))
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|e| match e {
+            GrooveError::Template(message) => {
+                GrooveError::Template(format!("{}: {}", path.display(), message))
+            }
+            e => e,
+        })
+    }
+
+    /// Split off and read the front matter. A file without any is all body.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut template = Template::default();
+        let Some(rest) = text
+            .strip_prefix("---\n")
+            .or_else(|| text.strip_prefix("---\r\n"))
+        else {
+            template.body = text.to_string();
+            return Ok(template);
+        };
+        let mut lines = rest.split_inclusive('\n');
+        let mut front = String::new();
+        let mut closed = false;
+        for line in lines.by_ref() {
+            if line.trim_end() == "---" {
+                closed = true;
+                break;
+            }
+            front.push_str(line);
+        }
+        if !closed {
+            return Err(GrooveError::Template(
+                "front matter isn't closed with '---'".to_string(),
+            ));
+        }
+        template.body = lines
+            .collect::<String>()
+            .trim_start_matches('\n')
+            .to_string();
+
+        // Blank or comment-only front matter is an empty YAML document
+        let front: FrontMatter = if front.lines().all(|l| {
+            let l = l.trim();
+            l.is_empty() || l.starts_with('#')
+        }) {
+            FrontMatter::default()
+        } else {
+            serde_yaml_ng::from_str(&front).map_err(|e| GrooveError::Template(e.to_string()))?
+        };
+        template.subject = front.subject;
+        template.tags = match front.tags {
+            Tags::One(tag) => vec![tag],
+            Tags::Many(tags) => tags,
+        };
+        template.close = front.close;
+        Ok(template)
+    }
+
+    /// Whether the subject or body use `agent.*` variables, which need the
+    /// current agent looked up
+    pub fn uses_agent(&self) -> bool {
+        variable_names(&self.body)
+            .chain(self.subject.iter().flat_map(|s| variable_names(s)))
+            .any(|name| name.starts_with("agent."))
+    }
+
+    /// The body and subject with variables filled in
+    pub fn render(&self, variables: &Variables) -> Result<(String, Option<String>)> {
+        let body = variables.fill(&self.body)?;
+        let subject = self
+            .subject
+            .as_deref()
+            .map(|s| variables.fill(s))
+            .transpose()?;
+        Ok((body, subject))
+    }
+}
+
+impl Variables {
+    pub fn new(conversation: &Conversation, agent: Option<&CurrentAgent>) -> Self {
+        let contact = conversation.contact.as_ref();
+        let contact_name = contact.and_then(|c| c.name.clone());
+        let agent_name = agent.and_then(|a| a.name.clone());
+        let mut values = BTreeMap::new();
+        values.insert("conversation.number", Some(conversation.number.to_string()));
+        values.insert("conversation.subject", conversation.subject.clone());
+        values.insert(
+            "contact.first_name",
+            contact_name.as_deref().map(first_name),
+        );
+        values.insert("contact.name", contact_name);
+        values.insert("contact.email", contact.and_then(|c| c.email.clone()));
+        values.insert("agent.first_name", agent_name.as_deref().map(first_name));
+        values.insert("agent.name", agent_name);
+        values.insert("agent.email", agent.map(|a| a.email.clone()));
+        Self(values)
+    }
+
+    /// Replace each `{{ name }}` or `{{ name | default }}` in `text`. A
+    /// missing value uses the default, or nothing.
+    pub fn fill(&self, text: &str) -> Result<String> {
+        let mut unknown = None;
+        let filled = variable_pattern().replace_all(text, |caps: &regex::Captures| {
+            match self.0.get(&caps[1]) {
+                Some(Some(value)) if !value.is_empty() => value.clone(),
+                Some(_) => caps
+                    .get(2)
+                    .map(|default| default.as_str().to_string())
+                    .unwrap_or_default(),
+                None => {
+                    unknown.get_or_insert_with(|| caps[1].to_string());
+                    String::new()
+                }
+            }
+        });
+        if let Some(name) = unknown {
+            let known: Vec<&str> = self.0.keys().copied().collect();
+            return Err(GrooveError::Template(format!(
+                "unknown variable '{}' (expected {})",
+                name,
+                known.join(", ")
+            )));
+        }
+        Ok(filled.into_owned())
+    }
+}
+
+fn first_name(name: &str) -> String {
+    name.split_whitespace().next().unwrap_or(name).to_string()
+}
+
+fn variable_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"\{\{\s*([\w.]+)\s*(?:\|\s*([^}]*?)\s*)?\}\}").expect("valid regex")
+    })
+}
+
+fn variable_names(text: &str) -> impl Iterator<Item = &str> {
+    variable_pattern()
+        .captures_iter(text)
+        .filter_map(|caps| caps.get(1).map(|m| m.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation() -> Conversation {
+        Conversation::builder(42)
+            .id("conv-1")
+            .subject("Order #881")
+            .contact("jane@customer.com", Some("Jane Doe"))
+            .build()
+    }
+
+    #[test]
+    fn test_parse_front_matter() {
+        let template = Template::parse(
+            "---\n\
+             subject: \"Re: {{ conversation.subject }}\"\n\
+             tags: [billing, 'refund']\n\
+             close: true\n\
+             ---\n\
+             Hi {{ contact.first_name }},\n",
+        )
+        .unwrap();
+        assert_eq!(
+            template.subject.as_deref(),
+            Some("Re: {{ conversation.subject }}")
+        );
+        assert_eq!(template.tags, ["billing", "refund"]);
+        assert!(template.close);
+        assert_eq!(template.body, "Hi {{ contact.first_name }},\n");
+
+        let block = Template::parse("---\ntags:\n  - vip\n  - urgent\n---\nBody").unwrap();
+        assert_eq!(block.tags, ["vip", "urgent"]);
+        assert!(!block.close);
+
+        let yaml = Template::parse(
+            "---\n\
+             tags: [\"a, b\", c]  # quoted commas stay in the tag\n\
+             close: true # comment\n\
+             ---\n",
+        )
+        .unwrap();
+        assert_eq!(yaml.tags, ["a, b", "c"]);
+        assert!(yaml.close);
+
+        let single = Template::parse("---\ntags: vip\n---\n").unwrap();
+        assert_eq!(single.tags, ["vip"]);
+        assert!(Template::parse("---\n# nothing yet\n---\nBody").is_ok());
+
+        let plain = Template::parse("Just a body").unwrap();
+        assert_eq!(plain.body, "Just a body");
+        assert!(plain.tags.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for text in [
+            "---\nsubject: x\nBody",
+            "---\ncolor: red\n---\n",
+            "---\nclose: maybe\n---\n",
+            "---\nsubject: [a, b]\n---\n",
+            "---\n- stray\n---\n",
+        ] {
+            assert!(
+                matches!(Template::parse(text), Err(GrooveError::Template(_))),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_render_variables() {
+        let template = Template::parse(
+            "---\nsubject: About {{conversation.subject}}\n---\n\
+             Hi {{ contact.first_name | there }} ({{ contact.email }}), \
+             re #{{ conversation.number }}. {{ agent.first_name | The team }}",
+        )
+        .unwrap();
+        assert!(template.uses_agent());
+
+        let conv = conversation();
+        let (body, subject) = template.render(&Variables::new(&conv, None)).unwrap();
+        assert_eq!(body, "Hi Jane (jane@customer.com), re #42. The team");
+        assert_eq!(subject.as_deref(), Some("About Order #881"));
+
+        let agent = CurrentAgent {
+            id: "agent-1".to_string(),
+            email: "alex@example.com".to_string(),
+            name: Some("Alex Rivera".to_string()),
+            role: None,
+        };
+        let (body, _) = template
+            .render(&Variables::new(&conv, Some(&agent)))
+            .unwrap();
+        assert!(body.ends_with("re #42. Alex"));
+
+        let typo = Template::parse("Hi {{ contact.frist_name }}").unwrap();
+        assert!(!typo.uses_agent());
+        let err = typo.render(&Variables::new(&conv, None)).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("unknown variable 'contact.frist_name'"));
+    }
+}
@@ -1,5 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
+
+/// Width HTML-only message bodies are wrapped to when rendered as text
+const HTML_TEXT_WIDTH: usize = 80;
 
 /// Wrapper for the Assignment type that contains an agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,20 +20,44 @@ pub struct TagConnection {
     pub nodes: Vec<Tag>,
 }
 
+/// Accepts both the API shape (`{ agent: {...} }`) and the flattened shape
+/// produced by serializing a `Conversation`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AssignedField {
+    Agent(Agent),
+    Assignment(Assignment),
+}
+
+/// Accepts both the API connection shape (`{ nodes: [...] }`) and a plain list
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TagsField {
+    Connection(TagConnection),
+    List(Vec<Tag>),
+}
+
 fn deserialize_assigned<'de, D>(deserializer: D) -> Result<Option<Agent>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let assignment: Option<Assignment> = Option::deserialize(deserializer)?;
-    Ok(assignment.and_then(|a| a.agent))
+    let assigned: Option<AssignedField> = Option::deserialize(deserializer)?;
+    Ok(assigned.and_then(|a| match a {
+        AssignedField::Agent(agent) => Some(agent),
+        AssignedField::Assignment(assignment) => assignment.agent,
+    }))
 }
 
 fn deserialize_tags<'de, D>(deserializer: D) -> Result<Vec<Tag>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let connection: Option<TagConnection> = Option::deserialize(deserializer)?;
-    Ok(connection.map(|c| c.nodes).unwrap_or_default())
+    let tags: Option<TagsField> = Option::deserialize(deserializer)?;
+    Ok(match tags {
+        Some(TagsField::Connection(c)) => c.nodes,
+        Some(TagsField::List(list)) => list,
+        None => Vec::new(),
+    })
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +69,12 @@ pub struct Conversation {
     pub state: ConversationState,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// When a snoozed conversation reopens
+    #[serde(default)]
+    pub snoozed_until: Option<DateTime<Utc>>,
+    /// Messages in the thread, when the query asked for it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages_count: Option<u32>,
     #[serde(default, deserialize_with = "deserialize_assigned")]
     pub assigned: Option<Agent>,
     #[serde(default)]
@@ -75,6 +109,158 @@ impl std::fmt::Display for ConversationState {
     }
 }
 
+impl std::str::FromStr for ConversationState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "unread" => Ok(ConversationState::Unread),
+            "opened" | "open" => Ok(ConversationState::Opened),
+            "closed" => Ok(ConversationState::Closed),
+            "snoozed" => Ok(ConversationState::Snoozed),
+            "spam" => Ok(ConversationState::Spam),
+            "deleted" => Ok(ConversationState::Deleted),
+            _ => Err(format!(
+                "Invalid status: {}. Use opened, closed, snoozed, unread, spam, or deleted",
+                s
+            )),
+        }
+    }
+}
+
+/// Timestamp a conversation date filter applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateField {
+    #[default]
+    Created,
+    Updated,
+}
+
+impl DateField {
+    /// Name of the field in `ConversationFilter`
+    pub fn filter_key(&self) -> &'static str {
+        match self {
+            DateField::Created => "createdAt",
+            DateField::Updated => "updatedAt",
+        }
+    }
+}
+
+/// Inclusive date range for filtering conversations
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DateRange {
+    pub field: DateField,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Order for conversation lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversationSort {
+    /// Most recently created first
+    Newest,
+    /// Oldest first
+    Oldest,
+    /// Most recently updated first
+    Updated,
+    /// Least recently updated first, i.e. the ones waiting longest
+    WaitingLongest,
+}
+
+impl ConversationSort {
+    /// `orderBy` field and direction for the GraphQL query
+    pub fn order_by(&self) -> (&'static str, &'static str) {
+        match self {
+            ConversationSort::Newest => ("CREATED_AT", "DESC"),
+            ConversationSort::Oldest => ("CREATED_AT", "ASC"),
+            ConversationSort::Updated => ("UPDATED_AT", "DESC"),
+            ConversationSort::WaitingLongest => ("UPDATED_AT", "ASC"),
+        }
+    }
+}
+
+impl std::str::FromStr for ConversationSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "newest" => Ok(ConversationSort::Newest),
+            "oldest" => Ok(ConversationSort::Oldest),
+            "updated" => Ok(ConversationSort::Updated),
+            "waiting-longest" | "waiting" => Ok(ConversationSort::WaitingLongest),
+            _ => Err(format!(
+                "Invalid sort: {}. Use newest, oldest, updated, or waiting-longest",
+                s
+            )),
+        }
+    }
+}
+
+/// Which conversations `GrooveClient::conversations` returns, and in what
+/// order. Unset fields don't filter.
+///
+/// ```
+/// use groovehq_cli::{ConversationFilter, ConversationSort, ConversationState};
+///
+/// let filter = ConversationFilter::new()
+///     .state(ConversationState::Opened)
+///     .keywords("refund")
+///     .sort(ConversationSort::WaitingLongest);
+/// assert_eq!(filter.state, Some(ConversationState::Opened));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ConversationFilter {
+    pub state: Option<ConversationState>,
+    pub folder_id: Option<String>,
+    /// Keyword search in subjects and bodies
+    pub keywords: Option<String>,
+    pub contact_id: Option<String>,
+    pub assignee_id: Option<String>,
+    pub dates: Option<DateRange>,
+    pub sort: Option<ConversationSort>,
+}
+
+impl ConversationFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn state(mut self, state: ConversationState) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    pub fn folder(mut self, folder_id: impl Into<String>) -> Self {
+        self.folder_id = Some(folder_id.into());
+        self
+    }
+
+    pub fn keywords(mut self, keywords: impl Into<String>) -> Self {
+        self.keywords = Some(keywords.into());
+        self
+    }
+
+    pub fn contact(mut self, contact_id: impl Into<String>) -> Self {
+        self.contact_id = Some(contact_id.into());
+        self
+    }
+
+    pub fn assignee(mut self, agent_id: impl Into<String>) -> Self {
+        self.assignee_id = Some(agent_id.into());
+        self
+    }
+
+    pub fn dates(mut self, dates: DateRange) -> Self {
+        self.dates = Some(dates);
+        self
+    }
+
+    pub fn sort(mut self, sort: ConversationSort) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Agent {
@@ -91,6 +277,44 @@ pub struct Contact {
     pub name: Option<String>,
 }
 
+impl Contact {
+    /// Name and email, e.g. `Jane Doe <jane@example.com>`, falling back to
+    /// whichever is known
+    pub fn display_name(&self) -> String {
+        match (self.name.as_deref(), self.email.as_deref()) {
+            (Some(name), Some(email)) => format!("{} <{}>", name, email),
+            (Some(name), None) => name.to_string(),
+            (None, Some(email)) => email.to_string(),
+            (None, None) => self.id.clone(),
+        }
+    }
+}
+
+/// Everything Groove holds about a customer, for data subject requests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactRecord {
+    #[serde(flatten)]
+    pub contact: Contact,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub title: Option<String>,
+    pub company_name: Option<String>,
+    pub phone_number: Option<String>,
+    pub avatar_url: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A customer with their most recent conversations, newest first
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactProfile {
+    #[serde(flatten)]
+    pub contact: Contact,
+    pub conversations: Vec<Conversation>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Channel {
@@ -122,6 +346,42 @@ pub struct Message {
     pub body_html: Option<String>,
     #[serde(default)]
     pub author: Option<MessageAuthor>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+}
+
+impl Message {
+    /// Readable body: the plain-text body, or the HTML body rendered as text
+    /// when there is no plain text (common for rich email)
+    pub fn text(&self) -> Option<Cow<'_, str>> {
+        match self.body_text.as_deref() {
+            Some(text) if !text.trim().is_empty() => Some(Cow::Borrowed(text)),
+            _ => {
+                let html = self.body_html.as_deref().filter(|h| !h.trim().is_empty())?;
+                // Links become numbered footnotes; no markdown-style emphasis
+                html2text::config::plain_no_decorate()
+                    .link_footnotes(true)
+                    .string_from_read(html.as_bytes(), HTML_TEXT_WIDTH)
+                    .ok()
+                    .map(Cow::Owned)
+            }
+        }
+    }
+}
+
+/// File attached to a message (metadata only; the file itself is at `url`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub id: String,
+    pub file_name: String,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// Size in bytes
+    #[serde(default)]
+    pub size: Option<i64>,
+    #[serde(default)]
+    pub url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -134,6 +394,45 @@ pub struct MessageAuthor {
     pub name: Option<String>,
 }
 
+/// An internal note on a conversation (not visible to the customer)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub body: Option<String>,
+    #[serde(default)]
+    pub author: Option<MessageAuthor>,
+}
+
+/// A note in which the current agent was @mentioned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Mention {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub read_at: Option<DateTime<Utc>>,
+    pub conversation: MentionedConversation,
+    #[serde(default)]
+    pub note: Option<Note>,
+}
+
+impl Mention {
+    pub fn is_unread(&self) -> bool {
+        self.read_at.is_none()
+    }
+}
+
+/// The conversation a mention was made in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MentionedConversation {
+    pub id: String,
+    pub number: i64,
+    pub subject: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CannedReply {
@@ -143,6 +442,52 @@ pub struct CannedReply {
     pub body: Option<String>,
 }
 
+/// A knowledge base article
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KbArticle {
+    pub id: String,
+    pub title: String,
+    /// Where the article is published
+    pub url: Option<String>,
+}
+
+/// An automation that runs its actions on conversations matching its
+/// conditions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    /// `ALL` when every condition must match, `ANY` when one is enough
+    pub match_type: Option<String>,
+    #[serde(default)]
+    pub conditions: Vec<RuleCondition>,
+    #[serde(default)]
+    pub actions: Vec<RuleStep>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A test like "subject contains refund"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleCondition {
+    pub param: String,
+    pub operator: String,
+    pub value: Option<String>,
+}
+
+/// One of the actions a rule runs, like "add tag billing"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleStep {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CurrentAgent {
@@ -152,9 +497,233 @@ pub struct CurrentAgent {
     pub role: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PageInfo {
     pub has_next_page: bool,
     pub end_cursor: Option<String>,
+    #[serde(default)]
+    pub has_previous_page: bool,
+    #[serde(default)]
+    pub start_cursor: Option<String>,
+}
+
+/// Which slice of a conversation's messages to fetch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessagePage {
+    /// The oldest `count` messages, or those after `after`
+    First { count: i32, after: Option<String> },
+    /// The newest `count` messages, or those before `before`
+    Last { count: i32, before: Option<String> },
+}
+
+/// Builds conversations for unit tests: an open conversation `conv-<number>`
+/// created now, with nothing else set unless asked for
+#[cfg(test)]
+pub(crate) struct ConversationBuilder(Conversation);
+
+#[cfg(test)]
+impl Conversation {
+    pub(crate) fn builder(number: i64) -> ConversationBuilder {
+        ConversationBuilder(Conversation {
+            id: format!("conv-{}", number),
+            number,
+            subject: None,
+            state: ConversationState::Opened,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            snoozed_until: None,
+            messages_count: None,
+            assigned: None,
+            channel: None,
+            contact: None,
+            tags: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+impl ConversationBuilder {
+    pub(crate) fn id(mut self, id: &str) -> Self {
+        self.0.id = id.to_string();
+        self
+    }
+
+    pub(crate) fn subject(mut self, subject: &str) -> Self {
+        self.0.subject = Some(subject.to_string());
+        self
+    }
+
+    pub(crate) fn state(mut self, state: ConversationState) -> Self {
+        self.0.state = state;
+        self
+    }
+
+    pub(crate) fn created_at(mut self, created_at: DateTime<Utc>) -> Self {
+        self.0.created_at = created_at;
+        self
+    }
+
+    pub(crate) fn updated_at(mut self, updated_at: DateTime<Utc>) -> Self {
+        self.0.updated_at = updated_at;
+        self
+    }
+
+    pub(crate) fn messages_count(mut self, count: Option<u32>) -> Self {
+        self.0.messages_count = count;
+        self
+    }
+
+    /// Assigned to agent `id`, whose email is `<id>@example.com`
+    pub(crate) fn assigned(mut self, id: Option<&str>) -> Self {
+        self.0.assigned = id.map(|id| Agent {
+            id: id.to_string(),
+            email: format!("{}@example.com", id),
+            name: None,
+        });
+        self
+    }
+
+    /// Received through channel `channel-<name>`
+    pub(crate) fn channel(mut self, name: Option<&str>) -> Self {
+        self.0.channel = name.map(|name| Channel {
+            id: format!("channel-{}", name),
+            name: Some(name.to_string()),
+        });
+        self
+    }
+
+    /// With customer `contact-1`
+    pub(crate) fn contact(mut self, email: &str, name: Option<&str>) -> Self {
+        self.0.contact = Some(Contact {
+            id: "contact-1".to_string(),
+            email: Some(email.to_string()),
+            name: name.map(str::to_string),
+        });
+        self
+    }
+
+    /// Tagged with these names, each also used as the tag's ID
+    pub(crate) fn tags(mut self, names: &[&str]) -> Self {
+        self.0.tags = names
+            .iter()
+            .map(|name| Tag {
+                id: name.to_string(),
+                name: name.to_string(),
+                color: None,
+            })
+            .collect();
+        self
+    }
+
+    pub(crate) fn build(self) -> Conversation {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_conversation_serialize_roundtrip() {
+        let conv: Conversation = serde_json::from_value(json!({
+            "id": "conv-1",
+            "number": 1,
+            "subject": null,
+            "state": "OPENED",
+            "createdAt": "2024-01-01T00:00:00Z",
+            "updatedAt": "2024-01-01T00:00:00Z",
+            "assigned": { "agent": { "id": "a1", "email": "agent@example.com", "name": null } },
+            "tags": { "nodes": [{ "id": "t1", "name": "urgent", "color": null }] }
+        }))
+        .unwrap();
+        assert_eq!(conv.assigned.as_ref().map(|a| a.id.as_str()), Some("a1"));
+        assert_eq!(conv.tags.len(), 1);
+
+        let roundtrip: Conversation =
+            serde_json::from_str(&serde_json::to_string(&conv).unwrap()).unwrap();
+        assert_eq!(roundtrip.assigned.map(|a| a.id), Some("a1".to_string()));
+        assert_eq!(roundtrip.tags.len(), 1);
+    }
+
+    #[test]
+    fn test_conversation_snoozed_until() {
+        let conv: Conversation = serde_json::from_value(json!({
+            "id": "conv-1",
+            "number": 1,
+            "subject": null,
+            "state": "SNOOZED",
+            "createdAt": "2024-01-01T00:00:00Z",
+            "updatedAt": "2024-01-01T00:00:00Z",
+            "snoozedUntil": "2024-01-03T09:00:00Z"
+        }))
+        .unwrap();
+        assert_eq!(
+            conv.snoozed_until.map(|t| t.to_rfc3339()),
+            Some("2024-01-03T09:00:00+00:00".to_string())
+        );
+    }
+
+    #[test]
+    fn test_conversation_unassigned() {
+        let conv: Conversation = serde_json::from_value(json!({
+            "id": "conv-1",
+            "number": 1,
+            "subject": null,
+            "state": "OPENED",
+            "createdAt": "2024-01-01T00:00:00Z",
+            "updatedAt": "2024-01-01T00:00:00Z",
+            "assigned": { "agent": null }
+        }))
+        .unwrap();
+        assert!(conv.assigned.is_none());
+    }
+
+    #[test]
+    fn test_message_text_falls_back_to_html() {
+        let mut msg: Message = serde_json::from_value(json!({
+            "id": "m1",
+            "createdAt": "2024-01-01T00:00:00Z",
+            "bodyText": null,
+            "bodyHtml": "<p>Hello <b>there</b></p><p>Second&nbsp;paragraph</p>"
+        }))
+        .unwrap();
+        let text = msg.text().unwrap();
+        assert_eq!(text, "Hello there\n\nSecond\u{a0}paragraph\n");
+
+        msg.body_text = Some("Plain body".to_string());
+        assert_eq!(msg.text().as_deref(), Some("Plain body"));
+
+        msg.body_text = None;
+        msg.body_html = None;
+        assert!(msg.text().is_none());
+    }
+
+    #[test]
+    fn test_conversation_sort_from_str() {
+        assert_eq!(
+            "waiting-longest".parse::<ConversationSort>(),
+            Ok(ConversationSort::WaitingLongest)
+        );
+        assert_eq!(
+            "Newest".parse::<ConversationSort>(),
+            Ok(ConversationSort::Newest)
+        );
+        assert!("random".parse::<ConversationSort>().is_err());
+        assert_eq!(
+            ConversationSort::WaitingLongest.order_by(),
+            ("UPDATED_AT", "ASC")
+        );
+    }
+
+    #[test]
+    fn test_conversation_state_from_str() {
+        assert_eq!("opened".parse(), Ok(ConversationState::Opened));
+        assert_eq!("Open".parse(), Ok(ConversationState::Opened));
+        assert_eq!("SNOOZED".parse(), Ok(ConversationState::Snoozed));
+        let err = "pending".parse::<ConversationState>().unwrap_err();
+        assert!(err.starts_with("Invalid status: pending"));
+    }
 }
@@ -1,4 +1,6 @@
 use groovehq_cli::api::GrooveClient;
+use groovehq_cli::types::{ConversationFilter, ConversationState};
+use groovehq_cli::GrooveError;
 use serde_json::json;
 use wiremock::matchers::{header, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -135,7 +137,7 @@ async fn test_conversations_list() {
 
     let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
     let result = client
-        .conversations(Some(25), None, None, None, None)
+        .conversations(&ConversationFilter::new(), Some(25), None)
         .await
         .unwrap();
 
@@ -145,6 +147,126 @@ async fn test_conversations_list() {
     assert_eq!(result.total_count, 1);
 }
 
+#[tokio::test]
+async fn test_conversations_stream_follows_cursors() {
+    use futures::TryStreamExt;
+    use wiremock::matchers::body_partial_json;
+
+    fn page(number: i64, end_cursor: Option<&str>) -> serde_json::Value {
+        json!({
+            "data": {
+                "conversations": {
+                    "nodes": [{
+                        "id": format!("conv-{}", number),
+                        "number": number,
+                        "subject": null,
+                        "state": "SNOOZED",
+                        "createdAt": "2024-01-01T00:00:00Z",
+                        "updatedAt": "2024-01-01T12:00:00Z"
+                    }],
+                    "pageInfo": {
+                        "hasNextPage": end_cursor.is_some(),
+                        "endCursor": end_cursor
+                    },
+                    "totalCount": 2
+                }
+            }
+        })
+    }
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(
+            json!({ "variables": { "after": "cursor-1" } }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(page(2, None)))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({
+            "variables": { "after": null, "filter": { "state": "SNOOZED" } }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(page(1, Some("cursor-1"))))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let filter = ConversationFilter::new().state(ConversationState::Snoozed);
+    let conversations: Vec<_> = client
+        .conversations_stream(&filter)
+        .try_collect()
+        .await
+        .unwrap();
+
+    let numbers: Vec<i64> = conversations.iter().map(|c| c.number).collect();
+    assert_eq!(numbers, vec![1, 2]);
+}
+
+#[tokio::test]
+async fn test_conversations_by_number_batches_lookups() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "c0": {
+                    "id": "conv-12",
+                    "number": 12,
+                    "subject": null,
+                    "state": "OPENED",
+                    "createdAt": "2024-01-01T00:00:00Z",
+                    "updatedAt": "2024-01-01T12:00:00Z"
+                },
+                "c1": null
+            }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let found = client.conversations_by_number(&[12, 13]).await.unwrap();
+
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].as_ref().map(|c| c.id.as_str()), Some("conv-12"));
+    assert!(found[1].is_none());
+}
+
+#[tokio::test]
+async fn test_close_many_sends_one_mutation() {
+    use wiremock::matchers::body_partial_json;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({
+            "variables": {
+                "input0": { "conversationId": "conv-1" },
+                "input1": { "conversationId": "conv-2" }
+            }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "m0": { "errors": [] },
+                "m1": { "errors": [{ "message": "Conversation is locked" }] }
+            }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let results = client.close_many(&["conv-1", "conv-2"]).await.unwrap();
+
+    assert!(results[0].is_ok());
+    let err = results[1].as_ref().unwrap_err();
+    assert!(err.to_string().contains("Conversation is locked"));
+}
+
 #[tokio::test]
 async fn test_folders_list() {
     let mock_server = MockServer::start().await;
@@ -177,7 +299,6 @@ async fn test_folders_list() {
 
     assert_eq!(result.len(), 2);
     assert_eq!(result[0].name, "Inbox");
-    assert_eq!(result[0].count, Some(42));
 }
 
 #[tokio::test]
@@ -210,6 +331,142 @@ async fn test_tags_list() {
     assert_eq!(result[0].color, Some("#ff0000".to_string()));
 }
 
+#[tokio::test]
+async fn test_tags_cached() {
+    use groovehq_cli::api::{Cache, DEFAULT_CACHE_TTL};
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "tags": {
+                    "nodes": [{ "id": "tag-1", "name": "urgent", "color": null }]
+                }
+            }
+        })))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let cache = Cache::new(dir.path(), DEFAULT_CACHE_TTL);
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri()))
+        .unwrap()
+        .with_cache(cache.clone());
+
+    // Second call is served from disk
+    assert_eq!(client.tags().await.unwrap()[0].name, "urgent");
+    assert_eq!(client.tags().await.unwrap()[0].name, "urgent");
+
+    // --refresh bypasses the cached copy
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri()))
+        .unwrap()
+        .with_cache(cache.refresh(true));
+    assert_eq!(client.tags().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_etag_revalidation() {
+    use groovehq_cli::api::{Cache, DEFAULT_CACHE_TTL};
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(header("if-none-match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(1)
+        .with_priority(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("etag", "\"v1\"")
+                .set_body_json(json!({
+                    "data": {
+                        "me": {
+                            "id": "agent-1",
+                            "email": "agent@example.com",
+                            "name": "Test Agent",
+                            "role": "admin"
+                        }
+                    }
+                })),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri()))
+        .unwrap()
+        .with_cache(Cache::new(dir.path(), DEFAULT_CACHE_TTL));
+
+    // The second request gets a 304 and reuses the stored body
+    assert_eq!(client.me().await.unwrap().email, "agent@example.com");
+    assert_eq!(client.me().await.unwrap().email, "agent@example.com");
+}
+
+#[tokio::test]
+async fn test_rules_and_set_rule_enabled() {
+    use wiremock::matchers::{body_partial_json, body_string_contains};
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .and(body_string_contains("query Rules"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "rules": {
+                    "nodes": [
+                        {
+                            "id": "rule-1",
+                            "name": "Tag refunds",
+                            "description": null,
+                            "enabled": true,
+                            "matchType": "ANY",
+                            "conditions": [
+                                { "param": "SUBJECT", "operator": "CONTAINS", "value": "refund" }
+                            ],
+                            "actions": [
+                                { "type": "ADD_TAG", "value": "billing" }
+                            ],
+                            "updatedAt": "2024-05-02T10:15:00Z"
+                        }
+                    ]
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .and(body_partial_json(json!({
+            "variables": { "input": { "ruleId": "rule-1", "enabled": false } }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "ruleUpdate": { "errors": [] } }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let rules = client.rules().await.unwrap();
+
+    assert_eq!(rules.len(), 1);
+    assert!(rules[0].enabled);
+    assert_eq!(rules[0].conditions[0].operator, "CONTAINS");
+    assert_eq!(rules[0].actions[0].kind, "ADD_TAG");
+
+    client.set_rule_enabled("rule-1", false).await.unwrap();
+}
+
 #[tokio::test]
 async fn test_close_conversation() {
     let mock_server = MockServer::start().await;
@@ -232,6 +489,31 @@ async fn test_close_conversation() {
     assert!(result.is_ok());
 }
 
+#[tokio::test]
+async fn test_reply_with_subject() {
+    use wiremock::matchers::body_partial_json;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .and(body_partial_json(json!({
+            "variables": { "input": { "conversationId": "conv-1", "subject": "Re: Order" } }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "conversationReply": { "errors": [] } }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    client
+        .reply_with_subject("conv-1", "Hello", "Re: Order")
+        .await
+        .unwrap();
+}
+
 #[tokio::test]
 async fn test_mutation_with_errors() {
     let mock_server = MockServer::start().await;
@@ -257,3 +539,1230 @@ async fn test_mutation_with_errors() {
     let err = result.unwrap_err();
     assert!(err.to_string().contains("already closed"));
 }
+
+#[tokio::test]
+async fn test_sync_to_store() {
+    use groovehq_cli::store::{sync, Store, SyncOptions};
+    use wiremock::matchers::body_string_contains;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "tags": { "nodes": [{ "id": "tag-1", "name": "urgent", "color": null }] } }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Conversations"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "conversations": {
+                    "nodes": [{
+                        "id": "conv-1",
+                        "number": 1,
+                        "subject": "Test Subject",
+                        "state": "OPENED",
+                        "createdAt": "2024-01-01T00:00:00Z",
+                        "updatedAt": "2024-01-01T12:00:00Z",
+                        "assigned": null,
+                        "contact": { "id": "contact-1", "email": "customer@example.com", "name": null },
+                        "channel": null,
+                        "tags": { "nodes": [] }
+                    }],
+                    "pageInfo": { "hasNextPage": false, "endCursor": null },
+                    "totalCount": 1
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "events": {
+                    "nodes": [{
+                        "createdAt": "2024-01-01T00:00:00Z",
+                        "change": {
+                            "__typename": "EmailMessage",
+                            "id": "msg-1",
+                            "bodyPlainText": "Hello",
+                            "body": "<p>Hello</p>",
+                            "author": null
+                        }
+                    }]
+                }
+            }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "events": {
+                    "nodes": [{
+                        "createdAt": "2024-01-01T00:00:00Z",
+                        "change": {
+                            "__typename": "Note",
+                            "id": "note-1",
+                            "body": "Customer wants a refund",
+                            "author": null
+                        }
+                    }]
+                }
+            }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let store = Store::open_in_memory().unwrap();
+
+    let first = sync(&client, &store, &SyncOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(first.conversations_seen, 1);
+    assert_eq!(first.conversations_updated, 1);
+    assert_eq!(first.messages_fetched, 1);
+
+    // Unchanged conversations don't refetch messages
+    let second = sync(&client, &store, &SyncOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(second.conversations_seen, 1);
+    assert_eq!(second.conversations_updated, 0);
+
+    let counts = store.counts().unwrap();
+    assert_eq!(counts.conversations, 1);
+    assert_eq!(counts.messages, 1);
+    assert_eq!(counts.notes, 1);
+    assert_eq!(counts.contacts, 1);
+    assert_eq!(counts.tags, 1);
+
+    let hits = store.search("refund", 10).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].number, 1);
+}
+
+#[tokio::test]
+async fn test_sync_lists_conversations_updated_since_last_sync() {
+    use groovehq_cli::store::{sync, Store, SyncOptions};
+    use wiremock::matchers::body_string_contains;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "tags": { "nodes": [] } }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    // Only the second sync asks for conversations updated since the first
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Conversations"))
+        .and(body_string_contains("\"gte\""))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "conversations": {
+                    "nodes": [],
+                    "pageInfo": { "hasNextPage": false, "endCursor": null },
+                    "totalCount": 0
+                }
+            }
+        })))
+        .expect(1)
+        .with_priority(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Conversations"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "conversations": {
+                    "nodes": [{
+                        "id": "conv-1",
+                        "number": 1,
+                        "subject": "Test Subject",
+                        "state": "OPENED",
+                        "createdAt": "2024-01-01T00:00:00Z",
+                        "updatedAt": "2024-01-01T12:00:00Z",
+                        "assigned": null,
+                        "contact": null,
+                        "channel": null,
+                        "tags": { "nodes": [] }
+                    }],
+                    "pageInfo": { "hasNextPage": false, "endCursor": null },
+                    "totalCount": 1
+                }
+            }
+        })))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    // Messages and notes
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "events": { "nodes": [] } }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let store = Store::open_in_memory().unwrap();
+
+    let first = sync(&client, &store, &SyncOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(first.conversations_seen, 1);
+
+    let second = sync(&client, &store, &SyncOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(second.conversations_seen, 0);
+
+    // --full lists everything again
+    let full = sync(&client, &store, &SyncOptions { full: true })
+        .await
+        .unwrap();
+    assert_eq!(full.conversations_seen, 1);
+    assert_eq!(full.conversations_updated, 1);
+}
+
+#[tokio::test]
+async fn test_sync_retries_thread_after_failed_fetch() {
+    use groovehq_cli::store::{sync, Store, SyncOptions};
+    use wiremock::matchers::body_string_contains;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "tags": { "nodes": [] } }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Conversations"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "conversations": {
+                    "nodes": [{
+                        "id": "conv-1",
+                        "number": 1,
+                        "subject": "Test Subject",
+                        "state": "OPENED",
+                        "createdAt": "2024-01-01T00:00:00Z",
+                        "updatedAt": "2024-01-01T12:00:00Z",
+                        "assigned": null,
+                        "contact": null,
+                        "channel": null,
+                        "tags": { "nodes": [] }
+                    }],
+                    "pageInfo": { "hasNextPage": false, "endCursor": null },
+                    "totalCount": 1
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    // The first message fetch fails; later ones succeed
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Messages"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "events": {
+                    "nodes": [{
+                        "createdAt": "2024-01-01T00:00:00Z",
+                        "change": {
+                            "__typename": "EmailMessage",
+                            "id": "msg-1",
+                            "bodyPlainText": "Hello",
+                            "body": "<p>Hello</p>",
+                            "author": null
+                        }
+                    }]
+                }
+            }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "events": { "nodes": [] } }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let store = Store::open_in_memory().unwrap();
+
+    assert!(sync(&client, &store, &SyncOptions::default())
+        .await
+        .is_err());
+    assert_eq!(store.counts().unwrap().conversations, 0);
+
+    // The conversation wasn't recorded, so the next run fetches its thread
+    let second = sync(&client, &store, &SyncOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(second.conversations_updated, 1);
+    assert_eq!(second.messages_fetched, 1);
+    assert_eq!(store.counts().unwrap().messages, 1);
+}
+
+#[tokio::test]
+async fn test_notes() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "events": {
+                    "nodes": [
+                        {
+                            "createdAt": "2024-01-01T00:00:00Z",
+                            "change": {
+                                "__typename": "Note",
+                                "id": "note-1",
+                                "body": "Internal note",
+                                "author": {
+                                    "__typename": "Agent",
+                                    "id": "agent-1",
+                                    "email": "agent@example.com",
+                                    "name": "Agent"
+                                }
+                            }
+                        },
+                        {
+                            "createdAt": "2024-01-01T00:01:00Z",
+                            "change": { "__typename": "StateChanged" }
+                        }
+                    ]
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let notes = client.notes("conv-1", None).await.unwrap();
+
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].id, "note-1");
+    assert_eq!(notes[0].body.as_deref(), Some("Internal note"));
+    assert_eq!(
+        notes[0].author.as_ref().and_then(|a| a.email.as_deref()),
+        Some("agent@example.com")
+    );
+}
+
+#[tokio::test]
+async fn test_rate_limit_headers() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("x-ratelimit-limit", "500")
+                .insert_header("x-ratelimit-remaining", "499")
+                .insert_header("x-ratelimit-reset", "1700000000")
+                .set_body_json(json!({
+                    "data": {
+                        "me": {
+                            "id": "agent-1",
+                            "email": "agent@example.com",
+                            "name": null,
+                            "role": null
+                        }
+                    }
+                })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    assert!(client.rate_limit().is_none());
+
+    client.me().await.unwrap();
+    let info = client.rate_limit().unwrap();
+    assert_eq!(info.limit, Some(500));
+    assert_eq!(info.remaining, Some(499));
+    assert!(info.reset_at.is_some());
+}
+
+#[tokio::test]
+async fn test_proxy() {
+    // The mock server stands in for the proxy; plain-HTTP requests are sent
+    // to it with the absolute target URL
+    let proxy = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "me": {
+                    "id": "agent-1",
+                    "email": "agent@example.com",
+                    "name": null,
+                    "role": null
+                }
+            }
+        })))
+        .expect(1)
+        .mount(&proxy)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some("http://groove.invalid/graphql"))
+        .unwrap()
+        .with_proxy(&proxy.uri())
+        .unwrap();
+    assert_eq!(client.me().await.unwrap().email, "agent@example.com");
+}
+
+#[test]
+fn test_invalid_proxy() {
+    let result = GrooveClient::new("test-token", None)
+        .unwrap()
+        .with_proxy("not a url");
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_graphql_error_includes_request_id() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("x-request-id", "req-abc123")
+                .set_body_json(json!({
+                    "data": null,
+                    "errors": [{ "message": "Something went wrong" }]
+                })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let err = client.me().await.unwrap_err();
+
+    assert_eq!(err.request_id(), Some("req-abc123"));
+    assert!(err.to_string().contains("(request ID: req-abc123)"));
+    assert_eq!(client.last_request_id().as_deref(), Some("req-abc123"));
+}
+
+#[tokio::test]
+async fn test_http_error_includes_request_id() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(503)
+                .insert_header("x-request-id", "req-503")
+                .set_body_string("<html><body>Service Unavailable</body></html>"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let err = client.me().await.unwrap_err();
+
+    assert!(matches!(err, GrooveError::GraphQL { .. }), "{:?}", err);
+    assert_eq!(err.request_id(), Some("req-503"));
+    assert!(
+        err.to_string().contains("HTTP 503 Service Unavailable"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_mutation_error_includes_its_request_id() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("x-request-id", "req-close")
+                .set_body_json(json!({
+                    "data": {
+                        "conversationClose": { "errors": [{ "message": "Already closed" }] }
+                    }
+                })),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let err = client.close("conv-1").await.unwrap_err();
+
+    assert_eq!(err.request_id(), Some("req-close"));
+    assert!(err.to_string().contains("Already closed"));
+}
+
+#[tokio::test]
+async fn test_contact_by_email() {
+    use wiremock::matchers::body_partial_json;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({
+            "variables": { "filter": { "email": "jane@example.com" } }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "contacts": {
+                    "nodes": [
+                        { "id": "contact-2", "email": "mary.jane@example.com", "name": "Mary" },
+                        { "id": "contact-1", "email": "Jane@Example.com", "name": "Jane" }
+                    ]
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    // Similar addresses aren't taken for the one asked for
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({
+            "variables": { "filter": { "email": "smith@example.com" } }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "contacts": {
+                    "nodes": [
+                        { "id": "contact-3", "email": "blacksmith@example.com", "name": null }
+                    ]
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "contacts": { "nodes": [] } }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let contact = client
+        .contact_by_email("jane@example.com")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(contact.id, "contact-1");
+
+    assert!(client
+        .contact_by_email("nobody@example.com")
+        .await
+        .unwrap()
+        .is_none());
+    assert!(client
+        .contact_by_email("smith@example.com")
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_search_contacts_and_articles() {
+    use wiremock::matchers::body_partial_json;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({
+            "variables": { "first": 5, "filter": { "keywords": "refund" } }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "contacts": {
+                    "nodes": [
+                        { "id": "contact-1", "email": "refunds@example.com", "name": null }
+                    ]
+                },
+                "kbArticles": {
+                    "nodes": [
+                        { "id": "article-1", "title": "How refunds work", "url": null }
+                    ]
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let contacts = client.search_contacts("refund", 5).await.unwrap();
+    assert_eq!(contacts[0].email.as_deref(), Some("refunds@example.com"));
+    let articles = client.kb_articles("refund", 5).await.unwrap();
+    assert_eq!(articles[0].title, "How refunds work");
+    assert!(articles[0].url.is_none());
+}
+
+#[tokio::test]
+async fn test_mentions() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "mentions": {
+                    "nodes": [
+                        {
+                            "id": "mention-1",
+                            "createdAt": "2024-01-02T00:00:00Z",
+                            "readAt": null,
+                            "conversation": { "id": "conv-1", "number": 12, "subject": "Refund" },
+                            "note": {
+                                "id": "note-1",
+                                "createdAt": "2024-01-02T00:00:00Z",
+                                "body": "@alice can you check this?",
+                                "author": { "__typename": "Agent", "id": "agent-2", "email": "bob@example.com", "name": "Bob" }
+                            }
+                        },
+                        {
+                            "id": "mention-2",
+                            "createdAt": "2024-01-01T00:00:00Z",
+                            "readAt": "2024-01-01T01:00:00Z",
+                            "conversation": { "id": "conv-2", "number": 7, "subject": null },
+                            "note": null
+                        }
+                    ]
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let mentions = client.mentions(25).await.unwrap();
+
+    assert_eq!(mentions.len(), 2);
+    assert!(mentions[0].is_unread());
+    assert_eq!(mentions[0].conversation.number, 12);
+    assert_eq!(
+        mentions[0]
+            .note
+            .as_ref()
+            .unwrap()
+            .author
+            .as_ref()
+            .unwrap()
+            .name
+            .as_deref(),
+        Some("Bob")
+    );
+    assert!(!mentions[1].is_unread());
+    assert!(mentions[1].note.is_none());
+}
+
+#[tokio::test]
+async fn test_add_note_with_mentions() {
+    use wiremock::matchers::body_partial_json;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({
+            "variables": {
+                "input": {
+                    "conversationId": "conv-1",
+                    "mentionedAgentIds": ["agent-1"]
+                }
+            }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "conversationAddNote": { "errors": [] } }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    client
+        .add_note("conv-1", "@alice please check", &["agent-1".to_string()])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_update_and_delete_note() {
+    use wiremock::matchers::body_string_contains;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("noteUpdate"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "noteUpdate": { "errors": [] } }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("noteDelete"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "noteDelete": { "errors": [{ "message": "Note not found" }] } }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    client.update_note("note-1", "Corrected").await.unwrap();
+
+    let err = client.delete_note("note-1").await.unwrap_err();
+    assert!(err.to_string().contains("Note not found"));
+}
+
+#[tokio::test]
+async fn test_merge_and_delete_contacts() {
+    use wiremock::matchers::{body_partial_json, body_string_contains};
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({
+            "variables": {
+                "input": { "contactId": "contact-1", "duplicateContactId": "contact-9" }
+            }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "contactMerge": { "errors": [] } }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    client
+        .merge_contacts("contact-1", "contact-9")
+        .await
+        .unwrap();
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("contactDelete"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "contactDelete": { "errors": [{ "message": "Contact not found" }] } }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let err = client.delete_contact("contact-9").await.unwrap_err();
+    assert!(err.to_string().contains("Contact not found"));
+}
+
+#[tokio::test]
+async fn test_redact_and_delete_message() {
+    use wiremock::matchers::{body_partial_json, body_string_contains};
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("messageRedact"))
+        .and(body_partial_json(json!({
+            "variables": { "input": { "messageId": "msg-1", "body": "My password is [redacted]" } }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "messageRedact": { "errors": [] } }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("messageDelete"))
+        .and(body_partial_json(json!({
+            "variables": { "input": { "messageId": "msg-2" } }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "messageDelete": { "errors": [] } }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    client
+        .redact_message("msg-1", "My password is [redacted]")
+        .await
+        .unwrap();
+    client.delete_message("msg-2").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_message_by_id() {
+    use wiremock::matchers::body_partial_json;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({ "variables": { "id": "msg-1" } })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "node": {
+                    "__typename": "EmailMessage",
+                    "id": "msg-1",
+                    "createdAt": "2024-01-01T10:00:00Z",
+                    "bodyPlainText": "Order #4521 never arrived",
+                    "body": "<p>Order #4521 never arrived</p>",
+                    "author": { "__typename": "Contact", "id": "contact-1", "email": "jane@example.com", "name": "Jane" },
+                    "attachments": [
+                        { "id": "att-1", "fileName": "receipt.pdf", "contentType": "application/pdf", "size": 2048, "url": null }
+                    ]
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "node": null }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let message = client.message("msg-1").await.unwrap();
+    assert_eq!(message.id, "msg-1");
+    assert_eq!(
+        message.body_text.as_deref(),
+        Some("Order #4521 never arrived")
+    );
+    assert_eq!(message.attachments.len(), 1);
+    assert_eq!(message.attachments[0].file_name, "receipt.pdf");
+    assert_eq!(
+        message.author.and_then(|a| a.typename),
+        Some("Contact".to_string())
+    );
+
+    let err = client.message("msg-missing").await.unwrap_err();
+    assert!(err.to_string().contains("Message 'msg-missing' not found"));
+}
+
+#[tokio::test]
+async fn test_all_messages_follows_pagination() {
+    use wiremock::matchers::body_partial_json;
+
+    let mock_server = MockServer::start().await;
+
+    let page = |id: &str, has_next: bool, cursor: Option<&str>| {
+        json!({
+            "data": {
+                "events": {
+                    "pageInfo": { "hasNextPage": has_next, "endCursor": cursor },
+                    "nodes": [{
+                        "createdAt": "2024-01-01T00:00:00Z",
+                        "change": {
+                            "__typename": "EmailMessage",
+                            "id": id,
+                            "bodyPlainText": "Hello",
+                            "body": null,
+                            "author": null
+                        }
+                    }]
+                }
+            }
+        })
+    };
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(
+            json!({ "variables": { "after": "cursor-1" } }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(page("msg-2", false, None)))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(page(
+            "msg-1",
+            true,
+            Some("cursor-1"),
+        )))
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let messages = client.all_messages("conv-1").await.unwrap();
+    let ids: Vec<&str> = messages.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, vec!["msg-1", "msg-2"]);
+}
+
+#[tokio::test]
+async fn test_all_notes_follows_pagination() {
+    use wiremock::matchers::body_partial_json;
+
+    let mock_server = MockServer::start().await;
+
+    // The first page of events holds no notes at all
+    Mock::given(method("POST"))
+        .and(body_partial_json(
+            json!({ "variables": { "after": "cursor-1" } }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "events": {
+                    "pageInfo": { "hasNextPage": false, "endCursor": null },
+                    "nodes": [{
+                        "createdAt": "2024-01-02T00:00:00Z",
+                        "change": {
+                            "__typename": "Note",
+                            "id": "note-1",
+                            "body": "Refund approved",
+                            "author": null
+                        }
+                    }]
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "events": {
+                    "pageInfo": { "hasNextPage": true, "endCursor": "cursor-1" },
+                    "nodes": [{
+                        "createdAt": "2024-01-01T00:00:00Z",
+                        "change": { "__typename": "EmailMessage" }
+                    }]
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let notes = client.all_notes("conv-1").await.unwrap();
+    let ids: Vec<&str> = notes.iter().map(|n| n.id.as_str()).collect();
+    assert_eq!(ids, vec!["note-1"]);
+}
+
+#[tokio::test]
+async fn test_backup_writes_files_and_manifest() {
+    use groovehq_cli::export::{self, BackupOptions, ExportFormat};
+    use wiremock::matchers::body_string_contains;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Conversations"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "conversations": {
+                    "nodes": [{
+                        "id": "conv-1",
+                        "number": 7,
+                        "subject": "Refund",
+                        "state": "CLOSED",
+                        "createdAt": "2024-01-01T00:00:00Z",
+                        "updatedAt": "2024-01-02T00:00:00Z"
+                    }],
+                    "pageInfo": { "hasNextPage": false, "endCursor": null },
+                    "totalCount": 1
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "events": {
+                    "pageInfo": { "hasNextPage": false, "endCursor": null },
+                    "nodes": [{
+                        "createdAt": "2024-01-01T00:00:00Z",
+                        "change": {
+                            "__typename": "EmailMessage",
+                            "id": "msg-1",
+                            "bodyPlainText": "Please refund order 4521",
+                            "body": null,
+                            "author": null
+                        }
+                    }]
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let options = BackupOptions {
+        dir: dir.path().join("backup"),
+        format: ExportFormat::Markdown,
+        status: None,
+        folder_id: None,
+        dates: None,
+        contact: None,
+        limit: None,
+        pdf_command: None,
+    };
+    let summary = export::backup(&client, &options).await.unwrap();
+    assert_eq!(summary.conversations, 1);
+    assert_eq!(summary.messages, 1);
+
+    let thread = std::fs::read_to_string(options.dir.join("7.md")).unwrap();
+    assert!(thread.contains("Please refund order 4521"));
+
+    let manifest: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(options.dir.join(export::MANIFEST_FILE)).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(manifest["format"], "md");
+    assert_eq!(manifest["conversations"][0]["number"], 7);
+    assert_eq!(manifest["conversations"][0]["file"], "7.md");
+}
+
+#[tokio::test]
+async fn test_follow_unfollow_and_followers() {
+    use wiremock::matchers::{body_partial_json, body_string_contains};
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("conversationFollow"))
+        .and(body_partial_json(json!({
+            "variables": { "input": { "conversationId": "conv-1", "agentId": "agent-1" } }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "conversationFollow": { "errors": [] } }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("conversationUnfollow"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "conversationUnfollow": { "errors": [] } }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Followers"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "conversation": {
+                    "followers": {
+                        "nodes": [{ "id": "agent-1", "email": "agent@example.com", "name": "Agent" }]
+                    }
+                }
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    client.follow("conv-1", "agent-1").await.unwrap();
+    client.unfollow("conv-1", "agent-1").await.unwrap();
+
+    let followers = client.followers(1).await.unwrap();
+    assert_eq!(followers.len(), 1);
+    assert_eq!(followers[0].email, "agent@example.com");
+}
+
+#[tokio::test]
+async fn test_messages_last_page_before_cursor() {
+    use groovehq_cli::types::MessagePage;
+    use wiremock::matchers::body_partial_json;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({
+            "variables": { "conversationId": "conv-1", "last": 2, "before": "cursor-9" }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "events": {
+                    "pageInfo": {
+                        "hasNextPage": true,
+                        "endCursor": "cursor-8",
+                        "hasPreviousPage": true,
+                        "startCursor": "cursor-7"
+                    },
+                    "nodes": [
+                        {
+                            "createdAt": "2024-01-01T00:00:00Z",
+                            "change": { "__typename": "EmailMessage", "id": "msg-7", "bodyPlainText": "a", "body": null, "author": null }
+                        },
+                        {
+                            "createdAt": "2024-01-01T01:00:00Z",
+                            "change": { "__typename": "Reply", "id": "msg-8", "bodyPlainText": "b", "body": null, "author": null }
+                        }
+                    ]
+                }
+            }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    let page = MessagePage::Last {
+        count: 2,
+        before: Some("cursor-9".to_string()),
+    };
+    let (messages, page_info) = client.messages("conv-1", &page).await.unwrap();
+    let ids: Vec<&str> = messages.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, vec!["msg-7", "msg-8"]);
+    assert!(page_info.has_previous_page);
+    assert_eq!(page_info.start_cursor.as_deref(), Some("cursor-7"));
+}
+
+#[tokio::test]
+async fn test_builder_user_agent_and_retry_policy() {
+    use groovehq_cli::api::RetryPolicy;
+    use groovehq_cli::GrooveError;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(header("user-agent", "my-tool/1.0"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "60"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::builder("test-token")
+        .endpoint(mock_server.uri())
+        .user_agent("my-tool/1.0")
+        .timeout(std::time::Duration::from_secs(5))
+        .retry(RetryPolicy::none())
+        .build()
+        .unwrap();
+
+    // No retry, so no 60 second wait
+    let err = client.me().await.unwrap_err();
+    assert!(matches!(
+        err,
+        GrooveError::RateLimited {
+            retry_after: Some(60)
+        }
+    ));
+}
+
+#[test]
+fn test_builder_rejects_invalid_user_agent() {
+    let result = GrooveClient::builder("test-token")
+        .user_agent("bad\nagent")
+        .build();
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_builder_middleware() {
+    use futures::future::BoxFuture;
+    use groovehq_cli::api::{Middleware, Next, Request, Response};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct CountAndTag(Arc<AtomicUsize>);
+
+    impl Middleware for CountAndTag {
+        fn handle<'a>(
+            &'a self,
+            mut request: Request,
+            next: Next<'a>,
+        ) -> BoxFuture<'a, groovehq_cli::Result<Response>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            request
+                .http
+                .headers_mut()
+                .insert("x-caller", "test".parse().unwrap());
+            next.run(request)
+        }
+    }
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(header("x-caller", "test"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("x-ratelimit-remaining", "499")
+                .set_body_json(json!({
+                    "data": {
+                        "me": { "id": "agent-1", "email": "agent@example.com" }
+                    }
+                })),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let client = GrooveClient::builder("test-token")
+        .endpoint(mock_server.uri())
+        .middleware(CountAndTag(calls.clone()))
+        .track_rate_limit(false)
+        .build()
+        .unwrap();
+
+    client.me().await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(client.rate_limit().is_none());
+}
+
+#[tokio::test]
+async fn test_change_contact() {
+    use wiremock::matchers::body_partial_json;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({
+            "variables": {
+                "input": { "conversationId": "conv-1", "contactId": "contact-2" }
+            }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "conversationChangeContact": { "errors": [] } }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = GrooveClient::new("test-token", Some(&mock_server.uri())).unwrap();
+    client.change_contact("conv-1", "contact-2").await.unwrap();
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "blocking")]
+
+use groovehq_cli::blocking::GrooveClient;
+use groovehq_cli::ConversationFilter;
+use serde_json::json;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[test]
+fn test_blocking_client() {
+    // The mock server needs a runtime; the client must be used outside it
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mock_server = runtime.block_on(async {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {
+                    "conversations": {
+                        "nodes": [{
+                            "id": "conv-1",
+                            "number": 1,
+                            "subject": "Test Subject",
+                            "state": "OPENED",
+                            "createdAt": "2024-01-01T00:00:00Z",
+                            "updatedAt": "2024-01-01T12:00:00Z"
+                        }],
+                        "pageInfo": { "hasNextPage": false, "endCursor": null },
+                        "totalCount": 1
+                    }
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+        mock_server
+    });
+
+    let client = GrooveClient::builder("test-token")
+        .endpoint(mock_server.uri())
+        .build_blocking()
+        .unwrap();
+
+    let filter = ConversationFilter::new();
+    let page = client.conversations(&filter, Some(25), None).unwrap();
+    assert_eq!(page.total_count, 1);
+
+    let numbers: Vec<i64> = client
+        .conversations_iter(&filter)
+        .map(|conv| conv.unwrap().number)
+        .collect();
+    assert_eq!(numbers, vec![1]);
+}
@@ -1,8 +1,24 @@
-use assert_cmd::Command;
+use assert_cmd::{cargo::cargo_bin_cmd, Command};
 use predicates::prelude::*;
 
 fn groove() -> Command {
-    Command::cargo_bin("groove").unwrap()
+    cargo_bin_cmd!("groove")
+}
+
+/// `groove` reading `config.toml` in `dir` rather than the developer's own
+/// config file
+fn groove_with_config(dir: &tempfile::TempDir) -> Command {
+    let mut cmd = groove();
+    cmd.env("GROOVE_CONFIG", dir.path().join("config.toml"));
+    cmd
+}
+
+/// A config with one empty profile, so a `--profile isolated` run finds no
+/// token in the config file, a token_cmd, or the keyring
+fn isolated_config() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("config.toml"), "[profiles.isolated]\n").unwrap();
+    dir
 }
 
 #[test]
@@ -25,8 +41,9 @@ fn test_version() {
 
 #[test]
 fn test_missing_token() {
-    groove()
-        .arg("me")
+    let dir = isolated_config();
+    groove_with_config(&dir)
+        .args(["--profile", "isolated", "me"])
         .env_remove("GROOVEHQ_API_TOKEN")
         .assert()
         .failure()
@@ -37,11 +54,44 @@ fn test_missing_token() {
 fn test_config_path() {
     groove()
         .args(["config", "path"])
+        .env_remove("GROOVE_CONFIG")
         .assert()
         .success()
         .stdout(predicate::str::contains("config.toml"));
 }
 
+#[test]
+fn test_config_path_override() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ci.toml");
+    let path = path.to_str().unwrap();
+
+    groove()
+        .args([
+            "--config",
+            path,
+            "config",
+            "set",
+            "aliases.hi",
+            "!echo hello",
+        ])
+        .assert()
+        .success();
+    groove()
+        .env("GROOVE_CONFIG", path)
+        .args(["config", "path"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", path));
+    // --config wins over GROOVE_CONFIG, and is read before aliases expand
+    groove()
+        .env("GROOVE_CONFIG", dir.path().join("other.toml"))
+        .args([&format!("--config={}", path), "hi"])
+        .assert()
+        .success()
+        .stdout("hello\n");
+}
+
 #[test]
 fn test_invalid_subcommand() {
     groove().arg("invalid").assert().failure();
@@ -96,7 +146,8 @@ fn test_canned_replies_help() {
 
 #[test]
 fn test_config_help() {
-    groove()
+    let dir = tempfile::tempdir().unwrap();
+    groove_with_config(&dir)
         .args(["config", "--help"])
         .assert()
         .success()
@@ -173,3 +224,823 @@ fn test_conversation_reply_canned_flag() {
         .success()
         .stdout(predicate::str::contains("--canned"));
 }
+
+#[test]
+fn test_conversation_copy_flags() {
+    groove()
+        .args(["conv", "url", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--copy"));
+
+    groove()
+        .args(["conv", "view", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--copy"));
+
+    groove()
+        .args(["conv", "copy", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--message"));
+}
+
+#[test]
+fn test_open_takes_numbers_or_filters() {
+    groove()
+        .args(["open", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--assignee"));
+
+    groove()
+        .args(["open", "123", "--status", "opened"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_profile_flag() {
+    groove()
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--profile"))
+        .stdout(predicate::str::contains("GROOVE_PROFILE"));
+}
+
+#[test]
+fn test_limits_help() {
+    groove()
+        .args(["limits", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("rate-limit"));
+}
+
+#[test]
+fn test_debug_flag() {
+    groove()
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--debug"));
+}
+
+#[test]
+fn test_refresh_flag() {
+    groove()
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--refresh"));
+}
+
+#[test]
+fn test_config_set_token_keyring_flag() {
+    let dir = tempfile::tempdir().unwrap();
+    groove_with_config(&dir)
+        .args(["config", "set-token", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--keyring"));
+}
+
+#[test]
+fn test_config_set_get_unset_help() {
+    let dir = tempfile::tempdir().unwrap();
+    groove_with_config(&dir)
+        .args(["config", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("set"))
+        .stdout(predicate::str::contains("get"))
+        .stdout(predicate::str::contains("unset"));
+}
+
+#[test]
+fn test_config_verify_missing_token() {
+    let dir = isolated_config();
+    groove_with_config(&dir)
+        .args(["--profile", "isolated", "config", "verify"])
+        .env_remove("GROOVEHQ_API_TOKEN")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("API token not found"));
+}
+
+#[test]
+fn test_bulk_concurrency_flag() {
+    groove()
+        .args(["conversation", "close", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--concurrency"))
+        .stdout(predicate::str::contains("--fail-fast"));
+
+    groove()
+        .args(["conversation", "close", "1", "--concurrency", "0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--concurrency"));
+}
+
+#[test]
+fn test_bulk_number_ranges() {
+    groove()
+        .args(["conversation", "close", "50-10"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("start is after end"));
+
+    groove()
+        .args(["conversation", "open", "1,abc"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid conversation number"));
+}
+
+#[test]
+fn test_conversation_bulk_help() {
+    groove()
+        .args(["conversation", "bulk", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--older-than"))
+        .stdout(predicate::str::contains("--tag"))
+        .stdout(predicate::str::contains("--yes"));
+}
+
+#[test]
+fn test_conversation_bulk_requires_action() {
+    groove()
+        .args(["conversation", "bulk", "--status", "opened"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Usage"));
+}
+
+#[test]
+fn test_conversation_bulk_invalid_older_than() {
+    groove()
+        .args([
+            "--token",
+            "test",
+            "conversation",
+            "bulk",
+            "--older-than",
+            "30x",
+            "--yes",
+            "close",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid duration unit"));
+}
+
+#[test]
+fn test_undo_help() {
+    groove()
+        .args(["undo", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--steps"))
+        .stdout(predicate::str::contains("--dry-run"));
+}
+
+#[test]
+fn test_macro_requires_numbers() {
+    groove()
+        .args(["macro", "resolve"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("required"));
+}
+
+#[test]
+fn test_search_local_missing_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("missing.db");
+    groove()
+        .args(["search-local", "invoice", "--db"])
+        .arg(&db)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("groove sync"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_external_plugin() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let plugin = dir.path().join("groove-hello");
+    std::fs::write(
+        &plugin,
+        "#!/bin/sh\necho \"hello $1 token=$GROOVEHQ_API_TOKEN\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&plugin, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let path = format!(
+        "{}:{}",
+        dir.path().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    groove()
+        .args(["--token", "abc", "hello", "world"])
+        .env("PATH", path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("hello world token=abc"));
+}
+
+#[test]
+fn test_conversation_list_invalid_since() {
+    groove()
+        .args(["--token", "test", "conversation", "list", "--since", "soon"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid date: soon"));
+}
+
+#[test]
+fn test_add_tag_takes_numbers_then_tags() {
+    groove()
+        .args(["--token", "test", "conversation", "add-tag", "12", "13"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No tags given"));
+
+    groove()
+        .args(["--token", "test", "conversation", "untag", "urgent", "12"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid conversation number: 'urgent'",
+        ));
+}
+
+#[test]
+fn test_conversation_list_invalid_sort() {
+    groove()
+        .args([
+            "--token",
+            "test",
+            "conversation",
+            "list",
+            "--sort",
+            "random",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid sort: random"));
+}
+
+#[test]
+fn test_conversation_list_group_by_help() {
+    groove()
+        .args(["conversation", "list", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--group-by"))
+        .stdout(predicate::str::contains("assignee"));
+}
+
+#[test]
+fn test_my_help() {
+    groove()
+        .args(["my", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("snoozed"));
+}
+
+#[test]
+fn test_my_invalid_scope() {
+    groove()
+        .args(["--token", "test", "my", "closed"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'closed'"));
+}
+
+#[test]
+fn test_conversation_list_waking_before_requires_snoozed() {
+    groove()
+        .args([
+            "--token",
+            "test",
+            "conversation",
+            "list",
+            "--status",
+            "opened",
+            "--waking-before",
+            "2d",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("only applies to snoozed"));
+}
+
+#[test]
+fn test_note_delete_requires_confirmation() {
+    groove()
+        .args(["--token", "test", "note", "delete", "note-1"])
+        .write_stdin("")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("pass --yes"));
+}
+
+#[test]
+fn test_message_view_help() {
+    groove()
+        .args(["message", "view", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Message ID"));
+}
+
+#[test]
+fn test_conversation_view_message_conflicts_with_copy() {
+    groove()
+        .args([
+            "--token",
+            "test",
+            "conversation",
+            "view",
+            "1",
+            "--message",
+            "msg-1",
+            "--copy",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_conversation_view_invalid_grep_pattern() {
+    groove()
+        .args([
+            "--token",
+            "test",
+            "conversation",
+            "view",
+            "1",
+            "--grep",
+            "(",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid --grep pattern"));
+}
+
+#[test]
+fn test_conversation_view_context_requires_grep() {
+    groove()
+        .args(["--token", "test", "conversation", "view", "1", "-C", "3"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--grep"));
+}
+
+#[test]
+fn test_conversation_export_invalid_format() {
+    groove()
+        .args([
+            "--token",
+            "test",
+            "conversation",
+            "export",
+            "1",
+            "--to",
+            "docx",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid export format"));
+}
+
+#[test]
+fn test_export_requires_dir() {
+    groove()
+        .args(["--token", "test", "export", "--folder", "inbox"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--dir"));
+}
+
+#[test]
+fn test_sync_maildir_conflicts_with_db() {
+    groove()
+        .args([
+            "--token",
+            "test",
+            "sync",
+            "--maildir",
+            "/tmp/groove-mail",
+            "--db",
+            "/tmp/groove.db",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_conversation_reply_assign_help() {
+    groove()
+        .args(["conversation", "reply", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--assign <AGENT>"));
+}
+
+#[test]
+fn test_conversation_view_all_messages_conflicts_with_last() {
+    groove()
+        .args([
+            "--token",
+            "test",
+            "conversation",
+            "view",
+            "1",
+            "--all-messages",
+            "--last",
+            "5",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_message_view_html_help() {
+    groove()
+        .args(["message", "view", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--html"));
+}
+
+#[test]
+fn test_conversation_view_show_quoted_help() {
+    groove()
+        .args(["conversation", "view", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--show-quoted"));
+}
+
+#[test]
+fn test_conversation_view_expand_requires_collapse() {
+    groove()
+        .args(["conversation", "view", "123", "--expand", "3,7"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--collapse"));
+}
+
+#[test]
+fn test_exit_codes() {
+    // Usage errors
+    groove().args(["conversation", "view"]).assert().code(2);
+
+    // Missing token
+    groove()
+        .arg("me")
+        .env_remove("GROOVEHQ_API_TOKEN")
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn test_completions_complete_cached_names() {
+    groove()
+        .args(["completions", "bash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("groove complete-names --"));
+
+    // Hidden from help
+    groove()
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("complete-names").not());
+}
+
+#[test]
+fn test_conversation_list_rejects_invalid_status() {
+    groove()
+        .args(["conversation", "list", "--status", "pending"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Invalid status: pending"));
+}
+
+#[test]
+fn test_watch_validates_arguments() {
+    groove()
+        .args(["watch", "--interval", "5"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--interval"));
+
+    groove()
+        .args(["watch", "--metrics", "localhost"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--metrics"));
+}
+
+// XDG_DATA_HOME only moves the data directory on Linux
+#[cfg(target_os = "linux")]
+#[test]
+fn test_remind_add_list_done() {
+    let dir = tempfile::tempdir().unwrap();
+    let remind = |args: &[&str]| {
+        let mut cmd = groove();
+        cmd.env("XDG_DATA_HOME", dir.path())
+            .arg("remind")
+            .args(args);
+        cmd
+    };
+
+    remind(&["12345", "2d", "check deploy"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Reminder 1 set for #12345"));
+    remind(&["list", "-o", "compact"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 #12345").and(predicate::str::contains("check deploy")));
+    remind(&["list", "--due"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No reminders"));
+    remind(&["done", "1"]).assert().success();
+    remind(&["done", "1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No reminder with ID 1"));
+
+    remind(&["12345"]).assert().code(2);
+    remind(&["12345", "later"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid time"));
+}
+
+#[test]
+fn test_reply_preview_needs_a_terminal() {
+    // Nothing is fetched or sent when there's no one to confirm
+    groove()
+        .args([
+            "--token",
+            "x",
+            "conversation",
+            "reply",
+            "1",
+            "Hi",
+            "--preview",
+        ])
+        .write_stdin("")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--preview needs an interactive terminal",
+        ));
+}
+
+// XDG_CONFIG_HOME only moves the config directory on Linux
+#[cfg(target_os = "linux")]
+#[test]
+fn test_snippet_add_use_remove() {
+    let dir = tempfile::tempdir().unwrap();
+    let snippet = |args: &[&str]| {
+        let mut cmd = groove();
+        cmd.env("XDG_CONFIG_HOME", dir.path())
+            .arg("snippet")
+            .args(args);
+        cmd
+    };
+
+    snippet(&["list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No snippets"));
+    snippet(&["add", "sig"])
+        .write_stdin("Thanks,\nAlex\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Saved snippet 'sig'"));
+    assert!(dir.path().join("groove-cli/snippets/sig.txt").exists());
+    snippet(&["use", "sig"])
+        .assert()
+        .success()
+        .stdout("Thanks,\nAlex\n");
+    snippet(&["use", "sgi"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Did you mean 'sig'?"));
+    snippet(&["add", "../escape", "x"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid snippet name"));
+    snippet(&["remove", "sig"]).assert().success();
+    snippet(&["use", "sig"]).assert().code(3);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_outbox_list_and_cancel() {
+    let dir = tempfile::tempdir().unwrap();
+    let outbox = |args: &[&str]| {
+        let mut cmd = groove();
+        cmd.env("XDG_DATA_HOME", dir.path())
+            .arg("outbox")
+            .args(args);
+        cmd
+    };
+
+    outbox(&["list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Outbox is empty"));
+    outbox(&["cancel", "1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No queued reply with ID 1"));
+
+    // The time is checked before anything is sent or queued
+    groove()
+        .env("XDG_DATA_HOME", dir.path())
+        .args(["--token", "x", "conversation", "reply", "1", "Hi"])
+        .args(["--send-at", "someday"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid time"));
+}
+
+#[test]
+fn test_compose_needs_a_terminal() {
+    groove()
+        .args(["--token", "x", "conversation", "compose", "1"])
+        .write_stdin("")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "compose needs an interactive terminal",
+        ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_view_json_includes_every_message() {
+    use serde_json::json;
+    use wiremock::matchers::{body_partial_json, body_string_contains, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Conversation("))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "conversation": {
+                    "id": "conv-1",
+                    "number": 1,
+                    "subject": "Long thread",
+                    "state": "OPENED",
+                    "createdAt": "2024-01-01T00:00:00Z",
+                    "updatedAt": "2024-01-01T00:00:00Z",
+                    "tags": { "nodes": [] }
+                }
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let page = |id: &str, cursor: Option<&str>| {
+        json!({
+            "data": {
+                "events": {
+                    "pageInfo": { "hasNextPage": cursor.is_some(), "endCursor": cursor },
+                    "nodes": [{
+                        "createdAt": "2024-01-01T00:00:00Z",
+                        "change": {
+                            "__typename": "EmailMessage",
+                            "id": id,
+                            "bodyPlainText": "Hello",
+                            "body": null,
+                            "author": null
+                        }
+                    }]
+                }
+            }
+        })
+    };
+    Mock::given(method("POST"))
+        .and(body_partial_json(
+            json!({ "variables": { "after": "cursor-1" } }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(page("msg-2", None)))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("query Messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(page("msg-1", Some("cursor-1"))))
+        .mount(&server)
+        .await;
+
+    let home = tempfile::tempdir().unwrap();
+    groove()
+        .env("XDG_CONFIG_HOME", home.path())
+        .env("XDG_CACHE_HOME", home.path())
+        .env("XDG_DATA_HOME", home.path())
+        .args(["--token", "x", "--endpoint", &server.uri()])
+        .args(["conversation", "view", "1", "-o", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("msg-1").and(predicate::str::contains("msg-2")));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_close_reports_failed_batch() {
+    use serde_json::json;
+    use wiremock::matchers::{body_string_contains, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    let conversation = |number: i64| {
+        json!({
+            "id": format!("conv-{}", number),
+            "number": number,
+            "subject": "Hello",
+            "state": "OPENED",
+            "createdAt": "2024-01-01T00:00:00Z",
+            "updatedAt": "2024-01-01T00:00:00Z",
+            "tags": { "nodes": [] }
+        })
+    };
+    Mock::given(method("POST"))
+        .and(body_string_contains("query ConversationsByNumber"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "c0": conversation(1), "c1": conversation(2) }
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("mutation UpdateStates"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let home = tempfile::tempdir().unwrap();
+    groove()
+        .env("XDG_CONFIG_HOME", home.path())
+        .env("XDG_CACHE_HOME", home.path())
+        .env("XDG_DATA_HOME", home.path())
+        .args(["--token", "x", "--endpoint", &server.uri()])
+        .args(["conversation", "close", "1", "2", "-o", "json"])
+        .assert()
+        .code(6)
+        .stdout(
+            predicate::str::contains("\"number\": 1")
+                .and(predicate::str::contains("\"number\": 2")),
+        )
+        .stderr(predicate::str::contains("2 of 2 conversations failed"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_config_verify_uses_endpoint_flag() {
+    use serde_json::json;
+    use wiremock::matchers::{body_string_contains, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("me {"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "me": { "id": "agent-1", "email": "agent@example.com" } }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let home = tempfile::tempdir().unwrap();
+    groove_with_config(&home)
+        .args(["--token", "x", "--endpoint", &server.uri()])
+        .args(["config", "verify"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "api_endpoint: {}",
+            server.uri()
+        )));
+}
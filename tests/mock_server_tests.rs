@@ -0,0 +1,200 @@
+#![cfg(feature = "mock-server")]
+
+use std::net::TcpListener;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use futures::TryStreamExt;
+use groovehq_cli::{ConversationFilter, ConversationState, GrooveClient, GrooveError};
+use predicates::prelude::*;
+
+fn serve() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || groovehq_cli::mock::serve(listener));
+    endpoint
+}
+
+fn start() -> GrooveClient {
+    GrooveClient::new("any-token", Some(&serve())).unwrap()
+}
+
+#[tokio::test]
+async fn test_mock_server_queries() {
+    let client = start();
+
+    let me = client.me().await.unwrap();
+    assert_eq!(me.email, "alex@example.com");
+    assert!(client.rate_limit().is_some());
+    assert!(client.last_request_id().unwrap().starts_with("mock-"));
+
+    let filter = ConversationFilter::new().state(ConversationState::Opened);
+    let open: Vec<_> = client
+        .conversations_stream(&filter)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(open.len(), 3);
+
+    let conv = client.conversation(1001).await.unwrap();
+    let messages = client.all_messages(&conv.id).await.unwrap();
+    assert_eq!(messages.len(), 2);
+    let notes = client.notes(&conv.id, None).await.unwrap();
+    assert_eq!(notes.len(), 1);
+
+    assert!(matches!(
+        client.conversation(1).await,
+        Err(GrooveError::ConversationNotFound(1))
+    ));
+    let found = client.conversations_by_number(&[1002, 1]).await.unwrap();
+    assert_eq!(found[0].as_ref().unwrap().number, 1002);
+    assert!(found[1].is_none());
+
+    let profile = client
+        .contact_profile("jane@customer.com", 10)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(profile.contact.id, "contact-1");
+    let numbers: Vec<i64> = profile.conversations.iter().map(|c| c.number).collect();
+    assert_eq!(numbers, [1001, 1005]);
+
+    let record = client.contact("contact-1").await.unwrap().unwrap();
+    assert_eq!(record.contact.email.as_deref(), Some("jane@customer.com"));
+    assert_eq!(record.company_name.as_deref(), Some("Customer Co"));
+    assert!(client.contact("contact-404").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_mock_server_mutations() {
+    let client = start();
+
+    client.close("conv-1001").await.unwrap();
+    let results = client.open_many(&["conv-1005", "conv-404"]).await.unwrap();
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+
+    // Mutations don't change the fixtures
+    let conv = client.conversation(1001).await.unwrap();
+    assert_eq!(conv.state, ConversationState::Opened);
+}
+
+/// `groove` pointed at a fresh mock server with `--endpoint`, keeping its
+/// config and cache in `home`
+fn groove(home: &tempfile::TempDir) -> assert_cmd::Command {
+    let mut cmd = cargo_bin_cmd!("groove");
+    cmd.env("XDG_CONFIG_HOME", home.path())
+        .env("XDG_CACHE_HOME", home.path())
+        .env_remove("GROOVEHQ_API_ENDPOINT")
+        .args(["--token", "any-token", "--endpoint", &serve()]);
+    cmd
+}
+
+#[test]
+fn test_cli_endpoint_override() {
+    let endpoint = serve();
+    let home = tempfile::tempdir().unwrap();
+    let groove = || {
+        let mut cmd = cargo_bin_cmd!("groove");
+        cmd.env("XDG_CONFIG_HOME", home.path())
+            .env("XDG_CACHE_HOME", home.path())
+            .env_remove("GROOVEHQ_API_ENDPOINT")
+            .args(["--token", "any-token"]);
+        cmd
+    };
+
+    groove()
+        .args(["--endpoint", &endpoint, "conversation", "view", "1001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Refund for order #4521"));
+    groove()
+        .env("GROOVEHQ_API_ENDPOINT", &endpoint)
+        .args(["tag", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("billing"));
+}
+
+#[test]
+fn test_cli_folder_view() {
+    let home = tempfile::tempdir().unwrap();
+    groove(&home)
+        .args(["folder", "view", "billing"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Total: 2"))
+        .stdout(predicate::str::contains(
+            "Invoice shows the wrong VAT number",
+        ));
+}
+
+#[test]
+fn test_cli_list_search_in_body() {
+    let home = tempfile::tempdir().unwrap();
+    groove(&home)
+        .args([
+            "conversation",
+            "list",
+            "-q",
+            "refund",
+            "--search-in",
+            "body",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("I'd like a refund"));
+}
+
+#[test]
+fn test_cli_search_filters() {
+    let home = tempfile::tempdir().unwrap();
+    groove(&home)
+        .args(["search", "folder:billing state:snoozed"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Invoice shows the wrong VAT number",
+        ))
+        .stdout(predicate::str::contains("Refund for order").not());
+}
+
+#[test]
+fn test_cli_search_every_source() {
+    let home = tempfile::tempdir().unwrap();
+    groove(&home)
+        .args(["search", "refund", "-o", "compact"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("#1001"))
+        .stdout(predicate::str::contains("Canned replies (1)"))
+        .stdout(predicate::str::contains("How refunds work"));
+    groove(&home)
+        .args(["search", "jane"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Contacts (1)"));
+}
+
+#[test]
+fn test_cli_add_tag_to_several_conversations() {
+    let home = tempfile::tempdir().unwrap();
+    groove(&home)
+        .args(["conversation", "add-tag", "-", "1003", "billing"])
+        .write_stdin("#1001 [open] Refund for order #4521 - jane@customer.com\n1002\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added tags to conversation #1002"))
+        .stdout(predicate::str::contains("1003"));
+}
+
+#[test]
+fn test_cli_clear_tags() {
+    let home = tempfile::tempdir().unwrap();
+    groove(&home)
+        .args(["conversation", "clear-tags", "1001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Cleared all tags from conversation #1001",
+        ));
+}